@@ -1,15 +1,16 @@
 use std::{
-    fs::File,
+    fs,
     path::PathBuf,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use block_compression::{
-    half::f16, BC6HSettings, BC7Settings, CompressionVariant, GpuBlockCompressor,
+    dds::{write_dds, DdsDescriptor},
+    half::f16,
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant, GpuBlockCompressor,
 };
 use bytemuck::cast_slice;
-use ddsfile::{AlphaMode, D3D10ResourceDimension, Dds, DxgiFormat, NewDxgiParams};
 use image::ImageReader;
 use pollster::block_on;
 use wgpu::{
@@ -67,7 +68,7 @@ fn main() {
 
     let start = Instant::now();
 
-    let block_data = download_blocks_data(&device, &queue, blocks_buffer);
+    let block_data = download_blocks_data(&compressor, &device, &queue, blocks_buffer);
 
     let duration = start.elapsed();
     println!(
@@ -283,48 +284,31 @@ fn compress(compressor: &mut GpuBlockCompressor, device: &Device, queue: &Queue)
     }
 }
 
-fn download_blocks_data(device: &Device, queue: &Queue, block_buffer: Buffer) -> Vec<u8> {
-    let size = block_buffer.size();
-
-    let staging_buffer = device.create_buffer(&BufferDescriptor {
-        label: Some("staging buffer"),
-        size,
-        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    });
-
+fn download_blocks_data(
+    compressor: &GpuBlockCompressor,
+    device: &Device,
+    queue: &Queue,
+    block_buffer: Buffer,
+) -> Vec<u8> {
     let mut copy_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
         label: Some("copy encoder"),
     });
 
-    copy_encoder.copy_buffer_to_buffer(&block_buffer, 0, &staging_buffer, 0, size);
+    let readback = compressor.read_back_blocks(
+        &mut copy_encoder,
+        &block_buffer,
+        0,
+        block_buffer.size() as usize,
+    );
 
     queue.submit([copy_encoder.finish()]);
 
-    let result;
-
-    {
-        let buffer_slice = staging_buffer.slice(..);
-
-        let (tx, rx) = std::sync::mpsc::channel();
-        buffer_slice.map_async(MapMode::Read, move |v| tx.send(v).unwrap());
-
-        let _ = device.poll(PollType::Wait {
-            submission_index: None,
-            timeout: Some(Duration::from_secs(60)),
-        });
-
-        match rx.recv() {
-            Ok(Ok(())) => {
-                result = buffer_slice.get_mapped_range().to_vec();
-            }
-            _ => panic!("couldn't read from buffer"),
-        }
-    }
-
-    staging_buffer.unmap();
+    let _ = device.poll(PollType::Wait {
+        submission_index: None,
+        timeout: Some(Duration::from_secs(60)),
+    });
 
-    result
+    block_on(readback).expect("couldn't read from buffer")
 }
 
 fn write_dds_file(
@@ -334,50 +318,26 @@ fn write_dds_file(
     height: u32,
     block_data: Vec<u8>,
 ) {
-    let mut dds = Dds::new_dxgi(NewDxgiParams {
-        height,
-        width,
-        depth: None,
-        format: dxgi_format(variant),
-        mipmap_levels: Some(1),
-        array_layers: None,
-        caps2: None,
-        is_cubemap: false,
-        resource_dimension: D3D10ResourceDimension::Texture2D,
-        alpha_mode: AlphaMode::Straight,
-    })
-    .expect("failed to create DDS header");
-
-    dds.data = block_data;
+    let descriptor = DdsDescriptor::new(variant, width, height).with_srgb(true);
+    let dds = write_dds(&descriptor, &block_data);
 
     let mut dds_name = PathBuf::from(file_name);
     dds_name.set_extension("dds");
 
-    let mut file = File::create(dds_name).expect("failed to create output file");
-    dds.write(&mut file).expect("failed to write DDS file");
-}
-
-fn dxgi_format(variant: CompressionVariant) -> DxgiFormat {
-    match variant {
-        CompressionVariant::BC1 => DxgiFormat::BC1_UNorm_sRGB,
-        CompressionVariant::BC2 => DxgiFormat::BC2_UNorm_sRGB,
-        CompressionVariant::BC3 => DxgiFormat::BC3_UNorm_sRGB,
-        CompressionVariant::BC4 => DxgiFormat::BC4_UNorm,
-        CompressionVariant::BC5 => DxgiFormat::BC5_UNorm,
-        CompressionVariant::BC6H(..) => DxgiFormat::BC6H_UF16,
-        CompressionVariant::BC7(..) => DxgiFormat::BC7_UNorm_sRGB,
-    }
+    fs::write(dds_name, dds).expect("failed to write DDS file");
 }
 
 fn print_help() {
     println!("Usage: compressor <compression_variant> <input_file>");
     println!("\nCompression variants:");
     println!("  bc1  - BC1 compression (RGB)");
+    println!("  bc1a - BC1 compression with 1-bit alpha (RGBA)");
     println!("  bc2  - BC2 compression with sharp alpha (RGBA)");
     println!("  bc3  - BC3 compression with smooth alpha (RGBA)");
     println!("  bc4  - BC4 compression (R)");
     println!("  bc5  - BC5 compression (RG)");
     println!("  bc6h - BC6H compression (RGB HDR)");
+    println!("  bc6hs - BC6H compression of signed data (RGB HDR)");
     println!("  bc7  - BC7 compression with smooth alpha (RGBA)");
 }
 
@@ -390,12 +350,14 @@ fn parse_args() -> Option<(CompressionVariant, String)> {
     }
 
     let variant = match args[1].to_lowercase().as_str() {
-        "bc1" => CompressionVariant::BC1,
-        "bc2" => CompressionVariant::BC2,
-        "bc3" => CompressionVariant::BC3,
+        "bc1" => CompressionVariant::BC1(BC123Settings::new()),
+        "bc1a" => CompressionVariant::BC1(BC123Settings::new().with_alpha_threshold(128)),
+        "bc2" => CompressionVariant::BC2(BC123Settings::new()),
+        "bc3" => CompressionVariant::BC3(BC123Settings::new()),
         "bc4" => CompressionVariant::BC4,
         "bc5" => CompressionVariant::BC5,
         "bc6h" => CompressionVariant::BC6H(BC6HSettings::very_slow()),
+        "bc6hs" => CompressionVariant::BC6H(BC6HSettings::very_slow().with_signed(true)),
         "bc7" => CompressionVariant::BC7(BC7Settings::alpha_slow()),
         _ => {
             println!("Error: Invalid compression variant");
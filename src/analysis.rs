@@ -0,0 +1,490 @@
+//! Format selection from image content.
+//!
+//! [`analyze_rgba8()`] and [`analyze_rgba16()`] inspect the pixels of an image and recommend the
+//! [`CompressionVariant`] which stores its content without wasting space on unused channels:
+//! BC4 for grayscale and single channel images, BC5 for normal maps and two channel images, BC1
+//! or BC7 without alpha for opaque images and BC6H for HDR images. The returned
+//! [`FormatAnalysis`] lists the [`Reason`]s for the recommendation, and its variant can be passed
+//! straight to [`encode::compress_rgba8()`].
+//!
+//! A constant alpha channel is not stored either: the recommended variant decodes alpha as
+//! `255`, and the reasons contain [`Reason::AlphaDiscarded`] when the constant value is not fully
+//! opaque. The value is reported by [`Reason::ConstantAlpha`] and must be restored by the caller.
+//!
+//! [`encode::compress_rgba8()`]: crate::encode::compress_rgba8
+//!
+//! # Example
+//! ```
+//! use block_compression::{
+//!     analysis::{analyze_rgba8, FormatPriority},
+//!     encode::compress_rgba8,
+//!     CompressionVariant,
+//! };
+//!
+//! let rgba_data = [200, 200, 200, 255].repeat(64 * 64); // An opaque grayscale mask
+//! let analysis = analyze_rgba8(&rgba_data, 64, 64, 64 * 4, FormatPriority::Quality);
+//!
+//! assert!(matches!(analysis.variant, CompressionVariant::BC4));
+//! for reason in &analysis.reasons {
+//!     println!("{reason}");
+//! }
+//!
+//! let mut blocks = vec![0u8; analysis.variant.blocks_byte_size(64, 64)];
+//! compress_rgba8(analysis.variant, &rgba_data, &mut blocks, 64, 64, 64 * 4);
+//! ```
+
+use std::fmt;
+
+use crate::{encode::validate_source, BC123Settings, BC7Settings, CompressionVariant, Error};
+
+/// The largest difference from unit length at which a pixel is still considered a normal.
+const NORMAL_LENGTH_TOLERANCE: f32 = 0.1;
+
+/// The alpha threshold used for BC1 when cutout alpha is stored with punch-through alpha.
+const CUTOUT_ALPHA_THRESHOLD: u8 = 128;
+
+/// Whether the recommendation should favor quality or size when both are possible.
+///
+/// The priority only matters for color images: with [`FormatPriority::Quality`] BC7 is
+/// recommended, with [`FormatPriority::Size`] BC1 or BC3 are recommended, which use half the
+/// space of BC7 for opaque and cutout images.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FormatPriority {
+    /// Favor the quality of the compressed image.
+    #[default]
+    Quality,
+    /// Favor the size of the compressed image.
+    Size,
+}
+
+/// The content of the alpha channel of an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AlphaContent {
+    /// All pixels are fully opaque.
+    Opaque,
+    /// All pixels have the same alpha value, which is not fully opaque. The recommended variant
+    /// doesn't store it and decodes alpha as `255`.
+    Constant,
+    /// All pixels are either fully transparent or fully opaque.
+    Cutout,
+    /// The alpha channel contains partially transparent pixels.
+    Smooth,
+}
+
+/// The content of the red, green and blue channel of an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelContent {
+    /// Red, green and blue are equal in every pixel.
+    Grayscale,
+    /// Only the red channel is used, green and blue are zero.
+    SingleChannel,
+    /// Only the red and green channel are used, blue is zero.
+    TwoChannel,
+    /// Every pixel is a unit length tangent-space normal, stored as `n * 0.5 + 0.5`.
+    ///
+    /// Normal maps are best compressed with [`encode::compress_normal_map_rgba8()`].
+    ///
+    /// [`encode::compress_normal_map_rgba8()`]: crate::encode::compress_normal_map_rgba8
+    NormalMap,
+    /// All three color channels are used.
+    Color,
+}
+
+/// A reason behind the variant recommended by the analysis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Reason {
+    /// All pixels are fully opaque.
+    OpaqueAlpha,
+    /// All pixels share the contained alpha value.
+    ConstantAlpha(f32),
+    /// The alpha channel only contains fully transparent and fully opaque pixels.
+    CutoutAlpha,
+    /// The alpha channel contains partially transparent pixels.
+    SmoothAlpha,
+    /// Red, green and blue are equal in every pixel.
+    Grayscale,
+    /// Only the red channel is used.
+    SingleChannel,
+    /// Only the red and green channel are used.
+    TwoChannel,
+    /// The pixels are unit length normals.
+    NormalMap,
+    /// The image contains values outside of the range of 0.0 to 1.0.
+    HighDynamicRange,
+    /// The image contains negative values.
+    NegativeValues,
+    /// The alpha channel is not opaque, but the recommended variant doesn't store alpha.
+    AlphaDiscarded,
+    /// The HDR image only contains values in the range of 0.0 to 1.0.
+    LowDynamicRange,
+    /// The recommendation followed the given priority.
+    Priority(FormatPriority),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::OpaqueAlpha => write!(f, "all pixels are opaque, so alpha isn't stored"),
+            Reason::ConstantAlpha(alpha) => write!(
+                f,
+                "all pixels have the alpha value {alpha}, so alpha isn't stored"
+            ),
+            Reason::CutoutAlpha => write!(
+                f,
+                "alpha only contains fully transparent and fully opaque pixels"
+            ),
+            Reason::SmoothAlpha => write!(
+                f,
+                "alpha contains partially transparent pixels, which need an alpha channel"
+            ),
+            Reason::Grayscale => write!(
+                f,
+                "red, green and blue are equal, so only the red channel is stored"
+            ),
+            Reason::SingleChannel => write!(f, "only the red channel is used"),
+            Reason::TwoChannel => write!(
+                f,
+                "the blue channel is unused, so only red and green are stored"
+            ),
+            Reason::NormalMap => write!(
+                f,
+                "the pixels are unit length normals, so only X and Y are stored and Z is reconstructed"
+            ),
+            Reason::HighDynamicRange => {
+                write!(f, "values outside of 0.0 to 1.0 need an HDR format")
+            }
+            Reason::NegativeValues => write!(f, "negative values need signed BC6H"),
+            Reason::AlphaDiscarded => write!(
+                f,
+                "the recommended variant doesn't store alpha, so alpha is discarded"
+            ),
+            Reason::LowDynamicRange => write!(
+                f,
+                "all values are in the range of 0.0 to 1.0, convert the image to RGBA8 to compress it"
+            ),
+            Reason::Priority(FormatPriority::Quality) => {
+                write!(f, "quality is preferred over size")
+            }
+            Reason::Priority(FormatPriority::Size) => write!(f, "size is preferred over quality"),
+        }
+    }
+}
+
+/// The result of analyzing the content of an image.
+#[derive(Clone, Debug)]
+pub struct FormatAnalysis {
+    /// The recommended compression variant.
+    pub variant: CompressionVariant,
+    /// The content of the alpha channel.
+    pub alpha: AlphaContent,
+    /// The content of the red, green and blue channel.
+    pub channels: ChannelContent,
+    /// Whether the image contains values outside of the range of 0.0 to 1.0.
+    pub high_dynamic_range: bool,
+    /// The reasons for the recommended variant, starting with the alpha channel.
+    pub reasons: Vec<Reason>,
+}
+
+/// Recommends a compression variant for raw RGBA8 data.
+///
+/// See [`try_analyze_rgba8()`] for the fallible version.
+///
+/// # Arguments
+/// * `rgba_data` - Source RGBA8 pixel data
+/// * `width` - Width of the image in pixels
+/// * `height` - Height of the image in pixels
+/// * `stride` - Number of bytes per row in the source data (for padding)
+/// * `priority` - Whether BC7 or the smaller BC1 and BC3 are recommended for color images
+///
+/// # Panics
+/// * If [`try_analyze_rgba8()`] returns an error
+pub fn analyze_rgba8(
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    priority: FormatPriority,
+) -> FormatAnalysis {
+    match try_analyze_rgba8(rgba_data, width, height, stride, priority) {
+        Ok(analysis) => analysis,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Recommends a compression variant for raw RGBA8 data.
+///
+/// This is the fallible version of [`analyze_rgba8()`], which returns an error instead of
+/// panicking on invalid input.
+///
+/// # Errors
+/// * [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// * [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+pub fn try_analyze_rgba8(
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    priority: FormatPriority,
+) -> Result<FormatAnalysis, Error> {
+    validate_source(rgba_data.len(), width, height, stride)?;
+
+    let mut content = ContentStatistics::new();
+    for y in 0..height as usize {
+        let row = &rgba_data[y * stride as usize..][..width as usize * 4];
+        for pixel in row.chunks_exact(4) {
+            content.add_pixel([0, 1, 2, 3].map(|channel| pixel[channel] as f32 / 255.0));
+        }
+    }
+
+    Ok(content.recommend(priority))
+}
+
+/// Recommends a compression variant for raw RGBA16 float data.
+///
+/// HDR images with values outside of the range of 0.0 to 1.0 are recommended BC6H, signed BC6H
+/// if they contain negative values. All other images are analyzed like RGBA8 images and need to
+/// be converted to RGBA8 before they can be compressed with the recommended variant.
+///
+/// See [`try_analyze_rgba16()`] for the fallible version.
+///
+/// # Arguments
+/// * `rgba_data` - Source RGBA16 float pixel data
+/// * `width` - Width of the image in pixels
+/// * `height` - Height of the image in pixels
+/// * `stride` - Number of elements per row in the source data (for padding)
+/// * `priority` - Whether BC7 or the smaller BC1 and BC3 are recommended for color images
+///
+/// # Panics
+/// * If [`try_analyze_rgba16()`] returns an error
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn analyze_rgba16(
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    priority: FormatPriority,
+) -> FormatAnalysis {
+    match try_analyze_rgba16(rgba_data, width, height, stride, priority) {
+        Ok(analysis) => analysis,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Recommends a compression variant for raw RGBA16 float data.
+///
+/// This is the fallible version of [`analyze_rgba16()`], which returns an error instead of
+/// panicking on invalid input.
+///
+/// # Errors
+/// * [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// * [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_analyze_rgba16(
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    priority: FormatPriority,
+) -> Result<FormatAnalysis, Error> {
+    validate_source(rgba_data.len(), width, height, stride)?;
+
+    let mut content = ContentStatistics::new();
+    for y in 0..height as usize {
+        let row = &rgba_data[y * stride as usize..][..width as usize * 4];
+        for pixel in row.chunks_exact(4) {
+            content.add_pixel([0, 1, 2, 3].map(|channel| pixel[channel].to_f32()));
+        }
+    }
+
+    if content.high_dynamic_range {
+        return Ok(content.recommend_hdr());
+    }
+
+    let mut analysis = content.recommend(priority);
+    analysis.reasons.push(Reason::LowDynamicRange);
+    Ok(analysis)
+}
+
+/// Properties of the pixels of an image, which hold as long as their flag is set.
+struct ContentStatistics {
+    first_alpha: Option<f32>,
+    opaque: bool,
+    constant_alpha: bool,
+    cutout_alpha: bool,
+    grayscale: bool,
+    red_only: bool,
+    blue_unused: bool,
+    normal_map: bool,
+    high_dynamic_range: bool,
+    #[cfg(feature = "bc6h")]
+    negative: bool,
+}
+
+impl ContentStatistics {
+    fn new() -> Self {
+        Self {
+            first_alpha: None,
+            opaque: true,
+            constant_alpha: true,
+            cutout_alpha: true,
+            grayscale: true,
+            red_only: true,
+            blue_unused: true,
+            normal_map: true,
+            high_dynamic_range: false,
+            #[cfg(feature = "bc6h")]
+            negative: false,
+        }
+    }
+
+    fn add_pixel(&mut self, [r, g, b, a]: [f32; 4]) {
+        let first_alpha = *self.first_alpha.get_or_insert(a);
+
+        self.opaque &= a == 1.0;
+        self.constant_alpha &= a == first_alpha;
+        self.cutout_alpha &= a == 0.0 || a == 1.0;
+
+        self.grayscale &= r == g && g == b;
+        self.red_only &= g == 0.0 && b == 0.0;
+        self.blue_unused &= b == 0.0;
+        self.normal_map = self.normal_map && is_unit_normal(r, g, b);
+
+        let rgb = [r, g, b];
+        #[cfg(feature = "bc6h")]
+        {
+            self.negative |= rgb.iter().any(|&value| value < 0.0);
+        }
+        self.high_dynamic_range |= rgb.iter().any(|&value| !(0.0..=1.0).contains(&value));
+    }
+
+    fn alpha(&self) -> AlphaContent {
+        if self.opaque {
+            AlphaContent::Opaque
+        } else if self.constant_alpha {
+            AlphaContent::Constant
+        } else if self.cutout_alpha {
+            AlphaContent::Cutout
+        } else {
+            AlphaContent::Smooth
+        }
+    }
+
+    fn channels(&self) -> ChannelContent {
+        if self.grayscale {
+            ChannelContent::Grayscale
+        } else if self.red_only {
+            ChannelContent::SingleChannel
+        } else if self.normal_map {
+            ChannelContent::NormalMap
+        } else if self.blue_unused {
+            ChannelContent::TwoChannel
+        } else {
+            ChannelContent::Color
+        }
+    }
+
+    fn alpha_reason(&self) -> Reason {
+        match self.alpha() {
+            AlphaContent::Opaque => Reason::OpaqueAlpha,
+            AlphaContent::Constant => Reason::ConstantAlpha(self.first_alpha.unwrap_or(1.0)),
+            AlphaContent::Cutout => Reason::CutoutAlpha,
+            AlphaContent::Smooth => Reason::SmoothAlpha,
+        }
+    }
+
+    /// Recommends a variant for data in the range of 0.0 to 1.0.
+    fn recommend(&self, priority: FormatPriority) -> FormatAnalysis {
+        let alpha = self.alpha();
+        let channels = self.channels();
+        let mut reasons = vec![self.alpha_reason()];
+
+        let alpha_unused = matches!(alpha, AlphaContent::Opaque | AlphaContent::Constant);
+
+        let variant = match channels {
+            ChannelContent::Grayscale if alpha_unused => {
+                reasons.push(Reason::Grayscale);
+                CompressionVariant::BC4
+            }
+            ChannelContent::SingleChannel if alpha_unused => {
+                reasons.push(Reason::SingleChannel);
+                CompressionVariant::BC4
+            }
+            ChannelContent::NormalMap if alpha_unused => {
+                reasons.push(Reason::NormalMap);
+                CompressionVariant::BC5
+            }
+            ChannelContent::TwoChannel if alpha_unused => {
+                reasons.push(Reason::TwoChannel);
+                CompressionVariant::BC5
+            }
+            _ => {
+                reasons.push(Reason::Priority(priority));
+
+                match (priority, alpha) {
+                    (FormatPriority::Quality, AlphaContent::Opaque | AlphaContent::Constant) => {
+                        CompressionVariant::BC7(BC7Settings::opaque_basic())
+                    }
+                    (FormatPriority::Quality, _) => {
+                        CompressionVariant::BC7(BC7Settings::alpha_basic())
+                    }
+                    (FormatPriority::Size, AlphaContent::Opaque | AlphaContent::Constant) => {
+                        CompressionVariant::BC1(BC123Settings::new())
+                    }
+                    (FormatPriority::Size, AlphaContent::Cutout) => CompressionVariant::BC1(
+                        BC123Settings::new().with_alpha_threshold(CUTOUT_ALPHA_THRESHOLD),
+                    ),
+                    (FormatPriority::Size, AlphaContent::Smooth) => {
+                        CompressionVariant::BC3(BC123Settings::new())
+                    }
+                }
+            }
+        };
+
+        if alpha == AlphaContent::Constant {
+            reasons.push(Reason::AlphaDiscarded);
+        }
+
+        FormatAnalysis {
+            variant,
+            alpha,
+            channels,
+            high_dynamic_range: self.high_dynamic_range,
+            reasons,
+        }
+    }
+
+    /// Recommends BC6H for data outside of the range of 0.0 to 1.0.
+    #[cfg(feature = "bc6h")]
+    fn recommend_hdr(&self) -> FormatAnalysis {
+        let alpha = self.alpha();
+        let mut reasons = vec![self.alpha_reason(), Reason::HighDynamicRange];
+
+        if self.negative {
+            reasons.push(Reason::NegativeValues);
+        }
+        if alpha != AlphaContent::Opaque {
+            reasons.push(Reason::AlphaDiscarded);
+        }
+
+        let settings = crate::BC6HSettings::basic().with_signed(self.negative);
+
+        FormatAnalysis {
+            variant: CompressionVariant::BC6H(settings),
+            alpha,
+            channels: self.channels(),
+            high_dynamic_range: true,
+            reasons,
+        }
+    }
+}
+
+/// Returns whether the unsigned color is the encoding of a unit length normal with a
+/// non-negative Z component.
+fn is_unit_normal(r: f32, g: f32, b: f32) -> bool {
+    let [x, y, z] = [r, g, b].map(|value| value * 2.0 - 1.0);
+    let length = (x * x + y * y + z * z).sqrt();
+
+    (length - 1.0).abs() <= NORMAL_LENGTH_TOLERANCE && z >= -NORMAL_LENGTH_TOLERANCE
+}
@@ -1,20 +1,31 @@
-use std::{collections::HashMap, num::NonZeroU64};
+use std::{borrow::Cow, collections::HashMap, num::NonZeroU64, ops::Range};
 
 use bytemuck::{cast_slice, Pod, Zeroable};
 use wgpu::{
-    self, include_wgsl, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
-    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, Buffer,
-    BufferBinding, BufferBindingType, BufferDescriptor, BufferUsages, ComputePass, ComputePipeline,
-    ComputePipelineDescriptor, Device, PipelineCompilationOptions, PipelineLayoutDescriptor, Queue,
-    ShaderModule, ShaderRuntimeChecks, ShaderStages, TextureSampleType, TextureView,
-    TextureViewDimension,
+    self,
+    util::{DeviceExt, TextureDataOrder},
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBinding, BufferBindingType,
+    BufferDescriptor, BufferUsages, CommandEncoder, ComputePass, ComputePipeline,
+    ComputePipelineDescriptor, Device, Extent3d, Origin3d, PipelineCompilationOptions,
+    PipelineLayoutDescriptor, Queue, ShaderModule, ShaderModuleDescriptor, ShaderRuntimeChecks,
+    ShaderSource, ShaderStages, TexelCopyBufferInfo, TexelCopyBufferLayout, TexelCopyTextureInfo,
+    Texture, TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType,
+    TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
 };
 
+#[cfg(feature = "bc15")]
+use crate::BC123Settings;
 #[cfg(feature = "bc6h")]
 use crate::BC6HSettings;
 #[cfg(feature = "bc7")]
 use crate::BC7Settings;
-use crate::CompressionVariant;
+use crate::{
+    mipmap::{compressed_mip_levels, CompressedMipLevel, MipLevel},
+    readback::BlocksReadback,
+    CompressionVariant, Error,
+};
 
 #[derive(Copy, Clone, Zeroable, Pod)]
 #[repr(C)]
@@ -27,6 +38,12 @@ struct Uniforms {
     texture_y_offset: u32,
     /// Start of the blocks data in u32 elements.
     blocks_offset: u32,
+    /// First array layer of the texture data we want to convert.
+    base_array_layer: u32,
+    /// Start of the block errors in f32 elements.
+    errors_offset: u32,
+    /// Whether the error of every block is written into the error buffer.
+    write_errors: u32,
 }
 
 struct Task {
@@ -34,12 +51,106 @@ struct Task {
     width: u32,
     height: u32,
     uniform_offset: u32,
-    #[cfg(any(feature = "bc6h", feature = "bc7"))]
+    #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
     setting_offset: u32,
     texture_y_offset: u32,
     buffer_offset: u32,
+    base_array_layer: u32,
+    layer_count: u32,
+    view_dimension: TextureViewDimension,
     texture_view: TextureView,
     buffer: Buffer,
+    error_buffer: Option<Buffer>,
+    errors_offset: u32,
+}
+
+/// Describes the compression of a range of mip levels and array layers of a texture.
+///
+/// Passed to [`GpuBlockCompressor::add_texture_compression_task()`], which schedules one
+/// dispatch per subresource. The subresources are compressed back to back into `buffer`, layer
+/// by layer, and every layer contains its mip levels starting with the first level of
+/// `mip_levels`. Compressing all mip levels and layers therefore produces the block layout that
+/// is used by the `dds` and `ktx2` modules.
+#[derive(Clone, Debug)]
+pub struct TextureCompressionTask<'a> {
+    /// The block compression format to use.
+    pub variant: CompressionVariant,
+    /// The source texture to compress.
+    pub texture: &'a Texture,
+    /// The range of mip levels to compress.
+    pub mip_levels: Range<u32>,
+    /// The range of array layers to compress. Cubemaps store their six faces as consecutive
+    /// layers in the order +X, -X, +Y, -Y, +Z, -Z.
+    pub array_layers: Range<u32>,
+    /// The destination storage buffer for the compressed data.
+    pub buffer: &'a Buffer,
+    /// The offset in bytes of the first subresource in `buffer`.
+    pub blocks_offset: u32,
+    /// The optional destination storage buffer for the error of every compressed block.
+    ///
+    /// The error of every block is stored as one `f32` in the same order as the blocks in
+    /// `buffer`, starting at the beginning of the error buffer. BC1 to BC5 and BC7 report the
+    /// squared error of the block on 8 bit channel values, weighted with the channel weights of
+    /// the settings. The signed BC4 and BC5 variants measure values from -127 to 127. BC6H
+    /// reports the squared error that its encoder minimizes, which is measured on the bit
+    /// patterns of the half-float values.
+    pub error_buffer: Option<&'a Buffer>,
+}
+
+impl<'a> TextureCompressionTask<'a> {
+    /// Creates a task that compresses all mip levels and array layers of `texture`.
+    pub fn new(variant: CompressionVariant, texture: &'a Texture, buffer: &'a Buffer) -> Self {
+        Self {
+            variant,
+            texture,
+            mip_levels: 0..texture.mip_level_count(),
+            array_layers: 0..texture.depth_or_array_layers(),
+            buffer,
+            blocks_offset: 0,
+            error_buffer: None,
+        }
+    }
+
+    /// Sets the range of mip levels to compress.
+    pub fn with_mip_levels(mut self, mip_levels: Range<u32>) -> Self {
+        self.mip_levels = mip_levels;
+        self
+    }
+
+    /// Sets the range of array layers to compress.
+    pub fn with_array_layers(mut self, array_layers: Range<u32>) -> Self {
+        self.array_layers = array_layers;
+        self
+    }
+
+    /// Sets the offset in bytes of the first subresource in the destination buffer.
+    pub fn with_blocks_offset(mut self, blocks_offset: u32) -> Self {
+        self.blocks_offset = blocks_offset;
+        self
+    }
+
+    /// Sets the storage buffer that receives the error of every compressed block.
+    pub fn with_error_buffer(mut self, error_buffer: &'a Buffer) -> Self {
+        self.error_buffer = Some(error_buffer);
+        self
+    }
+}
+
+/// The location of a single compressed subresource inside the destination buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompressedSubresource {
+    /// The mip level of the subresource.
+    pub mip_level: u32,
+    /// The array layer of the subresource.
+    pub array_layer: u32,
+    /// The width of the subresource in pixels.
+    pub width: u32,
+    /// The height of the subresource in pixels.
+    pub height: u32,
+    /// The offset of the compressed blocks in bytes.
+    pub offset: usize,
+    /// The size of the compressed blocks in bytes.
+    pub size: usize,
 }
 
 /// Compresses texture data with a block compression algorithm using WGPU compute shader.
@@ -47,15 +158,20 @@ pub struct GpuBlockCompressor {
     scratch_buffer: Vec<u8>,
     task: Vec<Task>,
     uniforms_buffer: Buffer,
+    #[cfg(feature = "bc15")]
+    bc123_settings_buffer: Buffer,
     #[cfg(feature = "bc6h")]
     bc6h_settings_buffer: Buffer,
     #[cfg(feature = "bc7")]
     bc7_settings_buffer: Buffer,
-    bind_group_layouts: HashMap<CompressionVariant, BindGroupLayout>,
-    pipelines: HashMap<CompressionVariant, ComputePipeline>,
+    no_errors_buffer: Buffer,
+    bind_group_layouts: HashMap<(CompressionVariant, TextureViewDimension), BindGroupLayout>,
+    pipelines: HashMap<(CompressionVariant, TextureViewDimension), ComputePipeline>,
     device: Device,
     queue: Queue,
     uniforms_aligned_size: usize,
+    #[cfg(feature = "bc15")]
+    bc123_aligned_size: usize,
     #[cfg(feature = "bc6h")]
     bc6h_aligned_size: usize,
     #[cfg(feature = "bc7")]
@@ -74,6 +190,13 @@ impl GpuBlockCompressor {
         let size = size_of::<Uniforms>();
         let uniforms_aligned_size = size.div_ceil(alignment) * alignment;
 
+        #[cfg(feature = "bc15")]
+        let bc123_aligned_size = {
+            let alignment = limits.min_storage_buffer_offset_alignment as usize;
+            let size = size_of::<BC123Settings>();
+            size.div_ceil(alignment) * alignment
+        };
+
         #[cfg(feature = "bc6h")]
         let bc6h_aligned_size = {
             let alignment = limits.min_storage_buffer_offset_alignment as usize;
@@ -90,24 +213,19 @@ impl GpuBlockCompressor {
 
         #[cfg(feature = "bc15")]
         let shader_module_bc1_to_5 =
-            device.create_shader_module(include_wgsl!("shader/bc1_to_5.wgsl"));
+            Self::create_shader_module(&device, CompressionVariant::BC4, TextureViewDimension::D2);
         #[cfg(feature = "bc6h")]
-        let shader_module_bc6h = device.create_shader_module(include_wgsl!("shader/bc6h.wgsl"));
-        // Keep wgpu's SPIR-V loop bounding enabled for BC7. Disabling it
-        // can produce incorrect BC7 alpha/basic output on RADV/Navi.
+        let shader_module_bc6h = Self::create_shader_module(
+            &device,
+            CompressionVariant::BC6H(BC6HSettings::basic()),
+            TextureViewDimension::D2,
+        );
         #[cfg(feature = "bc7")]
-        let shader_module_bc7 = {
-            unsafe {
-                let checks = ShaderRuntimeChecks {
-                    bounds_checks: true,
-                    force_loop_bounding: true,
-                    ray_query_initialization_tracking: false,
-                    task_shader_dispatch_tracking: false,
-                    mesh_shader_primitive_indices_clamp: false,
-                };
-                device.create_shader_module_trusted(include_wgsl!("shader/bc7.wgsl"), checks)
-            }
-        };
+        let shader_module_bc7 = Self::create_shader_module(
+            &device,
+            CompressionVariant::BC7(BC7Settings::alpha_basic()),
+            TextureViewDimension::D2,
+        );
 
         let uniforms_buffer = device.create_buffer(&BufferDescriptor {
             label: Some("uniforms"),
@@ -116,6 +234,14 @@ impl GpuBlockCompressor {
             mapped_at_creation: false,
         });
 
+        #[cfg(feature = "bc15")]
+        let bc123_settings_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("bc123 settings"),
+            size: (bc123_aligned_size * 16) as _,
+            usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
         #[cfg(feature = "bc6h")]
         let bc6h_settings_buffer = device.create_buffer(&BufferDescriptor {
             label: Some("bc6h settings"),
@@ -132,6 +258,14 @@ impl GpuBlockCompressor {
             mapped_at_creation: false,
         });
 
+        // Bound in place of the error buffer by tasks that don't write the block errors.
+        let no_errors_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("no errors"),
+            size: size_of::<f32>() as _,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
         let mut bind_group_layouts = HashMap::new();
         let mut pipelines = HashMap::new();
 
@@ -141,7 +275,8 @@ impl GpuBlockCompressor {
             &shader_module_bc1_to_5,
             &mut bind_group_layouts,
             &mut pipelines,
-            CompressionVariant::BC1,
+            CompressionVariant::BC1(BC123Settings::new()),
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc15")]
         Self::create_pipeline(
@@ -149,7 +284,8 @@ impl GpuBlockCompressor {
             &shader_module_bc1_to_5,
             &mut bind_group_layouts,
             &mut pipelines,
-            CompressionVariant::BC2,
+            CompressionVariant::BC2(BC123Settings::new()),
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc15")]
         Self::create_pipeline(
@@ -157,7 +293,8 @@ impl GpuBlockCompressor {
             &shader_module_bc1_to_5,
             &mut bind_group_layouts,
             &mut pipelines,
-            CompressionVariant::BC3,
+            CompressionVariant::BC3(BC123Settings::new()),
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc15")]
         Self::create_pipeline(
@@ -166,6 +303,7 @@ impl GpuBlockCompressor {
             &mut bind_group_layouts,
             &mut pipelines,
             CompressionVariant::BC4,
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc15")]
         Self::create_pipeline(
@@ -174,6 +312,25 @@ impl GpuBlockCompressor {
             &mut bind_group_layouts,
             &mut pipelines,
             CompressionVariant::BC5,
+            TextureViewDimension::D2,
+        );
+        #[cfg(feature = "bc15")]
+        Self::create_pipeline(
+            &device,
+            &shader_module_bc1_to_5,
+            &mut bind_group_layouts,
+            &mut pipelines,
+            CompressionVariant::BC4Snorm,
+            TextureViewDimension::D2,
+        );
+        #[cfg(feature = "bc15")]
+        Self::create_pipeline(
+            &device,
+            &shader_module_bc1_to_5,
+            &mut bind_group_layouts,
+            &mut pipelines,
+            CompressionVariant::BC5Snorm,
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc6h")]
         Self::create_pipeline(
@@ -182,6 +339,7 @@ impl GpuBlockCompressor {
             &mut bind_group_layouts,
             &mut pipelines,
             CompressionVariant::BC6H(BC6HSettings::basic()),
+            TextureViewDimension::D2,
         );
         #[cfg(feature = "bc7")]
         Self::create_pipeline(
@@ -190,21 +348,27 @@ impl GpuBlockCompressor {
             &mut bind_group_layouts,
             &mut pipelines,
             CompressionVariant::BC7(BC7Settings::alpha_basic()),
+            TextureViewDimension::D2,
         );
 
         Self {
             scratch_buffer: Vec::default(),
             task: Vec::default(),
             uniforms_buffer,
+            #[cfg(feature = "bc15")]
+            bc123_settings_buffer,
             #[cfg(feature = "bc6h")]
             bc6h_settings_buffer,
             #[cfg(feature = "bc7")]
             bc7_settings_buffer,
+            no_errors_buffer,
             bind_group_layouts,
             pipelines,
             device,
             queue,
             uniforms_aligned_size,
+            #[cfg(feature = "bc15")]
+            bc123_aligned_size,
             #[cfg(feature = "bc6h")]
             bc6h_aligned_size,
             #[cfg(feature = "bc7")]
@@ -212,13 +376,73 @@ impl GpuBlockCompressor {
         }
     }
 
+    /// Creates the shader module of `variant` that reads from texture views with the given
+    /// `view_dimension`. The shader sources are completed with the binding of the source texture.
+    fn create_shader_module(
+        device: &Device,
+        variant: CompressionVariant,
+        view_dimension: TextureViewDimension,
+    ) -> ShaderModule {
+        macro_rules! shader_module_descriptor {
+            ($name:literal) => {
+                ShaderModuleDescriptor {
+                    label: Some(concat!($name, " shader")),
+                    source: ShaderSource::Wgsl(Cow::Borrowed(match view_dimension {
+                        TextureViewDimension::D2Array => concat!(
+                            include_str!(concat!("shader/", $name, ".wgsl")),
+                            include_str!("shader/texture_2d_array.wgsl"),
+                        ),
+                        _ => concat!(
+                            include_str!(concat!("shader/", $name, ".wgsl")),
+                            include_str!("shader/texture_2d.wgsl"),
+                        ),
+                    })),
+                }
+            };
+        }
+
+        match variant {
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC1(..)
+            | CompressionVariant::BC2(..)
+            | CompressionVariant::BC3(..)
+            | CompressionVariant::BC4
+            | CompressionVariant::BC5
+            | CompressionVariant::BC4Snorm
+            | CompressionVariant::BC5Snorm => {
+                device.create_shader_module(shader_module_descriptor!("bc1_to_5"))
+            }
+            #[cfg(feature = "bc6h")]
+            CompressionVariant::BC6H(..) => {
+                device.create_shader_module(shader_module_descriptor!("bc6h"))
+            }
+            // Keep wgpu's SPIR-V loop bounding enabled for BC7. Disabling it
+            // can produce incorrect BC7 alpha/basic output on RADV/Navi.
+            #[cfg(feature = "bc7")]
+            CompressionVariant::BC7(..) => unsafe {
+                let checks = ShaderRuntimeChecks {
+                    bounds_checks: true,
+                    force_loop_bounding: true,
+                    ray_query_initialization_tracking: false,
+                    task_shader_dispatch_tracking: false,
+                    mesh_shader_primitive_indices_clamp: false,
+                };
+                device.create_shader_module_trusted(shader_module_descriptor!("bc7"), checks)
+            },
+        }
+    }
+
     #[allow(unused_mut)]
     fn create_pipeline(
         device: &Device,
         shader_module: &ShaderModule,
-        bind_group_layouts: &mut HashMap<CompressionVariant, BindGroupLayout>,
-        pipelines: &mut HashMap<CompressionVariant, ComputePipeline>,
+        bind_group_layouts: &mut HashMap<
+            (CompressionVariant, TextureViewDimension),
+            BindGroupLayout,
+        >,
+        pipelines: &mut HashMap<(CompressionVariant, TextureViewDimension), ComputePipeline>,
         variant: CompressionVariant,
+        view_dimension: TextureViewDimension,
     ) {
         let mut layout_entries = vec![
             BindGroupLayoutEntry {
@@ -226,7 +450,7 @@ impl GpuBlockCompressor {
                 visibility: ShaderStages::COMPUTE,
                 ty: BindingType::Texture {
                     sample_type: TextureSampleType::Float { filterable: true },
-                    view_dimension: TextureViewDimension::D2,
+                    view_dimension,
                     multisampled: false,
                 },
                 count: None,
@@ -251,9 +475,34 @@ impl GpuBlockCompressor {
                 },
                 count: None,
             },
+            BindGroupLayoutEntry {
+                binding: 4,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
         ];
 
         match variant {
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC1(..)
+            | CompressionVariant::BC2(..)
+            | CompressionVariant::BC3(..) => {
+                layout_entries.push(BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: true,
+                        min_binding_size: NonZeroU64::new(size_of::<BC123Settings>() as _),
+                    },
+                    count: None,
+                });
+            }
             #[cfg(feature = "bc6h")]
             CompressionVariant::BC6H(..) => {
                 layout_entries.push(BindGroupLayoutEntry {
@@ -284,7 +533,10 @@ impl GpuBlockCompressor {
             _ => {}
         }
 
-        let name = variant.name();
+        let name = match view_dimension {
+            TextureViewDimension::D2Array => format!("{} array", variant.name()),
+            _ => variant.name().to_string(),
+        };
 
         let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some(&format!("{name} bind group layout")),
@@ -306,8 +558,8 @@ impl GpuBlockCompressor {
             cache: None,
         });
 
-        bind_group_layouts.insert(variant, bind_group_layout);
-        pipelines.insert(variant, pipeline);
+        bind_group_layouts.insert((variant, view_dimension), bind_group_layout);
+        pipelines.insert((variant, view_dimension), pipeline);
     }
 
     /// Adds a texture compression task to the queue.
@@ -325,9 +577,18 @@ impl GpuBlockCompressor {
     ///
     /// BC1, 2, 3, 4, 5 and 7 expect to work on an `unorm` format. `Rgba8Unorm` should be correct
     /// for 99.9% of cases.
+    /// BC1 with an alpha threshold also needs the alpha channel to find the transparent pixels.
+    ///
+    /// The signed BC4 and BC5 variants expect to work on a `snorm` format like `Rgba8Snorm`.
     ///
     /// BC6H needs an `unorm` or `float` format. `Rgba16Float` is optimal for HDR textures.
-    /// Colors should be in linear space and not in sRGBA space.
+    /// Colors should be in linear space and not in sRGBA space. Signed BC6H needs a `float`
+    /// format to provide negative values.
+    ///
+    /// # Texture Dimensions
+    /// The `width`, `height` and `texture_y_offset` don't need to be a multiple of 4. Blocks on
+    /// the right and bottom edge are padded by clamping the texel coordinates to the last pixel
+    /// column and row. The padding pixels are not considered when fitting the block endpoints.
     ///
     /// # Buffer Requirements
     /// The destination buffer must have sufficient capacity to store the compressed blocks at the
@@ -352,9 +613,7 @@ impl GpuBlockCompressor {
     /// * `blocks_offset` - Optional offset in bytes into the destination buffer
     ///
     /// # Panics
-    /// - If `width` or `height` or `texture_y_offset`, if set, is not a multiple of 4
-    /// - If the destination `buffer` is not a storage buffer
-    /// - If the destination `buffer` is too small to hold the compressed blocks at the specified offset
+    /// - If [`GpuBlockCompressor::try_add_compression_task()`] returns an error
     #[allow(clippy::too_many_arguments)]
     pub fn add_compression_task(
         &mut self,
@@ -366,41 +625,878 @@ impl GpuBlockCompressor {
         texture_y_offset: Option<u32>,
         blocks_offset: Option<u32>,
     ) {
-        assert_eq!(height % 4, 0);
-        assert_eq!(width % 4, 0);
+        if let Err(error) = self.try_add_compression_task(
+            variant,
+            texture_view,
+            width,
+            height,
+            buffer,
+            texture_y_offset,
+            blocks_offset,
+        ) {
+            panic!("{error}");
+        }
+    }
 
-        if let Some(texture_y_offset) = texture_y_offset {
-            assert_eq!(texture_y_offset % 4, 0);
+    /// Adds a texture compression task to the queue.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::add_compression_task()`], which
+    /// returns an error instead of panicking on invalid input.
+    ///
+    /// # Errors
+    /// - [`Error::NotStorageBuffer`] if the destination `buffer` is not a storage buffer
+    /// - [`Error::InvalidDimensions`] if the compressed area lies outside the source texture
+    /// - [`Error::BufferTooSmall`] if the destination `buffer` is too small to hold the
+    ///   compressed blocks at the specified offset
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_add_compression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture_view: &TextureView,
+        width: u32,
+        height: u32,
+        buffer: &Buffer,
+        texture_y_offset: Option<u32>,
+        blocks_offset: Option<u32>,
+    ) -> Result<(), Error> {
+        self.push_compression_task(
+            variant,
+            texture_view,
+            width,
+            height,
+            buffer,
+            texture_y_offset,
+            blocks_offset,
+            None,
+        )
+    }
+
+    /// Validates and queues a compression task, which writes the error of every block into the
+    /// optional error buffer at the given offset in bytes.
+    #[allow(clippy::too_many_arguments)]
+    fn push_compression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture_view: &TextureView,
+        width: u32,
+        height: u32,
+        buffer: &Buffer,
+        texture_y_offset: Option<u32>,
+        blocks_offset: Option<u32>,
+        errors: Option<(&Buffer, u32)>,
+    ) -> Result<(), Error> {
+        if !buffer.usage().contains(BufferUsages::STORAGE) {
+            return Err(Error::NotStorageBuffer);
         }
 
-        assert!(
-            buffer.usage().contains(BufferUsages::STORAGE),
-            "buffer needs to be a storage buffer"
-        );
+        let texture = texture_view.texture();
+        let texture_height = u64::from(texture_y_offset.unwrap_or(0)) + u64::from(height);
+        if width > texture.width() || texture_height > u64::from(texture.height()) {
+            return Err(Error::InvalidDimensions { width, height });
+        }
 
         let required_size = variant.blocks_byte_size(width, height);
         let total_size = blocks_offset.unwrap_or(0) as usize + required_size;
 
-        assert!(
-            buffer.size() as usize >= total_size,
-            "buffer size ({}) is too small to hold compressed blocks at offset {}. Required size: {}",
-            buffer.size(),
-            blocks_offset.unwrap_or(0),
-            total_size
-        );
+        if (buffer.size() as usize) < total_size {
+            return Err(Error::BufferTooSmall {
+                required: total_size,
+                actual: buffer.size() as usize,
+            });
+        }
 
         self.task.push(Task {
             variant,
             width,
             height,
             uniform_offset: 0,
-            #[cfg(any(feature = "bc6h", feature = "bc7"))]
+            #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
             setting_offset: 0,
             texture_y_offset: texture_y_offset.unwrap_or(0),
             buffer_offset: blocks_offset.unwrap_or(0),
+            base_array_layer: 0,
+            layer_count: 1,
+            view_dimension: TextureViewDimension::D2,
             texture_view: texture_view.clone(),
             buffer: buffer.clone(),
+            error_buffer: errors.map(|(error_buffer, _)| error_buffer.clone()),
+            errors_offset: errors.map_or(0, |(_, errors_offset)| errors_offset),
+        });
+
+        Ok(())
+    }
+
+    /// Adds a compression task for a range of array layers to the queue.
+    ///
+    /// The layers are compressed back to back into `buffer`, starting with the first layer of
+    /// `layers` at `blocks_offset`. Every layer takes
+    /// [`CompressionVariant::blocks_byte_size()`] bytes.
+    ///
+    /// # Texture Requirements
+    /// The layers are read through a [`TextureViewDimension::D2Array`] view of the base mip level
+    /// of all array layers of `texture`, which is created internally. Cubemaps are compressed
+    /// the same way, since compute shaders can't load texels from `Cube` views. The six faces are
+    /// stored in the order `+X`, `-X`, `+Y`, `-Y`, `+Z` and `-Z`.
+    ///
+    /// The same texture format requirements as for
+    /// [`GpuBlockCompressor::add_compression_task()`] apply. Textures with a sRGB format are
+    /// viewed with the non-sRGB format, which then needs to be listed in the `view_formats` of the
+    /// texture.
+    ///
+    /// The pipelines for array views are created when the first array task of a variant is added.
+    ///
+    /// # Arguments
+    /// * `variant` - The block compression format to use
+    /// * `texture` - Source texture array or cubemap to compress
+    /// * `width` - Width of the compressed area in pixels
+    /// * `height` - Height of the compressed area in pixels
+    /// * `layers` - Range of the array layers of the texture to compress
+    /// * `buffer` - Destination storage buffer for the compressed data
+    /// * `blocks_offset` - Optional offset in bytes into the destination buffer
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_add_array_compression_task()`] returns an error
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_array_compression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture: &Texture,
+        width: u32,
+        height: u32,
+        layers: Range<u32>,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) {
+        if let Err(error) = self.try_add_array_compression_task(
+            variant,
+            texture,
+            width,
+            height,
+            layers,
+            buffer,
+            blocks_offset,
+        ) {
+            panic!("{error}");
+        }
+    }
+
+    /// Adds a compression task for a range of array layers to the queue.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::add_array_compression_task()`],
+    /// which returns an error instead of panicking on invalid input.
+    ///
+    /// # Errors
+    /// - [`Error::NotStorageBuffer`] if the destination `buffer` is not a storage buffer
+    /// - [`Error::InvalidDimensions`] if the compressed area lies outside the source texture
+    /// - [`Error::InvalidLayerRange`] if `layers` is empty or lies outside the source texture
+    /// - [`Error::BufferTooSmall`] if the destination `buffer` is too small to hold the
+    ///   compressed blocks of all layers at the specified offset
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_add_array_compression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture: &Texture,
+        width: u32,
+        height: u32,
+        layers: Range<u32>,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) -> Result<(), Error> {
+        if !buffer.usage().contains(BufferUsages::STORAGE) {
+            return Err(Error::NotStorageBuffer);
+        }
+
+        if width > texture.width() || height > texture.height() {
+            return Err(Error::InvalidDimensions { width, height });
+        }
+
+        let layer_count = texture.depth_or_array_layers();
+        if layers.is_empty() || layers.end > layer_count {
+            return Err(Error::InvalidLayerRange {
+                start: layers.start,
+                end: layers.end,
+                layer_count,
+            });
+        }
+
+        let required_size = variant.blocks_byte_size(width, height) * layers.len();
+        let total_size = blocks_offset.unwrap_or(0) as usize + required_size;
+
+        if (buffer.size() as usize) < total_size {
+            return Err(Error::BufferTooSmall {
+                required: total_size,
+                actual: buffer.size() as usize,
+            });
+        }
+
+        let view_dimension = TextureViewDimension::D2Array;
+        if !self.pipelines.contains_key(&(variant, view_dimension)) {
+            let shader_module = Self::create_shader_module(&self.device, variant, view_dimension);
+            Self::create_pipeline(
+                &self.device,
+                &shader_module,
+                &mut self.bind_group_layouts,
+                &mut self.pipelines,
+                variant,
+                view_dimension,
+            );
+        }
+
+        // The view covers all layers, so that the layers of the task index the layers of the
+        // texture.
+        let texture_view = texture.create_view(&TextureViewDescriptor {
+            label: Some("array layers view"),
+            format: Some(texture.format().remove_srgb_suffix()),
+            dimension: Some(view_dimension),
+            base_mip_level: 0,
+            mip_level_count: Some(1),
+            base_array_layer: 0,
+            array_layer_count: Some(layer_count),
+            ..Default::default()
+        });
+
+        self.task.push(Task {
+            variant,
+            width,
+            height,
+            uniform_offset: 0,
+            #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+            setting_offset: 0,
+            texture_y_offset: 0,
+            buffer_offset: blocks_offset.unwrap_or(0),
+            base_array_layer: layers.start,
+            layer_count: layers.len() as u32,
+            view_dimension,
+            texture_view,
+            buffer: buffer.clone(),
+            error_buffer: None,
+            errors_offset: 0,
         });
+
+        Ok(())
+    }
+
+    /// Adds compression tasks for every mip level of a texture to the queue.
+    ///
+    /// The mip levels are compressed back to back into `buffer`, starting with the base level at
+    /// `blocks_offset`. The returned levels describe the location of every compressed mip level
+    /// inside `buffer`. A texture with a full mip chain can be created from the output of
+    /// [`crate::mipmap::generate_mipmaps_rgba8()`] with
+    /// [`GpuBlockCompressor::create_mipmapped_texture_rgba8()`].
+    ///
+    /// The same texture format requirements as for
+    /// [`GpuBlockCompressor::add_compression_task()`] apply. Textures with a sRGB format are
+    /// viewed with the non-sRGB format, which then needs to be listed in the `view_formats` of the
+    /// texture. Only the first array layer of array textures and cubemaps is compressed, use
+    /// [`GpuBlockCompressor::add_texture_compression_task()`] to compress all layers.
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_add_mipmap_compression_tasks()`] returns an error
+    pub fn add_mipmap_compression_tasks(
+        &mut self,
+        variant: CompressionVariant,
+        texture: &Texture,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) -> Vec<CompressedMipLevel> {
+        match self.try_add_mipmap_compression_tasks(variant, texture, buffer, blocks_offset) {
+            Ok(levels) => levels,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Adds compression tasks for every mip level of a texture to the queue.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::add_mipmap_compression_tasks()`].
+    /// No task is added if an error is returned.
+    ///
+    /// # Errors
+    /// - [`Error::NotStorageBuffer`] if the destination `buffer` is not a storage buffer
+    /// - [`Error::BufferTooSmall`] if the destination `buffer` is too small to hold all compressed
+    ///   mip levels at the specified offset
+    /// - [`Error::OffsetOverflow`] if a mip level starts beyond 4 GiB in the destination `buffer`
+    pub fn try_add_mipmap_compression_tasks(
+        &mut self,
+        variant: CompressionVariant,
+        texture: &Texture,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) -> Result<Vec<CompressedMipLevel>, Error> {
+        if !buffer.usage().contains(BufferUsages::STORAGE) {
+            return Err(Error::NotStorageBuffer);
+        }
+
+        let blocks_offset = blocks_offset.unwrap_or(0) as usize;
+        let mut levels = compressed_mip_levels(
+            variant,
+            texture.width(),
+            texture.height(),
+            texture.mip_level_count(),
+        );
+        for level in levels.iter_mut() {
+            level.offset += blocks_offset;
+        }
+
+        let total_size = levels.last().map_or(0, |level| level.offset + level.size);
+        if (buffer.size() as usize) < total_size {
+            return Err(Error::BufferTooSmall {
+                required: total_size,
+                actual: buffer.size() as usize,
+            });
+        }
+
+        let offsets = levels
+            .iter()
+            .map(|level| {
+                u32::try_from(level.offset).map_err(|_| Error::OffsetOverflow {
+                    offset: level.offset,
+                })
+            })
+            .collect::<Result<Vec<u32>, Error>>()?;
+
+        for (mip_level, (level, offset)) in levels.iter().zip(offsets).enumerate() {
+            let texture_view = texture.create_view(&TextureViewDescriptor {
+                label: Some("mip level view"),
+                format: Some(texture.format().remove_srgb_suffix()),
+                dimension: Some(TextureViewDimension::D2),
+                base_mip_level: mip_level as u32,
+                mip_level_count: Some(1),
+                base_array_layer: 0,
+                array_layer_count: Some(1),
+                ..Default::default()
+            });
+
+            self.try_add_compression_task(
+                variant,
+                &texture_view,
+                level.width,
+                level.height,
+                buffer,
+                None,
+                Some(offset),
+            )?;
+        }
+
+        Ok(levels)
+    }
+
+    /// Adds compression tasks for a range of mip levels and array layers of a texture to the
+    /// queue.
+    ///
+    /// Every subresource is compressed with its own dispatch. The returned subresources describe
+    /// the location of every compressed subresource inside the destination buffer, in the order
+    /// in which they are stored (see [`TextureCompressionTask`]).
+    ///
+    /// The same texture format requirements as for
+    /// [`GpuBlockCompressor::add_compression_task()`] apply. Textures with a sRGB format are
+    /// viewed with the non-sRGB format, which then needs to be listed in the `view_formats` of the
+    /// texture.
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_add_texture_compression_task()`] returns an error
+    pub fn add_texture_compression_task(
+        &mut self,
+        task: &TextureCompressionTask,
+    ) -> Vec<CompressedSubresource> {
+        match self.try_add_texture_compression_task(task) {
+            Ok(subresources) => subresources,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Adds compression tasks for a range of mip levels and array layers of a texture to the
+    /// queue.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::add_texture_compression_task()`].
+    /// No task is added if an error is returned.
+    ///
+    /// # Errors
+    /// - [`Error::NotStorageBuffer`] if the destination buffer or the error buffer is not a
+    ///   storage buffer
+    /// - [`Error::InvalidMipRange`] if the mip level range is empty or lies outside the texture
+    /// - [`Error::InvalidLayerRange`] if the array layer range is empty or lies outside the
+    ///   texture
+    /// - [`Error::BufferTooSmall`] if the destination buffer is too small to hold all compressed
+    ///   subresources at the specified offset, or the error buffer is too small to hold the
+    ///   error of every block
+    /// - [`Error::OffsetOverflow`] if a subresource starts beyond 4 GiB in the destination buffer
+    pub fn try_add_texture_compression_task(
+        &mut self,
+        task: &TextureCompressionTask,
+    ) -> Result<Vec<CompressedSubresource>, Error> {
+        let TextureCompressionTask {
+            variant,
+            texture,
+            buffer,
+            blocks_offset,
+            error_buffer,
+            ..
+        } = *task;
+
+        if !buffer.usage().contains(BufferUsages::STORAGE) {
+            return Err(Error::NotStorageBuffer);
+        }
+
+        let subresources = subresource_layout(task)?;
+
+        // Every block stores its error as one f32, starting at the beginning of the error buffer.
+        let block_size = variant.block_byte_size() as usize;
+        let errors_offset = |offset: usize| (offset - blocks_offset as usize) / block_size * 4;
+
+        if let Some(error_buffer) = error_buffer {
+            if !error_buffer.usage().contains(BufferUsages::STORAGE) {
+                return Err(Error::NotStorageBuffer);
+            }
+
+            let required = subresources
+                .last()
+                .map_or(0, |last| errors_offset(last.offset + last.size));
+            if (error_buffer.size() as usize) < required {
+                return Err(Error::BufferTooSmall {
+                    required,
+                    actual: error_buffer.size() as usize,
+                });
+            }
+        }
+
+        for subresource in subresources.iter() {
+            let texture_view = texture.create_view(&TextureViewDescriptor {
+                label: Some("subresource view"),
+                format: Some(texture.format().remove_srgb_suffix()),
+                dimension: Some(TextureViewDimension::D2),
+                base_mip_level: subresource.mip_level,
+                mip_level_count: Some(1),
+                base_array_layer: subresource.array_layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            });
+
+            // `subresource_layout()` checked that all offsets fit into 32 bits, the error offsets
+            // are smaller.
+            self.push_compression_task(
+                variant,
+                &texture_view,
+                subresource.width,
+                subresource.height,
+                buffer,
+                None,
+                Some(subresource.offset as u32),
+                error_buffer
+                    .map(|error_buffer| (error_buffer, errors_offset(subresource.offset) as u32)),
+            )?;
+        }
+
+        Ok(subresources)
+    }
+
+    /// Returns the BC texture format of `variant`.
+    ///
+    /// `srgb` selects the sRGB format for BC1, BC2, BC3 and BC7 and is ignored for all other
+    /// variants. Signed BC6H settings return [`TextureFormat::Bc6hRgbFloat`].
+    pub fn texture_format(variant: CompressionVariant, srgb: bool) -> TextureFormat {
+        match (variant, srgb) {
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC1(..), true) => TextureFormat::Bc1RgbaUnormSrgb,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC1(..), _) => TextureFormat::Bc1RgbaUnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC2(..), true) => TextureFormat::Bc2RgbaUnormSrgb,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC2(..), _) => TextureFormat::Bc2RgbaUnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC3(..), true) => TextureFormat::Bc3RgbaUnormSrgb,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC3(..), _) => TextureFormat::Bc3RgbaUnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC4, _) => TextureFormat::Bc4RUnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC5, _) => TextureFormat::Bc5RgUnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC4Snorm, _) => TextureFormat::Bc4RSnorm,
+            #[cfg(feature = "bc15")]
+            (CompressionVariant::BC5Snorm, _) => TextureFormat::Bc5RgSnorm,
+            #[cfg(feature = "bc6h")]
+            (CompressionVariant::BC6H(settings), _) if settings.is_signed() => {
+                TextureFormat::Bc6hRgbFloat
+            }
+            #[cfg(feature = "bc6h")]
+            (CompressionVariant::BC6H(..), _) => TextureFormat::Bc6hRgbUfloat,
+            #[cfg(feature = "bc7")]
+            (CompressionVariant::BC7(..), true) => TextureFormat::Bc7RgbaUnormSrgb,
+            #[cfg(feature = "bc7")]
+            (CompressionVariant::BC7(..), _) => TextureFormat::Bc7RgbaUnorm,
+        }
+    }
+
+    /// Creates a BC texture for the compressed output of `task` and records the copies of all
+    /// compressed subresources into `encoder`.
+    ///
+    /// The texture has the dimensions, mip levels and array layers of the source texture of
+    /// `task` and the format returned by [`GpuBlockCompressor::texture_format()`]. Subresources
+    /// outside the mip level and array layer ranges of `task` are left uninitialized. The copies
+    /// need to be recorded after the compute pass that compressed `task`. The device needs the
+    /// [`wgpu::Features::TEXTURE_COMPRESSION_BC`] feature.
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_create_compressed_texture()`] returns an error
+    pub fn create_compressed_texture(
+        &self,
+        encoder: &mut CommandEncoder,
+        task: &TextureCompressionTask,
+        srgb: bool,
+    ) -> Texture {
+        match self.try_create_compressed_texture(encoder, task, srgb) {
+            Ok(texture) => texture,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Creates a BC texture for the compressed output of `task` and records the copies of all
+    /// compressed subresources into `encoder`.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::create_compressed_texture()`].
+    ///
+    /// # Errors
+    /// - [`Error::InvalidDimensions`] if the source texture dimensions are not a multiple of 4,
+    ///   which BC textures require
+    /// - All errors of [`GpuBlockCompressor::try_copy_to_compressed_texture()`]
+    pub fn try_create_compressed_texture(
+        &self,
+        encoder: &mut CommandEncoder,
+        task: &TextureCompressionTask,
+        srgb: bool,
+    ) -> Result<Texture, Error> {
+        let source = task.texture;
+        if source.width() % 4 != 0 || source.height() % 4 != 0 {
+            return Err(Error::InvalidDimensions {
+                width: source.width(),
+                height: source.height(),
+            });
+        }
+
+        subresource_layout(task)?;
+
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("compressed texture"),
+            size: Extent3d {
+                width: source.width(),
+                height: source.height(),
+                depth_or_array_layers: source.depth_or_array_layers(),
+            },
+            mip_level_count: source.mip_level_count(),
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: Self::texture_format(task.variant, srgb),
+            usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        self.try_copy_to_compressed_texture(encoder, task, &texture)?;
+
+        Ok(texture)
+    }
+
+    /// Records the copies of all compressed subresources of `task` into the same mip levels and
+    /// array layers of the BC `texture`.
+    ///
+    /// `texture` needs the dimensions of the source texture of `task`, the format returned by
+    /// [`GpuBlockCompressor::texture_format()`] and the `COPY_DST` usage. The destination buffer
+    /// of `task` needs the `COPY_SRC` usage. Rows of blocks that don't meet
+    /// [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] are padded in an internal staging buffer. The copies
+    /// need to be recorded after the compute pass that compressed `task`.
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_copy_to_compressed_texture()`] returns an error
+    pub fn copy_to_compressed_texture(
+        &self,
+        encoder: &mut CommandEncoder,
+        task: &TextureCompressionTask,
+        texture: &Texture,
+    ) {
+        if let Err(error) = self.try_copy_to_compressed_texture(encoder, task, texture) {
+            panic!("{error}");
+        }
+    }
+
+    /// Records the copies of all compressed subresources of `task` into the same mip levels and
+    /// array layers of the BC `texture`.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::copy_to_compressed_texture()`].
+    /// No copy is recorded if an error is returned.
+    ///
+    /// # Errors
+    /// - [`Error::IncompatibleTextureFormat`] if the format of `texture` doesn't match the
+    ///   compression variant of `task`
+    /// - [`Error::InvalidDimensions`] if `texture` doesn't have the dimensions of the source
+    ///   texture
+    /// - [`Error::InvalidMipRange`] if the mip level range is empty or lies outside the source
+    ///   texture or `texture`
+    /// - [`Error::InvalidLayerRange`] if the array layer range is empty or lies outside the
+    ///   source texture or `texture`
+    /// - [`Error::BufferTooSmall`] if the destination buffer of `task` is too small to hold all
+    ///   compressed subresources
+    pub fn try_copy_to_compressed_texture(
+        &self,
+        encoder: &mut CommandEncoder,
+        task: &TextureCompressionTask,
+        texture: &Texture,
+    ) -> Result<(), Error> {
+        let variant = task.variant;
+        if texture.format().remove_srgb_suffix() != Self::texture_format(variant, false) {
+            return Err(Error::IncompatibleTextureFormat);
+        }
+
+        if texture.width() != task.texture.width() || texture.height() != task.texture.height() {
+            return Err(Error::InvalidDimensions {
+                width: texture.width(),
+                height: texture.height(),
+            });
+        }
+
+        let subresources = subresource_layout(task)?;
+
+        if task.mip_levels.end > texture.mip_level_count() {
+            return Err(Error::InvalidMipRange {
+                start: task.mip_levels.start,
+                end: task.mip_levels.end,
+                mip_level_count: texture.mip_level_count(),
+            });
+        }
+        if task.array_layers.end > texture.depth_or_array_layers() {
+            return Err(Error::InvalidLayerRange {
+                start: task.array_layers.start,
+                end: task.array_layers.end,
+                layer_count: texture.depth_or_array_layers(),
+            });
+        }
+
+        let block_size = u64::from(variant.block_byte_size());
+        let needs_padding = |subresource: &CompressedSubresource| {
+            variant.bytes_per_row(subresource.width) % COPY_BYTES_PER_ROW_ALIGNMENT != 0
+                || subresource.offset as u64 % block_size != 0
+        };
+        let padded_size = |subresource: &CompressedSubresource| {
+            let padded_bytes_per_row = variant
+                .bytes_per_row(subresource.width)
+                .next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+            u64::from(padded_bytes_per_row) * u64::from(subresource.height.div_ceil(4))
+        };
+
+        let staging_size: u64 = subresources
+            .iter()
+            .filter(|subresource| needs_padding(subresource))
+            .map(padded_size)
+            .sum();
+        let staging_buffer = (staging_size > 0).then(|| {
+            self.device.create_buffer(&BufferDescriptor {
+                label: Some("padded blocks buffer"),
+                size: staging_size,
+                usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        let mut staging_offset = 0;
+        for subresource in subresources.iter() {
+            let bytes_per_row = variant.bytes_per_row(subresource.width);
+            let block_rows = subresource.height.div_ceil(4);
+
+            let destination = TexelCopyTextureInfo {
+                texture,
+                mip_level: subresource.mip_level,
+                origin: Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: subresource.array_layer,
+                },
+                aspect: TextureAspect::All,
+            };
+            // Copies of BC textures cover the physical size of a mip level, which is rounded up
+            // to whole blocks.
+            let copy_size = Extent3d {
+                width: subresource.width.div_ceil(4) * 4,
+                height: block_rows * 4,
+                depth_or_array_layers: 1,
+            };
+
+            if !needs_padding(subresource) {
+                encoder.copy_buffer_to_texture(
+                    TexelCopyBufferInfo {
+                        buffer: task.buffer,
+                        layout: TexelCopyBufferLayout {
+                            offset: subresource.offset as u64,
+                            bytes_per_row: Some(bytes_per_row),
+                            rows_per_image: Some(block_rows),
+                        },
+                    },
+                    destination,
+                    copy_size,
+                );
+                continue;
+            }
+
+            let staging_buffer = staging_buffer
+                .as_ref()
+                .expect("staging buffer holds all padded subresources");
+            let padded_bytes_per_row = bytes_per_row.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+
+            for row in 0..u64::from(block_rows) {
+                encoder.copy_buffer_to_buffer(
+                    task.buffer,
+                    subresource.offset as u64 + row * u64::from(bytes_per_row),
+                    staging_buffer,
+                    staging_offset + row * u64::from(padded_bytes_per_row),
+                    u64::from(bytes_per_row),
+                );
+            }
+
+            encoder.copy_buffer_to_texture(
+                TexelCopyBufferInfo {
+                    buffer: staging_buffer,
+                    layout: TexelCopyBufferLayout {
+                        offset: staging_offset,
+                        bytes_per_row: Some(padded_bytes_per_row),
+                        rows_per_image: Some(block_rows),
+                    },
+                },
+                destination,
+                copy_size,
+            );
+
+            staging_offset += padded_size(subresource);
+        }
+
+        Ok(())
+    }
+
+    /// Records a copy of compressed blocks into a new staging buffer and returns a readback that
+    /// resolves to the copied bytes.
+    ///
+    /// `offset` and `size` describe the blocks of a single task inside `buffer`, for example the
+    /// `blocks_offset` and [`CompressionVariant::blocks_byte_size()`] of a compression task or a
+    /// [`CompressedSubresource`]. Both need to be a multiple of 4, which is always the case for
+    /// block data. `buffer` needs the `COPY_SRC` usage and the copy needs to be recorded after
+    /// the compute pass that compressed the blocks.
+    ///
+    /// Nothing waits for the GPU. The staging buffer is mapped when `encoder` is submitted, see
+    /// [`BlocksReadback`] for how to receive the data.
+    ///
+    /// # Panics
+    /// - If [`GpuBlockCompressor::try_read_back_blocks()`] returns an error
+    pub fn read_back_blocks(
+        &self,
+        encoder: &mut CommandEncoder,
+        buffer: &Buffer,
+        offset: usize,
+        size: usize,
+    ) -> BlocksReadback {
+        match self.try_read_back_blocks(encoder, buffer, offset, size) {
+            Ok(readback) => readback,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Records a copy of compressed blocks into a new staging buffer and returns a readback that
+    /// resolves to the copied bytes.
+    ///
+    /// This is the fallible version of [`GpuBlockCompressor::read_back_blocks()`].
+    ///
+    /// # Errors
+    /// - [`Error::UnalignedCopy`] if `offset` or `size` is not a multiple of 4
+    /// - [`Error::BufferTooSmall`] if `buffer` doesn't contain `size` bytes at `offset`
+    pub fn try_read_back_blocks(
+        &self,
+        encoder: &mut CommandEncoder,
+        buffer: &Buffer,
+        offset: usize,
+        size: usize,
+    ) -> Result<BlocksReadback, Error> {
+        if offset % 4 != 0 || size % 4 != 0 {
+            return Err(Error::UnalignedCopy { offset, size });
+        }
+
+        let total_size = offset.checked_add(size).ok_or(Error::BufferTooSmall {
+            required: usize::MAX,
+            actual: buffer.size() as usize,
+        })?;
+        if (buffer.size() as usize) < total_size {
+            return Err(Error::BufferTooSmall {
+                required: total_size,
+                actual: buffer.size() as usize,
+            });
+        }
+
+        if size == 0 {
+            return Ok(BlocksReadback::empty());
+        }
+
+        let staging_buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("readback staging buffer"),
+            size: size as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_buffer_to_buffer(buffer, offset as u64, &staging_buffer, 0, size as u64);
+
+        Ok(BlocksReadback::new(encoder, staging_buffer))
+    }
+
+    /// Creates a `Rgba8Unorm` texture with a mip chain from the given RGBA8 mip levels.
+    ///
+    /// The mip levels are usually generated with [`crate::mipmap::generate_mipmaps_rgba8()`].
+    ///
+    /// # Panics
+    /// - If `mip_levels` is empty
+    /// - If the data of a mip level doesn't match its dimensions
+    pub fn create_mipmapped_texture_rgba8(&self, mip_levels: &[MipLevel<u8>]) -> Texture {
+        self.create_mipmapped_texture(mip_levels, TextureFormat::Rgba8Unorm)
+    }
+
+    /// Creates a `Rgba16Float` texture with a mip chain from the given RGBA16F mip levels.
+    ///
+    /// The mip levels are usually generated with [`crate::mipmap::generate_mipmaps_rgba16f()`].
+    ///
+    /// # Panics
+    /// - If `mip_levels` is empty
+    /// - If the data of a mip level doesn't match its dimensions
+    #[cfg(feature = "bc6h")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+    pub fn create_mipmapped_texture_rgba16f(&self, mip_levels: &[MipLevel<half::f16>]) -> Texture {
+        self.create_mipmapped_texture(mip_levels, TextureFormat::Rgba16Float)
+    }
+
+    fn create_mipmapped_texture<T: Pod>(
+        &self,
+        mip_levels: &[MipLevel<T>],
+        format: TextureFormat,
+    ) -> Texture {
+        let base = mip_levels.first().expect("mip_levels must not be empty");
+
+        let data: Vec<u8> = mip_levels
+            .iter()
+            .flat_map(|level| cast_slice::<T, u8>(&level.data).iter().copied())
+            .collect();
+
+        self.device.create_texture_with_data(
+            &self.queue,
+            &TextureDescriptor {
+                label: Some("mipmapped texture"),
+                size: Extent3d {
+                    width: base.width,
+                    height: base.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: mip_levels.len() as u32,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format,
+                usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            TextureDataOrder::LayerMajor,
+            &data,
+        )
     }
 
     fn update_buffer_sizes(&mut self) {
@@ -414,6 +1510,32 @@ impl GpuBlockCompressor {
             });
         }
 
+        #[cfg(feature = "bc15")]
+        {
+            let bc123_setting_count = self
+                .task
+                .iter()
+                .filter(|task| {
+                    matches!(
+                        task.variant,
+                        CompressionVariant::BC1(..)
+                            | CompressionVariant::BC2(..)
+                            | CompressionVariant::BC3(..)
+                    )
+                })
+                .count();
+
+            let total_bc123_size = self.bc123_aligned_size * bc123_setting_count;
+            if total_bc123_size > self.bc123_settings_buffer.size() as usize {
+                self.bc123_settings_buffer = self.device.create_buffer(&BufferDescriptor {
+                    label: Some("bc123 settings buffer"),
+                    size: total_bc123_size as u64,
+                    usage: BufferUsages::COPY_DST | BufferUsages::STORAGE,
+                    mapped_at_creation: false,
+                });
+            }
+        }
+
         #[cfg(feature = "bc6h")]
         {
             let bc6_setting_count = self
@@ -464,6 +1586,9 @@ impl GpuBlockCompressor {
                 height: task.height,
                 texture_y_offset: task.texture_y_offset,
                 blocks_offset: task.buffer_offset / 4,
+                base_array_layer: task.base_array_layer,
+                errors_offset: task.errors_offset / 4,
+                write_errors: u32::from(task.error_buffer.is_some()),
             };
 
             self.scratch_buffer
@@ -481,6 +1606,38 @@ impl GpuBlockCompressor {
             }
         }
 
+        #[cfg(feature = "bc15")]
+        {
+            self.scratch_buffer.clear();
+            for (index, (settings, task)) in self
+                .task
+                .iter_mut()
+                .filter_map(|task| match task.variant {
+                    CompressionVariant::BC1(settings)
+                    | CompressionVariant::BC2(settings)
+                    | CompressionVariant::BC3(settings) => Some((settings, task)),
+                    _ => None,
+                })
+                .enumerate()
+            {
+                let offset = index * self.bc123_aligned_size;
+                task.setting_offset = offset as u32;
+                self.scratch_buffer
+                    .resize(offset + self.bc123_aligned_size, 0);
+                self.scratch_buffer[offset..offset + size_of::<BC123Settings>()]
+                    .copy_from_slice(cast_slice(&[settings]));
+            }
+            if !self.scratch_buffer.is_empty() {
+                if let Some(mut data) = self.queue.write_buffer_with(
+                    &self.bc123_settings_buffer,
+                    0,
+                    NonZeroU64::new(self.scratch_buffer.len() as u64).unwrap(),
+                ) {
+                    data.copy_from_slice(&self.scratch_buffer);
+                }
+            }
+        }
+
         #[cfg(feature = "bc6h")]
         {
             self.scratch_buffer.clear();
@@ -567,12 +1724,22 @@ impl GpuBlockCompressor {
         for (task, bind_group) in self.task.drain(..).zip(bind_groups.drain(..)) {
             let pipeline = self
                 .pipelines
-                .get(&task.variant)
+                .get(&(task.variant, task.view_dimension))
                 .expect("can't find pipeline for variant");
 
             pass.set_pipeline(pipeline);
 
             match task.variant {
+                #[cfg(feature = "bc15")]
+                CompressionVariant::BC1(..)
+                | CompressionVariant::BC2(..)
+                | CompressionVariant::BC3(..) => {
+                    pass.set_bind_group(
+                        0,
+                        &bind_group,
+                        &[task.uniform_offset, task.setting_offset],
+                    );
+                }
                 #[cfg(feature = "bc6h")]
                 CompressionVariant::BC6H(..) => {
                     pass.set_bind_group(
@@ -602,23 +1769,60 @@ impl GpuBlockCompressor {
             let workgroup_width = block_width.div_ceil(8);
             let workgroup_height = block_height.div_ceil(8);
 
-            pass.dispatch_workgroups(workgroup_width, workgroup_height, 1);
+            pass.dispatch_workgroups(workgroup_width, workgroup_height, task.layer_count);
         }
     }
 
     fn create_bind_group(&self, task: &Task) -> BindGroup {
         let bind_group_layout = self
             .bind_group_layouts
-            .get(&task.variant)
+            .get(&(task.variant, task.view_dimension))
             .expect("Can't find bind group layout for variant");
+        let error_buffer = task.error_buffer.as_ref().unwrap_or(&self.no_errors_buffer);
 
         match task.variant {
             #[cfg(feature = "bc15")]
-            CompressionVariant::BC1
-            | CompressionVariant::BC2
-            | CompressionVariant::BC3
-            | CompressionVariant::BC4
-            | CompressionVariant::BC5 => self.device.create_bind_group(&BindGroupDescriptor {
+            CompressionVariant::BC1(..)
+            | CompressionVariant::BC2(..)
+            | CompressionVariant::BC3(..) => self.device.create_bind_group(&BindGroupDescriptor {
+                label: Some("bind group"),
+                layout: bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&task.texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: task.buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::Buffer(BufferBinding {
+                            buffer: &self.uniforms_buffer,
+                            offset: 0,
+                            size: Some(NonZeroU64::new(self.uniforms_aligned_size as u64).unwrap()),
+                        }),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::Buffer(BufferBinding {
+                            buffer: &self.bc123_settings_buffer,
+                            offset: 0,
+                            size: Some(NonZeroU64::new(self.bc123_aligned_size as u64).unwrap()),
+                        }),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: error_buffer.as_entire_binding(),
+                    },
+                ],
+            }),
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC4
+            | CompressionVariant::BC5
+            | CompressionVariant::BC4Snorm
+            | CompressionVariant::BC5Snorm => self.device.create_bind_group(&BindGroupDescriptor {
                 label: Some("bind group"),
                 layout: bind_group_layout,
                 entries: &[
@@ -638,6 +1842,10 @@ impl GpuBlockCompressor {
                             size: Some(NonZeroU64::new(self.uniforms_aligned_size as u64).unwrap()),
                         }),
                     },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: error_buffer.as_entire_binding(),
+                    },
                 ],
             }),
             #[cfg(feature = "bc6h")]
@@ -669,6 +1877,10 @@ impl GpuBlockCompressor {
                             size: Some(NonZeroU64::new(self.bc6h_aligned_size as u64).unwrap()),
                         }),
                     },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: error_buffer.as_entire_binding(),
+                    },
                 ],
             }),
             #[cfg(feature = "bc7")]
@@ -700,8 +1912,73 @@ impl GpuBlockCompressor {
                             size: Some(NonZeroU64::new(self.bc7_aligned_size as u64).unwrap()),
                         }),
                     },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: error_buffer.as_entire_binding(),
+                    },
                 ],
             }),
         }
     }
 }
+
+/// Returns the location of every subresource of `task` inside its destination buffer.
+fn subresource_layout(task: &TextureCompressionTask) -> Result<Vec<CompressedSubresource>, Error> {
+    let TextureCompressionTask {
+        variant,
+        texture,
+        buffer,
+        ..
+    } = *task;
+
+    let mip_levels = task.mip_levels.clone();
+    let mip_level_count = texture.mip_level_count();
+    if mip_levels.is_empty() || mip_levels.end > mip_level_count {
+        return Err(Error::InvalidMipRange {
+            start: mip_levels.start,
+            end: mip_levels.end,
+            mip_level_count,
+        });
+    }
+
+    let array_layers = task.array_layers.clone();
+    let layer_count = texture.depth_or_array_layers();
+    if array_layers.is_empty() || array_layers.end > layer_count {
+        return Err(Error::InvalidLayerRange {
+            start: array_layers.start,
+            end: array_layers.end,
+            layer_count,
+        });
+    }
+
+    let levels = compressed_mip_levels(variant, texture.width(), texture.height(), mip_levels.end);
+
+    let mut offset = task.blocks_offset as usize;
+    let mut subresources = Vec::with_capacity(mip_levels.len() * array_layers.len());
+    for array_layer in array_layers {
+        for mip_level in mip_levels.clone() {
+            let level = levels[mip_level as usize];
+            if u32::try_from(offset).is_err() {
+                return Err(Error::OffsetOverflow { offset });
+            }
+            subresources.push(CompressedSubresource {
+                mip_level,
+                array_layer,
+                width: level.width,
+                height: level.height,
+                offset,
+                size: level.size,
+            });
+            offset += level.size;
+        }
+    }
+
+    if (buffer.size() as usize) < offset {
+        return Err(Error::BufferTooSmall {
+            required: offset,
+            actual: buffer.size() as usize,
+        });
+    }
+
+    Ok(subresources)
+}
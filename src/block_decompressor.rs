@@ -0,0 +1,498 @@
+use std::{borrow::Cow, collections::HashMap, num::NonZeroU64};
+
+use bytemuck::{cast_slice, Pod, Zeroable};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBinding, BufferBindingType,
+    BufferDescriptor, BufferUsages, ComputePass, ComputePipeline, ComputePipelineDescriptor,
+    Device, PipelineCompilationOptions, PipelineLayoutDescriptor, Queue, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, StorageTextureAccess, TextureFormat,
+    TextureUsages, TextureView, TextureViewDimension,
+};
+
+#[cfg(feature = "bc15")]
+use crate::BC123Settings;
+#[cfg(feature = "bc6h")]
+use crate::BC6HSettings;
+#[cfg(feature = "bc7")]
+use crate::BC7Settings;
+use crate::{CompressionVariant, Error};
+
+#[derive(Copy, Clone, Zeroable, Pod)]
+#[repr(C)]
+struct Uniforms {
+    /// The width of the image data.
+    width: u32,
+    /// The height of the image data.
+    height: u32,
+    /// Start of the blocks data in u32 elements.
+    blocks_offset: u32,
+    /// Non-zero if the blocks contain signed BC6H data.
+    is_signed: u32,
+}
+
+struct Task {
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    uniform_offset: u32,
+    buffer_offset: u32,
+    texture_view: TextureView,
+    buffer: Buffer,
+}
+
+/// Decompresses block data into textures using WGPU compute shader.
+///
+/// The shaders are ports of the CPU decoder of the [`crate::decode`] module. The decompressed
+/// textures are bit-exact to the output of [`crate::decode::decompress_blocks_as_rgba8()`] for
+/// BC1, BC2, BC3, BC4, BC5 and BC7, of [`crate::decode::decompress_blocks_as_rgba8_snorm()`] for
+/// signed BC4 and BC5 and of [`crate::decode::decompress_blocks_as_rgba16f()`] for BC6H.
+pub struct GpuBlockDecompressor {
+    scratch_buffer: Vec<u8>,
+    task: Vec<Task>,
+    uniforms_buffer: Buffer,
+    bind_group_layouts: HashMap<CompressionVariant, BindGroupLayout>,
+    pipelines: HashMap<CompressionVariant, ComputePipeline>,
+    device: Device,
+    queue: Queue,
+    uniforms_aligned_size: usize,
+}
+
+impl GpuBlockDecompressor {
+    /// Creates a new block decompressor instance.
+    ///
+    /// [`wgpu::Device`] and [`wgpu::Queue`] are internally reference counted and can cheaply and
+    /// safely be cloned.
+    pub fn new(device: Device, queue: Queue) -> Self {
+        let limits = device.limits();
+
+        let alignment = limits.min_uniform_buffer_offset_alignment as usize;
+        let size = size_of::<Uniforms>();
+        let uniforms_aligned_size = size.div_ceil(alignment) * alignment;
+
+        let uniforms_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("decompression uniforms"),
+            size: (uniforms_aligned_size * 16) as _,
+            usage: BufferUsages::COPY_DST | BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+
+        let mut bind_group_layouts = HashMap::new();
+        let mut pipelines = HashMap::new();
+
+        #[cfg(feature = "bc15")]
+        {
+            let shader_module_unorm = Self::create_shader_module(&device, CompressionVariant::BC4);
+            let shader_module_snorm =
+                Self::create_shader_module(&device, CompressionVariant::BC4Snorm);
+
+            for variant in [
+                CompressionVariant::BC1(BC123Settings::new()),
+                CompressionVariant::BC2(BC123Settings::new()),
+                CompressionVariant::BC3(BC123Settings::new()),
+                CompressionVariant::BC4,
+                CompressionVariant::BC5,
+            ] {
+                Self::create_pipeline(
+                    &device,
+                    &shader_module_unorm,
+                    &mut bind_group_layouts,
+                    &mut pipelines,
+                    variant,
+                );
+            }
+
+            for variant in [CompressionVariant::BC4Snorm, CompressionVariant::BC5Snorm] {
+                Self::create_pipeline(
+                    &device,
+                    &shader_module_snorm,
+                    &mut bind_group_layouts,
+                    &mut pipelines,
+                    variant,
+                );
+            }
+        }
+
+        #[cfg(feature = "bc6h")]
+        {
+            let variant = CompressionVariant::BC6H(BC6HSettings::basic());
+            Self::create_pipeline(
+                &device,
+                &Self::create_shader_module(&device, variant),
+                &mut bind_group_layouts,
+                &mut pipelines,
+                variant,
+            );
+        }
+
+        #[cfg(feature = "bc7")]
+        {
+            let variant = CompressionVariant::BC7(BC7Settings::alpha_basic());
+            Self::create_pipeline(
+                &device,
+                &Self::create_shader_module(&device, variant),
+                &mut bind_group_layouts,
+                &mut pipelines,
+                variant,
+            );
+        }
+
+        Self {
+            scratch_buffer: Vec::default(),
+            task: Vec::default(),
+            uniforms_buffer,
+            bind_group_layouts,
+            pipelines,
+            device,
+            queue,
+            uniforms_aligned_size,
+        }
+    }
+
+    /// Creates the decode shader module of `variant`. The shader sources are completed with the
+    /// binding of the destination texture.
+    fn create_shader_module(device: &Device, variant: CompressionVariant) -> ShaderModule {
+        macro_rules! shader_module_descriptor {
+            ($name:literal, $output:literal) => {
+                ShaderModuleDescriptor {
+                    label: Some(concat!($name, " ", $output, " shader")),
+                    source: ShaderSource::Wgsl(Cow::Borrowed(concat!(
+                        include_str!(concat!("shader/", $name, ".wgsl")),
+                        include_str!(concat!("shader/", $output, ".wgsl")),
+                    ))),
+                }
+            };
+        }
+
+        match variant {
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC4Snorm | CompressionVariant::BC5Snorm => device
+                .create_shader_module(shader_module_descriptor!(
+                    "decode_bc1_to_5",
+                    "output_rgba8snorm"
+                )),
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC1(..)
+            | CompressionVariant::BC2(..)
+            | CompressionVariant::BC3(..)
+            | CompressionVariant::BC4
+            | CompressionVariant::BC5 => device.create_shader_module(shader_module_descriptor!(
+                "decode_bc1_to_5",
+                "output_rgba8unorm"
+            )),
+            #[cfg(feature = "bc6h")]
+            CompressionVariant::BC6H(..) => device.create_shader_module(shader_module_descriptor!(
+                "decode_bc6h",
+                "output_rgba16float"
+            )),
+            #[cfg(feature = "bc7")]
+            CompressionVariant::BC7(..) => device
+                .create_shader_module(shader_module_descriptor!("decode_bc7", "output_rgba8unorm")),
+        }
+    }
+
+    fn create_pipeline(
+        device: &Device,
+        shader_module: &ShaderModule,
+        bind_group_layouts: &mut HashMap<CompressionVariant, BindGroupLayout>,
+        pipelines: &mut HashMap<CompressionVariant, ComputePipeline>,
+        variant: CompressionVariant,
+    ) {
+        let name = variant.name();
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some(&format!("{name} decompression bind group layout")),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::StorageTexture {
+                        access: StorageTextureAccess::WriteOnly,
+                        format: Self::output_format(variant),
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some(&format!("{name} block decompression pipeline layout")),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some(&format!("{name} block decompression pipeline")),
+            layout: Some(&pipeline_layout),
+            module: shader_module,
+            entry_point: Some(&format!("decode_{name}")),
+            compilation_options: PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        bind_group_layouts.insert(variant, bind_group_layout);
+        pipelines.insert(variant, pipeline);
+    }
+
+    /// Returns the format of the storage texture that the blocks of `variant` are decompressed
+    /// into.
+    ///
+    /// - BC1, BC2, BC3, BC4, BC5 and BC7 are decompressed into [`TextureFormat::Rgba8Unorm`]
+    /// - Signed BC4 and BC5 are decompressed into [`TextureFormat::Rgba8Snorm`]
+    /// - BC6H is decompressed into [`TextureFormat::Rgba16Float`]
+    pub fn output_format(variant: CompressionVariant) -> TextureFormat {
+        match variant {
+            #[cfg(feature = "bc15")]
+            CompressionVariant::BC4Snorm | CompressionVariant::BC5Snorm => {
+                TextureFormat::Rgba8Snorm
+            }
+            #[cfg(feature = "bc6h")]
+            CompressionVariant::BC6H(..) => TextureFormat::Rgba16Float,
+            #[allow(unreachable_patterns)]
+            _ => TextureFormat::Rgba8Unorm,
+        }
+    }
+
+    /// Adds a block decompression task to the queue.
+    ///
+    /// # Texture View Requirements
+    /// The destination texture needs the format returned by
+    /// [`GpuBlockDecompressor::output_format()`] and the [`TextureUsages::STORAGE_BINDING`]
+    /// usage. The texels are written like the CPU decoder writes them: BC4 and BC5 fill the
+    /// unused channels with 0, and BC6H writes an alpha value of 0.
+    ///
+    /// # Texture Dimensions
+    /// The `width` and `height` don't need to be a multiple of 4. Texels of the right and bottom
+    /// edge blocks that lie outside the image are not written.
+    ///
+    /// # Buffer Requirements
+    /// The source buffer needs to be a storage buffer and contain
+    /// [`CompressionVariant::blocks_byte_size()`] bytes of blocks at the specified offset.
+    ///
+    /// # Arguments
+    /// * `variant` - The block compression format of the blocks
+    /// * `texture_view` - View into the destination texture
+    /// * `width` - Width of the image in pixels
+    /// * `height` - Height of the image in pixels
+    /// * `buffer` - Source storage buffer with the compressed data
+    /// * `blocks_offset` - Optional offset in bytes into the source buffer
+    ///
+    /// # Panics
+    /// - If [`GpuBlockDecompressor::try_add_decompression_task()`] returns an error
+    pub fn add_decompression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture_view: &TextureView,
+        width: u32,
+        height: u32,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) {
+        if let Err(error) = self.try_add_decompression_task(
+            variant,
+            texture_view,
+            width,
+            height,
+            buffer,
+            blocks_offset,
+        ) {
+            panic!("{error}");
+        }
+    }
+
+    /// Adds a block decompression task to the queue.
+    ///
+    /// This is the fallible version of [`GpuBlockDecompressor::add_decompression_task()`], which
+    /// returns an error instead of panicking on invalid input.
+    ///
+    /// # Errors
+    /// - [`Error::NotStorageBuffer`] if the source `buffer` is not a storage buffer
+    /// - [`Error::NotStorageTexture`] if the destination texture is not a storage texture
+    /// - [`Error::IncompatibleTextureFormat`] if the destination texture doesn't have the format
+    ///   returned by [`GpuBlockDecompressor::output_format()`]
+    /// - [`Error::InvalidDimensions`] if the decompressed area lies outside the destination
+    ///   texture
+    /// - [`Error::BufferTooSmall`] if the source `buffer` is too small to hold the compressed
+    ///   blocks at the specified offset
+    pub fn try_add_decompression_task(
+        &mut self,
+        variant: CompressionVariant,
+        texture_view: &TextureView,
+        width: u32,
+        height: u32,
+        buffer: &Buffer,
+        blocks_offset: Option<u32>,
+    ) -> Result<(), Error> {
+        if !buffer.usage().contains(BufferUsages::STORAGE) {
+            return Err(Error::NotStorageBuffer);
+        }
+
+        let texture = texture_view.texture();
+        if !texture.usage().contains(TextureUsages::STORAGE_BINDING) {
+            return Err(Error::NotStorageTexture);
+        }
+
+        if texture.format() != Self::output_format(variant) {
+            return Err(Error::IncompatibleTextureFormat);
+        }
+
+        if width > texture.width() || height > texture.height() {
+            return Err(Error::InvalidDimensions { width, height });
+        }
+
+        let required_size = variant.blocks_byte_size(width, height);
+        let total_size = blocks_offset.unwrap_or(0) as usize + required_size;
+
+        if (buffer.size() as usize) < total_size {
+            return Err(Error::BufferTooSmall {
+                required: total_size,
+                actual: buffer.size() as usize,
+            });
+        }
+
+        self.task.push(Task {
+            variant,
+            width,
+            height,
+            uniform_offset: 0,
+            buffer_offset: blocks_offset.unwrap_or(0),
+            texture_view: texture_view.clone(),
+            buffer: buffer.clone(),
+        });
+
+        Ok(())
+    }
+
+    fn update_buffer_sizes(&mut self) {
+        let total_uniforms_size = self.uniforms_aligned_size * self.task.len();
+        if total_uniforms_size > self.uniforms_buffer.size() as usize {
+            self.uniforms_buffer = self.device.create_buffer(&BufferDescriptor {
+                label: Some("decompression uniforms buffer"),
+                size: total_uniforms_size as u64,
+                usage: BufferUsages::COPY_DST | BufferUsages::UNIFORM,
+                mapped_at_creation: false,
+            });
+        }
+    }
+
+    fn upload(&mut self) {
+        self.scratch_buffer.clear();
+        for (index, task) in self.task.iter_mut().enumerate() {
+            let offset = index * self.uniforms_aligned_size;
+            task.uniform_offset = offset as u32;
+
+            #[allow(unused_mut)]
+            let mut is_signed = false;
+            #[cfg(feature = "bc6h")]
+            #[allow(irrefutable_let_patterns)]
+            if let CompressionVariant::BC6H(settings) = task.variant {
+                is_signed = settings.is_signed();
+            }
+
+            let uniforms = Uniforms {
+                width: task.width,
+                height: task.height,
+                blocks_offset: task.buffer_offset / 4,
+                is_signed: u32::from(is_signed),
+            };
+
+            self.scratch_buffer
+                .resize(offset + self.uniforms_aligned_size, 0);
+            self.scratch_buffer[offset..offset + size_of::<Uniforms>()]
+                .copy_from_slice(cast_slice(&[uniforms]));
+        }
+        if !self.scratch_buffer.is_empty() {
+            if let Some(mut data) = self.queue.write_buffer_with(
+                &self.uniforms_buffer,
+                0,
+                NonZeroU64::new(self.scratch_buffer.len() as u64).unwrap(),
+            ) {
+                data.copy_from_slice(&self.scratch_buffer);
+            }
+        }
+    }
+
+    /// Will upload all dispatch data and then dispatches all decompression tasks to the GPU.
+    ///
+    /// # Arguments
+    /// * `pass` - The compute pass to record commands into
+    pub fn decompress(&mut self, pass: &mut ComputePass) {
+        self.update_buffer_sizes();
+        self.upload();
+
+        let mut bind_groups: Vec<BindGroup> = self
+            .task
+            .iter()
+            .map(|task| self.create_bind_group(task))
+            .collect();
+
+        for (task, bind_group) in self.task.drain(..).zip(bind_groups.drain(..)) {
+            let pipeline = self
+                .pipelines
+                .get(&task.variant)
+                .expect("can't find pipeline for variant");
+
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[task.uniform_offset]);
+
+            let block_width = task.width.div_ceil(4);
+            let block_height = task.height.div_ceil(4);
+
+            let workgroup_width = block_width.div_ceil(8);
+            let workgroup_height = block_height.div_ceil(8);
+
+            pass.dispatch_workgroups(workgroup_width, workgroup_height, 1);
+        }
+    }
+
+    fn create_bind_group(&self, task: &Task) -> BindGroup {
+        let bind_group_layout = self
+            .bind_group_layouts
+            .get(&task.variant)
+            .expect("Can't find bind group layout for variant");
+
+        self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("decompression bind group"),
+            layout: bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: task.buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&task.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: &self.uniforms_buffer,
+                        offset: 0,
+                        size: Some(NonZeroU64::new(self.uniforms_aligned_size as u64).unwrap()),
+                    }),
+                },
+            ],
+        })
+    }
+}
@@ -0,0 +1,406 @@
+//! Reading and writing of DDS container files.
+//!
+//! [`write_dds()`] serializes compressed blocks of textures, texture arrays and cubemaps with
+//! their mip chains into a DDS file. [`read_dds()`] parses a DDS file back into a [`DdsImage`],
+//! whose [`DdsImage::level_data()`] can be passed straight to the functions of the
+//! [`decode`](crate::decode) module.
+//!
+//! The block data of a DDS file is stored layer by layer. Every layer contains all of its mip
+//! levels back to back, starting with the base level. Cubemaps store their six faces as
+//! consecutive layers in the order +X, -X, +Y, -Y, +Z, -Z.
+
+use ddsfile::{AlphaMode, Caps2, D3D10ResourceDimension, Dds, DxgiFormat, MiscFlag, NewDxgiParams};
+
+use crate::{
+    mipmap::{compressed_mip_levels, mip_level_count, CompressedMipLevel},
+    CompressionVariant, Error,
+};
+
+/// Describes the layout of the compressed blocks stored in a DDS file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DdsDescriptor {
+    /// The compression variant of the blocks.
+    pub variant: CompressionVariant,
+    /// The width of the base level in pixels.
+    pub width: u32,
+    /// The height of the base level in pixels.
+    pub height: u32,
+    /// The number of mip levels of every layer.
+    pub mip_level_count: u32,
+    /// The number of array layers. For cubemaps this is the number of cubes.
+    pub array_layer_count: u32,
+    /// Whether every array layer is a cube with six faces.
+    pub cubemap: bool,
+    /// Whether the color channels are sRGB encoded. Only BC1, BC2, BC3 and BC7 have sRGB formats,
+    /// all other variants ignore this setting.
+    pub srgb: bool,
+}
+
+impl DdsDescriptor {
+    /// Creates a descriptor of a single 2D texture without mip levels.
+    pub const fn new(variant: CompressionVariant, width: u32, height: u32) -> Self {
+        Self {
+            variant,
+            width,
+            height,
+            mip_level_count: 1,
+            array_layer_count: 1,
+            cubemap: false,
+            srgb: false,
+        }
+    }
+
+    /// Sets the number of mip levels of every layer.
+    pub const fn with_mip_level_count(mut self, mip_level_count: u32) -> Self {
+        self.mip_level_count = mip_level_count;
+        self
+    }
+
+    /// Sets the number of array layers. For cubemaps this is the number of cubes.
+    pub const fn with_array_layer_count(mut self, array_layer_count: u32) -> Self {
+        self.array_layer_count = array_layer_count;
+        self
+    }
+
+    /// Sets whether every array layer is a cube with six faces.
+    pub const fn with_cubemap(mut self, cubemap: bool) -> Self {
+        self.cubemap = cubemap;
+        self
+    }
+
+    /// Sets whether the color channels are sRGB encoded.
+    pub const fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Returns the number of stored layers. Every face of a cubemap counts as a layer.
+    pub const fn layer_count(&self) -> u32 {
+        if self.cubemap {
+            self.array_layer_count * 6
+        } else {
+            self.array_layer_count
+        }
+    }
+
+    /// Returns the location of every mip level inside a single layer.
+    pub fn mip_levels(&self) -> Vec<CompressedMipLevel> {
+        compressed_mip_levels(self.variant, self.width, self.height, self.mip_level_count)
+    }
+
+    /// Returns the byte size of a single layer including all of its mip levels.
+    pub fn layer_byte_size(&self) -> usize {
+        self.mip_levels()
+            .last()
+            .map_or(0, |level| level.offset + level.size)
+    }
+
+    /// Returns the byte size of the blocks of all layers and mip levels.
+    pub fn blocks_byte_size(&self) -> usize {
+        self.layer_byte_size() * self.layer_count() as usize
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.width == 0
+            || self.height == 0
+            || self.mip_level_count == 0
+            || self.mip_level_count > mip_level_count(self.width, self.height)
+            || self.array_layer_count == 0
+        {
+            return Err(Error::InvalidDimensions {
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The compressed blocks of a DDS file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DdsImage {
+    /// The layout of the blocks.
+    pub descriptor: DdsDescriptor,
+    /// The compressed blocks of all layers and mip levels.
+    pub blocks: Vec<u8>,
+}
+
+impl DdsImage {
+    /// Returns the compressed blocks of the given mip `level` of the given `layer`. Every face of a
+    /// cubemap counts as a layer.
+    ///
+    /// # Panics
+    /// - If `layer` or `level` is out of range
+    pub fn level_data(&self, layer: u32, level: u32) -> &[u8] {
+        assert!(
+            layer < self.descriptor.layer_count(),
+            "layer {layer} is out of range"
+        );
+
+        let levels = self.descriptor.mip_levels();
+        let level = levels[level as usize];
+        let offset = self.descriptor.layer_byte_size() * layer as usize + level.offset;
+        &self.blocks[offset..offset + level.size]
+    }
+}
+
+/// Returns the DXGI format that stores blocks of the given `variant`.
+///
+/// `srgb` selects the sRGB format for BC1, BC2, BC3 and BC7 and is ignored for all other variants.
+pub fn dxgi_format(variant: CompressionVariant, srgb: bool) -> DxgiFormat {
+    match (variant, srgb) {
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC1(..), true) => DxgiFormat::BC1_UNorm_sRGB,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC1(..), _) => DxgiFormat::BC1_UNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC2(..), true) => DxgiFormat::BC2_UNorm_sRGB,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC2(..), _) => DxgiFormat::BC2_UNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC3(..), true) => DxgiFormat::BC3_UNorm_sRGB,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC3(..), _) => DxgiFormat::BC3_UNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC4, _) => DxgiFormat::BC4_UNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC5, _) => DxgiFormat::BC5_UNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC4Snorm, _) => DxgiFormat::BC4_SNorm,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC5Snorm, _) => DxgiFormat::BC5_SNorm,
+        #[cfg(feature = "bc6h")]
+        (CompressionVariant::BC6H(settings), _) if settings.is_signed() => DxgiFormat::BC6H_SF16,
+        #[cfg(feature = "bc6h")]
+        (CompressionVariant::BC6H(..), _) => DxgiFormat::BC6H_UF16,
+        #[cfg(feature = "bc7")]
+        (CompressionVariant::BC7(..), true) => DxgiFormat::BC7_UNorm_sRGB,
+        #[cfg(feature = "bc7")]
+        (CompressionVariant::BC7(..), _) => DxgiFormat::BC7_UNorm,
+    }
+}
+
+/// Returns the compression variant and whether the format is sRGB encoded for the given DXGI
+/// format, or `None` if the format is not supported by this crate.
+///
+/// The settings of BC1 to BC3, BC6H and BC7 variants are set to their default and basic presets,
+/// since they are only needed for compression. Signed BC6H formats return settings with signed
+/// encoding enabled.
+pub fn variant_from_dxgi_format(format: DxgiFormat) -> Option<(CompressionVariant, bool)> {
+    let variant = match format {
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC1_Typeless | DxgiFormat::BC1_UNorm => {
+            (CompressionVariant::BC1(crate::BC123Settings::new()), false)
+        }
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC1_UNorm_sRGB => (CompressionVariant::BC1(crate::BC123Settings::new()), true),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC2_Typeless | DxgiFormat::BC2_UNorm => {
+            (CompressionVariant::BC2(crate::BC123Settings::new()), false)
+        }
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC2_UNorm_sRGB => (CompressionVariant::BC2(crate::BC123Settings::new()), true),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC3_Typeless | DxgiFormat::BC3_UNorm => {
+            (CompressionVariant::BC3(crate::BC123Settings::new()), false)
+        }
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC3_UNorm_sRGB => (CompressionVariant::BC3(crate::BC123Settings::new()), true),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC4_Typeless | DxgiFormat::BC4_UNorm => (CompressionVariant::BC4, false),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC5_Typeless | DxgiFormat::BC5_UNorm => (CompressionVariant::BC5, false),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC4_SNorm => (CompressionVariant::BC4Snorm, false),
+        #[cfg(feature = "bc15")]
+        DxgiFormat::BC5_SNorm => (CompressionVariant::BC5Snorm, false),
+        #[cfg(feature = "bc6h")]
+        DxgiFormat::BC6H_Typeless | DxgiFormat::BC6H_UF16 => (
+            CompressionVariant::BC6H(crate::BC6HSettings::basic()),
+            false,
+        ),
+        #[cfg(feature = "bc6h")]
+        DxgiFormat::BC6H_SF16 => (
+            CompressionVariant::BC6H(crate::BC6HSettings::basic().with_signed(true)),
+            false,
+        ),
+        #[cfg(feature = "bc7")]
+        DxgiFormat::BC7_Typeless | DxgiFormat::BC7_UNorm => (
+            CompressionVariant::BC7(crate::BC7Settings::alpha_basic()),
+            false,
+        ),
+        #[cfg(feature = "bc7")]
+        DxgiFormat::BC7_UNorm_sRGB => (
+            CompressionVariant::BC7(crate::BC7Settings::alpha_basic()),
+            true,
+        ),
+        _ => return None,
+    };
+
+    Some(variant)
+}
+
+/// Serializes compressed blocks into a DDS file.
+///
+/// `blocks` contains the blocks of all layers and mip levels in the layout described in the
+/// [module documentation](self).
+///
+/// # Panics
+/// - If [`try_write_dds()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{
+///     dds::{read_dds, write_dds, DdsDescriptor},
+///     mipmap::{compress_mipmaps_rgba8, MipmapSettings},
+///     BC123Settings, CompressionVariant,
+/// };
+///
+/// let variant = CompressionVariant::BC1(BC123Settings::new());
+/// let rgba_data = vec![0u8; 64 * 64 * 4]; // Your RGBA data
+/// let mipmaps = compress_mipmaps_rgba8(
+///     variant,
+///     &rgba_data,
+///     64,
+///     64,
+///     64 * 4,
+///     MipmapSettings::default(),
+/// );
+///
+/// let descriptor = DdsDescriptor::new(variant, 64, 64)
+///     .with_mip_level_count(mipmaps.levels.len() as u32)
+///     .with_srgb(true);
+///
+/// let file = write_dds(&descriptor, &mipmaps.blocks);
+/// let image = read_dds(&file).unwrap();
+///
+/// assert_eq!(image.descriptor, descriptor);
+/// assert_eq!(image.level_data(0, 6), mipmaps.level_data(6));
+/// ```
+pub fn write_dds(descriptor: &DdsDescriptor, blocks: &[u8]) -> Vec<u8> {
+    match try_write_dds(descriptor, blocks) {
+        Ok(file) => file,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Serializes compressed blocks into a DDS file.
+///
+/// This is the fallible version of [`write_dds()`].
+///
+/// # Errors
+/// - [`Error::InvalidDimensions`] if the dimensions, the mip level count or the array layer count
+///   of `descriptor` are invalid
+/// - [`Error::BufferSizeMismatch`] if the size of `blocks` doesn't match the size described by
+///   `descriptor`
+pub fn try_write_dds(descriptor: &DdsDescriptor, blocks: &[u8]) -> Result<Vec<u8>, Error> {
+    descriptor.validate()?;
+
+    let expected = descriptor.blocks_byte_size();
+    if blocks.len() != expected {
+        return Err(Error::BufferSizeMismatch {
+            expected,
+            actual: blocks.len(),
+        });
+    }
+
+    let caps2 = descriptor.cubemap.then_some(
+        Caps2::CUBEMAP
+            | Caps2::CUBEMAP_POSITIVEX
+            | Caps2::CUBEMAP_NEGATIVEX
+            | Caps2::CUBEMAP_POSITIVEY
+            | Caps2::CUBEMAP_NEGATIVEY
+            | Caps2::CUBEMAP_POSITIVEZ
+            | Caps2::CUBEMAP_NEGATIVEZ,
+    );
+
+    let alpha_mode = match descriptor.variant {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC1(..) | CompressionVariant::BC2(..) | CompressionVariant::BC3(..) => {
+            AlphaMode::Straight
+        }
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(..) => AlphaMode::Straight,
+        #[allow(unreachable_patterns)]
+        _ => AlphaMode::Unknown,
+    };
+
+    let mut dds = Dds::new_dxgi(NewDxgiParams {
+        height: descriptor.height,
+        width: descriptor.width,
+        depth: None,
+        format: dxgi_format(descriptor.variant, descriptor.srgb),
+        mipmap_levels: Some(descriptor.mip_level_count),
+        array_layers: Some(descriptor.layer_count()),
+        caps2,
+        is_cubemap: descriptor.cubemap,
+        resource_dimension: D3D10ResourceDimension::Texture2D,
+        alpha_mode,
+    })
+    .map_err(|_| Error::InvalidContainer)?;
+
+    dds.data = blocks.to_vec();
+
+    let mut file = Vec::with_capacity(blocks.len() + 148);
+    dds.write(&mut file).map_err(|_| Error::InvalidContainer)?;
+
+    Ok(file)
+}
+
+/// Parses a DDS file into its compressed blocks.
+///
+/// Files with a DX10 header and legacy files using the `DXT1`, `DXT3`, `DXT5`, `ATI1` and `ATI2`
+/// formats are supported. Trailing data after the described blocks is ignored.
+///
+/// # Errors
+/// - [`Error::InvalidContainer`] if `data` is not a valid DDS file
+/// - [`Error::UnsupportedFormat`] if the file is a volume texture or stores a format that is not
+///   supported by the enabled features
+/// - [`Error::InvalidDimensions`] if the dimensions, the mip level count or the array layer count
+///   are invalid
+/// - [`Error::BufferTooSmall`] if the file doesn't contain all described blocks
+pub fn read_dds(data: &[u8]) -> Result<DdsImage, Error> {
+    let dds = Dds::read(data).map_err(|_| Error::InvalidContainer)?;
+
+    if dds.get_depth() > 1 {
+        return Err(Error::UnsupportedFormat);
+    }
+
+    let format = dds.get_dxgi_format().ok_or(Error::UnsupportedFormat)?;
+    let (variant, srgb) = variant_from_dxgi_format(format).ok_or(Error::UnsupportedFormat)?;
+
+    let (array_layer_count, cubemap) = match dds.header10.as_ref() {
+        Some(header10) => (
+            header10.array_size,
+            header10.misc_flag.contains(MiscFlag::TEXTURECUBE),
+        ),
+        None => (1, dds.header.caps2.contains(Caps2::CUBEMAP)),
+    };
+
+    let descriptor = DdsDescriptor {
+        variant,
+        width: dds.get_width(),
+        height: dds.get_height(),
+        mip_level_count: dds.get_num_mipmap_levels().max(1),
+        array_layer_count,
+        cubemap,
+        // Legacy files don't distinguish sRGB formats.
+        srgb: srgb && dds.header10.is_some(),
+    };
+
+    descriptor.validate()?;
+
+    let required = descriptor.blocks_byte_size();
+    if dds.data.len() < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: dds.data.len(),
+        });
+    }
+
+    let mut blocks = dds.data;
+    blocks.truncate(required);
+
+    Ok(DdsImage { descriptor, blocks })
+}
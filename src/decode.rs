@@ -9,17 +9,21 @@ pub use self::block::decode_block_bc7;
 #[cfg(feature = "bc15")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
 pub use self::block::{
-    decode_block_bc1, decode_block_bc2, decode_block_bc3, decode_block_bc4, decode_block_bc5,
+    decode_block_bc1, decode_block_bc2, decode_block_bc3, decode_block_bc4, decode_block_bc4_snorm,
+    decode_block_bc4_snorm_float, decode_block_bc5, decode_block_bc5_snorm,
+    decode_block_bc5_snorm_float,
 };
 #[cfg(feature = "bc6h")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
 pub use self::block::{decode_block_bc6h, decode_block_bc6h_float};
+#[cfg(feature = "bc15")]
+use crate::BC123Settings;
 #[cfg(feature = "bc6h")]
 use crate::BC6HSettings;
 #[cfg(feature = "bc7")]
 use crate::BC7Settings;
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
-use crate::CompressionVariant;
+use crate::{for_each_row, CompressionVariant, Error};
 
 /// Trait to decode a BC variant into RGBA8 data.
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
@@ -28,6 +32,13 @@ trait BlockRgba8Decoder {
     fn block_byte_size() -> u32;
 }
 
+/// Trait to decode a signed BC variant into signed RGBA8 data.
+#[cfg(feature = "bc15")]
+trait BlockRgba8SnormDecoder {
+    fn decode_block_rgba8_snorm(compressed: &[u8], decompressed: &mut [i8], pitch: usize);
+    fn block_byte_size() -> u32;
+}
+
 /// Trait to decode a BC variant into RGBA16F data.
 #[cfg(feature = "bc6h")]
 trait BlockRgba16fDecoder {
@@ -36,7 +47,7 @@ trait BlockRgba16fDecoder {
 }
 
 /// Trait to decode a BC variant into RGBA32F data.
-#[cfg(feature = "bc6h")]
+#[cfg(any(feature = "bc15", feature = "bc6h"))]
 trait BlockRgba32fDecoder {
     fn decode_block_rgba32f(compressed: &[u8], decompressed: &mut [f32], pitch: usize);
     fn block_byte_size() -> u32;
@@ -52,8 +63,12 @@ struct BC3Decoder;
 struct BC4Decoder;
 #[cfg(feature = "bc15")]
 struct BC5Decoder;
+#[cfg(feature = "bc15")]
+struct BC4SnormDecoder;
+#[cfg(feature = "bc15")]
+struct BC5SnormDecoder;
 #[cfg(feature = "bc6h")]
-struct BC6HDecoder;
+struct BC6HDecoder<const SIGNED: bool>;
 #[cfg(feature = "bc7")]
 struct BC7Decoder;
 
@@ -65,7 +80,7 @@ impl BlockRgba8Decoder for BC1Decoder {
     }
 
     fn block_byte_size() -> u32 {
-        CompressionVariant::BC1.block_byte_size()
+        CompressionVariant::BC1(BC123Settings::new()).block_byte_size()
     }
 }
 
@@ -77,7 +92,7 @@ impl BlockRgba8Decoder for BC2Decoder {
     }
 
     fn block_byte_size() -> u32 {
-        CompressionVariant::BC2.block_byte_size()
+        CompressionVariant::BC2(BC123Settings::new()).block_byte_size()
     }
 }
 
@@ -89,7 +104,7 @@ impl BlockRgba8Decoder for BC3Decoder {
     }
 
     fn block_byte_size() -> u32 {
-        CompressionVariant::BC3.block_byte_size()
+        CompressionVariant::BC3(BC123Settings::new()).block_byte_size()
     }
 }
 
@@ -147,6 +162,120 @@ impl BlockRgba8Decoder for BC5Decoder {
     }
 }
 
+/// Maps a signed SNORM8 value to an unsigned UNORM8 value, so that -1.0 maps to 0 and 1.0 to 255.
+#[cfg(feature = "bc15")]
+fn snorm_to_unorm(value: i8) -> u8 {
+    ((i32::max(value as i32, -127) + 127) * 255 / 254) as u8
+}
+
+#[cfg(feature = "bc15")]
+impl BlockRgba8Decoder for BC4SnormDecoder {
+    #[inline(always)]
+    fn decode_block_rgba8(compressed: &[u8], decompressed: &mut [u8], pitch: usize) {
+        const PITCH: usize = 4;
+        let mut buffer = [0i8; 16];
+        decode_block_bc4_snorm(compressed, &mut buffer, PITCH);
+
+        // Convert R8 SNORM to RGBA8
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x;
+
+                decompressed[out_pos] = snorm_to_unorm(buffer[in_pos]);
+                decompressed[out_pos + 1] = 0;
+                decompressed[out_pos + 2] = 0;
+                decompressed[out_pos + 3] = 0;
+            }
+        }
+    }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC4Snorm.block_byte_size()
+    }
+}
+
+#[cfg(feature = "bc15")]
+impl BlockRgba8Decoder for BC5SnormDecoder {
+    #[inline(always)]
+    fn decode_block_rgba8(compressed: &[u8], decompressed: &mut [u8], pitch: usize) {
+        const PITCH: usize = 8;
+        let mut buffer = [0i8; 32];
+        decode_block_bc5_snorm(compressed, &mut buffer, PITCH);
+
+        // Convert RG8 SNORM to RGBA8
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x * 2;
+
+                decompressed[out_pos] = snorm_to_unorm(buffer[in_pos]);
+                decompressed[out_pos + 1] = snorm_to_unorm(buffer[in_pos + 1]);
+                decompressed[out_pos + 2] = 0;
+                decompressed[out_pos + 3] = 0;
+            }
+        }
+    }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC5Snorm.block_byte_size()
+    }
+}
+
+#[cfg(feature = "bc15")]
+impl BlockRgba8SnormDecoder for BC4SnormDecoder {
+    #[inline(always)]
+    fn decode_block_rgba8_snorm(compressed: &[u8], decompressed: &mut [i8], pitch: usize) {
+        const PITCH: usize = 4;
+        let mut buffer = [0i8; 16];
+        decode_block_bc4_snorm(compressed, &mut buffer, PITCH);
+
+        // Convert R8 SNORM to RGBA8 SNORM
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = 0;
+                decompressed[out_pos + 2] = 0;
+                decompressed[out_pos + 3] = 0;
+            }
+        }
+    }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC4Snorm.block_byte_size()
+    }
+}
+
+#[cfg(feature = "bc15")]
+impl BlockRgba8SnormDecoder for BC5SnormDecoder {
+    #[inline(always)]
+    fn decode_block_rgba8_snorm(compressed: &[u8], decompressed: &mut [i8], pitch: usize) {
+        const PITCH: usize = 8;
+        let mut buffer = [0i8; 32];
+        decode_block_bc5_snorm(compressed, &mut buffer, PITCH);
+
+        // Convert RG8 SNORM to RGBA8 SNORM
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x * 2;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = buffer[in_pos + 1];
+                decompressed[out_pos + 2] = 0;
+                decompressed[out_pos + 3] = 0;
+            }
+        }
+    }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC5Snorm.block_byte_size()
+    }
+}
+
 #[cfg(feature = "bc6h")]
 fn linear_to_srgb(linear: f32) -> u8 {
     let v = if linear <= 0.0031308 {
@@ -159,12 +288,12 @@ fn linear_to_srgb(linear: f32) -> u8 {
 }
 
 #[cfg(feature = "bc6h")]
-impl BlockRgba8Decoder for BC6HDecoder {
+impl<const SIGNED: bool> BlockRgba8Decoder for BC6HDecoder<SIGNED> {
     #[inline(always)]
     fn decode_block_rgba8(compressed: &[u8], decompressed: &mut [u8], pitch: usize) {
         const PITCH: usize = 12;
         let mut buffer = [0.0_f32; 48];
-        decode_block_bc6h_float(compressed, &mut buffer, PITCH, false);
+        decode_block_bc6h_float(compressed, &mut buffer, PITCH, SIGNED);
 
         // Convert RGB16F to RGBA8
         for y in 0..4 {
@@ -197,45 +326,105 @@ impl BlockRgba8Decoder for BC7Decoder {
     }
 }
 
+/// Decodes the block at `bx` into `output_row` using `decode`.
+///
+/// Blocks on the right and bottom edge of images whose dimensions are not a multiple of 4 are
+/// decoded into a temporary block first, and only their pixels inside the image are copied.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn decode_block_clipped<T, F>(output_row: &mut [T], output_row_pitch: usize, bx: usize, decode: F)
+where
+    T: Copy + Default,
+    F: Fn(&mut [T], usize),
+{
+    const BLOCK_PITCH: usize = 16;
+
+    let offset = bx * BLOCK_PITCH;
+    let visible_width = usize::min(output_row_pitch - offset, BLOCK_PITCH);
+    let visible_rows = output_row.len() / output_row_pitch;
+
+    if visible_width == BLOCK_PITCH && visible_rows == 4 {
+        decode(&mut output_row[offset..], output_row_pitch);
+        return;
+    }
+
+    let mut block = [T::default(); BLOCK_PITCH * 4];
+    decode(&mut block, BLOCK_PITCH);
+
+    for y in 0..visible_rows {
+        let row_offset = y * output_row_pitch + offset;
+        output_row[row_offset..row_offset + visible_width]
+            .copy_from_slice(&block[y * BLOCK_PITCH..y * BLOCK_PITCH + visible_width]);
+    }
+}
+
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
-fn decompress_rgba8<D: BlockRgba8Decoder>(
+fn decompress_rgba8<D: BlockRgba8Decoder>(width: u32, blocks_data: &[u8], rgba_data: &mut [u8]) {
+    let blocks_x = width.div_ceil(4) as usize;
+    let block_byte_size = D::block_byte_size() as usize;
+    let blocks_row_size = blocks_x * block_byte_size;
+    let output_row_pitch = width as usize * 4; // Always RGBA
+
+    for_each_row(rgba_data, output_row_pitch * 4, |by, output_row| {
+        let blocks_row = &blocks_data[by * blocks_row_size..(by + 1) * blocks_row_size];
+
+        for bx in 0..blocks_x {
+            let block_offset = bx * block_byte_size;
+
+            let block = &blocks_row[block_offset..block_offset + block_byte_size];
+
+            decode_block_clipped(output_row, output_row_pitch, bx, |decompressed, pitch| {
+                D::decode_block_rgba8(block, decompressed, pitch)
+            });
+        }
+    });
+}
+
+#[cfg(feature = "bc15")]
+fn decompress_rgba8_snorm<D: BlockRgba8SnormDecoder>(
     width: u32,
-    height: u32,
     blocks_data: &[u8],
-    rgba_data: &mut [u8],
+    rgba_data: &mut [i8],
 ) {
-    let blocks_x = width.div_ceil(4);
-    let blocks_y = height.div_ceil(4);
+    let blocks_x = width.div_ceil(4) as usize;
     let block_byte_size = D::block_byte_size() as usize;
+    let blocks_row_size = blocks_x * block_byte_size;
     let output_row_pitch = width as usize * 4; // Always RGBA
 
-    for by in 0..blocks_y {
-        for bx in 0..blocks_x {
-            let block_index = (by * blocks_x + bx) as usize;
-            let block_offset = block_index * block_byte_size;
+    for_each_row(rgba_data, output_row_pitch * 4, |by, output_row| {
+        let blocks_row = &blocks_data[by * blocks_row_size..(by + 1) * blocks_row_size];
 
-            if block_offset + block_byte_size > blocks_data.len() {
-                break;
-            }
+        for bx in 0..blocks_x {
+            let block_offset = bx * block_byte_size;
 
-            let output_offset = (by * 4 * output_row_pitch as u32 + bx * 16) as usize;
+            let block = &blocks_row[block_offset..block_offset + block_byte_size];
 
-            if output_offset < rgba_data.len() {
-                D::decode_block_rgba8(
-                    &blocks_data[block_offset..block_offset + block_byte_size],
-                    &mut rgba_data[output_offset..],
-                    output_row_pitch,
-                );
-            }
+            decode_block_clipped(output_row, output_row_pitch, bx, |decompressed, pitch| {
+                D::decode_block_rgba8_snorm(block, decompressed, pitch)
+            });
         }
-    }
+    });
 }
 
 #[cfg(feature = "bc6h")]
-impl BlockRgba16fDecoder for BC6HDecoder {
+impl<const SIGNED: bool> BlockRgba16fDecoder for BC6HDecoder<SIGNED> {
     #[inline(always)]
     fn decode_block_rgba16f(compressed: &[u8], decompressed: &mut [half::f16], pitch: usize) {
-        decode_block_bc6h(compressed, decompressed, pitch, false);
+        const PITCH: usize = 12;
+        let mut buffer = [half::f16::ZERO; 48];
+        decode_block_bc6h(compressed, &mut buffer, PITCH, SIGNED);
+
+        // Convert RGB16F to RGBA16F
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x * 3;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = buffer[in_pos + 1];
+                decompressed[out_pos + 2] = buffer[in_pos + 2];
+                decompressed[out_pos + 3] = half::f16::ZERO;
+            }
+        }
     }
 
     fn block_byte_size() -> u32 {
@@ -246,88 +435,140 @@ impl BlockRgba16fDecoder for BC6HDecoder {
 #[cfg(feature = "bc6h")]
 fn decompress_rgba16f<D: BlockRgba16fDecoder>(
     width: u32,
-    height: u32,
     blocks_data: &[u8],
     rgba_data: &mut [half::f16],
 ) {
-    let blocks_x = width.div_ceil(4);
-    let blocks_y = height.div_ceil(4);
+    let blocks_x = width.div_ceil(4) as usize;
     let block_byte_size = D::block_byte_size() as usize;
+    let blocks_row_size = blocks_x * block_byte_size;
     let output_row_pitch = width as usize * 4; // Always RGBA16f
 
-    for by in 0..blocks_y {
+    for_each_row(rgba_data, output_row_pitch * 4, |by, output_row| {
+        let blocks_row = &blocks_data[by * blocks_row_size..(by + 1) * blocks_row_size];
+
         for bx in 0..blocks_x {
-            let block_index = (by * blocks_x + bx) as usize;
-            let block_offset = block_index * block_byte_size;
+            let block_offset = bx * block_byte_size;
+
+            let block = &blocks_row[block_offset..block_offset + block_byte_size];
+
+            decode_block_clipped(output_row, output_row_pitch, bx, |decompressed, pitch| {
+                D::decode_block_rgba16f(block, decompressed, pitch)
+            });
+        }
+    });
+}
 
-            if block_offset + block_byte_size > blocks_data.len() {
-                break;
+#[cfg(feature = "bc6h")]
+impl<const SIGNED: bool> BlockRgba32fDecoder for BC6HDecoder<SIGNED> {
+    #[inline(always)]
+    fn decode_block_rgba32f(compressed: &[u8], decompressed: &mut [f32], pitch: usize) {
+        const PITCH: usize = 12;
+        let mut buffer = [0.0_f32; 48];
+        decode_block_bc6h_float(compressed, &mut buffer, PITCH, SIGNED);
+
+        // Convert RGB32F to RGBA32F
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x * 3;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = buffer[in_pos + 1];
+                decompressed[out_pos + 2] = buffer[in_pos + 2];
+                decompressed[out_pos + 3] = 0.0;
             }
+        }
+    }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC6H(BC6HSettings::basic()).block_byte_size()
+    }
+}
 
-            let output_offset = (by * 4 * output_row_pitch as u32 + bx * 16) as usize;
+#[cfg(feature = "bc15")]
+impl BlockRgba32fDecoder for BC4SnormDecoder {
+    #[inline(always)]
+    fn decode_block_rgba32f(compressed: &[u8], decompressed: &mut [f32], pitch: usize) {
+        const PITCH: usize = 4;
+        let mut buffer = [0.0_f32; 16];
+        decode_block_bc4_snorm_float(compressed, &mut buffer, PITCH);
 
-            if output_offset < rgba_data.len() {
-                D::decode_block_rgba16f(
-                    &blocks_data[block_offset..block_offset + block_byte_size],
-                    &mut rgba_data[output_offset..],
-                    output_row_pitch,
-                );
+        // Convert R32F to RGBA32F
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = 0.0;
+                decompressed[out_pos + 2] = 0.0;
+                decompressed[out_pos + 3] = 0.0;
             }
         }
     }
+
+    fn block_byte_size() -> u32 {
+        CompressionVariant::BC4Snorm.block_byte_size()
+    }
 }
 
-#[cfg(feature = "bc6h")]
-impl BlockRgba32fDecoder for BC6HDecoder {
+#[cfg(feature = "bc15")]
+impl BlockRgba32fDecoder for BC5SnormDecoder {
     #[inline(always)]
     fn decode_block_rgba32f(compressed: &[u8], decompressed: &mut [f32], pitch: usize) {
-        decode_block_bc6h_float(compressed, decompressed, pitch, false);
+        const PITCH: usize = 8;
+        let mut buffer = [0.0_f32; 32];
+        decode_block_bc5_snorm_float(compressed, &mut buffer, PITCH);
+
+        // Convert RG32F to RGBA32F
+        for y in 0..4 {
+            for x in 0..4 {
+                let out_pos = y * pitch + x * 4;
+                let in_pos = y * PITCH + x * 2;
+
+                decompressed[out_pos] = buffer[in_pos];
+                decompressed[out_pos + 1] = buffer[in_pos + 1];
+                decompressed[out_pos + 2] = 0.0;
+                decompressed[out_pos + 3] = 0.0;
+            }
+        }
     }
 
     fn block_byte_size() -> u32 {
-        CompressionVariant::BC6H(BC6HSettings::basic()).block_byte_size()
+        CompressionVariant::BC5Snorm.block_byte_size()
     }
 }
 
-#[cfg(feature = "bc6h")]
+#[cfg(any(feature = "bc15", feature = "bc6h"))]
 fn decompress_rgba32f<D: BlockRgba32fDecoder>(
     width: u32,
-    height: u32,
     blocks_data: &[u8],
     rgba_data: &mut [f32],
 ) {
-    let blocks_x = width.div_ceil(4);
-    let blocks_y = height.div_ceil(4);
+    let blocks_x = width.div_ceil(4) as usize;
     let block_byte_size = D::block_byte_size() as usize;
+    let blocks_row_size = blocks_x * block_byte_size;
     let output_row_pitch = width as usize * 4; // Always RGBA32f
 
-    for by in 0..blocks_y {
-        for bx in 0..blocks_x {
-            let block_index = (by * blocks_x + bx) as usize;
-            let block_offset = block_index * block_byte_size;
+    for_each_row(rgba_data, output_row_pitch * 4, |by, output_row| {
+        let blocks_row = &blocks_data[by * blocks_row_size..(by + 1) * blocks_row_size];
 
-            if block_offset + block_byte_size > blocks_data.len() {
-                break;
-            }
+        for bx in 0..blocks_x {
+            let block_offset = bx * block_byte_size;
 
-            let output_offset = (by * 4 * output_row_pitch as u32 + bx * 16) as usize;
+            let block = &blocks_row[block_offset..block_offset + block_byte_size];
 
-            if output_offset < rgba_data.len() {
-                D::decode_block_rgba32f(
-                    &blocks_data[block_offset..block_offset + block_byte_size],
-                    &mut rgba_data[output_offset..],
-                    output_row_pitch,
-                );
-            }
+            decode_block_clipped(output_row, output_row_pitch, bx, |decompressed, pitch| {
+                D::decode_block_rgba32f(block, decompressed, pitch)
+            });
         }
-    }
+    });
 }
 
 /// Helper function to easily decompress block data into RGBA8 data.
 ///
 /// # Panics
-/// - The `blocks_data` has not the expected size (`variant.blocks_byte_size()`)
-/// - The `rgba_data` has not the expected size (`width * height * 4`)
+/// - If [`try_decompress_blocks_as_rgba8()`] returns an error
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
 #[cfg_attr(
     docsrs,
@@ -340,58 +581,84 @@ pub fn decompress_blocks_as_rgba8(
     blocks_data: &[u8],
     rgba_data: &mut [u8],
 ) {
-    let expected_input_size = variant.blocks_byte_size(width, height);
-    assert_eq!(
-        blocks_data.len(),
-        expected_input_size,
-        "the input bitstream slice has not the expected size"
-    );
+    if let Err(error) =
+        try_decompress_blocks_as_rgba8(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
 
-    let expected_output_size = width as usize * height as usize * 4;
-    assert_eq!(
-        rgba_data.len(),
-        expected_output_size,
-        "the output slice has not the expected size"
-    );
+/// Decompresses block data into RGBA8 data.
+///
+/// This is the fallible version of [`decompress_blocks_as_rgba8()`]. Signed BC4 and BC5 data is
+/// mapped from -1.0..1.0 to 0..255, use [`try_decompress_blocks_as_rgba8_snorm()`] to get the
+/// signed values.
+///
+/// # Errors
+/// - [`Error::BufferSizeMismatch`] if the `blocks_data` has not the expected size
+///   (`variant.blocks_byte_size()`)
+/// - [`Error::BufferSizeMismatch`] if the `rgba_data` has not the expected size
+///   (`width * height * 4`)
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub fn try_decompress_blocks_as_rgba8(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [u8],
+) -> Result<(), Error> {
+    validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
 
     match variant {
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC1 => {
-            decompress_rgba8::<BC1Decoder>(width, height, blocks_data, rgba_data)
+        CompressionVariant::BC1(..) => {
+            decompress_rgba8::<BC1Decoder>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC2 => {
-            decompress_rgba8::<BC2Decoder>(width, height, blocks_data, rgba_data)
+        CompressionVariant::BC2(..) => {
+            decompress_rgba8::<BC2Decoder>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC3 => {
-            decompress_rgba8::<BC3Decoder>(width, height, blocks_data, rgba_data)
+        CompressionVariant::BC3(..) => {
+            decompress_rgba8::<BC3Decoder>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC4 => {
-            decompress_rgba8::<BC4Decoder>(width, height, blocks_data, rgba_data)
+        CompressionVariant::BC4 => decompress_rgba8::<BC4Decoder>(width, blocks_data, rgba_data),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 => decompress_rgba8::<BC5Decoder>(width, blocks_data, rgba_data),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4Snorm => {
+            decompress_rgba8::<BC4SnormDecoder>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC5 => {
-            decompress_rgba8::<BC5Decoder>(width, height, blocks_data, rgba_data)
+        CompressionVariant::BC5Snorm => {
+            decompress_rgba8::<BC5SnormDecoder>(width, blocks_data, rgba_data)
+        }
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(settings) if settings.is_signed() => {
+            decompress_rgba8::<BC6HDecoder<true>>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc6h")]
         CompressionVariant::BC6H(..) => {
-            decompress_rgba8::<BC6HDecoder>(width, height, blocks_data, rgba_data)
+            decompress_rgba8::<BC6HDecoder<false>>(width, blocks_data, rgba_data)
         }
         #[cfg(feature = "bc7")]
         CompressionVariant::BC7(..) => {
-            decompress_rgba8::<BC7Decoder>(width, height, blocks_data, rgba_data)
+            decompress_rgba8::<BC7Decoder>(width, blocks_data, rgba_data)
         }
     }
+
+    Ok(())
 }
 
 /// Helper function to easily decompress block data into RGBA16F data. Only BCH6 is currently supported.
 ///
 /// # Panics
-/// - The `blocks_data` has not the expected size (`variant.blocks_byte_size()`)
-/// - The `rgba_data` has not the expected size (`width * height * 4`)
-/// - If `variant` is any other value than BC6H.
+/// - If [`try_decompress_blocks_as_rgba16f()`] returns an error
 #[cfg(feature = "bc6h")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
 pub fn decompress_blocks_as_rgba16f(
@@ -401,68 +668,402 @@ pub fn decompress_blocks_as_rgba16f(
     blocks_data: &[u8],
     rgba_data: &mut [half::f16],
 ) {
-    let expected_input_size = variant.blocks_byte_size(width, height);
+    if let Err(error) =
+        try_decompress_blocks_as_rgba16f(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Decompresses block data into RGBA16F data. Only BCH6 is currently supported.
+///
+/// This is the fallible version of [`decompress_blocks_as_rgba16f()`].
+///
+/// # Errors
+/// - [`Error::UnsupportedVariant`] if `variant` is any other value than BC6H
+/// - [`Error::BufferSizeMismatch`] if the `blocks_data` has not the expected size
+///   (`variant.blocks_byte_size()`)
+/// - [`Error::BufferSizeMismatch`] if the `rgba_data` has not the expected size
+///   (`width * height * 4`)
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_decompress_blocks_as_rgba16f(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [half::f16],
+) -> Result<(), Error> {
+    #[allow(irrefutable_let_patterns)]
+    let CompressionVariant::BC6H(settings) = variant
+    else {
+        return Err(Error::UnsupportedVariant);
+    };
 
-    assert_eq!(
-        blocks_data.len(),
-        expected_input_size,
-        "the input bitstream slice has not the expected size"
-    );
+    validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
 
-    let expected_output_size = width as usize * height as usize * 4;
-    assert_eq!(
-        rgba_data.len(),
-        expected_output_size,
-        "the output slice has not the expected size"
-    );
+    if settings.is_signed() {
+        decompress_rgba16f::<BC6HDecoder<true>>(width, blocks_data, rgba_data);
+    } else {
+        decompress_rgba16f::<BC6HDecoder<false>>(width, blocks_data, rgba_data);
+    }
+
+    Ok(())
+}
 
+/// Helper function to easily decompress block data into RGBA32F data. Only BCH6, signed BC4 and
+/// signed BC5 are currently supported.
+///
+/// # Panics
+/// - If [`try_decompress_blocks_as_rgba32f()`] returns an error
+#[cfg(any(feature = "bc15", feature = "bc6h"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc6h"))))]
+pub fn decompress_blocks_as_rgba32f(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [f32],
+) {
+    if let Err(error) =
+        try_decompress_blocks_as_rgba32f(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Decompresses block data into RGBA32F data. Only BCH6, signed BC4 and signed BC5 are currently
+/// supported.
+///
+/// This is the fallible version of [`decompress_blocks_as_rgba32f()`]. Signed BC4 and BC5 data is
+/// written in the range of -1.0 to 1.0.
+///
+/// # Errors
+/// - [`Error::UnsupportedVariant`] if `variant` is any other value than BC6H, BC4Snorm or BC5Snorm
+/// - [`Error::BufferSizeMismatch`] if the `blocks_data` has not the expected size
+///   (`variant.blocks_byte_size()`)
+/// - [`Error::BufferSizeMismatch`] if the `rgba_data` has not the expected size
+///   (`width * height * 4`)
+#[cfg(any(feature = "bc15", feature = "bc6h"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc6h"))))]
+pub fn try_decompress_blocks_as_rgba32f(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [f32],
+) -> Result<(), Error> {
     match variant {
-        CompressionVariant::BC6H(..) => {
-            decompress_rgba16f::<BC6HDecoder>(width, height, blocks_data, rgba_data)
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4Snorm => {
+            validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+            decompress_rgba32f::<BC4SnormDecoder>(width, blocks_data, rgba_data);
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5Snorm => {
+            validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+            decompress_rgba32f::<BC5SnormDecoder>(width, blocks_data, rgba_data);
+        }
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(settings) => {
+            validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+
+            if settings.is_signed() {
+                decompress_rgba32f::<BC6HDecoder<true>>(width, blocks_data, rgba_data);
+            } else {
+                decompress_rgba32f::<BC6HDecoder<false>>(width, blocks_data, rgba_data);
+            }
         }
         #[allow(unreachable_patterns)]
-        _ => {
-            panic!("unsupported compression variant");
+        _ => return Err(Error::UnsupportedVariant),
+    }
+
+    Ok(())
+}
+
+/// Helper function to easily decompress signed BC4 and BC5 block data into signed RGBA8 data.
+///
+/// # Panics
+/// - If [`try_decompress_blocks_as_rgba8_snorm()`] returns an error
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+pub fn decompress_blocks_as_rgba8_snorm(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [i8],
+) {
+    if let Err(error) =
+        try_decompress_blocks_as_rgba8_snorm(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Decompresses signed BC4 and BC5 block data into signed RGBA8 data.
+///
+/// This is the fallible version of [`decompress_blocks_as_rgba8_snorm()`].
+///
+/// # Errors
+/// - [`Error::UnsupportedVariant`] if `variant` is any other value than BC4Snorm or BC5Snorm
+/// - [`Error::BufferSizeMismatch`] if the `blocks_data` has not the expected size
+///   (`variant.blocks_byte_size()`)
+/// - [`Error::BufferSizeMismatch`] if the `rgba_data` has not the expected size
+///   (`width * height * 4`)
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+pub fn try_decompress_blocks_as_rgba8_snorm(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [i8],
+) -> Result<(), Error> {
+    match variant {
+        CompressionVariant::BC4Snorm => {
+            validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+            decompress_rgba8_snorm::<BC4SnormDecoder>(width, blocks_data, rgba_data);
         }
+        CompressionVariant::BC5Snorm => {
+            validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+            decompress_rgba8_snorm::<BC5SnormDecoder>(width, blocks_data, rgba_data);
+        }
+        _ => return Err(Error::UnsupportedVariant),
     }
+
+    Ok(())
 }
 
-/// Helper function to easily decompress block data into RGBA32F data. Only BCH6 is currently supported.
+/// Helper function to decompress a normal map and reconstruct the Z component of its normals.
+///
+/// The normals are written as `n * 0.5 + 0.5` into the red, green and blue channel, alpha is
+/// 255. See [`try_decompress_normal_map_as_rgba32f()`] for the supported variants.
 ///
 /// # Panics
-/// - The `blocks_data` has not the expected size (`variant.blocks_byte_size()`)
-/// - The `rgba_data` has not the expected size (`width * height * 4`)
-/// - If `variant` is any other value than BC6H.
-#[cfg(feature = "bc6h")]
-#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
-pub fn decompress_blocks_as_rgba32f(
+/// - If [`try_decompress_normal_map_as_rgba8()`] returns an error
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc7"))))]
+pub fn decompress_normal_map_as_rgba8(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [u8],
+) {
+    if let Err(error) =
+        try_decompress_normal_map_as_rgba8(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Decompresses a normal map and reconstructs the Z component of its normals into RGBA8 data.
+///
+/// This is the fallible version of [`decompress_normal_map_as_rgba8()`].
+///
+/// # Errors
+/// - The same errors as [`try_decompress_normal_map_as_rgba32f()`]
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc7"))))]
+pub fn try_decompress_normal_map_as_rgba8(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [u8],
+) -> Result<(), Error> {
+    let mut normals = vec![0.0; rgba_data.len()];
+    try_decompress_normal_map_as_rgba32f(variant, width, height, blocks_data, &mut normals)?;
+
+    for (pixel, normal) in rgba_data.chunks_exact_mut(4).zip(normals.chunks_exact(4)) {
+        for axis in 0..3 {
+            pixel[axis] = ((normal[axis] * 0.5 + 0.5) * 255.0).round() as u8;
+        }
+        pixel[3] = 255;
+    }
+
+    Ok(())
+}
+
+/// Helper function to decompress a normal map and reconstruct the Z component of its normals.
+///
+/// The normals are written in the range of -1.0 to 1.0 into the red, green and blue channel,
+/// alpha is 1.0.
+///
+/// # Panics
+/// - If [`try_decompress_normal_map_as_rgba32f()`] returns an error
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc7"))))]
+pub fn decompress_normal_map_as_rgba32f(
     variant: CompressionVariant,
     width: u32,
     height: u32,
     blocks_data: &[u8],
     rgba_data: &mut [f32],
 ) {
+    if let Err(error) =
+        try_decompress_normal_map_as_rgba32f(variant, width, height, blocks_data, rgba_data)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Decompresses a normal map and reconstructs the Z component of its normals into RGBA32F data.
+///
+/// This is the fallible version of [`decompress_normal_map_as_rgba32f()`]. The X and Y component
+/// are read from the channels written by [`encode::compress_normal_map_rgba8()`]: red and green
+/// for BC5, signed BC5 and BC7, alpha and green for BC3. Z is reconstructed as
+/// `sqrt(1 - x * x - y * y)`, normals with an X/Y length above 1.0 are shortened to unit length.
+///
+/// [`encode::compress_normal_map_rgba8()`]: crate::encode::compress_normal_map_rgba8
+///
+/// # Errors
+/// - [`Error::UnsupportedVariant`] if `variant` is any other value than BC3, BC5, BC5Snorm or BC7
+/// - [`Error::BufferSizeMismatch`] if the `blocks_data` has not the expected size
+///   (`variant.blocks_byte_size()`)
+/// - [`Error::BufferSizeMismatch`] if the `rgba_data` has not the expected size
+///   (`width * height * 4`)
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc7"))))]
+pub fn try_decompress_normal_map_as_rgba32f(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_data: &[u8],
+    rgba_data: &mut [f32],
+) -> Result<(), Error> {
+    let [x_channel, y_channel] = normal_map_channels(variant).ok_or(Error::UnsupportedVariant)?;
+
+    validate_buffers(variant, width, height, blocks_data.len(), rgba_data.len())?;
+
+    #[cfg(feature = "bc15")]
+    if variant == CompressionVariant::BC5Snorm {
+        decompress_rgba32f::<BC5SnormDecoder>(width, blocks_data, rgba_data);
+        reconstruct_normals(rgba_data, x_channel, y_channel);
+        return Ok(());
+    }
+
+    let mut unorm_data = vec![0; rgba_data.len()];
+    try_decompress_blocks_as_rgba8(variant, width, height, blocks_data, &mut unorm_data)?;
+
+    for (value, unorm) in rgba_data.iter_mut().zip(unorm_data) {
+        *value = unorm as f32 / 255.0 * 2.0 - 1.0;
+    }
+    reconstruct_normals(rgba_data, x_channel, y_channel);
+
+    Ok(())
+}
+
+/// Validates the sizes of the input and output buffers of the decompression.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn validate_buffers(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks_len: usize,
+    output_len: usize,
+) -> Result<(), Error> {
     let expected_input_size = variant.blocks_byte_size(width, height);
-    assert_eq!(
-        blocks_data.len(),
-        expected_input_size,
-        "the input bitstream slice has not the expected size"
-    );
+    if blocks_len != expected_input_size {
+        return Err(Error::BufferSizeMismatch {
+            expected: expected_input_size,
+            actual: blocks_len,
+        });
+    }
 
     let expected_output_size = width as usize * height as usize * 4;
-    assert_eq!(
-        rgba_data.len(),
-        expected_output_size,
-        "the output slice has not the expected size"
-    );
+    if output_len != expected_output_size {
+        return Err(Error::BufferSizeMismatch {
+            expected: expected_output_size,
+            actual: output_len,
+        });
+    }
+
+    Ok(())
+}
 
+/// Decodes a single block of `variant` into RGBA8 data with `pitch` many bytes per output row.
+///
+/// The data is converted like in [`try_decompress_blocks_as_rgba8()`].
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+pub(crate) fn decode_block_rgba8(
+    variant: CompressionVariant,
+    compressed: &[u8],
+    decompressed: &mut [u8],
+    pitch: usize,
+) {
     match variant {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC1(..) => {
+            BC1Decoder::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC2(..) => {
+            BC2Decoder::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC3(..) => {
+            BC3Decoder::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4 => BC4Decoder::decode_block_rgba8(compressed, decompressed, pitch),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 => BC5Decoder::decode_block_rgba8(compressed, decompressed, pitch),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4Snorm => {
+            BC4SnormDecoder::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5Snorm => {
+            BC5SnormDecoder::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(settings) if settings.is_signed() => {
+            BC6HDecoder::<true>::decode_block_rgba8(compressed, decompressed, pitch)
+        }
+        #[cfg(feature = "bc6h")]
         CompressionVariant::BC6H(..) => {
-            decompress_rgba32f::<BC6HDecoder>(width, height, blocks_data, rgba_data)
+            BC6HDecoder::<false>::decode_block_rgba8(compressed, decompressed, pitch)
         }
-        #[allow(unreachable_patterns)]
-        _ => {
-            panic!("unsupported compression variant");
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(..) => {
+            BC7Decoder::decode_block_rgba8(compressed, decompressed, pitch)
         }
     }
 }
+
+/// Returns the channels which store the X and Y component of a normal map compressed with
+/// `variant`, or `None` if the variant can't store normal maps.
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+pub(crate) fn normal_map_channels(variant: CompressionVariant) -> Option<[usize; 2]> {
+    match variant {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC3(..) => Some([3, 1]),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 | CompressionVariant::BC5Snorm => Some([0, 1]),
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(..) => Some([0, 1]),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Replaces the RGBA data with the unit length normals reconstructed from the X and Y component
+/// in `x_channel` and `y_channel`.
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+fn reconstruct_normals(rgba_data: &mut [f32], x_channel: usize, y_channel: usize) {
+    for pixel in rgba_data.chunks_exact_mut(4) {
+        let (x, y) = (pixel[x_channel], pixel[y_channel]);
+        let length_squared = x * x + y * y;
+
+        let normal = if length_squared > 1.0 {
+            let length = length_squared.sqrt();
+            [x / length, y / length, 0.0]
+        } else {
+            [x, y, (1.0 - length_squared).sqrt()]
+        };
+
+        pixel.copy_from_slice(&[normal[0], normal[1], normal[2], 1.0]);
+    }
+}
@@ -118,6 +118,82 @@ pub fn decode_block_bc5(
     );
 }
 
+/// Decodes a signed BC4 block by reading 8 bytes from `compressed_block` and writing the R8 SNORM data into `decompressed_block` with `destination_pitch` many bytes per output row.
+#[cfg(feature = "bc15")]
+#[inline(always)]
+pub fn decode_block_bc4_snorm(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i8],
+    destination_pitch: usize,
+) {
+    decode_signed_smooth_block::<1, _>(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        snorm_to_i8,
+    );
+}
+
+/// Decodes a signed BC4 block by reading 8 bytes from `compressed_block` and writing the R32F data (in the range of -1.0 to 1.0) into `decompressed_block` with `destination_pitch` many floats per output row.
+#[cfg(feature = "bc15")]
+#[inline(always)]
+pub fn decode_block_bc4_snorm_float(
+    compressed_block: &[u8],
+    decompressed_block: &mut [f32],
+    destination_pitch: usize,
+) {
+    decode_signed_smooth_block::<1, _>(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        |value| value,
+    );
+}
+
+/// Decodes a signed BC5 block by reading 16 bytes from `compressed_block` and writing the RG8 SNORM data into `decompressed_block` with `destination_pitch` many bytes per output row.
+#[cfg(feature = "bc15")]
+#[inline(always)]
+pub fn decode_block_bc5_snorm(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i8],
+    destination_pitch: usize,
+) {
+    decode_signed_smooth_block::<2, _>(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        snorm_to_i8,
+    );
+    decode_signed_smooth_block::<2, _>(
+        &compressed_block[8..],
+        &mut decompressed_block[1..],
+        destination_pitch,
+        snorm_to_i8,
+    );
+}
+
+/// Decodes a signed BC5 block by reading 16 bytes from `compressed_block` and writing the RG32F data (in the range of -1.0 to 1.0) into `decompressed_block` with `destination_pitch` many floats per output row.
+#[cfg(feature = "bc15")]
+#[inline(always)]
+pub fn decode_block_bc5_snorm_float(
+    compressed_block: &[u8],
+    decompressed_block: &mut [f32],
+    destination_pitch: usize,
+) {
+    decode_signed_smooth_block::<2, _>(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        |value| value,
+    );
+    decode_signed_smooth_block::<2, _>(
+        &compressed_block[8..],
+        &mut decompressed_block[1..],
+        destination_pitch,
+        |value| value,
+    );
+}
+
 /// Decompresses a BC1/DXT1 color block
 #[cfg(feature = "bc15")]
 #[inline(always)]
@@ -257,6 +333,58 @@ fn decode_smooth_alpha_block<const PIXEL_SIZE: usize>(
     }
 }
 
+/// Decodes a signed BC4/BC5 channel block (smooth transitions)
+#[cfg(feature = "bc15")]
+#[inline(always)]
+#[rustfmt::skip]
+fn decode_signed_smooth_block<const PIXEL_SIZE: usize, T>(
+    compressed_block: &[u8],
+    decompressed_block: &mut [T],
+    destination_pitch: usize,
+    convert: impl Fn(f32) -> T,
+) {
+    let block = u64::from_le_bytes(compressed_block[0..8].try_into().unwrap());
+
+    // -128 and -127 both map to -1.0
+    let mut red = [0.0f32; 8];
+    red[0] = f32::max((block & 0xFF) as u8 as i8 as f32 / 127.0, -1.0);
+    red[1] = f32::max(((block >> 8) & 0xFF) as u8 as i8 as f32 / 127.0, -1.0);
+
+    if red[0] > red[1] {
+        // 6 interpolated values
+        red[2] = (6.0 * red[0] +       red[1]) / 7.0;   // 6/7*red_0 + 1/7*red_1
+        red[3] = (5.0 * red[0] + 2.0 * red[1]) / 7.0;   // 5/7*red_0 + 2/7*red_1
+        red[4] = (4.0 * red[0] + 3.0 * red[1]) / 7.0;   // 4/7*red_0 + 3/7*red_1
+        red[5] = (3.0 * red[0] + 4.0 * red[1]) / 7.0;   // 3/7*red_0 + 4/7*red_1
+        red[6] = (2.0 * red[0] + 5.0 * red[1]) / 7.0;   // 2/7*red_0 + 5/7*red_1
+        red[7] = (      red[0] + 6.0 * red[1]) / 7.0;   // 1/7*red_0 + 6/7*red_1
+    } else {
+        // 4 interpolated values
+        red[2] = (4.0 * red[0] +       red[1]) / 5.0;   // 4/5*red_0 + 1/5*red_1
+        red[3] = (3.0 * red[0] + 2.0 * red[1]) / 5.0;   // 3/5*red_0 + 2/5*red_1
+        red[4] = (2.0 * red[0] + 3.0 * red[1]) / 5.0;   // 2/5*red_0 + 3/5*red_1
+        red[5] = (      red[0] + 4.0 * red[1]) / 5.0;   // 1/5*red_0 + 4/5*red_1
+        red[6] = -1.0;
+        red[7] =  1.0;
+    }
+
+    let mut indices = block >> 16;
+
+    for i in 0..4 {
+        for j in 0..4 {
+            decompressed_block[i * destination_pitch + j * PIXEL_SIZE] = convert(red[(indices & 0x07) as usize]);
+            indices >>= 3;
+        }
+    }
+}
+
+/// Converts a signed normalized value in the range of -1.0 to 1.0 into a SNORM8 value.
+#[cfg(feature = "bc15")]
+#[inline(always)]
+fn snorm_to_i8(value: f32) -> i8 {
+    (value * 127.0).round() as i8
+}
+
 /// Decodes a BC7 block by reading 16 bytes from `compressed_block` and writing the RGB16F data (half float) into `decompressed_block` with `destination_pitch` many bytes per output row.
 #[cfg(feature = "bc6h")]
 pub fn decode_block_bc6h(
@@ -1707,6 +1835,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bc4_snorm_interpolated() {
+        let compressed_block = [0x7F, 0x81, 0x88, 0xC6, 0xFA, 0x00, 0x00, 0x00];
+        let expected_output: [i8; 16] = [
+            127, -127, 91, 54, 18, -18, -54, -91, 127, 127, 127, 127, 127, 127, 127, 127,
+        ];
+
+        let mut decoded = [0i8; 16];
+        decode_block_bc4_snorm(&compressed_block, &mut decoded, 4);
+
+        assert_eq!(decoded, expected_output, "BC4 SNORM interpolated");
+
+        let mut decoded = [0.0f32; 16];
+        decode_block_bc4_snorm_float(&compressed_block, &mut decoded, 4);
+
+        assert_eq!(decoded[0], 1.0, "BC4 SNORM float endpoint 0");
+        assert_eq!(decoded[1], -1.0, "BC4 SNORM float endpoint 1");
+        assert_eq!(decoded[4], 1.0 / 7.0, "BC4 SNORM float interpolated");
+    }
+
     #[cfg(feature = "bc6h")]
     #[test]
     fn test_bc6h_block_0() {
@@ -8,6 +8,10 @@ mod bc6h;
 mod bc7;
 #[cfg(any(feature = "bc6h", feature = "bc7"))]
 mod common;
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+mod normal_map;
+#[cfg(any(feature = "bc6h", feature = "bc7"))]
+mod quality;
 
 #[cfg(feature = "bc15")]
 use self::bc1_to_5::BlockCompressorBC15;
@@ -15,12 +19,31 @@ use self::bc1_to_5::BlockCompressorBC15;
 use self::bc6h::BlockCompressorBC6H;
 #[cfg(feature = "bc7")]
 use self::bc7::BlockCompressorBC7;
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc15", feature = "bc7"))))]
+pub use self::normal_map::{compress_normal_map_rgba8, try_compress_normal_map_rgba8};
 #[cfg(feature = "bc6h")]
-use crate::BC6HSettings;
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub use self::quality::{compress_rgba16_to_quality, try_compress_rgba16_to_quality};
+#[cfg(any(feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bc6h", feature = "bc7"))))]
+pub use self::quality::{
+    compress_rgba8_to_quality, try_compress_rgba8_to_quality, QualitySelection, QualityTarget,
+    TileSelection,
+};
 #[cfg(feature = "bc7")]
 use crate::BC7Settings;
+#[cfg(feature = "bc6h")]
+use crate::{decode::decode_block_bc6h, metrics::hdr_peak, BC6HSettings};
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
-use crate::CompressionVariant;
+use crate::{
+    decode::decode_block_rgba8,
+    for_each_row_with,
+    metrics::{stored_channels, CompressionMetrics, ErrorMetrics},
+    CompressionVariant, Error,
+};
+#[cfg(feature = "bc15")]
+use crate::{for_each_row, BC123Settings};
 
 /// Compresses raw RGBA8 data into using a texture block compression format.
 ///
@@ -31,6 +54,12 @@ use crate::CompressionVariant;
 /// The input data must be in RGBA8 format (8 bits per channel, 32 bits per pixel). The data is
 /// expected to be in row-major order, with optional stride for padding between rows.
 ///
+/// # Image Dimensions
+/// The `width` and `height` don't need to be a multiple of 4. Blocks on the right and bottom
+/// edge of the image are padded by replicating the last pixel column and row. The padding pixels
+/// are not considered when fitting the block endpoints, so they don't affect the quality of the
+/// visible pixels.
+///
 /// # Buffer Requirements
 /// The destination buffer must have sufficient capacity to store the compressed blocks.
 /// The required size can be calculated using [`CompressionVariant::blocks_byte_size()`].
@@ -51,18 +80,17 @@ use crate::CompressionVariant;
 ///   Must be `width * 4` for tightly packed RGBA data.
 ///
 /// # Panics
-/// * If `width` or `height` is not a multiple of 4
-/// * If the destination `blocks_buffer` is too small to hold the compressed data
+/// * If [`try_compress_rgba8()`] returns an error
 ///
 /// # Example
 /// ```
-/// use block_compression::{encode::compress_rgba8, CompressionVariant};
+/// use block_compression::{encode::compress_rgba8, BC123Settings, CompressionVariant};
 ///
 /// let rgba_data = vec![0u8; 256 * 256 * 4]; // Your RGBA data
 /// let width = 256;
 /// let height = 256;
 /// let stride = width * 4; // Tightly packed rows
-/// let variant = CompressionVariant::BC1;
+/// let variant = CompressionVariant::BC1(BC123Settings::new());
 ///
 /// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
 ///
@@ -88,52 +116,149 @@ pub fn compress_rgba8(
     height: u32,
     stride: u32,
 ) {
-    assert_eq!(height % 4, 0);
-    assert_eq!(width % 4, 0);
-
-    let required_size = variation.blocks_byte_size(width, height);
+    if let Err(error) =
+        try_compress_rgba8(variation, rgba_data, blocks_buffer, width, height, stride)
+    {
+        panic!("{error}");
+    }
+}
 
-    assert!(
-        blocks_buffer.len() >= required_size,
-        "blocks_buffer size ({}) is too small to hold compressed blocks. Required size: {}",
-        blocks_buffer.len(),
-        required_size
-    );
+/// Compresses raw RGBA8 data into using a texture block compression format.
+///
+/// This is the fallible version of [`compress_rgba8()`], which returns an error instead of
+/// panicking on invalid input. See [`compress_rgba8()`] for the data layout and buffer
+/// requirements.
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is `CompressionVariant::BC4Snorm` or
+///   `CompressionVariant::BC5Snorm`, which are compressed with [`try_compress_rgba8_snorm()`]
+/// * [`Error::InvalidDimensions`] if `width * 4` overflows a `u32`
+/// * [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// * [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+/// * [`Error::BufferTooSmall`] if the destination `blocks_buffer` is too small to hold the
+///   compressed data
+///
+/// # Example
+/// ```
+/// use block_compression::{encode::try_compress_rgba8, BC123Settings, CompressionVariant, Error};
+///
+/// let rgba_data = vec![0u8; 256 * 256 * 4]; // Your RGBA data
+/// let width = 256;
+/// let height = 256;
+/// let stride = width * 4; // Tightly packed rows
+/// let variant = CompressionVariant::BC1(BC123Settings::new());
+///
+/// let mut blocks_buffer = vec![0u8; 16];
+///
+/// let result = try_compress_rgba8(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     stride,
+/// );
+///
+/// assert!(matches!(result, Err(Error::BufferTooSmall { .. })));
+/// ```
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub fn try_compress_rgba8(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<(), Error> {
+    validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size = validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
 
-    let stride = stride as usize;
-    let block_width = (width as usize).div_ceil(4);
-    let block_height = (height as usize).div_ceil(4);
+    compress_rgba8_blocks(
+        variation,
+        rgba_data,
+        &mut blocks_buffer[..required_size],
+        width as usize,
+        height as usize,
+        stride as usize,
+        &mut [],
+    )
+}
 
+/// Compresses the validated RGBA8 data and measures the error of every block into `metrics`,
+/// unless `metrics` is empty.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn compress_rgba8_blocks(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    metrics: &mut [ErrorMetrics],
+) -> Result<(), Error> {
     match variation {
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC1 => {
-            compress_bc1(rgba_data, blocks_buffer, block_width, block_height, stride);
+        CompressionVariant::BC1(settings) => {
+            compress_bc1(
+                rgba_data,
+                blocks_buffer,
+                width,
+                height,
+                stride,
+                &settings,
+                metrics,
+            );
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC2 => {
-            compress_bc2(rgba_data, blocks_buffer, block_width, block_height, stride);
+        CompressionVariant::BC2(settings) => {
+            compress_bc2(
+                rgba_data,
+                blocks_buffer,
+                width,
+                height,
+                stride,
+                &settings,
+                metrics,
+            );
         }
         #[cfg(feature = "bc15")]
-        CompressionVariant::BC3 => {
-            compress_bc3(rgba_data, blocks_buffer, block_width, block_height, stride);
+        CompressionVariant::BC3(settings) => {
+            compress_bc3(
+                rgba_data,
+                blocks_buffer,
+                width,
+                height,
+                stride,
+                &settings,
+                metrics,
+            );
         }
         #[cfg(feature = "bc15")]
         CompressionVariant::BC4 => {
-            compress_bc4(rgba_data, blocks_buffer, block_width, block_height, stride);
+            compress_bc4(rgba_data, blocks_buffer, width, height, stride, metrics);
         }
         #[cfg(feature = "bc15")]
         CompressionVariant::BC5 => {
-            compress_bc5(rgba_data, blocks_buffer, block_width, block_height, stride);
+            compress_bc5(rgba_data, blocks_buffer, width, height, stride, metrics);
+        }
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4Snorm | CompressionVariant::BC5Snorm => {
+            return Err(Error::UnsupportedVariant);
         }
         #[cfg(feature = "bc6h")]
         CompressionVariant::BC6H(settings) => {
             compress_bc6h_8bit(
                 rgba_data,
                 blocks_buffer,
-                block_width,
-                block_height,
+                width,
+                height,
                 stride,
                 &settings,
+                metrics,
             );
         }
         #[cfg(feature = "bc7")]
@@ -141,10 +266,156 @@ pub fn compress_rgba8(
             compress_bc7(
                 rgba_data,
                 blocks_buffer,
-                block_width,
-                block_height,
+                width,
+                height,
                 stride,
                 &settings,
+                metrics,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Compresses raw RGBA8 data like [`compress_rgba8()`] and reports the compression error.
+///
+/// Every block is measured against the original pixels right after it was encoded, in the same
+/// pass over the image, so no decoded copy of the image is allocated. The returned
+/// [`CompressionMetrics`] contain the mean squared error and the PSNR per channel of every block
+/// and of the whole image, which can be used to find textures that need slower settings.
+///
+/// The error is measured on the stored channels of the variant: RGB for BC1 (plus alpha with an
+/// alpha threshold), RGBA for BC2 and BC3, red for BC4, red and green for BC5, RGB for BC6H and
+/// RGB for BC7 (plus alpha if the settings encode alpha). BC6H is measured after converting
+/// the decoded HDR values back to sRGB.
+///
+/// # Panics
+/// * If [`try_compress_rgba8_with_metrics()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{encode::compress_rgba8_with_metrics, BC7Settings, CompressionVariant};
+///
+/// let rgba_data = vec![0u8; 256 * 256 * 4]; // Your RGBA data
+/// let width = 256;
+/// let height = 256;
+/// let variant = CompressionVariant::BC7(BC7Settings::alpha_ultrafast());
+///
+/// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
+///
+/// let metrics = compress_rgba8_with_metrics(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     width * 4,
+/// );
+///
+/// if metrics.image().psnr() < 40.0 {
+///     // Compress again with slower settings
+/// }
+/// ```
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub fn compress_rgba8_with_metrics(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> CompressionMetrics {
+    match try_compress_rgba8_with_metrics(
+        variation,
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+    ) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compresses raw RGBA8 data like [`try_compress_rgba8()`] and reports the compression error.
+///
+/// This is the fallible version of [`compress_rgba8_with_metrics()`].
+///
+/// # Errors
+/// * The same errors as [`try_compress_rgba8()`]
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub fn try_compress_rgba8_with_metrics(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<CompressionMetrics, Error> {
+    validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size = validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let channels = stored_channels(variation);
+    let block_width = width.div_ceil(4);
+    let mut blocks =
+        vec![ErrorMetrics::new(channels); block_width as usize * height.div_ceil(4) as usize];
+
+    compress_rgba8_blocks(
+        variation,
+        rgba_data,
+        &mut blocks_buffer[..required_size],
+        width as usize,
+        height as usize,
+        stride as usize,
+        &mut blocks,
+    )?;
+
+    Ok(CompressionMetrics::from_blocks(
+        ErrorMetrics::new(channels),
+        blocks,
+        block_width,
+    ))
+}
+
+/// Measures the error of the block that was just compressed into `block` against the original
+/// pixels starting at `source_block` and adds it to `metrics`.
+///
+/// Only the pixels in `valid_mask` are measured, see [`valid_pixel_mask()`].
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn measure_block_rgba8(
+    variation: CompressionVariant,
+    block: &[u8],
+    source_block: &[u8],
+    stride: usize,
+    valid_mask: u32,
+    metrics: &mut ErrorMetrics,
+) {
+    const PITCH: usize = 16;
+
+    let mut decoded_block = [0u8; PITCH * 4];
+    decode_block_rgba8(variation, block, &mut decoded_block, PITCH);
+
+    for y in 0..4 {
+        for x in 0..4 {
+            if valid_mask & (1 << (y * 4 + x)) == 0 {
+                continue;
+            }
+
+            let original = y * stride + x * 4;
+            let decoded = y * PITCH + x * 4;
+            metrics.add_pixel_rgba8(
+                &source_block[original..original + 4],
+                &decoded_block[decoded..decoded + 4],
             );
         }
     }
@@ -159,6 +430,12 @@ pub fn compress_rgba8(
 /// The input data must be in RGBA16 format (16 bits per channel using half-float). The data is
 /// expected to be in row-major order, with optional stride for padding between rows.
 ///
+/// # Image Dimensions
+/// The `width` and `height` don't need to be a multiple of 4. Blocks on the right and bottom
+/// edge of the image are padded by replicating the last pixel column and row. The padding pixels
+/// are not considered when fitting the block endpoints, so they don't affect the quality of the
+/// visible pixels.
+///
 /// # Buffer Requirements
 /// The destination buffer must have sufficient capacity to store the compressed blocks.
 /// The required size can be calculated using [`CompressionVariant::blocks_byte_size()`].
@@ -179,9 +456,7 @@ pub fn compress_rgba8(
 ///   Must be `width * 4` for tightly packed RGBA data.
 ///
 /// # Panics
-/// * If `width` or `height` is not a multiple of 4
-/// * If the destination `blocks_buffer` is too small to hold the compressed data
-/// * If `variation` is not `CompressionVariant::BC6H`
+/// * If [`try_compress_rgba16()`] returns an error
 ///
 /// # Example
 /// ```
@@ -216,228 +491,823 @@ pub fn compress_rgba16(
     height: u32,
     stride: u32,
 ) {
-    assert_eq!(height % 4, 0);
-    assert_eq!(width % 4, 0);
+    if let Err(error) =
+        try_compress_rgba16(variation, rgba_data, blocks_buffer, width, height, stride)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Compresses raw RGBA16 data using the BC6H texture block compression format.
+///
+/// This is the fallible version of [`compress_rgba16()`], which returns an error instead of
+/// panicking on invalid input. See [`compress_rgba16()`] for the data layout and buffer
+/// requirements.
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is not `CompressionVariant::BC6H`
+/// * [`Error::InvalidDimensions`] if `width * 4` overflows a `u32`
+/// * [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// * [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+/// * [`Error::BufferTooSmall`] if the destination `blocks_buffer` is too small to hold the
+///   compressed data
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_compress_rgba16(
+    variation: CompressionVariant,
+    rgba_data: &[half::f16],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<(), Error> {
+    #[allow(irrefutable_let_patterns)]
+    let CompressionVariant::BC6H(settings) = variation
+    else {
+        return Err(Error::UnsupportedVariant);
+    };
+
+    validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size = validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
 
-    let required_size = variation.blocks_byte_size(width, height);
+    let blocks_buffer = &mut blocks_buffer[..required_size];
+    let width = width as usize;
+    let height = height as usize;
+    let stride = stride as usize;
 
-    assert!(
-        blocks_buffer.len() >= required_size,
-        "blocks_buffer size ({}) is too small to hold compressed blocks. Required size: {}",
-        blocks_buffer.len(),
-        required_size
+    compress_bc6h_16bit(
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+        &settings,
+        &mut [],
     );
 
+    Ok(())
+}
+
+/// Compresses raw RGBA16 (half-float) data like [`compress_rgba16()`] and reports the
+/// compression error.
+///
+/// Every block is measured against the original pixels right after it was encoded, in the same
+/// pass over the image, so no decoded copy of the image is allocated. The error is measured on
+/// the RGB channels in linear HDR values and the PSNR is calculated relative to the largest
+/// absolute value of the RGB channels of the image.
+///
+/// # Panics
+/// * If [`try_compress_rgba16_with_metrics()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{encode::compress_rgba16_with_metrics, BC6HSettings, CompressionVariant};
+/// use half::f16;
+///
+/// let rgba_data = vec![f16::ZERO; 256 * 256 * 4]; // Your RGBA16 data
+/// let width = 256;
+/// let height = 256;
+/// let variant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+///
+/// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
+///
+/// let metrics = compress_rgba16_with_metrics(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     width * 4,
+/// );
+///
+/// if metrics.image().psnr() < 40.0 {
+///     // Compress again with slower settings
+/// }
+/// ```
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn compress_rgba16_with_metrics(
+    variation: CompressionVariant,
+    rgba_data: &[half::f16],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> CompressionMetrics {
+    match try_compress_rgba16_with_metrics(
+        variation,
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+    ) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compresses raw RGBA16 (half-float) data like [`try_compress_rgba16()`] and reports the
+/// compression error.
+///
+/// This is the fallible version of [`compress_rgba16_with_metrics()`].
+///
+/// # Errors
+/// * The same errors as [`try_compress_rgba16()`]
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_compress_rgba16_with_metrics(
+    variation: CompressionVariant,
+    rgba_data: &[half::f16],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<CompressionMetrics, Error> {
+    #[allow(irrefutable_let_patterns)]
+    let CompressionVariant::BC6H(settings) = variation
+    else {
+        return Err(Error::UnsupportedVariant);
+    };
+
+    validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size = validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let blocks_buffer = &mut blocks_buffer[..required_size];
+    let width = width as usize;
+    let height = height as usize;
     let stride = stride as usize;
-    let block_width = (width as usize).div_ceil(4);
-    let block_height = (height as usize).div_ceil(4);
+    let channels = stored_channels(variation);
+    let pixels = (0..height).flat_map(|y| {
+        rgba_data[y * stride..y * stride + width * 4]
+            .iter()
+            .map(|value| value.to_f64())
+    });
+    let metrics = ErrorMetrics::new(channels).with_peak(hdr_peak(pixels, channels));
 
-    match variation {
-        CompressionVariant::BC6H(settings) => {
-            compress_bc6h_16bit(
-                rgba_data,
-                blocks_buffer,
-                block_width,
-                block_height,
-                stride,
-                &settings,
+    let block_width = width.div_ceil(4);
+    let mut blocks = vec![metrics; block_width * height.div_ceil(4)];
+
+    compress_bc6h_16bit(
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+        &settings,
+        &mut blocks,
+    );
+
+    Ok(CompressionMetrics::from_blocks(
+        metrics,
+        blocks,
+        block_width as u32,
+    ))
+}
+
+/// Measures the error of the BC6H block that was just compressed into `block` against the
+/// original HDR pixels starting at `source_block` and adds it to `metrics`.
+///
+/// Only the RGB channels of the pixels in `valid_mask` are measured, see [`valid_pixel_mask()`].
+#[cfg(feature = "bc6h")]
+fn measure_block_rgba16f(
+    is_signed: bool,
+    block: &[u8],
+    source_block: &[half::f16],
+    stride: usize,
+    valid_mask: u32,
+    metrics: &mut ErrorMetrics,
+) {
+    const PITCH: usize = 12;
+
+    let mut decoded_block = [half::f16::ZERO; PITCH * 4];
+    decode_block_bc6h(block, &mut decoded_block, PITCH, is_signed);
+
+    for y in 0..4 {
+        for x in 0..4 {
+            if valid_mask & (1 << (y * 4 + x)) == 0 {
+                continue;
+            }
+
+            let original = y * stride + x * 4;
+            let decoded = y * PITCH + x * 3;
+            metrics.add_pixel(
+                std::array::from_fn(|channel| {
+                    if channel < 3 {
+                        source_block[original + channel].to_f64()
+                    } else {
+                        0.0
+                    }
+                }),
+                std::array::from_fn(|channel| {
+                    if channel < 3 {
+                        decoded_block[decoded + channel].to_f64()
+                    } else {
+                        0.0
+                    }
+                }),
             );
         }
-        #[allow(unreachable_patterns)]
-        _ => {
-            panic!("only BC6H is supported for calling compress_rgba16");
-        }
     }
 }
 
+/// Compresses raw signed RGBA8 data using the BC4 or BC5 signed texture block compression format.
+///
+/// It supports only the `BC4Snorm` and `BC5Snorm` compression formats. BC4 compresses the red
+/// channel and BC5 the red and green channels, the other channels are ignored.
+///
+/// # Data Layout Requirements
+/// The input data must be in signed RGBA8 format (8 bits per channel, 32 bits per pixel), where
+/// -127 and -128 map to -1.0 and 127 maps to 1.0. The data is expected to be in row-major order,
+/// with optional stride for padding between rows.
+///
+/// # Image Dimensions
+/// The `width` and `height` don't need to be a multiple of 4. Blocks on the right and bottom
+/// edge of the image are padded by replicating the last pixel column and row.
+///
+/// # Buffer Requirements
+/// The destination buffer must have sufficient capacity to store the compressed blocks.
+/// The required size can be calculated using [`CompressionVariant::blocks_byte_size()`].
+///
+/// # Arguments
+/// * `variation` - The block compression format to use (must be BC4Snorm or BC5Snorm)
+/// * `rgba_data` - Source signed RGBA8 pixel data
+/// * `blocks_buffer` - Destination buffer for the compressed blocks
+/// * `width` - Width of the image in pixels
+/// * `height` - Height of the image in pixels
+/// * `stride` - Number of bytes per row in the source data (for padding).
+///   Must be `width * 4` for tightly packed RGBA data.
+///
+/// # Panics
+/// * If [`try_compress_rgba8_snorm()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{encode::compress_rgba8_snorm, CompressionVariant};
+///
+/// let rgba_data = vec![0i8; 256 * 256 * 4]; // Your signed RGBA data, for example a normal map
+/// let width = 256;
+/// let height = 256;
+/// let stride = width * 4; // Tightly packed rows
+/// let variant = CompressionVariant::BC5Snorm;
+///
+/// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
+///
+/// compress_rgba8_snorm(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     stride,
+/// );
+/// ```
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+pub fn compress_rgba8_snorm(
+    variation: CompressionVariant,
+    rgba_data: &[i8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) {
+    if let Err(error) =
+        try_compress_rgba8_snorm(variation, rgba_data, blocks_buffer, width, height, stride)
+    {
+        panic!("{error}");
+    }
+}
+
+/// Compresses raw signed RGBA8 data using the BC4 or BC5 signed texture block compression format.
+///
+/// This is the fallible version of [`compress_rgba8_snorm()`], which returns an error instead of
+/// panicking on invalid input. See [`compress_rgba8_snorm()`] for the data layout and buffer
+/// requirements.
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is not `CompressionVariant::BC4Snorm` or
+///   `CompressionVariant::BC5Snorm`
+/// * [`Error::InvalidDimensions`] if `width * 4` overflows a `u32`
+/// * [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// * [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+/// * [`Error::BufferTooSmall`] if the destination `blocks_buffer` is too small to hold the
+///   compressed data
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+pub fn try_compress_rgba8_snorm(
+    variation: CompressionVariant,
+    rgba_data: &[i8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<(), Error> {
+    if !matches!(
+        variation,
+        CompressionVariant::BC4Snorm | CompressionVariant::BC5Snorm
+    ) {
+        return Err(Error::UnsupportedVariant);
+    }
+
+    validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size = validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let blocks_buffer = &mut blocks_buffer[..required_size];
+    let width = width as usize;
+    let height = height as usize;
+    let stride = stride as usize;
+
+    if variation == CompressionVariant::BC4Snorm {
+        compress_bc4_snorm(rgba_data, blocks_buffer, width, height, stride);
+    } else {
+        compress_bc5_snorm(rgba_data, blocks_buffer, width, height, stride);
+    }
+
+    Ok(())
+}
+
+/// Validates that the source data of `data_len` elements holds an image of the given dimensions.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+pub(crate) fn validate_source(
+    data_len: usize,
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<(), Error> {
+    let min_stride = width
+        .checked_mul(4)
+        .ok_or(Error::InvalidDimensions { width, height })?;
+
+    if stride < min_stride {
+        return Err(Error::InvalidStride { stride, min_stride });
+    }
+
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    let required = (height as usize - 1) * stride as usize + min_stride as usize;
+    if data_len < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: data_len,
+        });
+    }
+
+    Ok(())
+}
+
+/// Validates that the blocks buffer can hold the compressed image and returns the required size.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn validate_blocks_buffer(
+    variation: CompressionVariant,
+    buffer_len: usize,
+    width: u32,
+    height: u32,
+) -> Result<usize, Error> {
+    let required = variation.blocks_byte_size(width, height);
+
+    if buffer_len < required {
+        return Err(Error::BufferTooSmall {
+            required,
+            actual: buffer_len,
+        });
+    }
+
+    Ok(required)
+}
+
+/// Returns the bit mask of the pixels of the block at `xx` and `yy` that lie inside the image.
+///
+/// Bit `y * 4 + x` is set if pixel `x`, `y` of the block is inside the image.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+fn valid_pixel_mask(xx: usize, yy: usize, width: usize, height: usize) -> u32 {
+    let valid_x = usize::min(width.saturating_sub(xx * 4), 4);
+    let valid_y = usize::min(height.saturating_sub(yy * 4), 4);
+
+    let row_mask = (1u32 << valid_x) - 1;
+    (0..valid_y).fold(0, |mask, y| mask | (row_mask << (y * 4)))
+}
+
 #[cfg(feature = "bc15")]
 fn compress_bc1(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
+    settings: &BC123Settings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC15::default();
+    let block_width = width.div_ceil(4);
 
-            block_compressor.load_block_interleaved_rgba(rgba_data, xx, yy, stride);
-            let color_result = block_compressor.compress_block_bc1_core();
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy, &color_result);
-        }
-    }
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 8,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC15::new(settings);
+
+                block_compressor
+                    .load_block_interleaved_rgba(rgba_data, xx, yy, width, height, stride);
+                let color_result = block_compressor.compress_block_bc1();
+                block_compressor.store_data(blocks_row, xx, &color_result);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC1(*settings),
+                        &blocks_row[xx * 8..(xx + 1) * 8],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
 
 #[cfg(feature = "bc15")]
 fn compress_bc2(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
+    settings: &BC123Settings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC15::default();
-            let mut compressed_data = [0; 4];
+    let block_width = width.div_ceil(4);
 
-            let alpha_result = block_compressor.load_block_alpha_4bit(rgba_data, xx, yy, stride);
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC15::new(settings);
+                let mut compressed_data = [0; 4];
 
-            compressed_data[0] = alpha_result[0];
-            compressed_data[1] = alpha_result[1];
+                let alpha_result = block_compressor
+                    .load_block_alpha_4bit(rgba_data, xx, yy, width, height, stride);
 
-            block_compressor.load_block_interleaved_rgba(rgba_data, xx, yy, stride);
+                compressed_data[0] = alpha_result[0];
+                compressed_data[1] = alpha_result[1];
 
-            let color_result = block_compressor.compress_block_bc1_core();
-            compressed_data[2] = color_result[0];
-            compressed_data[3] = color_result[1];
+                block_compressor
+                    .load_block_interleaved_rgba(rgba_data, xx, yy, width, height, stride);
 
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy, &compressed_data);
-        }
-    }
+                let color_result = block_compressor.compress_block_bc1_core();
+                compressed_data[2] = color_result[0];
+                compressed_data[3] = color_result[1];
+
+                block_compressor.store_data(blocks_row, xx, &compressed_data);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC2(*settings),
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
 
 #[cfg(feature = "bc15")]
 fn compress_bc3(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
+    settings: &BC123Settings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC15::default();
+    let block_width = width.div_ceil(4);
 
-            let mut compressed_data = [0; 4];
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC15::new(settings);
 
-            block_compressor.load_block_interleaved_rgba(rgba_data, xx, yy, stride);
+                let mut compressed_data = [0; 4];
 
-            let alpha_result = block_compressor.compress_block_bc3_alpha();
-            compressed_data[0] = alpha_result[0];
-            compressed_data[1] = alpha_result[1];
+                block_compressor
+                    .load_block_interleaved_rgba(rgba_data, xx, yy, width, height, stride);
 
-            let color_result = block_compressor.compress_block_bc1_core();
-            compressed_data[2] = color_result[0];
-            compressed_data[3] = color_result[1];
+                let alpha_result = block_compressor.compress_block_bc3_alpha();
+                compressed_data[0] = alpha_result[0];
+                compressed_data[1] = alpha_result[1];
 
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy, &compressed_data);
-        }
-    }
+                let color_result = block_compressor.compress_block_bc1_core();
+                compressed_data[2] = color_result[0];
+                compressed_data[3] = color_result[1];
+
+                block_compressor.store_data(blocks_row, xx, &compressed_data);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC3(*settings),
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
 
 #[cfg(feature = "bc15")]
 fn compress_bc4(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
+    let block_width = width.div_ceil(4);
+
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 8,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC15::default();
+
+                let mut compressed_data = [0; 2];
+
+                block_compressor.load_block_r_8bit(rgba_data, xx, yy, width, height, stride);
+
+                let color_result = block_compressor.compress_block_bc3_alpha();
+                compressed_data[0] = color_result[0];
+                compressed_data[1] = color_result[1];
+
+                block_compressor.store_data(blocks_row, xx, &compressed_data);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC4,
+                        &blocks_row[xx * 8..(xx + 1) * 8],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
+}
+
+#[cfg(feature = "bc15")]
+fn compress_bc5(
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    metrics: &mut [ErrorMetrics],
+) {
+    let block_width = width.div_ceil(4);
+
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC15::default();
+
+                let mut compressed_data = [0; 4];
+
+                block_compressor.load_block_r_8bit(rgba_data, xx, yy, width, height, stride);
+
+                let red_result = block_compressor.compress_block_bc3_alpha();
+                compressed_data[0] = red_result[0];
+                compressed_data[1] = red_result[1];
+
+                block_compressor.load_block_g_8bit(rgba_data, xx, yy, width, height, stride);
+
+                let green_result = block_compressor.compress_block_bc3_alpha();
+                compressed_data[2] = green_result[0];
+                compressed_data[3] = green_result[1];
+
+                block_compressor.store_data(blocks_row, xx, &compressed_data);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC5,
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
+}
+
+#[cfg(feature = "bc15")]
+fn compress_bc4_snorm(
+    rgba_data: &[i8],
+    blocks_buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+) {
+    let block_width = width.div_ceil(4);
+
+    for_each_row(blocks_buffer, block_width * 8, |yy, blocks_row| {
         for xx in 0..block_width {
             let mut block_compressor = BlockCompressorBC15::default();
 
             let mut compressed_data = [0; 2];
 
-            block_compressor.load_block_r_8bit(rgba_data, xx, yy, stride);
+            block_compressor.load_block_r_snorm(rgba_data, xx, yy, width, height, stride);
 
-            let color_result = block_compressor.compress_block_bc3_alpha();
+            let color_result = block_compressor.compress_block_bc4_snorm();
             compressed_data[0] = color_result[0];
             compressed_data[1] = color_result[1];
 
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy, &compressed_data);
+            block_compressor.store_data(blocks_row, xx, &compressed_data);
         }
-    }
+    });
 }
 
 #[cfg(feature = "bc15")]
-fn compress_bc5(
-    rgba_data: &[u8],
+fn compress_bc5_snorm(
+    rgba_data: &[i8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
 ) {
-    for yy in 0..block_height {
+    let block_width = width.div_ceil(4);
+
+    for_each_row(blocks_buffer, block_width * 16, |yy, blocks_row| {
         for xx in 0..block_width {
             let mut block_compressor = BlockCompressorBC15::default();
 
             let mut compressed_data = [0; 4];
 
-            block_compressor.load_block_r_8bit(rgba_data, xx, yy, stride);
+            block_compressor.load_block_r_snorm(rgba_data, xx, yy, width, height, stride);
 
-            let red_result = block_compressor.compress_block_bc3_alpha();
+            let red_result = block_compressor.compress_block_bc4_snorm();
             compressed_data[0] = red_result[0];
             compressed_data[1] = red_result[1];
 
-            block_compressor.load_block_g_8bit(rgba_data, xx, yy, stride);
+            block_compressor.load_block_g_snorm(rgba_data, xx, yy, width, height, stride);
 
-            let green_result = block_compressor.compress_block_bc3_alpha();
+            let green_result = block_compressor.compress_block_bc4_snorm();
             compressed_data[2] = green_result[0];
             compressed_data[3] = green_result[1];
 
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy, &compressed_data);
+            block_compressor.store_data(blocks_row, xx, &compressed_data);
         }
-    }
+    });
 }
 
 #[cfg(feature = "bc6h")]
 fn compress_bc6h_8bit(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
     settings: &BC6HSettings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC6H::new(settings);
-            block_compressor.load_block_interleaved_8bit(rgba_data, xx, yy, stride);
-            block_compressor.compress_bc6h_core();
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy);
-        }
-    }
+    let block_width = width.div_ceil(4);
+
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC6H::new(settings);
+                block_compressor
+                    .load_block_interleaved_8bit(rgba_data, xx, yy, width, height, stride);
+                block_compressor.compress_bc6h_core();
+                block_compressor.store_data(blocks_row, xx);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC6H(*settings),
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
 
 #[cfg(feature = "bc6h")]
 fn compress_bc6h_16bit(
     rgba_data: &[half::f16],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
     settings: &BC6HSettings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC6H::new(settings);
-            block_compressor.load_block_interleaved_16bit(rgba_data, xx, yy, stride);
-            block_compressor.compress_bc6h_core();
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy);
-        }
-    }
+    let block_width = width.div_ceil(4);
+
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC6H::new(settings);
+                block_compressor
+                    .load_block_interleaved_16bit(rgba_data, xx, yy, width, height, stride);
+                block_compressor.compress_bc6h_core();
+                block_compressor.store_data(blocks_row, xx);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba16f(
+                        settings.is_signed(),
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
 
 #[cfg(feature = "bc7")]
 fn compress_bc7(
     rgba_data: &[u8],
     blocks_buffer: &mut [u8],
-    block_width: usize,
-    block_height: usize,
+    width: usize,
+    height: usize,
     stride: usize,
     settings: &BC7Settings,
+    metrics: &mut [ErrorMetrics],
 ) {
-    for yy in 0..block_height {
-        for xx in 0..block_width {
-            let mut block_compressor = BlockCompressorBC7::new(settings);
+    let block_width = width.div_ceil(4);
 
-            block_compressor.load_block_interleaved_rgba(rgba_data, xx, yy, stride);
-            block_compressor.compute_opaque_err();
-            block_compressor.compress_block_bc7_core();
-            block_compressor.store_data(blocks_buffer, block_width, xx, yy);
-        }
-    }
+    for_each_row_with(
+        blocks_buffer,
+        block_width * 16,
+        metrics,
+        block_width,
+        |yy, blocks_row, metrics_row| {
+            for xx in 0..block_width {
+                let mut block_compressor = BlockCompressorBC7::new(settings);
+
+                block_compressor
+                    .load_block_interleaved_rgba(rgba_data, xx, yy, width, height, stride);
+                block_compressor.compute_opaque_err();
+                block_compressor.compress_block_bc7_core();
+                block_compressor.store_data(blocks_row, xx);
+
+                if let Some(metrics) = metrics_row.get_mut(xx) {
+                    measure_block_rgba8(
+                        CompressionVariant::BC7(*settings),
+                        &blocks_row[xx * 16..(xx + 1) * 16],
+                        &rgba_data[yy * 4 * stride + xx * 16..],
+                        stride,
+                        valid_pixel_mask(xx, yy, width, height),
+                        metrics,
+                    );
+                }
+            }
+        },
+    );
 }
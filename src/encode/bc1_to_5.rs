@@ -1,25 +1,43 @@
+use crate::BC123Settings;
+
 pub(crate) struct BlockCompressorBC15 {
     block: [f32; 64],
+    valid_mask: u32,
+    alpha_threshold: u32,
+    channel_weights: [f32; 3],
 }
 
 impl Default for BlockCompressorBC15 {
     fn default() -> Self {
-        Self { block: [0.0; 64] }
+        Self::new(&BC123Settings::new())
     }
 }
 
 impl BlockCompressorBC15 {
+    pub(crate) fn new(settings: &BC123Settings) -> Self {
+        Self {
+            block: [0.0; 64],
+            valid_mask: 0xFFFF,
+            alpha_threshold: settings.alpha_threshold,
+            channel_weights: settings.channel_weights(),
+        }
+    }
+
     pub(crate) fn load_block_interleaved_rgba(
         &mut self,
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
 
@@ -41,12 +59,16 @@ impl BlockCompressorBC15 {
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
                 let red = rgba_data[offset] as f32;
@@ -61,12 +83,16 @@ impl BlockCompressorBC15 {
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
                 let green = rgba_data[offset + 1] as f32;
@@ -76,19 +102,73 @@ impl BlockCompressorBC15 {
         }
     }
 
+    pub(crate) fn load_block_r_snorm(
+        &mut self,
+        data: &[i8],
+        xx: usize,
+        yy: usize,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
+
+                let offset = pixel_y * stride + pixel_x * 4;
+                // -128 and -127 both map to -1.0
+                let red = i8::max(data[offset], -127) as f32;
+
+                self.block[48 + y * 4 + x] = red;
+            }
+        }
+    }
+
+    pub(crate) fn load_block_g_snorm(
+        &mut self,
+        data: &[i8],
+        xx: usize,
+        yy: usize,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
+
+                let offset = pixel_y * stride + pixel_x * 4;
+                // -128 and -127 both map to -1.0
+                let green = i8::max(data[offset + 1], -127) as f32;
+
+                self.block[48 + y * 4 + x] = green;
+            }
+        }
+    }
+
     pub(crate) fn load_block_alpha_4bit(
         &mut self,
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) -> [u32; 2] {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         let mut alpha_bits = [0; 2];
 
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
                 let alpha = rgba_data[offset + 3] as f32 / 255.0;
@@ -108,32 +188,35 @@ impl BlockCompressorBC15 {
         alpha_bits
     }
 
-    pub(crate) fn store_data(
-        &self,
-        blocks_buffer: &mut [u8],
-        block_width: usize,
-        xx: usize,
-        yy: usize,
-        data: &[u32],
-    ) {
-        let offset = (yy * block_width + xx) * (data.len() * 4);
+    pub(crate) fn store_data(&self, blocks_row: &mut [u8], xx: usize, data: &[u32]) {
+        let offset = xx * (data.len() * 4);
 
         for (index, &value) in data.iter().enumerate() {
             let byte_offset = offset + index * 4;
-            blocks_buffer[byte_offset] = value as u8;
-            blocks_buffer[byte_offset + 1] = (value >> 8) as u8;
-            blocks_buffer[byte_offset + 2] = (value >> 16) as u8;
-            blocks_buffer[byte_offset + 3] = (value >> 24) as u8;
+            blocks_row[byte_offset] = value as u8;
+            blocks_row[byte_offset + 1] = (value >> 8) as u8;
+            blocks_row[byte_offset + 2] = (value >> 16) as u8;
+            blocks_row[byte_offset + 3] = (value >> 24) as u8;
         }
     }
 
+    fn is_valid_pixel(&self, k: usize) -> bool {
+        self.valid_mask & (1 << k) != 0
+    }
+
+    fn valid_pixel_count(&self) -> f32 {
+        self.valid_mask.count_ones() as f32
+    }
+
     fn compute_covar_dc(&self, covar: &mut [f32; 6], dc: &mut [f32; 3]) {
+        let pixel_count = self.valid_pixel_count();
+
         for (p, value) in dc.iter_mut().enumerate() {
             let mut acc = 0.0;
-            for k in 0..16 {
+            for k in (0..16).filter(|&k| self.is_valid_pixel(k)) {
                 acc += self.block[k + p * 16];
             }
-            *value = acc / 16.0;
+            *value = acc / pixel_count;
         }
 
         let mut covar0 = 0.0;
@@ -143,7 +226,7 @@ impl BlockCompressorBC15 {
         let mut covar4 = 0.0;
         let mut covar5 = 0.0;
 
-        for k in 0..16 {
+        for k in (0..16).filter(|&k| self.is_valid_pixel(k)) {
             let rgb0 = self.block[k] - dc[0];
             let rgb1 = self.block[k + 16] - dc[1];
             let rgb2 = self.block[k + 32] - dc[2];
@@ -156,12 +239,15 @@ impl BlockCompressorBC15 {
             covar5 += rgb2 * rgb2;
         }
 
-        covar[0] = covar0;
-        covar[1] = covar1;
-        covar[2] = covar2;
-        covar[3] = covar3;
-        covar[4] = covar4;
-        covar[5] = covar5;
+        // The covariance of the colors scaled by the square root of the channel weights.
+        let scale = self.channel_weights.map(f32::sqrt);
+
+        covar[0] = covar0 * scale[0] * scale[0];
+        covar[1] = covar1 * scale[0] * scale[1];
+        covar[2] = covar2 * scale[0] * scale[2];
+        covar[3] = covar3 * scale[1] * scale[1];
+        covar[4] = covar4 * scale[1] * scale[2];
+        covar[5] = covar5 * scale[2] * scale[2];
     }
 
     fn ssymv(result: &mut [f32; 3], covar: &[f32; 6], a_vector: &[f32; 3]) {
@@ -195,20 +281,20 @@ impl BlockCompressorBC15 {
         axis.copy_from_slice(&a_vector);
     }
 
+    /// Picks the endpoints along the `axis` of the weighted color space.
     fn pick_endpoints(&self, c0: &mut [f32; 3], c1: &mut [f32; 3], axis: &[f32; 3], dc: &[f32; 3]) {
+        let scale = self.channel_weights.map(f32::sqrt);
         let mut min_dot: f32 = 256.0 * 256.0;
         let mut max_dot: f32 = 0.0;
 
-        for y in 0..4 {
-            for x in 0..4 {
-                let mut dot = 0.0;
-                for p in 0..3 {
-                    dot += (self.block[p * 16 + y * 4 + x] - dc[p]) * axis[p];
-                }
-
-                min_dot = f32::min(min_dot, dot);
-                max_dot = f32::max(max_dot, dot);
+        for k in (0..16).filter(|&k| self.is_valid_pixel(k)) {
+            let mut dot = 0.0;
+            for p in 0..3 {
+                dot += (self.block[p * 16 + k] - dc[p]) * scale[p] * axis[p];
             }
+
+            min_dot = f32::min(min_dot, dot);
+            max_dot = f32::max(max_dot, dot);
         }
 
         if max_dot - min_dot < 1.0 {
@@ -223,8 +309,9 @@ impl BlockCompressorBC15 {
 
         let rnorm_sq = norm_sq.recip();
         for p in 0..3 {
-            c0[p] = f32::clamp(dc[p] + min_dot * rnorm_sq * axis[p], 0.0, 255.0);
-            c1[p] = f32::clamp(dc[p] + max_dot * rnorm_sq * axis[p], 0.0, 255.0);
+            let direction = axis[p] / scale[p];
+            c0[p] = f32::clamp(dc[p] + min_dot * rnorm_sq * direction, 0.0, 255.0);
+            c1[p] = f32::clamp(dc[p] + max_dot * rnorm_sq * direction, 0.0, 255.0);
         }
     }
 
@@ -250,7 +337,10 @@ impl BlockCompressorBC15 {
         (r5 << 11) + (g6 << 5) + b5
     }
 
-    fn fast_quant(&self, p0: i32, p1: i32) -> u32 {
+    /// Projects the pixels onto the line between both endpoints in the weighted color space and
+    /// quantizes them to `steps + 1` evenly spaced positions. The returned bits store the position
+    /// of every pixel with 0 being the first endpoint.
+    fn fast_quant(&self, p0: i32, p1: i32, steps: i32) -> u32 {
         let mut c0 = [0.0; 3];
         let mut c1 = [0.0; 3];
         Self::dec_rgb565(&mut c0, p0);
@@ -262,14 +352,18 @@ impl BlockCompressorBC15 {
         }
 
         let mut sq_norm = 0.0;
-        for value in dir.iter() {
-            sq_norm += value.powi(2);
+        for (value, weight) in dir.iter().zip(self.channel_weights) {
+            sq_norm += weight * value.powi(2);
+        }
+
+        if sq_norm == 0.0 {
+            return 0;
         }
 
         let rsq_norm = sq_norm.recip();
 
-        for value in dir.iter_mut() {
-            *value *= rsq_norm * 3.0;
+        for (value, weight) in dir.iter_mut().zip(self.channel_weights) {
+            *value *= weight * rsq_norm * steps as f32;
         }
 
         let mut bias = 0.5;
@@ -285,7 +379,7 @@ impl BlockCompressorBC15 {
                 dot += self.block[k + p * 16] * value;
             }
 
-            let q = i32::clamp((dot + bias) as i32, 0, 3);
+            let q = i32::clamp((dot + bias) as i32, 0, steps);
             bits += q as u32 * scaler;
             scaler = scaler.wrapping_mul(4);
         }
@@ -293,45 +387,54 @@ impl BlockCompressorBC15 {
         bits
     }
 
-    fn bc1_refine(&self, pe: &mut [i32; 2], bits: u32, dc: &[f32; 3]) {
+    fn bc1_refine(&self, pe: &mut [i32; 2], bits: u32, dc: &[f32; 3], steps: i32) {
+        let steps = steps as f32;
         let mut c0 = [0.0; 3];
         let mut c1 = [0.0; 3];
 
-        if (bits ^ (bits.wrapping_mul(4))) < 4 {
-            c0.copy_from_slice(&dc[..]);
-            c1.copy_from_slice(&dc[..]);
-        } else {
-            let mut atb1 = [0.0; 3];
-            let mut sum_q = 0.0;
-            let mut sum_qq = 0.0;
-            let mut shifted_bits = bits;
+        let mut atb1 = [0.0; 3];
+        let mut sum_q = 0.0;
+        let mut sum_qq = 0.0;
+        let mut shifted_bits = bits;
+
+        for k in 0..16 {
+            let q = (shifted_bits & 3) as f32;
+            shifted_bits >>= 2;
 
-            for k in 0..16 {
-                let q = (shifted_bits & 3) as f32;
-                shifted_bits >>= 2;
+            // Padding pixels outside the image don't contribute to the fit.
+            if !self.is_valid_pixel(k) {
+                continue;
+            }
 
-                let x = 3.0 - q;
+            let x = steps - q;
 
-                sum_q += q;
-                sum_qq += q * q;
+            sum_q += q;
+            sum_qq += q * q;
 
-                for (p, value) in atb1.iter_mut().enumerate() {
-                    *value += x * self.block[k + p * 16];
-                }
+            for (p, value) in atb1.iter_mut().enumerate() {
+                *value += x * self.block[k + p * 16];
             }
+        }
 
+        let pixel_count = self.valid_pixel_count();
+
+        // All pixels share the same position, so there is no line to fit.
+        if sum_q * sum_q == sum_qq * pixel_count {
+            c0.copy_from_slice(&dc[..]);
+            c1.copy_from_slice(&dc[..]);
+        } else {
             let mut sum = [0.0; 3];
             let mut atb2 = [0.0; 3];
 
             for p in 0..3 {
-                sum[p] = dc[p] * 16.0;
-                atb2[p] = 3.0 * sum[p] - atb1[p];
+                sum[p] = dc[p] * pixel_count;
+                atb2[p] = steps * sum[p] - atb1[p];
             }
 
-            let cxx = 16.0 * 9.0 - 2.0 * 3.0 * sum_q + sum_qq;
+            let cxx = pixel_count * steps * steps - 2.0 * steps * sum_q + sum_qq;
             let cyy = sum_qq;
-            let cxy = 3.0 * sum_q - sum_qq;
-            let scale = 3.0 * (cxx * cyy - cxy * cxy).recip();
+            let cxy = steps * sum_q - sum_qq;
+            let scale = steps * (cxx * cyy - cxy * cxy).recip();
 
             for p in 0..3 {
                 c0[p] = (atb1[p] * cyy - atb2[p] * cxy) * scale;
@@ -356,6 +459,42 @@ impl BlockCompressorBC15 {
         (qbits1 >> 1) + (qbits1 ^ (qbits0 << 1))
     }
 
+    /// Maps the line positions of the three color mode to the palette indices and sets the index
+    /// of the transparent pixels to 3.
+    fn fix_qbits_3color(qbits: u32, transparent_mask: u32) -> u32 {
+        const MASK_01B: u32 = 0x55555555;
+
+        // Position 1 is the midpoint with index 2 and position 2 is the second endpoint.
+        let mut indices = ((qbits & MASK_01B) << 1) | ((qbits >> 1) & MASK_01B);
+
+        for k in 0..16 {
+            if transparent_mask & (1 << k) != 0 {
+                indices |= 3 << (k * 2);
+            }
+        }
+
+        indices
+    }
+
+    /// Returns the bit mask of the pixels with an alpha value below the threshold.
+    fn transparent_mask(&self) -> u32 {
+        let threshold = self.alpha_threshold as f32;
+
+        (0..16)
+            .filter(|&k| self.block[48 + k] < threshold)
+            .fold(0, |mask, k| mask | (1 << k))
+    }
+
+    pub(crate) fn compress_block_bc1(&mut self) -> [u32; 2] {
+        let transparent_mask = self.transparent_mask();
+
+        if transparent_mask == 0 {
+            self.compress_block_bc1_core()
+        } else {
+            self.compress_block_bc1_punch_through(transparent_mask)
+        }
+    }
+
     pub(crate) fn compress_block_bc1_core(&self) -> [u32; 2] {
         let power_iterations = 4;
         let refine_iterations = 1;
@@ -385,15 +524,15 @@ impl BlockCompressorBC15 {
 
         let mut data = [0; 2];
         data[0] = ((p[1] as u32) << 16) | p[0] as u32;
-        data[1] = self.fast_quant(p[0], p[1]);
+        data[1] = self.fast_quant(p[0], p[1], 3);
 
         for _ in 0..refine_iterations {
-            self.bc1_refine(&mut p, data[1], &dc);
+            self.bc1_refine(&mut p, data[1], &dc, 3);
             if p[0] < p[1] {
                 p.swap(0, 1);
             }
             data[0] = ((p[1] as u32) << 16) | p[0] as u32;
-            data[1] = self.fast_quant(p[0], p[1]);
+            data[1] = self.fast_quant(p[0], p[1], 3);
         }
 
         data[1] = Self::fix_qbits(data[1]);
@@ -401,8 +540,70 @@ impl BlockCompressorBC15 {
         data
     }
 
+    /// Compresses the block with the three color mode of BC1, which stores transparent black as
+    /// the fourth palette entry. The transparent pixels are not considered when fitting the
+    /// endpoints.
+    fn compress_block_bc1_punch_through(&mut self, transparent_mask: u32) -> [u32; 2] {
+        let power_iterations = 4;
+        let refine_iterations = 1;
+
+        self.valid_mask &= !transparent_mask;
+
+        if self.valid_mask == 0 {
+            return [0, u32::MAX];
+        }
+
+        let mut covar = [0.0; 6];
+        let mut dc = [0.0; 3];
+        self.compute_covar_dc(&mut covar, &mut dc);
+
+        const EPS: f32 = f32::EPSILON;
+        covar[0] += EPS;
+        covar[3] += EPS;
+        covar[5] += EPS;
+
+        let mut axis = [0.0; 3];
+        Self::compute_axis3(&mut axis, &covar, power_iterations);
+
+        let mut c0 = [0.0; 3];
+        let mut c1 = [0.0; 3];
+        self.pick_endpoints(&mut c0, &mut c1, &axis, &dc);
+
+        // The three color mode is selected by storing the smaller endpoint first.
+        let mut p = [0; 2];
+        p[0] = Self::enc_rgb565(&c0);
+        p[1] = Self::enc_rgb565(&c1);
+        if p[0] > p[1] {
+            p.swap(0, 1);
+        }
+
+        let mut bits = self.fast_quant(p[0], p[1], 2);
+
+        for _ in 0..refine_iterations {
+            self.bc1_refine(&mut p, bits, &dc, 2);
+            if p[0] > p[1] {
+                p.swap(0, 1);
+            }
+            bits = self.fast_quant(p[0], p[1], 2);
+        }
+
+        let mut data = [0; 2];
+        data[0] = ((p[1] as u32) << 16) | p[0] as u32;
+        data[1] = Self::fix_qbits_3color(bits, transparent_mask);
+
+        data
+    }
+
     pub(crate) fn compress_block_bc3_alpha(&self) -> [u32; 2] {
-        let mut ep = [255.0, 0.0];
+        self.compress_block_bc4_channel(false)
+    }
+
+    pub(crate) fn compress_block_bc4_snorm(&self) -> [u32; 2] {
+        self.compress_block_bc4_channel(true)
+    }
+
+    fn compress_block_bc4_channel(&self, signed: bool) -> [u32; 2] {
+        let mut ep = [f32::MAX, f32::MIN];
 
         // Find min/max endpoints using block[48] to block[63] for alpha
         for k in 0..16 {
@@ -435,8 +636,16 @@ impl BlockCompressorBC15 {
             qblock[k / 8] |= (q as u32) << ((k % 8) * 3);
         }
 
+        let endpoints = if signed {
+            let ep0 = i32::clamp(ep[0].round() as i32, -127, 127) as u32 & 0xFF;
+            let ep1 = i32::clamp(ep[1].round() as i32, -127, 127) as u32 & 0xFF;
+            (ep0 << 8) | ep1
+        } else {
+            (u32::clamp(ep[0] as u32, 0, 255) << 8) | u32::clamp(ep[1] as u32, 0, 255)
+        };
+
         let mut data = [0; 2];
-        data[0] = (u32::clamp(ep[0] as u32, 0, 255) << 8) | u32::clamp(ep[1] as u32, 0, 255);
+        data[0] = endpoints;
         data[0] |= qblock[0] << 16;
         data[1] = qblock[0] >> 16;
         data[1] |= qblock[1] << 8;
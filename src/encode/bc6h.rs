@@ -3,6 +3,7 @@ use crate::BC6HSettings;
 
 pub(crate) struct BlockCompressorBC6H<'a> {
     block: [f32; 64],
+    valid_mask: u32,
     data: [u32; 5],
     best_err: f32,
 
@@ -30,6 +31,7 @@ impl<'a> BlockCompressorBC6H<'a> {
     pub(crate) fn new(settings: &'a BC6HSettings) -> Self {
         Self {
             block: [0.0; 64],
+            valid_mask: 0xFFFF,
             data: [0; 5],
             best_err: f32::INFINITY,
             rgb_bounds: [0.0; 6],
@@ -47,12 +49,16 @@ impl<'a> BlockCompressorBC6H<'a> {
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
 
@@ -75,12 +81,16 @@ impl<'a> BlockCompressorBC6H<'a> {
         rgba_data: &[half::f16],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
 
@@ -96,21 +106,15 @@ impl<'a> BlockCompressorBC6H<'a> {
         }
     }
 
-    pub(crate) fn store_data(
-        &self,
-        blocks_buffer: &mut [u8],
-        block_width: usize,
-        xx: usize,
-        yy: usize,
-    ) {
-        let offset = (yy * block_width + xx) * 16;
+    pub(crate) fn store_data(&self, blocks_row: &mut [u8], xx: usize) {
+        let offset = xx * 16;
 
         for (index, &value) in self.data[..4].iter().enumerate() {
             let byte_offset = offset + index * 4;
-            blocks_buffer[byte_offset] = value as u8;
-            blocks_buffer[byte_offset + 1] = (value >> 8) as u8;
-            blocks_buffer[byte_offset + 2] = (value >> 16) as u8;
-            blocks_buffer[byte_offset + 3] = (value >> 24) as u8;
+            blocks_row[byte_offset] = value as u8;
+            blocks_row[byte_offset + 1] = (value >> 8) as u8;
+            blocks_row[byte_offset + 2] = (value >> 16) as u8;
+            blocks_row[byte_offset + 3] = (value >> 24) as u8;
         }
     }
 
@@ -150,11 +154,10 @@ impl<'a> BlockCompressorBC6H<'a> {
     }
 
     fn ep_quant_bc6h_8(&mut self, ep: &[f32; 8], bits: u32, pairs: u32) {
-        let levels = 1 << bits;
+        let signed = self.settings.signed != 0;
 
         for i in 0..8 * pairs as usize {
-            let v = (ep[i] / (256.0 * 256.0 - 1.0) * (levels - 1) as f32 + 0.5) as i32;
-            self.qbounds[i] = i32::clamp(v, 0, levels - 1);
+            self.qbounds[i] = Self::quantize(ep[i], bits, signed);
         }
     }
 
@@ -196,24 +199,60 @@ impl<'a> BlockCompressorBC6H<'a> {
         (v * 2 + 1) << (15 - bits)
     }
 
-    fn ep_quant_bc6h(qep: &mut [i32; 24], ep: &[f32; 24], bits: u32, pairs: usize) {
-        let levels = 1 << bits;
+    /// Unquantizes a signed endpoint and offsets it by 0x7FFF, so that the signed range
+    /// -0x7FFF to 0x7FFF maps to the same positive range the unsigned encoding works with.
+    fn unpack_to_sf16(v: i32, bits: u32) -> i32 {
+        let magnitude = v.abs();
+
+        let unquantized = if bits >= 16 || magnitude == 0 {
+            magnitude
+        } else if magnitude >= (1 << (bits - 1)) - 1 {
+            0x7FFF
+        } else {
+            ((magnitude << 15) + 0x4000) >> (bits - 1)
+        };
+
+        0x7FFF + unquantized * v.signum()
+    }
 
+    fn quantize(value: f32, bits: u32, signed: bool) -> i32 {
+        if signed {
+            let max = (1 << (bits - 1)) - 1;
+            let v = value - 32767.0;
+            let q = i32::min((f32::abs(v) / 32767.0 * max as f32 + 0.5) as i32, max);
+
+            if v < 0.0 {
+                -q
+            } else {
+                q
+            }
+        } else {
+            let levels = 1 << bits;
+            let v = (value / (256.0 * 256.0 - 1.0) * (levels - 1) as f32 + 0.5) as i32;
+            i32::clamp(v, 0, levels - 1)
+        }
+    }
+
+    fn ep_quant_bc6h(qep: &mut [i32; 24], ep: &[f32; 24], bits: u32, pairs: usize, signed: bool) {
         for i in 0..8 * pairs {
-            let v = (ep[i] / (256.0 * 256.0 - 1.0) * (levels - 1) as f32 + 0.5) as i32;
-            qep[i] = i32::clamp(v, 0, levels - 1);
+            qep[i] = Self::quantize(ep[i], bits, signed);
         }
     }
 
-    fn ep_dequant_bc6h(ep: &mut [f32; 24], qep: &[i32; 24], bits: u32, pairs: usize) {
+    fn ep_dequant_bc6h(ep: &mut [f32; 24], qep: &[i32; 24], bits: u32, pairs: usize, signed: bool) {
         for i in 0..8 * pairs {
-            ep[i] = Self::unpack_to_uf16(qep[i] as u32, bits) as f32;
+            ep[i] = if signed {
+                Self::unpack_to_sf16(qep[i], bits) as f32
+            } else {
+                Self::unpack_to_uf16(qep[i] as u32, bits) as f32
+            };
         }
     }
 
     fn ep_quant_dequant_bc6h(&self, qep: &mut [i32; 24], ep: &mut [f32; 24], pairs: usize) {
         let bits = self.epb;
-        Self::ep_quant_bc6h(qep, ep, bits, pairs);
+        let signed = self.settings.signed != 0;
+        Self::ep_quant_bc6h(qep, ep, bits, pairs, signed);
 
         for i in 0..2 * pairs {
             for p in 0..3 {
@@ -221,13 +260,22 @@ impl<'a> BlockCompressorBC6H<'a> {
             }
         }
 
-        Self::ep_dequant_bc6h(ep, qep, bits, pairs);
+        Self::ep_dequant_bc6h(ep, qep, bits, pairs, signed);
+    }
+
+    /// Signed endpoints are stored as two's complement values with `epb` bits.
+    fn mask_signed_endpoints(&self, qep: &mut [i32; 24]) {
+        if self.settings.signed != 0 {
+            let mask = (1 << self.epb) - 1;
+            qep.iter_mut().for_each(|value| *value &= mask);
+        }
     }
 
     fn bc6h_code_2p(&mut self, qep: &mut [i32; 24], qblock: [u32; 2], part_id: i32, mode: usize) {
         let bits = 3;
 
         let flips = bc7_code_apply_swap_mode01237(qep, qblock, 1, part_id);
+        self.mask_signed_endpoints(qep);
 
         self.data = [0; 5];
         let mut pos = 0;
@@ -253,6 +301,7 @@ impl<'a> BlockCompressorBC6H<'a> {
 
     fn bc6h_code_1p(&mut self, qep: &mut [i32; 24], qblock: &mut [u32; 2], mode: usize) {
         bc7_code_apply_swap_mode456(qep, 4, qblock, 4);
+        self.mask_signed_endpoints(qep);
 
         self.data = [0; 5];
         let mut pos = 0;
@@ -273,11 +322,11 @@ impl<'a> BlockCompressorBC6H<'a> {
 
     fn bc6h_enc_2p(&mut self) {
         let mut full_stats = [0.0; 15];
-        compute_stats_masked(&mut full_stats, &self.block, 0xFFFFFFFF, 3);
+        compute_stats_masked(&mut full_stats, &self.block, self.valid_mask, 3);
 
         let mut part_list = [0; 32];
         for part in 0..32 {
-            let mask = get_pattern_mask(part, 0);
+            let mask = get_pattern_mask(part, 0) & self.valid_mask;
             let bound12 = block_pca_bound_split(&self.block, mask, full_stats, 3);
             let bound = bound12 as i32;
             part_list[part as usize] = part + bound * 64;
@@ -300,13 +349,13 @@ impl<'a> BlockCompressorBC6H<'a> {
 
         let mut ep = [0.0; 24];
         for j in 0..pairs as usize {
-            let mask = get_pattern_mask(part_id, j as u32);
+            let mask = mask_valid_pixels(get_pattern_mask(part_id, j as u32), self.valid_mask);
             block_segment_core(&mut ep[j * 8..], &self.block, mask, channels);
         }
 
         self.ep_quant_dequant_bc6h(qep, &mut ep, 2);
 
-        block_quant(qblock, &self.block, bits, &ep, pattern, channels)
+        block_quant(qblock, &self.block, bits, &ep, pattern, channels, &[1.0; 4])
     }
 
     fn bc6h_enc_2p_list(&mut self, part_list: &[i32; 32], part_count: u32) {
@@ -342,7 +391,8 @@ impl<'a> BlockCompressorBC6H<'a> {
         for _ in 0..self.settings.refine_iterations_2p {
             let mut ep = [0.0; 24];
             for j in 0..pairs {
-                let mask = get_pattern_mask(best_part_id, j as u32);
+                let mask =
+                    mask_valid_pixels(get_pattern_mask(best_part_id, j as u32), self.valid_mask);
                 opt_endpoints(
                     &mut ep[j * 8..],
                     &self.block,
@@ -358,7 +408,15 @@ impl<'a> BlockCompressorBC6H<'a> {
             self.ep_quant_dequant_bc6h(&mut qep, &mut ep, 2);
 
             let pattern = get_pattern(best_part_id);
-            let err = block_quant(&mut qblock, &self.block, bits, &ep, pattern, channels);
+            let err = block_quant(
+                &mut qblock,
+                &self.block,
+                bits,
+                &ep,
+                pattern,
+                channels,
+                &[1.0; 4],
+            );
 
             if err < best_err {
                 best_qep[..(8 * pairs)].copy_from_slice(&qep[..(8 * pairs)]);
@@ -375,20 +433,20 @@ impl<'a> BlockCompressorBC6H<'a> {
 
     fn bc6h_enc_1p(&mut self) {
         let mut ep = [0.0; 24];
-        block_segment_core(&mut ep, &self.block, 0xFFFFFFFF, 3);
+        block_segment_core(&mut ep, &self.block, self.valid_mask, 3);
 
         let mut qep = [0; 24];
         self.ep_quant_dequant_bc6h(&mut qep, &mut ep, 1);
 
         let mut qblock = [0; 2];
-        let mut err = block_quant(&mut qblock, &self.block, 4, &ep, 0, 3);
+        let mut err = block_quant(&mut qblock, &self.block, 4, &ep, 0, 3, &[1.0; 4]);
 
         // Refine
         let refine_iterations = self.settings.refine_iterations_1p;
         for _ in 0..refine_iterations {
-            opt_endpoints(&mut ep, &self.block, 4, qblock, 0xFFFFFFFF, 3);
+            opt_endpoints(&mut ep, &self.block, 4, qblock, self.valid_mask, 3);
             self.ep_quant_dequant_bc6h(&mut qep, &mut ep, 1);
-            err = block_quant(&mut qblock, &self.block, 4, &ep, 0, 3);
+            err = block_quant(&mut qblock, &self.block, 4, &ep, 0, 3, &[1.0; 4]);
         }
 
         if err < self.best_err {
@@ -785,7 +843,18 @@ impl<'a> BlockCompressorBC6H<'a> {
         // Find min/max bounds
         for p in 0..3 {
             for k in 0..16 {
-                let value = (self.block[p * 16 + k] / 31.0) * 64.0;
+                let value = if self.settings.signed != 0 {
+                    // Signed values are offset by 0x7FFF to stay positive
+                    let bits = self.block[p * 16 + k] as u32;
+                    let magnitude = ((bits & 0x7FFF) as f32 / 31.0) * 32.0;
+                    if bits & 0x8000 != 0 {
+                        32767.0 - magnitude
+                    } else {
+                        32767.0 + magnitude
+                    }
+                } else {
+                    (self.block[p * 16 + k] / 31.0) * 64.0
+                };
                 self.block[p * 16 + k] = value;
                 self.rgb_bounds[p] = f32::min(self.rgb_bounds[p], value);
                 self.rgb_bounds[3 + p] = f32::max(self.rgb_bounds[3 + p], value);
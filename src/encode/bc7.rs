@@ -13,9 +13,11 @@ struct Mode45Parameters {
 
 pub(crate) struct BlockCompressorBC7<'a> {
     block: [f32; 64],
+    valid_mask: u32,
     data: [u32; 5],
     best_err: f32,
     opaque_err: f32,
+    channel_weights: [f32; 4],
     settings: &'a BC7Settings,
 }
 
@@ -28,9 +30,14 @@ impl<'a> BlockCompressorBC7<'a> {
     pub(crate) fn new(settings: &'a BC7Settings) -> Self {
         Self {
             block: [0.0; 64],
+            valid_mask: 0xFFFF,
             data: [0; 5],
             best_err: f32::INFINITY,
             opaque_err: 0.0,
+            channel_weights: {
+                let [red, green, blue] = settings.channel_weights();
+                [red, green, blue, 1.0]
+            },
             settings,
         }
     }
@@ -40,12 +47,16 @@ impl<'a> BlockCompressorBC7<'a> {
         rgba_data: &[u8],
         xx: usize,
         yy: usize,
+        width: usize,
+        height: usize,
         stride: usize,
     ) {
+        self.valid_mask = super::valid_pixel_mask(xx, yy, width, height);
+
         for y in 0..4 {
             for x in 0..4 {
-                let pixel_x = xx * 4 + x;
-                let pixel_y = yy * 4 + y;
+                let pixel_x = usize::min(xx * 4 + x, width - 1);
+                let pixel_y = usize::min(yy * 4 + y, height - 1);
 
                 let offset = pixel_y * stride + pixel_x * 4;
 
@@ -62,21 +73,15 @@ impl<'a> BlockCompressorBC7<'a> {
         }
     }
 
-    pub(crate) fn store_data(
-        &self,
-        blocks_buffer: &mut [u8],
-        block_width: usize,
-        xx: usize,
-        yy: usize,
-    ) {
-        let offset = (yy * block_width + xx) * 16;
+    pub(crate) fn store_data(&self, blocks_row: &mut [u8], xx: usize) {
+        let offset = xx * 16;
 
         for (index, &value) in self.data[..4].iter().enumerate() {
             let byte_offset = offset + index * 4;
-            blocks_buffer[byte_offset] = value as u8;
-            blocks_buffer[byte_offset + 1] = (value >> 8) as u8;
-            blocks_buffer[byte_offset + 2] = (value >> 16) as u8;
-            blocks_buffer[byte_offset + 3] = (value >> 24) as u8;
+            blocks_row[byte_offset] = value as u8;
+            blocks_row[byte_offset + 1] = (value >> 8) as u8;
+            blocks_row[byte_offset + 2] = (value >> 16) as u8;
+            blocks_row[byte_offset + 3] = (value >> 24) as u8;
         }
     }
 
@@ -243,7 +248,7 @@ impl<'a> BlockCompressorBC7<'a> {
         // Refine
         let refine_iterations = self.settings.refine_iterations_channel;
         for _ in 0..refine_iterations {
-            Self::channel_opt_endpoints(&mut ep, channel_block, bits, *qblock);
+            Self::channel_opt_endpoints(&mut ep, channel_block, bits, *qblock, self.valid_mask);
             Self::channel_quant_dequant(qep, &mut ep, epbits);
             err = Self::channel_opt_quant(qblock, channel_block, bits, &ep);
         }
@@ -310,6 +315,7 @@ impl<'a> BlockCompressorBC7<'a> {
         channel_block: &[f32; 16],
         bits: u32,
         qblock: [u32; 2],
+        mask: u32,
     ) {
         let levels = 1 << bits;
 
@@ -317,7 +323,9 @@ impl<'a> BlockCompressorBC7<'a> {
         let mut sum_q = 0.0;
         let mut sum_qq = 0.0;
         let mut sum = 0.0;
+        let mut count = 0.0;
 
+        let mut mask_shifted = mask << 1;
         for k1 in 0..2 {
             let mut qbits_shifted = qblock[k1];
             for k2 in 0..8 {
@@ -325,6 +333,13 @@ impl<'a> BlockCompressorBC7<'a> {
                 let q = (qbits_shifted & 15) as f32;
                 qbits_shifted >>= 4;
 
+                mask_shifted >>= 1;
+                if (mask_shifted & 1) == 0 {
+                    continue;
+                }
+
+                count += 1.0;
+
                 let x = (levels - 1) as f32 - q;
 
                 sum_q += q;
@@ -337,7 +352,7 @@ impl<'a> BlockCompressorBC7<'a> {
 
         let atb2 = (levels - 1) as f32 * sum - atb1;
 
-        let cxx = 16.0 * sq((levels - 1) as f32) - 2.0 * (levels - 1) as f32 * sum_q + sum_qq;
+        let cxx = count * sq((levels - 1) as f32) - 2.0 * (levels - 1) as f32 * sum_q + sum_qq;
         let cyy = sum_qq;
         let cxy = (levels - 1) as f32 * sum_q - sum_qq;
         let scale = (levels - 1) as f32 / (cxx * cyy - cxy * cxy);
@@ -349,7 +364,7 @@ impl<'a> BlockCompressorBC7<'a> {
         ep[1] = f32::clamp(ep[1], 0.0, 255.0);
 
         if f32::abs(cxx * cyy - cxy * cxy) < 0.001 {
-            ep[0] = sum / 16.0;
+            ep[0] = sum / count;
             ep[1] = ep[0];
         }
     }
@@ -514,13 +529,21 @@ impl<'a> BlockCompressorBC7<'a> {
 
         let mut ep = [0.0; 24];
         for j in 0..pairs {
-            let mask = get_pattern_mask(part_id, j as u32);
+            let mask = mask_valid_pixels(get_pattern_mask(part_id, j as u32), self.valid_mask);
             Self::block_segment(&mut ep[j * 8..], &self.block, mask, channels);
         }
 
         Self::ep_quant_dequant(qep, &mut ep, mode, channels);
 
-        block_quant(qblock, &self.block, bits, &ep, pattern, channels)
+        block_quant(
+            qblock,
+            &self.block,
+            bits,
+            &ep,
+            pattern,
+            channels,
+            &self.channel_weights,
+        )
     }
 
     fn bc7_enc_mode01237(&mut self, mode: usize, part_list: &[i32; 64], part_count: usize) {
@@ -558,7 +581,8 @@ impl<'a> BlockCompressorBC7<'a> {
         for _ in 0..refine_iterations {
             let mut ep = [0.0; 24];
             for j in 0..pairs {
-                let mask = get_pattern_mask(best_part_id, j as u32);
+                let mask =
+                    mask_valid_pixels(get_pattern_mask(best_part_id, j as u32), self.valid_mask);
                 opt_endpoints(
                     &mut ep[j * 8..],
                     &self.block,
@@ -575,7 +599,15 @@ impl<'a> BlockCompressorBC7<'a> {
             Self::ep_quant_dequant(&mut qep, &mut ep, mode, channels);
 
             let pattern = get_pattern(best_part_id);
-            let err = block_quant(&mut qblock, &self.block, bits, &ep, pattern, channels);
+            let err = block_quant(
+                &mut qblock,
+                &self.block,
+                bits,
+                &ep,
+                pattern,
+                channels,
+                &self.channel_weights,
+            );
 
             if err < best_err {
                 best_qep[..(8 * pairs)].copy_from_slice(&qep[..(8 * pairs)]);
@@ -598,46 +630,45 @@ impl<'a> BlockCompressorBC7<'a> {
     fn bc7_enc_mode02(&mut self) {
         let part_list: [i32; 64] = std::array::from_fn(|part| part as i32);
 
-        self.bc7_enc_mode01237(0, &part_list, 16);
-
-        if self.settings.skip_mode2 == 0 {
+        if self.settings.mode_selection[0] != 0 {
+            self.bc7_enc_mode01237(0, &part_list, 16);
+        }
+        if self.settings.mode_selection[2] != 0 {
             self.bc7_enc_mode01237(2, &part_list, 64);
         }
     }
 
     fn bc7_enc_mode13(&mut self) {
-        if self.settings.fast_skip_threshold_mode1 == 0
-            && self.settings.fast_skip_threshold_mode3 == 0
-        {
+        let fast_skip_threshold_mode1 = if self.settings.mode_selection[1] != 0 {
+            self.settings.fast_skip_threshold_mode1
+        } else {
+            0
+        };
+        let fast_skip_threshold_mode3 = if self.settings.mode_selection[3] != 0 {
+            self.settings.fast_skip_threshold_mode3
+        } else {
+            0
+        };
+
+        if fast_skip_threshold_mode1 == 0 && fast_skip_threshold_mode3 == 0 {
             return;
         }
 
         let mut full_stats = [0.0; 15];
-        compute_stats_masked(&mut full_stats, &self.block, 0xFFFFFFFF, 3);
+        compute_stats_masked(&mut full_stats, &self.block, self.valid_mask, 3);
 
         let mut part_list = [0; 64];
         for part in 0..64 {
-            let mask = get_pattern_mask(part, 0);
+            let mask = get_pattern_mask(part, 0) & self.valid_mask;
             let bound12 = block_pca_bound_split(&self.block, mask, full_stats, 3);
             let bound = bound12 as i32;
             part_list[part as usize] = part + bound * 64;
         }
 
-        let partial_count = u32::max(
-            self.settings.fast_skip_threshold_mode1,
-            self.settings.fast_skip_threshold_mode3,
-        );
+        let partial_count = u32::max(fast_skip_threshold_mode1, fast_skip_threshold_mode3);
         partial_sort_list(&mut part_list, 64, partial_count);
-        self.bc7_enc_mode01237(
-            1,
-            &part_list,
-            self.settings.fast_skip_threshold_mode1 as usize,
-        );
-        self.bc7_enc_mode01237(
-            3,
-            &part_list,
-            self.settings.fast_skip_threshold_mode3 as usize,
-        );
+        self.bc7_enc_mode01237(1, &part_list, fast_skip_threshold_mode1 as usize);
+        self.bc7_enc_mode01237(3, &part_list, fast_skip_threshold_mode3 as usize);
     }
 
     fn bc7_enc_mode45_candidate(
@@ -681,21 +712,41 @@ impl<'a> BlockCompressorBC7<'a> {
             }
         }
 
+        // The rotated channel takes the weight of the alpha channel and vice versa.
+        let mut candidate_weights = self.channel_weights;
+        candidate_weights.swap(rotation as usize, 3);
+
         let mut ep = [0.0; 8];
-        Self::block_segment(&mut ep, &candidate_block, 0xFFFFFFFF, 3);
+        Self::block_segment(&mut ep, &candidate_block, self.valid_mask, 3);
 
         let mut qep = [0; 8];
         Self::ep_quant_dequant(&mut qep, &mut ep, mode, 3);
 
         let mut qblock = [0; 2];
-        let mut err = block_quant(&mut qblock, &candidate_block, bits, &ep, 0, 3);
+        let mut err = block_quant(
+            &mut qblock,
+            &candidate_block,
+            bits,
+            &ep,
+            0,
+            3,
+            &candidate_weights,
+        );
 
         // Refine
         let refine_iterations = self.settings.refine_iterations[mode];
         for _ in 0..refine_iterations {
-            opt_endpoints(&mut ep, &candidate_block, bits, qblock, 0xFFFFFFFF, 3);
+            opt_endpoints(&mut ep, &candidate_block, bits, qblock, self.valid_mask, 3);
             Self::ep_quant_dequant(&mut qep, &mut ep, mode, 3);
-            err = block_quant(&mut qblock, &candidate_block, bits, &ep, 0, 3);
+            err = block_quant(
+                &mut qblock,
+                &candidate_block,
+                bits,
+                &ep,
+                0,
+                3,
+                &candidate_weights,
+            );
         }
 
         let channel_data: [f32; 16] =
@@ -705,7 +756,8 @@ impl<'a> BlockCompressorBC7<'a> {
         let mut aqep = [0; 2];
         let mut aqblock = [0; 2];
 
-        err += self.opt_channel(&mut aqblock, &mut aqep, &channel_data, abits, aepbits);
+        err += candidate_weights[3]
+            * self.opt_channel(&mut aqblock, &mut aqep, &channel_data, abits, aepbits);
 
         if err < *best_err {
             best_candidate.qep.copy_from_slice(&qep[..8]);
@@ -723,9 +775,11 @@ impl<'a> BlockCompressorBC7<'a> {
         let mut best_err = self.best_err;
 
         let channel0 = self.settings.mode45_channel0;
-        for p in channel0..self.settings.channels {
-            self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 4, p, 0);
-            self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 4, p, 1);
+        if self.settings.mode_selection[4] != 0 {
+            for p in channel0..self.settings.channels {
+                self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 4, p, 0);
+                self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 4, p, 1);
+            }
         }
 
         // Mode 4
@@ -734,8 +788,10 @@ impl<'a> BlockCompressorBC7<'a> {
             self.bc7_code_mode45(&best_candidate, 4);
         }
 
-        for p in channel0..self.settings.channels {
-            self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 5, p, 0);
+        if self.settings.mode_selection[5] != 0 {
+            for p in channel0..self.settings.channels {
+                self.bc7_enc_mode45_candidate(&mut best_candidate, &mut best_err, 5, p, 0);
+            }
         }
 
         // Mode 5
@@ -753,7 +809,7 @@ impl<'a> BlockCompressorBC7<'a> {
         Self::block_segment(
             &mut ep,
             &self.block,
-            0xFFFFFFFF,
+            self.valid_mask,
             self.settings.channels as usize,
         );
 
@@ -773,6 +829,7 @@ impl<'a> BlockCompressorBC7<'a> {
             &ep,
             0,
             self.settings.channels as usize,
+            &self.channel_weights,
         );
 
         let refine_iterations = self.settings.refine_iterations[MODE];
@@ -782,7 +839,7 @@ impl<'a> BlockCompressorBC7<'a> {
                 &self.block,
                 BITS,
                 qblock,
-                0xFFFFFFFF,
+                self.valid_mask,
                 self.settings.channels as usize,
             );
             Self::ep_quant_dequant(&mut qep, &mut ep, MODE, self.settings.channels as usize);
@@ -793,6 +850,7 @@ impl<'a> BlockCompressorBC7<'a> {
                 &ep,
                 0,
                 self.settings.channels as usize,
+                &self.channel_weights,
             );
         }
 
@@ -803,7 +861,7 @@ impl<'a> BlockCompressorBC7<'a> {
     }
 
     fn bc7_enc_mode7(&mut self) {
-        if self.settings.fast_skip_threshold_mode7 == 0 {
+        if self.settings.mode_selection[7] == 0 || self.settings.fast_skip_threshold_mode7 == 0 {
             return;
         }
 
@@ -811,13 +869,13 @@ impl<'a> BlockCompressorBC7<'a> {
         compute_stats_masked(
             &mut full_stats,
             &self.block,
-            0xFFFFFFFF,
+            self.valid_mask,
             self.settings.channels as usize,
         );
 
         let mut part_list = [0; 64];
         for part in 0..64 {
-            let mask = get_pattern_mask(part, 0);
+            let mask = get_pattern_mask(part, 0) & self.valid_mask;
             let bound12 = block_pca_bound_split(
                 &self.block,
                 mask,
@@ -837,17 +895,11 @@ impl<'a> BlockCompressorBC7<'a> {
     }
 
     pub(crate) fn compress_block_bc7_core(&mut self) {
-        if self.settings.mode_selection[0] != 0 {
-            self.bc7_enc_mode02();
-        }
-        if self.settings.mode_selection[1] != 0 {
-            self.bc7_enc_mode13();
-            self.bc7_enc_mode7();
-        }
-        if self.settings.mode_selection[2] != 0 {
-            self.bc7_enc_mode45();
-        }
-        if self.settings.mode_selection[3] != 0 {
+        self.bc7_enc_mode02();
+        self.bc7_enc_mode13();
+        self.bc7_enc_mode7();
+        self.bc7_enc_mode45();
+        if self.settings.mode_selection[6] != 0 {
             self.bc7_enc_mode6();
         }
     }
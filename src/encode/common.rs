@@ -82,6 +82,19 @@ pub(crate) fn get_pattern_mask(part_id: i32, j: u32) -> u32 {
     }
 }
 
+/// Restricts a pixel `mask` to the pixels that lie inside the image.
+///
+/// Falls back to the unrestricted `mask` if none of its pixels are inside the image, so that
+/// the fitting functions never operate on an empty set of pixels.
+pub(crate) fn mask_valid_pixels(mask: u32, valid_mask: u32) -> u32 {
+    let masked = mask & valid_mask;
+    if masked != 0 {
+        masked
+    } else {
+        mask
+    }
+}
+
 pub(crate) fn get_skips(part_id: i32) -> [u32; 3] {
     const SKIP_TABLE: [u32; 128] = [
         0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0, 0xF0,
@@ -426,6 +439,7 @@ pub(crate) fn block_quant(
     ep: &[f32],
     pattern: u32,
     channels: usize,
+    weights: &[f32; 4],
 ) -> f32 {
     let mut total_err = 0.0;
     let levels = 1 << bits;
@@ -443,8 +457,8 @@ pub(crate) fn block_quant(
         for p in 0..channels {
             let ep_a = ep[8 * j + p];
             let ep_b = ep[8 * j + 4 + p];
-            proj += (block[k + p * 16] - ep_a) * (ep_b - ep_a);
-            div += sq(ep_b - ep_a);
+            proj += weights[p] * (block[k + p * 16] - ep_a) * (ep_b - ep_a);
+            div += weights[p] * sq(ep_b - ep_a);
         }
 
         proj /= div;
@@ -462,8 +476,8 @@ pub(crate) fn block_quant(
             let ep_b = ep[8 * j + 4 + p];
             let dec_v0 = (((64 - w0) * ep_a as i32 + w0 * ep_b as i32 + 32) / 64) as f32;
             let dec_v1 = (((64 - w1) * ep_a as i32 + w1 * ep_b as i32 + 32) / 64) as f32;
-            err0 += sq(dec_v0 - block[k + p * 16]);
-            err1 += sq(dec_v1 - block[k + p * 16]);
+            err0 += weights[p] * sq(dec_v0 - block[k + p * 16]);
+            err1 += weights[p] * sq(dec_v1 - block[k + p * 16]);
         }
 
         let mut best_err = err1;
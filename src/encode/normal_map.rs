@@ -0,0 +1,189 @@
+use crate::{
+    decode::{normal_map_channels, try_decompress_normal_map_as_rgba32f},
+    metrics::{unpack_normal, AngularErrorMetrics},
+    CompressionVariant, Error,
+};
+
+/// Compresses a tangent-space normal map stored as raw RGBA8 data.
+///
+/// The normals are read from the red, green and blue channel as `n * 0.5 + 0.5` and
+/// renormalized, normals pointing below the surface are clamped onto the horizon. Only their X
+/// and Y component are stored, the Z component is reconstructed by
+/// [`decode::decompress_normal_map_as_rgba8()`] and [`decode::decompress_normal_map_as_rgba32f()`]:
+///
+/// * BC5 and signed BC5 store X in red and Y in green.
+/// * BC3 stores X in alpha and Y in green, red is 255 and blue is 0 (the "DXT5nm" layout).
+/// * BC7 stores X in red and Y in green, blue is 0 and alpha is 255.
+///
+/// The input alpha channel is ignored. See [`compress_rgba8()`] for the data layout and buffer
+/// requirements.
+///
+/// Returns the angle between the renormalized and the decoded normals.
+///
+/// # Panics
+/// * If [`try_compress_normal_map_rgba8()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{
+///     decode::decompress_normal_map_as_rgba8, encode::compress_normal_map_rgba8,
+///     CompressionVariant,
+/// };
+///
+/// let rgba_data = [128u8, 128, 255, 255].repeat(256 * 256); // Your normal map
+/// let width = 256;
+/// let height = 256;
+/// let variant = CompressionVariant::BC5;
+///
+/// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
+///
+/// let metrics = compress_normal_map_rgba8(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     width * 4,
+/// );
+/// assert!(metrics.max_angle() < 1.0);
+///
+/// let mut normals = vec![0u8; rgba_data.len()];
+/// decompress_normal_map_as_rgba8(variant, width, height, &blocks_buffer, &mut normals);
+/// ```
+///
+/// [`compress_rgba8()`]: super::compress_rgba8
+/// [`decode::decompress_normal_map_as_rgba8()`]: crate::decode::decompress_normal_map_as_rgba8
+/// [`decode::decompress_normal_map_as_rgba32f()`]: crate::decode::decompress_normal_map_as_rgba32f
+pub fn compress_normal_map_rgba8(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> AngularErrorMetrics {
+    match try_compress_normal_map_rgba8(variation, rgba_data, blocks_buffer, width, height, stride)
+    {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compresses a tangent-space normal map stored as raw RGBA8 data.
+///
+/// This is the fallible version of [`compress_normal_map_rgba8()`].
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is not `CompressionVariant::BC3`,
+///   `CompressionVariant::BC5`, `CompressionVariant::BC5Snorm` or `CompressionVariant::BC7`
+/// * The same errors as [`try_compress_rgba8()`]
+///
+/// [`try_compress_rgba8()`]: super::try_compress_rgba8
+pub fn try_compress_normal_map_rgba8(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+) -> Result<AngularErrorMetrics, Error> {
+    let channels = normal_map_channels(variation).ok_or(Error::UnsupportedVariant)?;
+    super::validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size =
+        super::validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let blocks_buffer = &mut blocks_buffer[..required_size];
+    let width = width as usize;
+    let height = height as usize;
+
+    let normals: Vec<[f32; 3]> = (0..height)
+        .flat_map(|y| rgba_data[y * stride as usize..][..width * 4].chunks_exact(4))
+        .map(unpack_normal)
+        .collect();
+
+    compress_normals(variation, &normals, channels, blocks_buffer, width, height);
+
+    let mut decoded = vec![0.0; width * height * 4];
+    try_decompress_normal_map_as_rgba32f(
+        variation,
+        width as u32,
+        height as u32,
+        blocks_buffer,
+        &mut decoded,
+    )?;
+
+    let mut metrics = AngularErrorMetrics::default();
+    for (&original, decoded) in normals.iter().zip(decoded.chunks_exact(4)) {
+        metrics.add_normal(original, [decoded[0], decoded[1], decoded[2]]);
+    }
+
+    Ok(metrics)
+}
+
+/// Compresses the normals into the channels of `variation` which store their X and Y component.
+fn compress_normals(
+    variation: CompressionVariant,
+    normals: &[[f32; 3]],
+    channels: [usize; 2],
+    blocks_buffer: &mut [u8],
+    width: usize,
+    height: usize,
+) {
+    let stride = width * 4;
+
+    match variation {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC3(settings) => super::compress_bc3(
+            &swizzle_unorm(normals, channels),
+            blocks_buffer,
+            width,
+            height,
+            stride,
+            &settings,
+            &mut [],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 => super::compress_bc5(
+            &swizzle_unorm(normals, channels),
+            blocks_buffer,
+            width,
+            height,
+            stride,
+            &mut [],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5Snorm => {
+            let snorm_data: Vec<i8> = normals
+                .iter()
+                .flat_map(|&[x, y, _]| [x, y, 0.0, 0.0].map(|value| (value * 127.0).round() as i8))
+                .collect();
+
+            super::compress_bc5_snorm(&snorm_data, blocks_buffer, width, height, stride)
+        }
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(settings) => super::compress_bc7(
+            &swizzle_unorm(normals, channels),
+            blocks_buffer,
+            width,
+            height,
+            stride,
+            &settings,
+            &mut [],
+        ),
+        #[allow(unreachable_patterns)]
+        _ => unreachable!("only normal map variants are compressed"),
+    }
+}
+
+/// Writes the X and Y component of the normals as `n * 0.5 + 0.5` into the given channels of
+/// tightly packed RGBA8 data. The other channels are 255 for red and alpha and 0 for blue.
+fn swizzle_unorm(normals: &[[f32; 3]], [x_channel, y_channel]: [usize; 2]) -> Vec<u8> {
+    let mut rgba_data = [255, 0, 0, 255].repeat(normals.len());
+
+    for (pixel, &[x, y, _]) in rgba_data.chunks_exact_mut(4).zip(normals) {
+        pixel[x_channel] = ((x * 0.5 + 0.5) * 255.0).round() as u8;
+        pixel[y_channel] = ((y * 0.5 + 0.5) * 255.0).round() as u8;
+    }
+
+    rgba_data
+}
@@ -0,0 +1,565 @@
+#[cfg(feature = "bc7")]
+use crate::BC7Settings;
+#[cfg(feature = "bc6h")]
+use crate::{metrics::hdr_peak, BC6HSettings};
+use crate::{metrics::ErrorMetrics, CompressionVariant, Error};
+
+#[cfg(feature = "bc6h")]
+const BC6H_PRESETS: [BC6HSettings; 5] = [
+    BC6HSettings::very_fast(),
+    BC6HSettings::fast(),
+    BC6HSettings::basic(),
+    BC6HSettings::slow(),
+    BC6HSettings::very_slow(),
+];
+
+#[cfg(feature = "bc7")]
+const BC7_OPAQUE_PRESETS: [BC7Settings; 5] = [
+    BC7Settings::opaque_ultra_fast(),
+    BC7Settings::opaque_very_fast(),
+    BC7Settings::opaque_fast(),
+    BC7Settings::opaque_basic(),
+    BC7Settings::opaque_slow(),
+];
+
+#[cfg(feature = "bc7")]
+const BC7_ALPHA_PRESETS: [BC7Settings; 5] = [
+    BC7Settings::alpha_ultrafast(),
+    BC7Settings::alpha_very_fast(),
+    BC7Settings::alpha_fast(),
+    BC7Settings::alpha_basic(),
+    BC7Settings::alpha_slow(),
+];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Criterion {
+    MinPsnr(f64),
+    MaxError(f64),
+}
+
+/// The quality that [`compress_rgba8_to_quality()`] and [`compress_rgba16_to_quality()`] try to
+/// reach with the cheapest preset.
+///
+/// By default the whole image is compressed with a single preset. With a tile size, every tile
+/// of the image escalates through the presets on its own, so that only the tiles with difficult
+/// content are compressed with the slow presets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct QualityTarget {
+    criterion: Criterion,
+    tile_size: u32,
+}
+
+impl QualityTarget {
+    /// The PSNR in dB of the stored channels must be at least `psnr`.
+    pub const fn min_psnr(psnr: f64) -> Self {
+        Self {
+            criterion: Criterion::MinPsnr(psnr),
+            tile_size: 0,
+        }
+    }
+
+    /// The absolute error of any stored channel of any pixel must not exceed `error`.
+    ///
+    /// The error is measured on channel values normalized to the range of 0.0 to 1.0 for RGBA8
+    /// images and on the float values for RGBA16 images.
+    pub const fn max_error(error: f64) -> Self {
+        Self {
+            criterion: Criterion::MaxError(error),
+            tile_size: 0,
+        }
+    }
+
+    /// Selects the preset for every `tile_size` x `tile_size` pixel tile separately.
+    ///
+    /// The tile size must be a multiple of 4. A tile size of 0 selects one preset for the whole
+    /// image, which is the default.
+    pub const fn with_tile_size(mut self, tile_size: u32) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    /// The tile size in pixels, or 0 if one preset is selected for the whole image.
+    pub const fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// Returns `true` if the measured `metrics` meet the target.
+    pub fn is_met(&self, metrics: &ErrorMetrics) -> bool {
+        match self.criterion {
+            Criterion::MinPsnr(psnr) => metrics.psnr() >= psnr,
+            Criterion::MaxError(error) => metrics.max_error() <= error,
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let value = match self.criterion {
+            Criterion::MinPsnr(psnr) => psnr,
+            Criterion::MaxError(error) => error,
+        };
+
+        if value.is_nan() {
+            return Err(Error::InvalidSettings {
+                reason: "the quality target must not be NaN",
+            });
+        }
+
+        if self.tile_size % 4 != 0 {
+            return Err(Error::InvalidSettings {
+                reason: "the tile size must be a multiple of 4",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The preset selected for a tile of the image.
+#[derive(Copy, Clone, Debug)]
+pub struct TileSelection {
+    /// The x coordinate of the tile in pixels.
+    pub x: u32,
+    /// The y coordinate of the tile in pixels.
+    pub y: u32,
+    /// The width of the tile in pixels.
+    pub width: u32,
+    /// The height of the tile in pixels.
+    pub height: u32,
+    /// The selected compression variant and settings.
+    pub variant: CompressionVariant,
+    /// The index of the selected preset, starting with 0 for the cheapest preset.
+    pub preset: usize,
+    /// The error metrics of the tile compressed with the selected preset.
+    pub metrics: ErrorMetrics,
+    /// Whether the selected preset meets the quality target. If no preset meets the target, the
+    /// slowest preset is selected.
+    pub target_met: bool,
+}
+
+/// The presets selected by [`compress_rgba8_to_quality()`] and [`compress_rgba16_to_quality()`].
+#[derive(Clone, Debug)]
+pub struct QualitySelection {
+    image: ErrorMetrics,
+    tiles: Vec<TileSelection>,
+    tiles_per_row: u32,
+}
+
+impl QualitySelection {
+    /// The error metrics of the whole compressed image.
+    pub fn image(&self) -> &ErrorMetrics {
+        &self.image
+    }
+
+    /// The selected preset of every tile in row-major order. Contains a single tile if the
+    /// preset is selected for the whole image.
+    pub fn tiles(&self) -> &[TileSelection] {
+        &self.tiles
+    }
+
+    /// The number of tiles per row of the image.
+    pub fn tiles_per_row(&self) -> u32 {
+        self.tiles_per_row
+    }
+
+    /// The compression variant with the slowest preset selected for any tile, or `None` if the
+    /// image is empty.
+    pub fn variant(&self) -> Option<CompressionVariant> {
+        self.tiles
+            .iter()
+            .max_by_key(|tile| tile.preset)
+            .map(|tile| tile.variant)
+    }
+
+    /// Returns `true` if all tiles meet the quality target.
+    pub fn target_met(&self) -> bool {
+        self.tiles.iter().all(|tile| tile.target_met)
+    }
+}
+
+/// Compresses raw RGBA8 data with the cheapest preset that meets the quality `target`.
+///
+/// The presets of `variation` are tried from the fastest to the slowest: the BC7 opaque or
+/// alpha presets, depending on whether the BC7 settings encode alpha, or the BC6H presets. The
+/// channel weights of the given BC7 settings and the signedness of the given BC6H settings are
+/// kept. Every preset is compressed and measured block by block like
+/// [`compress_rgba8_with_metrics()`] until the target is met. The blocks of the selected presets
+/// are written into `blocks_buffer`.
+///
+/// See [`compress_rgba8()`] for the data layout and buffer requirements.
+///
+/// # Panics
+/// * If [`try_compress_rgba8_to_quality()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{
+///     encode::{compress_rgba8_to_quality, QualityTarget},
+///     BC7Settings, CompressionVariant,
+/// };
+///
+/// let rgba_data = vec![0u8; 256 * 256 * 4]; // Your RGBA data
+/// let width = 256;
+/// let height = 256;
+/// let variant = CompressionVariant::BC7(BC7Settings::alpha_basic());
+///
+/// let mut blocks_buffer = vec![0u8; variant.blocks_byte_size(width, height)];
+///
+/// let selection = compress_rgba8_to_quality(
+///     variant,
+///     &rgba_data,
+///     &mut blocks_buffer,
+///     width,
+///     height,
+///     width * 4,
+///     QualityTarget::min_psnr(45.0).with_tile_size(64),
+/// );
+///
+/// assert!(selection.target_met());
+/// ```
+///
+/// [`compress_rgba8()`]: super::compress_rgba8
+/// [`compress_rgba8_with_metrics()`]: super::compress_rgba8_with_metrics
+pub fn compress_rgba8_to_quality(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    target: QualityTarget,
+) -> QualitySelection {
+    match try_compress_rgba8_to_quality(
+        variation,
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+        target,
+    ) {
+        Ok(selection) => selection,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compresses raw RGBA8 data with the cheapest preset that meets the quality `target`.
+///
+/// This is the fallible version of [`compress_rgba8_to_quality()`].
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is not `CompressionVariant::BC6H` or
+///   `CompressionVariant::BC7`, which are the variants with presets
+/// * [`Error::InvalidSettings`] if the target is NaN or the tile size is not a multiple of 4
+/// * The same errors as [`try_compress_rgba8()`]
+///
+/// [`try_compress_rgba8()`]: super::try_compress_rgba8
+pub fn try_compress_rgba8_to_quality(
+    variation: CompressionVariant,
+    rgba_data: &[u8],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    target: QualityTarget,
+) -> Result<QualitySelection, Error> {
+    let presets = presets(variation)?;
+    target.validate()?;
+    super::validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size =
+        super::validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let stride = stride as usize;
+    let channels = crate::metrics::stored_channels(variation);
+
+    Ok(select_presets(
+        &presets,
+        rgba_data,
+        &mut blocks_buffer[..required_size],
+        width,
+        height,
+        stride,
+        target,
+        ErrorMetrics::new(channels),
+        |variant, tile_data, tile_blocks, tile_width, tile_height| {
+            let mut blocks =
+                vec![ErrorMetrics::new(channels); tile_width.div_ceil(4) * tile_height.div_ceil(4)];
+
+            match variant {
+                #[cfg(feature = "bc6h")]
+                CompressionVariant::BC6H(settings) => super::compress_bc6h_8bit(
+                    tile_data,
+                    tile_blocks,
+                    tile_width,
+                    tile_height,
+                    stride,
+                    &settings,
+                    &mut blocks,
+                ),
+                #[cfg(feature = "bc7")]
+                CompressionVariant::BC7(settings) => super::compress_bc7(
+                    tile_data,
+                    tile_blocks,
+                    tile_width,
+                    tile_height,
+                    stride,
+                    &settings,
+                    &mut blocks,
+                ),
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("only variants with presets are compressed"),
+            }
+
+            merge_blocks(ErrorMetrics::new(channels), &blocks)
+        },
+    ))
+}
+
+/// Compresses raw RGBA16 (half-float) data with the cheapest BC6H preset that meets the quality
+/// `target`.
+///
+/// Works like [`compress_rgba8_to_quality()`] for HDR data. The error is measured on the RGB
+/// float values and the PSNR is calculated relative to the largest absolute RGB value of the
+/// image. See [`compress_rgba16()`] for the data layout and buffer requirements.
+///
+/// # Panics
+/// * If [`try_compress_rgba16_to_quality()`] returns an error
+///
+/// [`compress_rgba16()`]: super::compress_rgba16
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn compress_rgba16_to_quality(
+    variation: CompressionVariant,
+    rgba_data: &[half::f16],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    target: QualityTarget,
+) -> QualitySelection {
+    match try_compress_rgba16_to_quality(
+        variation,
+        rgba_data,
+        blocks_buffer,
+        width,
+        height,
+        stride,
+        target,
+    ) {
+        Ok(selection) => selection,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compresses raw RGBA16 (half-float) data with the cheapest BC6H preset that meets the quality
+/// `target`.
+///
+/// This is the fallible version of [`compress_rgba16_to_quality()`].
+///
+/// # Errors
+/// * [`Error::UnsupportedVariant`] if `variation` is not `CompressionVariant::BC6H`
+/// * [`Error::InvalidSettings`] if the target is NaN or the tile size is not a multiple of 4
+/// * The same errors as [`try_compress_rgba16()`]
+///
+/// [`try_compress_rgba16()`]: super::try_compress_rgba16
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_compress_rgba16_to_quality(
+    variation: CompressionVariant,
+    rgba_data: &[half::f16],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    target: QualityTarget,
+) -> Result<QualitySelection, Error> {
+    #[allow(irrefutable_let_patterns)]
+    let CompressionVariant::BC6H(..) = variation
+    else {
+        return Err(Error::UnsupportedVariant);
+    };
+
+    let presets = presets(variation)?;
+    target.validate()?;
+    super::validate_source(rgba_data.len(), width, height, stride)?;
+    let required_size =
+        super::validate_blocks_buffer(variation, blocks_buffer.len(), width, height)?;
+
+    let stride = stride as usize;
+    let channels = [true, true, true, false];
+    let pixels = (0..height as usize).flat_map(|y| {
+        rgba_data[y * stride..y * stride + width as usize * 4]
+            .iter()
+            .map(|value| value.to_f64())
+    });
+    let peak = hdr_peak(pixels, channels);
+
+    Ok(select_presets(
+        &presets,
+        rgba_data,
+        &mut blocks_buffer[..required_size],
+        width,
+        height,
+        stride,
+        target,
+        ErrorMetrics::new(channels).with_peak(peak),
+        |variant, tile_data, tile_blocks, tile_width, tile_height| {
+            #[allow(irrefutable_let_patterns)]
+            let CompressionVariant::BC6H(settings) = variant
+            else {
+                unreachable!("only BC6H presets are compressed")
+            };
+
+            let mut blocks = vec![
+                ErrorMetrics::new(channels).with_peak(peak);
+                tile_width.div_ceil(4) * tile_height.div_ceil(4)
+            ];
+
+            super::compress_bc6h_16bit(
+                tile_data,
+                tile_blocks,
+                tile_width,
+                tile_height,
+                stride,
+                &settings,
+                &mut blocks,
+            );
+
+            merge_blocks(ErrorMetrics::new(channels).with_peak(peak), &blocks)
+        },
+    ))
+}
+
+/// Returns the presets of `variation` from the fastest to the slowest.
+fn presets(variation: CompressionVariant) -> Result<Vec<CompressionVariant>, Error> {
+    match variation {
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(settings) => Ok(BC6H_PRESETS
+            .iter()
+            .map(|preset| CompressionVariant::BC6H(preset.with_signed(settings.is_signed())))
+            .collect()),
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(settings) => {
+            let presets = if settings.channels == 4 {
+                BC7_ALPHA_PRESETS
+            } else {
+                BC7_OPAQUE_PRESETS
+            };
+
+            Ok(presets
+                .iter()
+                .map(|&preset| {
+                    CompressionVariant::BC7(BC7Settings {
+                        channel_weights: settings.channel_weights,
+                        ..preset
+                    })
+                })
+                .collect())
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(Error::UnsupportedVariant),
+    }
+}
+
+/// Escalates every tile of the image through `presets` until `compress_and_measure` reports
+/// metrics that meet the `target`, and copies the blocks of the selected preset into
+/// `blocks_buffer`.
+///
+/// `compress_and_measure` receives the data starting at the first pixel of the tile, the blocks
+/// buffer of the tile and the tile dimensions.
+#[allow(clippy::too_many_arguments)]
+fn select_presets<T, F>(
+    presets: &[CompressionVariant],
+    data: &[T],
+    blocks_buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: usize,
+    target: QualityTarget,
+    mut image: ErrorMetrics,
+    compress_and_measure: F,
+) -> QualitySelection
+where
+    F: Fn(CompressionVariant, &[T], &mut [u8], usize, usize) -> ErrorMetrics,
+{
+    let tile_width = if target.tile_size == 0 {
+        width
+    } else {
+        target.tile_size
+    };
+    let tile_height = if target.tile_size == 0 {
+        height
+    } else {
+        target.tile_size
+    };
+
+    if width == 0 || height == 0 {
+        return QualitySelection {
+            image,
+            tiles: Vec::new(),
+            tiles_per_row: 0,
+        };
+    }
+
+    let block_size = presets[0].block_byte_size() as usize;
+    let blocks_row_pitch = width.div_ceil(4) as usize * block_size;
+    let tiles_per_row = width.div_ceil(tile_width);
+    let mut tiles = Vec::new();
+
+    for y in (0..height).step_by(tile_height as usize) {
+        for x in (0..width).step_by(tile_width as usize) {
+            let width = u32::min(tile_width, width - x);
+            let height = u32::min(tile_height, height - y);
+
+            let tile_data = &data[y as usize * stride + x as usize * 4..];
+            let tile_row_pitch = width.div_ceil(4) as usize * block_size;
+            let mut tile_blocks = vec![0; tile_row_pitch * height.div_ceil(4) as usize];
+
+            for (preset, &variant) in presets.iter().enumerate() {
+                let metrics = compress_and_measure(
+                    variant,
+                    tile_data,
+                    &mut tile_blocks,
+                    width as usize,
+                    height as usize,
+                );
+                let target_met = target.is_met(&metrics);
+
+                if !target_met && preset + 1 < presets.len() {
+                    continue;
+                }
+
+                for (row, tile_row) in tile_blocks.chunks(tile_row_pitch).enumerate() {
+                    let offset =
+                        (y as usize / 4 + row) * blocks_row_pitch + x as usize / 4 * block_size;
+                    blocks_buffer[offset..offset + tile_row_pitch].copy_from_slice(tile_row);
+                }
+
+                image.merge(&metrics);
+                tiles.push(TileSelection {
+                    x,
+                    y,
+                    width,
+                    height,
+                    variant,
+                    preset,
+                    metrics,
+                    target_met,
+                });
+                break;
+            }
+        }
+    }
+
+    QualitySelection {
+        image,
+        tiles,
+        tiles_per_row,
+    }
+}
+
+/// Merges the metrics of the `blocks` of a tile into the empty `tile` metrics.
+fn merge_blocks(tile: ErrorMetrics, blocks: &[ErrorMetrics]) -> ErrorMetrics {
+    blocks.iter().fold(tile, |mut tile, block| {
+        tile.merge(block);
+        tile
+    })
+}
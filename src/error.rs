@@ -0,0 +1,146 @@
+use std::fmt;
+
+/// Errors returned by the fallible `try_` functions of this crate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A buffer is too small to hold the required data.
+    BufferTooSmall {
+        /// The required size in bytes or elements.
+        required: usize,
+        /// The actual size in bytes or elements.
+        actual: usize,
+    },
+    /// A buffer doesn't have the exact size that is required.
+    BufferSizeMismatch {
+        /// The expected size in bytes or elements.
+        expected: usize,
+        /// The actual size in bytes or elements.
+        actual: usize,
+    },
+    /// The image dimensions are not valid for the source data.
+    InvalidDimensions {
+        /// The width of the image in pixels.
+        width: u32,
+        /// The height of the image in pixels.
+        height: u32,
+    },
+    /// The row stride is smaller than a row of pixels.
+    InvalidStride {
+        /// The given stride.
+        stride: u32,
+        /// The minimal stride for the given width.
+        min_stride: u32,
+    },
+    /// The range of array layers is empty or lies outside the texture.
+    InvalidLayerRange {
+        /// The first layer of the range.
+        start: u32,
+        /// The end of the range, which is excluded.
+        end: u32,
+        /// The number of array layers of the texture.
+        layer_count: u32,
+    },
+    /// The range of mip levels is empty or lies outside the texture.
+    InvalidMipRange {
+        /// The first mip level of the range.
+        start: u32,
+        /// The end of the range, which is excluded.
+        end: u32,
+        /// The number of mip levels of the texture.
+        mip_level_count: u32,
+    },
+    /// The offset of compressed blocks in the destination buffer doesn't fit into the 32-bit
+    /// offset of a compression task.
+    OffsetOverflow {
+        /// The offset in bytes.
+        offset: usize,
+    },
+    /// The offset or size of a buffer copy is not a multiple of 4 bytes.
+    UnalignedCopy {
+        /// The offset of the copy in bytes.
+        offset: usize,
+        /// The size of the copy in bytes.
+        size: usize,
+    },
+    /// The compression variant is not supported by the called function.
+    UnsupportedVariant,
+    /// The format of the destination texture doesn't match the compression variant.
+    IncompatibleTextureFormat,
+    /// The destination buffer was not created with `BufferUsages::STORAGE`.
+    NotStorageBuffer,
+    /// The destination texture was not created with `TextureUsages::STORAGE_BINDING`.
+    NotStorageTexture,
+    /// A staging buffer couldn't be mapped to read back data from the GPU.
+    ReadbackFailed,
+    /// The data is not a valid container file.
+    InvalidContainer,
+    /// The texture format of a container file is not supported.
+    UnsupportedFormat,
+    /// The compression settings are not valid.
+    InvalidSettings {
+        /// Why the settings are not valid.
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BufferTooSmall { required, actual } => write!(
+                f,
+                "buffer size ({actual}) is too small to hold the data. Required size: {required}"
+            ),
+            Error::BufferSizeMismatch { expected, actual } => write!(
+                f,
+                "buffer size ({actual}) does not match the expected size ({expected})"
+            ),
+            Error::InvalidDimensions { width, height } => {
+                write!(f, "invalid image dimensions {width}x{height}")
+            }
+            Error::InvalidStride { stride, min_stride } => write!(
+                f,
+                "stride ({stride}) is smaller than the minimal stride ({min_stride})"
+            ),
+            Error::InvalidLayerRange {
+                start,
+                end,
+                layer_count,
+            } => write!(
+                f,
+                "invalid array layer range {start}..{end} for a texture with {layer_count} layers"
+            ),
+            Error::InvalidMipRange {
+                start,
+                end,
+                mip_level_count,
+            } => write!(
+                f,
+                "invalid mip level range {start}..{end} for a texture with {mip_level_count} mip levels"
+            ),
+            Error::OffsetOverflow { offset } => write!(
+                f,
+                "blocks offset ({offset}) doesn't fit into the 32-bit offset of a compression task"
+            ),
+            Error::UnalignedCopy { offset, size } => write!(
+                f,
+                "buffer copy offset ({offset}) and size ({size}) must be a multiple of 4"
+            ),
+            Error::UnsupportedVariant => write!(f, "unsupported compression variant"),
+            Error::IncompatibleTextureFormat => write!(
+                f,
+                "texture format doesn't match the compression variant"
+            ),
+            Error::NotStorageBuffer => write!(f, "buffer needs to be a storage buffer"),
+            Error::NotStorageTexture => write!(f, "texture needs to be a storage texture"),
+            Error::ReadbackFailed => write!(f, "couldn't map the readback staging buffer"),
+            Error::InvalidContainer => write!(f, "invalid container file"),
+            Error::UnsupportedFormat => write!(f, "unsupported texture format"),
+            Error::InvalidSettings { reason } => {
+                write!(f, "invalid compression settings: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,615 @@
+//! Reading and writing of KTX2 container files.
+//!
+//! [`write_ktx2()`] serializes compressed blocks of textures, texture arrays and cubemaps with
+//! their mip chains into a KTX2 file, including the Data Format Descriptor and the level index.
+//! [`read_ktx2()`] parses a KTX2 file back into a [`Ktx2Image`], whose
+//! [`Ktx2Image::level_data()`] can be passed straight to the functions of the
+//! [`decode`](crate::decode) module.
+//!
+//! The blocks passed to and returned by this module are stored layer by layer. Every layer
+//! contains all of its mip levels back to back, starting with the base level. Cubemaps store their
+//! six faces as consecutive layers in the order +X, -X, +Y, -Y, +Z, -Z. This is the same layout as
+//! used by the `dds` module and [`CompressedMipmaps`](crate::mipmap::CompressedMipmaps). The
+//! blocks are reordered into the level by level layout of KTX2 when writing and reading.
+
+use std::num::NonZeroU8;
+
+pub use ktx2::Format;
+use ktx2::{
+    ChannelTypeQualifiers, ColorModel, ColorPrimaries, DataFormatFlags, DfdBlockHeaderBasic,
+    DfdHeader, Header, Index, LevelIndex, Reader, SampleInformation, TransferFunction,
+};
+
+use crate::{
+    mipmap::{compressed_mip_levels, mip_level_count, CompressedMipLevel},
+    CompressionVariant, Error,
+};
+
+/// Describes the layout of the compressed blocks stored in a KTX2 file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ktx2Descriptor {
+    /// The compression variant of the blocks.
+    pub variant: CompressionVariant,
+    /// The width of the base level in pixels.
+    pub width: u32,
+    /// The height of the base level in pixels.
+    pub height: u32,
+    /// The number of mip levels of every layer.
+    pub mip_level_count: u32,
+    /// The number of array layers. For cubemaps this is the number of cubes.
+    pub array_layer_count: u32,
+    /// Whether every array layer is a cube with six faces.
+    pub cubemap: bool,
+    /// Whether the color channels are sRGB encoded. Only BC1, BC2, BC3 and BC7 have sRGB formats,
+    /// all other variants ignore this setting.
+    pub srgb: bool,
+}
+
+impl Ktx2Descriptor {
+    /// Creates a descriptor of a single 2D texture without mip levels.
+    pub const fn new(variant: CompressionVariant, width: u32, height: u32) -> Self {
+        Self {
+            variant,
+            width,
+            height,
+            mip_level_count: 1,
+            array_layer_count: 1,
+            cubemap: false,
+            srgb: false,
+        }
+    }
+
+    /// Sets the number of mip levels of every layer.
+    pub const fn with_mip_level_count(mut self, mip_level_count: u32) -> Self {
+        self.mip_level_count = mip_level_count;
+        self
+    }
+
+    /// Sets the number of array layers. For cubemaps this is the number of cubes.
+    pub const fn with_array_layer_count(mut self, array_layer_count: u32) -> Self {
+        self.array_layer_count = array_layer_count;
+        self
+    }
+
+    /// Sets whether every array layer is a cube with six faces.
+    pub const fn with_cubemap(mut self, cubemap: bool) -> Self {
+        self.cubemap = cubemap;
+        self
+    }
+
+    /// Sets whether the color channels are sRGB encoded.
+    pub const fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Returns the number of stored layers. Every face of a cubemap counts as a layer.
+    pub const fn layer_count(&self) -> u32 {
+        if self.cubemap {
+            self.array_layer_count * 6
+        } else {
+            self.array_layer_count
+        }
+    }
+
+    /// Returns the location of every mip level inside a single layer.
+    pub fn mip_levels(&self) -> Vec<CompressedMipLevel> {
+        compressed_mip_levels(self.variant, self.width, self.height, self.mip_level_count)
+    }
+
+    /// Returns the byte size of a single layer including all of its mip levels.
+    pub fn layer_byte_size(&self) -> usize {
+        self.mip_levels()
+            .last()
+            .map_or(0, |level| level.offset + level.size)
+    }
+
+    /// Returns the byte size of the blocks of all layers and mip levels.
+    pub fn blocks_byte_size(&self) -> usize {
+        self.layer_byte_size() * self.layer_count() as usize
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.width == 0
+            || self.height == 0
+            || self.mip_level_count == 0
+            || self.mip_level_count > mip_level_count(self.width, self.height)
+            || self.array_layer_count == 0
+        {
+            return Err(Error::InvalidDimensions {
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The compressed blocks of a KTX2 file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ktx2Image {
+    /// The layout of the blocks.
+    pub descriptor: Ktx2Descriptor,
+    /// The compressed blocks of all layers and mip levels.
+    pub blocks: Vec<u8>,
+}
+
+impl Ktx2Image {
+    /// Returns the compressed blocks of the given mip `level` of the given `layer`. Every face of a
+    /// cubemap counts as a layer.
+    ///
+    /// # Panics
+    /// - If `layer` or `level` is out of range
+    pub fn level_data(&self, layer: u32, level: u32) -> &[u8] {
+        assert!(
+            layer < self.descriptor.layer_count(),
+            "layer {layer} is out of range"
+        );
+
+        let levels = self.descriptor.mip_levels();
+        let level = levels[level as usize];
+        let offset = self.descriptor.layer_byte_size() * layer as usize + level.offset;
+        &self.blocks[offset..offset + level.size]
+    }
+}
+
+/// Returns the Vulkan format that stores blocks of the given `variant`.
+///
+/// `srgb` selects the sRGB format for BC1, BC2, BC3 and BC7 and is ignored for all other variants.
+pub fn vk_format(variant: CompressionVariant, srgb: bool) -> Format {
+    match (variant, srgb) {
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC1(..), true) => Format::BC1_RGBA_SRGB_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC1(..), _) => Format::BC1_RGBA_UNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC2(..), true) => Format::BC2_SRGB_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC2(..), _) => Format::BC2_UNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC3(..), true) => Format::BC3_SRGB_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC3(..), _) => Format::BC3_UNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC4, _) => Format::BC4_UNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC5, _) => Format::BC5_UNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC4Snorm, _) => Format::BC4_SNORM_BLOCK,
+        #[cfg(feature = "bc15")]
+        (CompressionVariant::BC5Snorm, _) => Format::BC5_SNORM_BLOCK,
+        #[cfg(feature = "bc6h")]
+        (CompressionVariant::BC6H(settings), _) if settings.is_signed() => {
+            Format::BC6H_SFLOAT_BLOCK
+        }
+        #[cfg(feature = "bc6h")]
+        (CompressionVariant::BC6H(..), _) => Format::BC6H_UFLOAT_BLOCK,
+        #[cfg(feature = "bc7")]
+        (CompressionVariant::BC7(..), true) => Format::BC7_SRGB_BLOCK,
+        #[cfg(feature = "bc7")]
+        (CompressionVariant::BC7(..), _) => Format::BC7_UNORM_BLOCK,
+    }
+}
+
+/// Returns the compression variant and whether the format is sRGB encoded for the given Vulkan
+/// format, or `None` if the format is not supported by this crate.
+///
+/// The settings of BC1 to BC3, BC6H and BC7 variants are set to their default and basic presets,
+/// since they are only needed for compression. Signed BC6H formats return settings with signed
+/// encoding enabled.
+pub fn variant_from_vk_format(format: Format) -> Option<(CompressionVariant, bool)> {
+    let variant = match format {
+        #[cfg(feature = "bc15")]
+        Format::BC1_RGB_UNORM_BLOCK | Format::BC1_RGBA_UNORM_BLOCK => {
+            (CompressionVariant::BC1(crate::BC123Settings::new()), false)
+        }
+        #[cfg(feature = "bc15")]
+        Format::BC1_RGB_SRGB_BLOCK | Format::BC1_RGBA_SRGB_BLOCK => {
+            (CompressionVariant::BC1(crate::BC123Settings::new()), true)
+        }
+        #[cfg(feature = "bc15")]
+        Format::BC2_UNORM_BLOCK => (CompressionVariant::BC2(crate::BC123Settings::new()), false),
+        #[cfg(feature = "bc15")]
+        Format::BC2_SRGB_BLOCK => (CompressionVariant::BC2(crate::BC123Settings::new()), true),
+        #[cfg(feature = "bc15")]
+        Format::BC3_UNORM_BLOCK => (CompressionVariant::BC3(crate::BC123Settings::new()), false),
+        #[cfg(feature = "bc15")]
+        Format::BC3_SRGB_BLOCK => (CompressionVariant::BC3(crate::BC123Settings::new()), true),
+        #[cfg(feature = "bc15")]
+        Format::BC4_UNORM_BLOCK => (CompressionVariant::BC4, false),
+        #[cfg(feature = "bc15")]
+        Format::BC5_UNORM_BLOCK => (CompressionVariant::BC5, false),
+        #[cfg(feature = "bc15")]
+        Format::BC4_SNORM_BLOCK => (CompressionVariant::BC4Snorm, false),
+        #[cfg(feature = "bc15")]
+        Format::BC5_SNORM_BLOCK => (CompressionVariant::BC5Snorm, false),
+        #[cfg(feature = "bc6h")]
+        Format::BC6H_UFLOAT_BLOCK => (
+            CompressionVariant::BC6H(crate::BC6HSettings::basic()),
+            false,
+        ),
+        #[cfg(feature = "bc6h")]
+        Format::BC6H_SFLOAT_BLOCK => (
+            CompressionVariant::BC6H(crate::BC6HSettings::basic().with_signed(true)),
+            false,
+        ),
+        #[cfg(feature = "bc7")]
+        Format::BC7_UNORM_BLOCK => (
+            CompressionVariant::BC7(crate::BC7Settings::alpha_basic()),
+            false,
+        ),
+        #[cfg(feature = "bc7")]
+        Format::BC7_SRGB_BLOCK => (
+            CompressionVariant::BC7(crate::BC7Settings::alpha_basic()),
+            true,
+        ),
+        _ => return None,
+    };
+
+    Some(variant)
+}
+
+/// The channel id of the alpha sample in the BC1A color model (`KHR_DF_CHANNEL_BC1A_ALPHA`).
+const BC1A_ALPHA_CHANNEL: u8 = 1;
+
+/// The channel id of alpha samples in the BC2 and BC3 color models.
+const ALPHA_CHANNEL: u8 = 15;
+
+/// A single sample of the basic Data Format Descriptor block.
+struct Sample {
+    channel: u8,
+    bit_offset: u16,
+    bit_length: u8,
+    qualifiers: ChannelTypeQualifiers,
+    lower: u32,
+    upper: u32,
+}
+
+impl Sample {
+    const fn unorm(channel: u8, bit_offset: u16, bit_length: u8) -> Self {
+        Self {
+            channel,
+            bit_offset,
+            bit_length,
+            qualifiers: ChannelTypeQualifiers::empty(),
+            lower: 0,
+            upper: u32::MAX,
+        }
+    }
+
+    #[cfg(feature = "bc15")]
+    const fn snorm(channel: u8, bit_offset: u16, bit_length: u8) -> Self {
+        Self {
+            channel,
+            bit_offset,
+            bit_length,
+            qualifiers: ChannelTypeQualifiers::SIGNED,
+            lower: i32::MIN as u32,
+            upper: i32::MAX as u32,
+        }
+    }
+}
+
+/// Returns the color model and the samples of the Data Format Descriptor of `variant`.
+fn dfd_samples(variant: CompressionVariant) -> (ColorModel, Vec<Sample>) {
+    match variant {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC1(..) => (
+            ColorModel::BC1A,
+            vec![Sample::unorm(BC1A_ALPHA_CHANNEL, 0, 64)],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC2(..) => (
+            ColorModel::BC2,
+            vec![
+                Sample::unorm(ALPHA_CHANNEL, 0, 64),
+                Sample::unorm(0, 64, 64),
+            ],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC3(..) => (
+            ColorModel::BC3,
+            vec![
+                Sample::unorm(ALPHA_CHANNEL, 0, 64),
+                Sample::unorm(0, 64, 64),
+            ],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4 => (ColorModel::BC4, vec![Sample::unorm(0, 0, 64)]),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 => (
+            ColorModel::BC5,
+            vec![Sample::unorm(0, 0, 64), Sample::unorm(1, 64, 64)],
+        ),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4Snorm => (ColorModel::BC4, vec![Sample::snorm(0, 0, 64)]),
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5Snorm => (
+            ColorModel::BC5,
+            vec![Sample::snorm(0, 0, 64), Sample::snorm(1, 64, 64)],
+        ),
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(settings) if settings.is_signed() => (
+            ColorModel::BC6H,
+            vec![Sample {
+                qualifiers: ChannelTypeQualifiers::FLOAT | ChannelTypeQualifiers::SIGNED,
+                lower: (-1.0f32).to_bits(),
+                upper: 1.0f32.to_bits(),
+                ..Sample::unorm(0, 0, 128)
+            }],
+        ),
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(..) => (
+            ColorModel::BC6H,
+            vec![Sample {
+                qualifiers: ChannelTypeQualifiers::FLOAT,
+                lower: 0,
+                upper: 1.0f32.to_bits(),
+                ..Sample::unorm(0, 0, 128)
+            }],
+        ),
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(..) => (ColorModel::BC7, vec![Sample::unorm(0, 0, 128)]),
+    }
+}
+
+/// Builds the Data Format Descriptor including its leading total size.
+fn data_format_descriptor(variant: CompressionVariant, srgb: bool) -> Vec<u8> {
+    let (color_model, samples) = dfd_samples(variant);
+    let srgb = srgb && vk_format(variant, true) != vk_format(variant, false);
+    let alpha_channel = if color_model == ColorModel::BC1A {
+        BC1A_ALPHA_CHANNEL
+    } else {
+        ALPHA_CHANNEL
+    };
+
+    let block_size = (DfdHeader::LENGTH
+        + DfdBlockHeaderBasic::LENGTH
+        + samples.len() * SampleInformation::LENGTH) as u16;
+
+    let header = DfdBlockHeaderBasic {
+        color_model: Some(color_model),
+        color_primaries: Some(ColorPrimaries::BT709),
+        transfer_function: Some(if srgb {
+            TransferFunction::SRGB
+        } else {
+            TransferFunction::Linear
+        }),
+        flags: DataFormatFlags::STRAIGHT_ALPHA,
+        texel_block_dimensions: [4, 4, 1, 1].map(|dimension| NonZeroU8::new(dimension).unwrap()),
+        bytes_planes: [variant.block_byte_size() as u8, 0, 0, 0, 0, 0, 0, 0],
+    };
+
+    let mut dfd = Vec::with_capacity(4 + block_size as usize);
+    dfd.extend_from_slice(&(4 + block_size as u32).to_le_bytes());
+    dfd.extend_from_slice(&DfdHeader::BASIC.as_bytes(block_size));
+    dfd.extend_from_slice(&header.as_bytes());
+
+    for sample in samples {
+        let mut qualifiers = sample.qualifiers;
+
+        // Alpha is always stored linearly.
+        if srgb && sample.channel == alpha_channel {
+            qualifiers |= ChannelTypeQualifiers::LINEAR;
+        }
+
+        let information = SampleInformation {
+            bit_offset: sample.bit_offset,
+            bit_length: NonZeroU8::new(sample.bit_length).unwrap(),
+            channel_type: sample.channel,
+            channel_type_qualifiers: qualifiers,
+            sample_positions: [0; 4],
+            lower: sample.lower,
+            upper: sample.upper,
+        };
+        dfd.extend_from_slice(&information.as_bytes());
+    }
+
+    dfd
+}
+
+/// Serializes compressed blocks into a KTX2 file.
+///
+/// `blocks` contains the blocks of all layers and mip levels in the layout described in the
+/// [module documentation](self).
+///
+/// # Panics
+/// - If [`try_write_ktx2()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{
+///     ktx2::{read_ktx2, write_ktx2, Ktx2Descriptor},
+///     mipmap::{compress_mipmaps_rgba8, MipmapSettings},
+///     BC123Settings, CompressionVariant,
+/// };
+///
+/// let variant = CompressionVariant::BC1(BC123Settings::new());
+/// let rgba_data = vec![0u8; 64 * 64 * 4]; // Your RGBA data
+/// let mipmaps = compress_mipmaps_rgba8(
+///     variant,
+///     &rgba_data,
+///     64,
+///     64,
+///     64 * 4,
+///     MipmapSettings::default(),
+/// );
+///
+/// let descriptor = Ktx2Descriptor::new(variant, 64, 64)
+///     .with_mip_level_count(mipmaps.levels.len() as u32)
+///     .with_srgb(true);
+///
+/// let file = write_ktx2(&descriptor, &mipmaps.blocks);
+/// let image = read_ktx2(&file).unwrap();
+///
+/// assert_eq!(image.descriptor, descriptor);
+/// assert_eq!(image.level_data(0, 6), mipmaps.level_data(6));
+/// ```
+pub fn write_ktx2(descriptor: &Ktx2Descriptor, blocks: &[u8]) -> Vec<u8> {
+    match try_write_ktx2(descriptor, blocks) {
+        Ok(file) => file,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Serializes compressed blocks into a KTX2 file.
+///
+/// This is the fallible version of [`write_ktx2()`].
+///
+/// # Errors
+/// - [`Error::InvalidDimensions`] if the dimensions, the mip level count or the array layer count
+///   of `descriptor` are invalid
+/// - [`Error::BufferSizeMismatch`] if the size of `blocks` doesn't match the size described by
+///   `descriptor`
+pub fn try_write_ktx2(descriptor: &Ktx2Descriptor, blocks: &[u8]) -> Result<Vec<u8>, Error> {
+    descriptor.validate()?;
+
+    let expected = descriptor.blocks_byte_size();
+    if blocks.len() != expected {
+        return Err(Error::BufferSizeMismatch {
+            expected,
+            actual: blocks.len(),
+        });
+    }
+
+    let variant = descriptor.variant;
+    let layer_count = descriptor.layer_count() as usize;
+    let layer_size = descriptor.layer_byte_size();
+    let levels = descriptor.mip_levels();
+    let dfd = data_format_descriptor(variant, descriptor.srgb);
+
+    let dfd_offset = Header::LENGTH + levels.len() * LevelIndex::LENGTH;
+    let data_offset = dfd_offset + dfd.len();
+
+    // Mip levels are stored from the smallest to the largest level. Every level is aligned to the
+    // least common multiple of the block size and 4, which is the block size itself.
+    let alignment = variant.block_byte_size() as usize;
+    let mut level_index = vec![
+        LevelIndex {
+            byte_offset: 0,
+            byte_length: 0,
+            uncompressed_byte_length: 0,
+        };
+        levels.len()
+    ];
+
+    let mut offset = data_offset;
+    for (level, index) in levels.iter().zip(level_index.iter_mut()).rev() {
+        offset = offset.next_multiple_of(alignment);
+        let length = (level.size * layer_count) as u64;
+
+        *index = LevelIndex {
+            byte_offset: offset as u64,
+            byte_length: length,
+            uncompressed_byte_length: length,
+        };
+        offset += length as usize;
+    }
+
+    let header = Header {
+        format: Some(vk_format(variant, descriptor.srgb)),
+        type_size: 1,
+        pixel_width: descriptor.width,
+        pixel_height: descriptor.height,
+        pixel_depth: 0,
+        layer_count: if descriptor.array_layer_count > 1 {
+            descriptor.array_layer_count
+        } else {
+            0
+        },
+        face_count: if descriptor.cubemap { 6 } else { 1 },
+        level_count: descriptor.mip_level_count,
+        supercompression_scheme: None,
+        index: Index {
+            dfd_byte_offset: dfd_offset as u32,
+            dfd_byte_length: dfd.len() as u32,
+            kvd_byte_offset: 0,
+            kvd_byte_length: 0,
+            sgd_byte_offset: 0,
+            sgd_byte_length: 0,
+        },
+    };
+
+    let mut file = Vec::with_capacity(offset);
+    file.extend_from_slice(&header.as_bytes());
+    for index in level_index.iter() {
+        file.extend_from_slice(&index.as_bytes());
+    }
+    file.extend_from_slice(&dfd);
+
+    for (level, index) in levels.iter().zip(level_index.iter()).rev() {
+        file.resize(index.byte_offset as usize, 0);
+
+        for layer in 0..layer_count {
+            let start = layer * layer_size + level.offset;
+            file.extend_from_slice(&blocks[start..start + level.size]);
+        }
+    }
+
+    Ok(file)
+}
+
+/// Parses a KTX2 file into its compressed blocks.
+///
+/// # Errors
+/// - [`Error::InvalidContainer`] if `data` is not a valid KTX2 file
+/// - [`Error::UnsupportedFormat`] if the file is a volume texture, uses supercompression or stores
+///   a format that is not supported by the enabled features
+/// - [`Error::InvalidDimensions`] if the dimensions, the mip level count or the array layer count
+///   are invalid
+/// - [`Error::BufferTooSmall`] if a mip level doesn't contain all described blocks
+pub fn read_ktx2(data: &[u8]) -> Result<Ktx2Image, Error> {
+    let reader = Reader::new(data).map_err(|_| Error::InvalidContainer)?;
+    let header = reader.header();
+
+    if header.pixel_depth > 1 || header.supercompression_scheme.is_some() {
+        return Err(Error::UnsupportedFormat);
+    }
+
+    let format = header.format.ok_or(Error::UnsupportedFormat)?;
+    let (variant, srgb) = variant_from_vk_format(format).ok_or(Error::UnsupportedFormat)?;
+
+    let cubemap = match header.face_count {
+        1 => false,
+        6 => true,
+        _ => return Err(Error::InvalidContainer),
+    };
+
+    let descriptor = Ktx2Descriptor {
+        variant,
+        width: header.pixel_width,
+        height: header.pixel_height.max(1),
+        mip_level_count: header.level_count.max(1),
+        array_layer_count: header.layer_count.max(1),
+        cubemap,
+        srgb,
+    };
+
+    descriptor.validate()?;
+
+    let layer_count = descriptor.layer_count() as usize;
+    let layer_size = descriptor.layer_byte_size();
+    let mut blocks = vec![0; descriptor.blocks_byte_size()];
+
+    for (level, level_data) in descriptor.mip_levels().iter().zip(reader.levels()) {
+        let required = level.size * layer_count;
+        if level_data.data.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                actual: level_data.data.len(),
+            });
+        }
+
+        for (layer, layer_data) in level_data.data.chunks_exact(level.size).enumerate() {
+            if layer == layer_count {
+                break;
+            }
+
+            let start = layer * layer_size + level.offset;
+            blocks[start..start + level.size].copy_from_slice(layer_data);
+        }
+    }
+
+    Ok(Ktx2Image { descriptor, blocks })
+}
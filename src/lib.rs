@@ -19,23 +19,140 @@
 //!
 //! Currently supported block compressions are:
 //!
-//!  * BC1
+//!  * BC1 (opaque and 1-bit alpha)
 //!  * BC2
 //!  * BC3
-//!  * BC4
-//!  * BC5
+//!  * BC4 (unsigned and signed)
+//!  * BC5 (unsigned and signed)
 //!  * BC6H
 //!  * BC7
+//!
+//! ## Multithreading
+//!
+//! The `rayon` feature enables multithreaded CPU based encoding and decoding. Rows of blocks are
+//! distributed over the current rayon thread pool. Use `rayon::ThreadPool::install()` to limit the number
+//! of threads used. The output is identical to the single threaded path.
+//!
+//! ## Mipmaps
+//!
+//! The `mipmap` module builds full mip chains with a box, Kaiser or Lanczos filter and compresses
+//! every mip level on the CPU. `MipmapSettings` can filter sRGB colors in linear space and weight
+//! colors by alpha, so that transparent pixels don't bleed into visible ones.
+//! `GpuBlockCompressor::add_mipmap_compression_tasks()` compresses all mip levels of a texture on
+//! the GPU. `GpuBlockCompressor::add_texture_compression_task()` compresses a range of mip levels
+//! and array layers described by a `TextureCompressionTask` and reports the offset of every
+//! compressed subresource. `GpuBlockCompressor::create_compressed_texture()` copies the output of
+//! such a task into a BC texture. `GpuBlockCompressor::read_back_blocks()` copies compressed blocks
+//! into a staging buffer and returns a `BlocksReadback`, which can be awaited or polled without
+//! blocking the render thread.
+//!
+//! ## GPU decompression
+//!
+//! `GpuBlockDecompressor` decodes BC1 to BC7 blocks from a storage buffer into an `Rgba8Unorm`,
+//! `Rgba8Snorm` or `Rgba16Float` storage texture with a compute shader. The output matches the
+//! CPU decoder bit for bit, which makes it useful to preview or validate compressed data without
+//! a round trip through the CPU.
+//!
+//! ## Error metrics
+//!
+//! `encode::compress_rgba8_with_metrics()` and `encode::compress_rgba16_with_metrics()` measure
+//! every block while it is encoded and report the mean squared error and the PSNR per
+//! channel of every block and of the whole image, so that textures which need slower settings
+//! can be found without allocating a decoded copy of the image. On the GPU,
+//! `TextureCompressionTask::with_error_buffer()` writes the squared error of every block, as
+//! measured by the encoder, into an optional storage buffer. The `metrics` module also compares
+//! decoded RGBA8 and HDR images against their originals and reports the RMSE, the PSNR, the SSIM
+//! and a perceptually weighted error, which can be used to enforce quality budgets in CI.
+//!
+//! `encode::compress_rgba8_to_quality()` and `encode::compress_rgba16_to_quality()` escalate through
+//! the BC7 or BC6H presets, from the fastest to the slowest, until a `QualityTarget` (a minimal
+//! PSNR or a maximal error) is met. The preset can be selected for the whole image or for every
+//! tile separately, so that only the difficult parts of an image use the slow presets.
+//!
+//! ## Format selection
+//!
+//! The `analysis` module inspects RGBA8 and RGBA16 float images and recommends a compression
+//! variant for their content: BC4 for grayscale masks, BC5 for normal maps and two channel images,
+//! BC1 or BC7 without alpha for opaque images, BC1 with punch-through alpha or BC7 for cutouts
+//! and BC6H for HDR images. The recommendation lists its reasons and can be passed straight to
+//! `encode::compress_rgba8()`.
+//!
+//! ## Normal maps
+//!
+//! `encode::compress_normal_map_rgba8()` renormalizes tangent-space normal maps and stores only
+//! the X and Y component: in red and green for BC5 and BC7, in alpha and green for BC3. It
+//! reports the angular error of the decoded normals. `decode::decompress_normal_map_as_rgba8()`
+//! and `decode::decompress_normal_map_as_rgba32f()` reconstruct the Z component.
+//!
+//! ## DDS
+//!
+//! The `dds` feature enables the `dds` module, which writes compressed textures, texture arrays
+//! and cubemaps with their mip chains into DDS files and reads them back.
+//!
+//! ## KTX2
+//!
+//! The `ktx2` feature enables the `ktx2` module, which does the same for KTX2 files. It writes the
+//! matching `VK_FORMAT_BC*` format, the Data Format Descriptor and the level index.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(all(feature = "bc15", feature = "bc7"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "bc15", feature = "bc7"))))]
+pub mod analysis;
 #[cfg(all(
     feature = "wgpu",
     any(feature = "bc15", feature = "bc6h", feature = "bc7")
 ))]
 mod block_compressor;
+#[cfg(all(
+    feature = "wgpu",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+mod block_decompressor;
+#[cfg(all(
+    feature = "dds",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        feature = "dds",
+        any(feature = "bc15", feature = "bc6h", feature = "bc7")
+    )))
+)]
+pub mod dds;
 pub mod decode;
 pub mod encode;
+mod error;
+#[cfg(all(
+    feature = "ktx2",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        feature = "ktx2",
+        any(feature = "bc15", feature = "bc6h", feature = "bc7")
+    )))
+)]
+pub mod ktx2;
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub mod metrics;
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7")))
+)]
+pub mod mipmap;
+#[cfg(all(
+    feature = "wgpu",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+mod readback;
 mod settings;
 
 #[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
@@ -52,17 +169,48 @@ use std::hash::{Hash, Hasher};
         any(feature = "bc15", feature = "bc6h", feature = "bc7")
     )))
 )]
-pub use block_compressor::GpuBlockCompressor;
+pub use block_compressor::{CompressedSubresource, GpuBlockCompressor, TextureCompressionTask};
+#[cfg(all(
+    feature = "wgpu",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        feature = "wgpu",
+        any(feature = "bc15", feature = "bc6h", feature = "bc7")
+    )))
+)]
+pub use block_decompressor::GpuBlockDecompressor;
 pub use bytemuck;
+#[cfg(feature = "dds")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dds")))]
+pub use ddsfile;
+pub use error::Error;
 #[cfg(feature = "bc6h")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
 pub use half;
+#[cfg(all(
+    feature = "wgpu",
+    any(feature = "bc15", feature = "bc6h", feature = "bc7")
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        feature = "wgpu",
+        any(feature = "bc15", feature = "bc6h", feature = "bc7")
+    )))
+)]
+pub use readback::BlocksReadback;
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+pub use settings::BC123Settings;
 #[cfg(feature = "bc6h")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
-pub use settings::BC6HSettings;
+pub use settings::{BC6HSettings, BC6HSettingsBuilder};
 #[cfg(feature = "bc7")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc7")))]
-pub use settings::BC7Settings;
+pub use settings::{BC7Settings, BC7SettingsBuilder};
 
 /// Block compression variants supported by this crate.
 #[derive(Copy, Clone, Debug)]
@@ -74,16 +222,16 @@ pub use settings::BC7Settings;
 pub enum CompressionVariant {
     #[cfg(feature = "bc15")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
-    /// BC1 compression (RGB)
-    BC1,
+    /// BC1 compression (RGB with optional 1-bit alpha)
+    BC1(BC123Settings),
     #[cfg(feature = "bc15")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
     /// BC2 compression with sharp alpha (RGBA)
-    BC2,
+    BC2(BC123Settings),
     #[cfg(feature = "bc15")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
     /// BC3 compression with smooth alpha (RGBA)
-    BC3,
+    BC3(BC123Settings),
     #[cfg(feature = "bc15")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
     /// BC4 compression (R)
@@ -92,6 +240,14 @@ pub enum CompressionVariant {
     #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
     /// BC5 compression (RG)
     BC5,
+    #[cfg(feature = "bc15")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+    /// BC4 compression of signed data (R)
+    BC4Snorm,
+    #[cfg(feature = "bc15")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+    /// BC5 compression of signed data (RG)
+    BC5Snorm,
     #[cfg(feature = "bc6h")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
     /// BC6H compression (RGB)
@@ -146,9 +302,9 @@ impl CompressionVariant {
     const fn block_byte_size(self) -> u32 {
         match self {
             #[cfg(feature = "bc15")]
-            Self::BC1 | Self::BC4 => 8,
+            Self::BC1(..) | Self::BC4 | Self::BC4Snorm => 8,
             #[cfg(feature = "bc15")]
-            Self::BC2 | Self::BC3 | Self::BC5 => 16,
+            Self::BC2(..) | Self::BC3(..) | Self::BC5 | Self::BC5Snorm => 16,
             #[cfg(feature = "bc6h")]
             Self::BC6H(..) => 16,
             #[cfg(feature = "bc7")]
@@ -160,15 +316,19 @@ impl CompressionVariant {
     const fn name(self) -> &'static str {
         match self {
             #[cfg(feature = "bc15")]
-            Self::BC1 => "bc1",
+            Self::BC1(..) => "bc1",
             #[cfg(feature = "bc15")]
-            Self::BC2 => "bc2",
+            Self::BC2(..) => "bc2",
             #[cfg(feature = "bc15")]
-            Self::BC3 => "bc3",
+            Self::BC3(..) => "bc3",
             #[cfg(feature = "bc15")]
             Self::BC4 => "bc4",
             #[cfg(feature = "bc15")]
             Self::BC5 => "bc5",
+            #[cfg(feature = "bc15")]
+            Self::BC4Snorm => "bc4_snorm",
+            #[cfg(feature = "bc15")]
+            Self::BC5Snorm => "bc5_snorm",
             #[cfg(feature = "bc6h")]
             Self::BC6H(..) => "bc6h",
             #[cfg(feature = "bc7")]
@@ -180,15 +340,19 @@ impl CompressionVariant {
     const fn entry_point(self) -> &'static str {
         match self {
             #[cfg(feature = "bc15")]
-            Self::BC1 => "compress_bc1",
+            Self::BC1(..) => "compress_bc1",
             #[cfg(feature = "bc15")]
-            Self::BC2 => "compress_bc2",
+            Self::BC2(..) => "compress_bc2",
             #[cfg(feature = "bc15")]
-            Self::BC3 => "compress_bc3",
+            Self::BC3(..) => "compress_bc3",
             #[cfg(feature = "bc15")]
             Self::BC4 => "compress_bc4",
             #[cfg(feature = "bc15")]
             Self::BC5 => "compress_bc5",
+            #[cfg(feature = "bc15")]
+            Self::BC4Snorm => "compress_bc4_snorm",
+            #[cfg(feature = "bc15")]
+            Self::BC5Snorm => "compress_bc5_snorm",
             #[cfg(feature = "bc6h")]
             Self::BC6H(..) => "compress_bc6h",
             #[cfg(feature = "bc7")]
@@ -196,3 +360,80 @@ impl CompressionVariant {
         }
     }
 }
+
+/// Calls `process_row` with the index and the content of every `row_size` sized row of `buffer`.
+///
+/// With the `rayon` feature enabled, the rows are processed in parallel on the current rayon
+/// thread pool.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+pub(crate) fn for_each_row<T, F>(buffer: &mut [T], row_size: usize, process_row: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Send + Sync,
+{
+    if row_size == 0 {
+        return;
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        buffer
+            .par_chunks_mut(row_size)
+            .enumerate()
+            .for_each(|(index, row)| process_row(index, row));
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    buffer
+        .chunks_mut(row_size)
+        .enumerate()
+        .for_each(|(index, row)| process_row(index, row));
+}
+
+/// Calls `process_row` with the index and the content of every `row_size` sized row of `buffer`
+/// together with the matching `extra_row_size` sized row of `extra`.
+///
+/// An empty `extra` buffer passes an empty row for every row of `buffer`.
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+pub(crate) fn for_each_row_with<T, U, F>(
+    buffer: &mut [T],
+    row_size: usize,
+    extra: &mut [U],
+    extra_row_size: usize,
+    process_row: F,
+) where
+    T: Send,
+    U: Send,
+    F: Fn(usize, &mut [T], &mut [U]) + Send + Sync,
+{
+    if extra.is_empty() || extra_row_size == 0 {
+        for_each_row(buffer, row_size, |index, row| {
+            process_row(index, row, &mut [])
+        });
+        return;
+    }
+
+    if row_size == 0 {
+        return;
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        buffer
+            .par_chunks_mut(row_size)
+            .zip(extra.par_chunks_mut(extra_row_size))
+            .enumerate()
+            .for_each(|(index, (row, extra_row))| process_row(index, row, extra_row));
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    buffer
+        .chunks_mut(row_size)
+        .zip(extra.chunks_mut(extra_row_size))
+        .enumerate()
+        .for_each(|(index, (row, extra_row))| process_row(index, row, extra_row));
+}
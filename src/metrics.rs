@@ -0,0 +1,813 @@
+//! Image quality metrics.
+//!
+//! The `compare_*` functions compare an original image against its decoded version, for example
+//! the output of [`decode::decompress_blocks_as_rgba8()`] or
+//! [`decode::decompress_blocks_as_rgba32f()`], and return [`ImageMetrics`] with the RMSE, the
+//! PSNR, the SSIM and a perceptually weighted error. They can be used to enforce quality budgets
+//! for compressed assets.
+//!
+//! [`ErrorMetrics`] holds the mean squared error and the PSNR of the red, green, blue and alpha
+//! channel of a set of pixels. [`CompressionMetrics`] is returned by
+//! [`encode::compress_rgba8_with_metrics()`] and [`encode::compress_rgba16_with_metrics()`] and
+//! reports the error of every block and of the whole image, which is measured while the blocks
+//! are encoded.
+//!
+//! [`AngularErrorMetrics`] measure the angle between original and decoded normals. They are
+//! returned by [`encode::compress_normal_map_rgba8()`] and [`compare_normal_maps_rgba8()`].
+//!
+//! [`decode::decompress_blocks_as_rgba8()`]: crate::decode::decompress_blocks_as_rgba8
+//! [`decode::decompress_blocks_as_rgba32f()`]: crate::decode::decompress_blocks_as_rgba32f
+//! [`encode::compress_rgba8_with_metrics()`]: crate::encode::compress_rgba8_with_metrics
+//! [`encode::compress_rgba16_with_metrics()`]: crate::encode::compress_rgba16_with_metrics
+//! [`encode::compress_normal_map_rgba8()`]: crate::encode::compress_normal_map_rgba8
+//!
+//! # Example
+//! ```
+//! use block_compression::{
+//!     decode::decompress_blocks_as_rgba8, encode::compress_rgba8, metrics, BC7Settings,
+//!     CompressionVariant,
+//! };
+//!
+//! let rgba_data = vec![0u8; 64 * 64 * 4]; // Your RGBA data
+//! let variant = CompressionVariant::BC7(BC7Settings::alpha_basic());
+//!
+//! let mut blocks = vec![0u8; variant.blocks_byte_size(64, 64)];
+//! compress_rgba8(variant, &rgba_data, &mut blocks, 64, 64, 64 * 4);
+//!
+//! let mut decoded = vec![0u8; rgba_data.len()];
+//! decompress_blocks_as_rgba8(variant, 64, 64, &blocks, &mut decoded);
+//!
+//! let quality = metrics::compare_rgba8(
+//!     &rgba_data,
+//!     &decoded,
+//!     64,
+//!     64,
+//!     metrics::stored_channels(variant),
+//! );
+//!
+//! assert!(quality.psnr() > 40.0);
+//! assert!(quality.ssim() > 0.95);
+//! ```
+
+use crate::{CompressionVariant, Error};
+
+/// The Rec. 601 luma weights of the red, green and blue channel used by the perceptual error.
+const PERCEPTUAL_WEIGHTS: [f64; 3] = [0.299, 0.587, 0.114];
+
+/// The width and height of the windows over which the SSIM is computed.
+const SSIM_WINDOW: usize = 8;
+/// The distance between two SSIM windows.
+const SSIM_STEP: usize = 4;
+
+/// Error statistics of the red, green, blue and alpha channel of a set of pixels.
+///
+/// The error of RGBA8 images is measured on channel values normalized to the range of 0.0 to
+/// 1.0, HDR images are measured on their float values. Channels that are not measured, for
+/// example the green, blue and alpha channel of BC4, have an error of 0.0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ErrorMetrics {
+    squared_error: [f64; 4],
+    max_error: f64,
+    pixel_count: u32,
+    channels: [bool; 4],
+    peak: f64,
+}
+
+impl Default for ErrorMetrics {
+    fn default() -> Self {
+        Self::new([false; 4])
+    }
+}
+
+impl ErrorMetrics {
+    /// Creates empty metrics, which measure the channels enabled in `channels`.
+    pub(crate) const fn new(channels: [bool; 4]) -> Self {
+        Self {
+            squared_error: [0.0; 4],
+            max_error: 0.0,
+            pixel_count: 0,
+            channels,
+            peak: 1.0,
+        }
+    }
+
+    /// Sets the peak value used to calculate the PSNR.
+    pub(crate) const fn with_peak(mut self, peak: f64) -> Self {
+        self.peak = peak;
+        self
+    }
+
+    /// Adds the error between an original and a decoded pixel.
+    pub(crate) fn add_pixel(&mut self, original: [f64; 4], decoded: [f64; 4]) {
+        for channel in 0..4 {
+            if self.channels[channel] {
+                let difference = original[channel] - decoded[channel];
+                self.squared_error[channel] += difference * difference;
+                self.max_error = self.max_error.max(difference.abs());
+            }
+        }
+        self.pixel_count += 1;
+    }
+
+    /// Adds the error between an original and a decoded RGBA8 pixel.
+    pub(crate) fn add_pixel_rgba8(&mut self, original: &[u8], decoded: &[u8]) {
+        self.add_pixel(unorm8_pixel(original), unorm8_pixel(decoded));
+    }
+
+    /// Adds the error of all pixels measured by `other`.
+    pub(crate) fn merge(&mut self, other: &ErrorMetrics) {
+        for channel in 0..4 {
+            self.squared_error[channel] += other.squared_error[channel];
+        }
+        self.max_error = self.max_error.max(other.max_error);
+        self.pixel_count += other.pixel_count;
+    }
+
+    /// The number of measured pixels.
+    pub fn pixel_count(&self) -> u32 {
+        self.pixel_count
+    }
+
+    /// Returns `true` for the red, green, blue and alpha channel if the channel is measured.
+    pub fn channels(&self) -> [bool; 4] {
+        self.channels
+    }
+
+    /// The peak value used to calculate the PSNR.
+    ///
+    /// This is 1.0 for RGBA8 images and the largest absolute value of the measured channels of
+    /// the original image for HDR images.
+    pub fn peak(&self) -> f64 {
+        self.peak
+    }
+
+    /// The mean squared error of the red, green, blue and alpha channel.
+    pub fn channel_mse(&self) -> [f64; 4] {
+        if self.pixel_count == 0 {
+            return [0.0; 4];
+        }
+
+        self.squared_error
+            .map(|squared_error| squared_error / self.pixel_count as f64)
+    }
+
+    /// The mean squared error averaged over all measured channels.
+    pub fn mse(&self) -> f64 {
+        let channel_count = self.channels.iter().filter(|&&measured| measured).count();
+        if channel_count == 0 {
+            return 0.0;
+        }
+
+        self.channel_mse().iter().sum::<f64>() / channel_count as f64
+    }
+
+    /// The largest absolute error of any measured channel of any pixel.
+    pub fn max_error(&self) -> f64 {
+        self.max_error
+    }
+
+    /// The root mean squared error of the red, green, blue and alpha channel.
+    pub fn channel_rmse(&self) -> [f64; 4] {
+        self.channel_mse().map(f64::sqrt)
+    }
+
+    /// The root of the mean squared error of all measured channels.
+    pub fn rmse(&self) -> f64 {
+        self.mse().sqrt()
+    }
+
+    /// The peak signal-to-noise ratio in dB of the red, green, blue and alpha channel.
+    ///
+    /// Channels without any error have a PSNR of [`f64::INFINITY`].
+    pub fn channel_psnr(&self) -> [f64; 4] {
+        self.channel_mse().map(|mse| psnr(mse, self.peak))
+    }
+
+    /// The peak signal-to-noise ratio in dB of the mean squared error of all measured channels.
+    ///
+    /// Returns [`f64::INFINITY`] if there is no error.
+    pub fn psnr(&self) -> f64 {
+        psnr(self.mse(), self.peak)
+    }
+}
+
+/// Error metrics of every block of a compressed image.
+///
+/// Created by [`encode::compress_rgba8_with_metrics()`] and
+/// [`encode::compress_rgba16_with_metrics()`], which measure every block right after it was
+/// encoded. Only the pixels inside the image are measured, the padding pixels of the
+/// blocks on the right and bottom edge are ignored.
+///
+/// [`encode::compress_rgba8_with_metrics()`]: crate::encode::compress_rgba8_with_metrics
+/// [`encode::compress_rgba16_with_metrics()`]: crate::encode::compress_rgba16_with_metrics
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompressionMetrics {
+    image: ErrorMetrics,
+    blocks: Vec<ErrorMetrics>,
+    blocks_per_row: u32,
+}
+
+impl CompressionMetrics {
+    /// Creates the metrics of an image from the metrics of its blocks in row-major order. The
+    /// blocks are merged into the empty `image` metrics.
+    pub(crate) fn from_blocks(
+        image: ErrorMetrics,
+        blocks: Vec<ErrorMetrics>,
+        blocks_per_row: u32,
+    ) -> Self {
+        let image = blocks.iter().fold(image, |mut image, block| {
+            image.merge(block);
+            image
+        });
+
+        Self {
+            image,
+            blocks,
+            blocks_per_row,
+        }
+    }
+
+    /// The error metrics of the whole image.
+    pub fn image(&self) -> &ErrorMetrics {
+        &self.image
+    }
+
+    /// The error metrics of every block in row-major order.
+    pub fn blocks(&self) -> &[ErrorMetrics] {
+        &self.blocks
+    }
+
+    /// The number of blocks per row of the image.
+    pub fn blocks_per_row(&self) -> u32 {
+        self.blocks_per_row
+    }
+
+    /// The error metrics of the block at column `x` and row `y`.
+    pub fn block(&self, x: u32, y: u32) -> Option<&ErrorMetrics> {
+        if x >= self.blocks_per_row {
+            return None;
+        }
+
+        self.blocks
+            .get(y as usize * self.blocks_per_row as usize + x as usize)
+    }
+
+    /// Returns the column, the row and the metrics of the block with the highest mean squared
+    /// error, or `None` if the image is empty.
+    pub fn worst_block(&self) -> Option<(u32, u32, &ErrorMetrics)> {
+        let (index, metrics) = self
+            .blocks
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.mse().total_cmp(&b.mse()))?;
+
+        let index = index as u32;
+        Some((
+            index % self.blocks_per_row,
+            index / self.blocks_per_row,
+            metrics,
+        ))
+    }
+}
+
+/// Quality metrics of a decoded image compared against its original image.
+///
+/// Created by [`compare_rgba8()`], [`compare_rgba16f()`] and [`compare_rgba32f()`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImageMetrics {
+    error: ErrorMetrics,
+    channel_ssim: [f64; 4],
+}
+
+impl ImageMetrics {
+    /// The mean squared error, the RMSE and the PSNR per channel.
+    pub fn error(&self) -> &ErrorMetrics {
+        &self.error
+    }
+
+    /// The root of the mean squared error of all measured channels.
+    pub fn rmse(&self) -> f64 {
+        self.error.rmse()
+    }
+
+    /// The peak signal-to-noise ratio in dB of all measured channels.
+    ///
+    /// Returns [`f64::INFINITY`] if the images are identical.
+    pub fn psnr(&self) -> f64 {
+        self.error.psnr()
+    }
+
+    /// The mean structural similarity index of the red, green, blue and alpha channel.
+    ///
+    /// The SSIM is computed over 8x8 pixel windows, which are 4 pixels apart. It ranges from -1.0
+    /// to 1.0, which is reached by identical images. Channels that are not measured have an SSIM
+    /// of 1.0.
+    pub fn channel_ssim(&self) -> [f64; 4] {
+        self.channel_ssim
+    }
+
+    /// The mean structural similarity index of all measured channels.
+    pub fn ssim(&self) -> f64 {
+        let measured = self.error.channels.iter().zip(self.channel_ssim);
+        let (sum, count) = measured
+            .filter(|(&measured, _)| measured)
+            .fold((0.0, 0), |(sum, count), (_, ssim)| (sum + ssim, count + 1));
+
+        if count == 0 {
+            return 1.0;
+        }
+
+        sum / count as f64
+    }
+
+    /// The mean squared error of the measured color channels, weighted by the Rec. 601 luma
+    /// weights.
+    ///
+    /// Errors in the green channel are weighted the most and errors in the blue channel the
+    /// least, which matches the sensitivity of the human eye better than [`ErrorMetrics::mse()`].
+    /// The alpha channel is not included.
+    pub fn perceptual_mse(&self) -> f64 {
+        let channel_mse = self.error.channel_mse();
+
+        let (error, weight) = (0..3).filter(|&channel| self.error.channels[channel]).fold(
+            (0.0, 0.0),
+            |(error, weight), channel| {
+                (
+                    error + PERCEPTUAL_WEIGHTS[channel] * channel_mse[channel],
+                    weight + PERCEPTUAL_WEIGHTS[channel],
+                )
+            },
+        );
+
+        if weight == 0.0 {
+            return 0.0;
+        }
+
+        error / weight
+    }
+
+    /// The peak signal-to-noise ratio in dB of the perceptual mean squared error.
+    ///
+    /// Returns [`f64::INFINITY`] if there is no error.
+    pub fn perceptual_psnr(&self) -> f64 {
+        psnr(self.perceptual_mse(), self.error.peak)
+    }
+}
+
+/// The angle between original and decoded normals of a normal map.
+///
+/// Created by [`compare_normal_maps_rgba8()`] and [`encode::compress_normal_map_rgba8()`]. All
+/// angles are in degrees.
+///
+/// [`encode::compress_normal_map_rgba8()`]: crate::encode::compress_normal_map_rgba8
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AngularErrorMetrics {
+    angle_sum: f64,
+    squared_angle_sum: f64,
+    max_angle: f64,
+    pixel_count: u32,
+}
+
+impl AngularErrorMetrics {
+    /// Adds the angle between an original and a decoded unit length normal.
+    pub(crate) fn add_normal(&mut self, original: [f32; 3], decoded: [f32; 3]) {
+        let cosine = (0..3)
+            .map(|axis| original[axis] as f64 * decoded[axis] as f64)
+            .sum::<f64>()
+            .clamp(-1.0, 1.0);
+        let angle = cosine.acos().to_degrees();
+
+        self.angle_sum += angle;
+        self.squared_angle_sum += angle * angle;
+        self.max_angle = self.max_angle.max(angle);
+        self.pixel_count += 1;
+    }
+
+    /// The number of measured pixels.
+    pub fn pixel_count(&self) -> u32 {
+        self.pixel_count
+    }
+
+    /// The mean angle between the original and the decoded normals.
+    pub fn mean_angle(&self) -> f64 {
+        if self.pixel_count == 0 {
+            return 0.0;
+        }
+        self.angle_sum / self.pixel_count as f64
+    }
+
+    /// The root mean square of the angles between the original and the decoded normals.
+    pub fn rms_angle(&self) -> f64 {
+        if self.pixel_count == 0 {
+            return 0.0;
+        }
+        (self.squared_angle_sum / self.pixel_count as f64).sqrt()
+    }
+
+    /// The largest angle between an original and a decoded normal.
+    pub fn max_angle(&self) -> f64 {
+        self.max_angle
+    }
+}
+
+/// Returns the channels of RGBA data that are stored by `variant`, which are measured by the
+/// error metrics.
+///
+/// These are RGB for BC1 (plus alpha with an alpha threshold), RGBA for BC2 and BC3, red for BC4,
+/// red and green for BC5, RGB for BC6H and RGB for BC7 (plus alpha if the settings encode
+/// alpha).
+pub fn stored_channels(variant: CompressionVariant) -> [bool; 4] {
+    match variant {
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC1(settings) => [true, true, true, settings.alpha_threshold() > 0],
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC2(..) | CompressionVariant::BC3(..) => [true; 4],
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC4 | CompressionVariant::BC4Snorm => [true, false, false, false],
+        #[cfg(feature = "bc15")]
+        CompressionVariant::BC5 | CompressionVariant::BC5Snorm => [true, true, false, false],
+        #[cfg(feature = "bc6h")]
+        CompressionVariant::BC6H(..) => [true, true, true, false],
+        #[cfg(feature = "bc7")]
+        CompressionVariant::BC7(settings) => [true, true, true, settings.channels == 4],
+    }
+}
+
+/// Compares a decoded RGBA8 image against its original image.
+///
+/// Both images must be tightly packed RGBA8 data with `width * height * 4` bytes. Only the
+/// channels enabled in `channels` are measured, see [`stored_channels()`]. The channel values
+/// are normalized to the range of 0.0 to 1.0.
+///
+/// # Panics
+/// * If [`try_compare_rgba8()`] returns an error
+pub fn compare_rgba8(
+    original: &[u8],
+    decoded: &[u8],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> ImageMetrics {
+    match try_compare_rgba8(original, decoded, width, height, channels) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compares a decoded RGBA8 image against its original image.
+///
+/// This is the fallible version of [`compare_rgba8()`].
+///
+/// # Errors
+/// * [`Error::BufferSizeMismatch`] if `original` or `decoded` has not the expected size
+///   (`width * height * 4`)
+pub fn try_compare_rgba8(
+    original: &[u8],
+    decoded: &[u8],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> Result<ImageMetrics, Error> {
+    validate_images(original.len(), decoded.len(), width, height)?;
+
+    Ok(compare(
+        |index| unorm8_pixel(&original[index * 4..]),
+        |index| unorm8_pixel(&decoded[index * 4..]),
+        width as usize,
+        height as usize,
+        channels,
+        1.0,
+    ))
+}
+
+/// Compares a decoded RGBA16F image against its original HDR image.
+///
+/// Both images must be tightly packed RGBA16F data with `width * height * 4` values. Only the
+/// channels enabled in `channels` are measured, see [`stored_channels()`]. The PSNR is
+/// calculated relative to the largest absolute value of the measured channels of the original
+/// image.
+///
+/// # Panics
+/// * If [`try_compare_rgba16f()`] returns an error
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn compare_rgba16f(
+    original: &[half::f16],
+    decoded: &[half::f16],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> ImageMetrics {
+    match try_compare_rgba16f(original, decoded, width, height, channels) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compares a decoded RGBA16F image against its original HDR image.
+///
+/// This is the fallible version of [`compare_rgba16f()`].
+///
+/// # Errors
+/// * [`Error::BufferSizeMismatch`] if `original` or `decoded` has not the expected size
+///   (`width * height * 4`)
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_compare_rgba16f(
+    original: &[half::f16],
+    decoded: &[half::f16],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> Result<ImageMetrics, Error> {
+    validate_images(original.len(), decoded.len(), width, height)?;
+
+    let pixel = |data: &[half::f16], index: usize| -> [f64; 4] {
+        std::array::from_fn(|channel| data[index * 4 + channel].to_f64())
+    };
+
+    Ok(compare(
+        |index| pixel(original, index),
+        |index| pixel(decoded, index),
+        width as usize,
+        height as usize,
+        channels,
+        hdr_peak(original.iter().map(|value| value.to_f64()), channels),
+    ))
+}
+
+/// Compares a decoded RGBA32F image against its original HDR image.
+///
+/// Both images must be tightly packed RGBA32F data with `width * height * 4` values. Only the
+/// channels enabled in `channels` are measured, see [`stored_channels()`]. The PSNR is
+/// calculated relative to the largest absolute value of the measured channels of the original
+/// image.
+///
+/// # Panics
+/// * If [`try_compare_rgba32f()`] returns an error
+pub fn compare_rgba32f(
+    original: &[f32],
+    decoded: &[f32],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> ImageMetrics {
+    match try_compare_rgba32f(original, decoded, width, height, channels) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compares a decoded RGBA32F image against its original HDR image.
+///
+/// This is the fallible version of [`compare_rgba32f()`].
+///
+/// # Errors
+/// * [`Error::BufferSizeMismatch`] if `original` or `decoded` has not the expected size
+///   (`width * height * 4`)
+pub fn try_compare_rgba32f(
+    original: &[f32],
+    decoded: &[f32],
+    width: u32,
+    height: u32,
+    channels: [bool; 4],
+) -> Result<ImageMetrics, Error> {
+    validate_images(original.len(), decoded.len(), width, height)?;
+
+    let pixel = |data: &[f32], index: usize| -> [f64; 4] {
+        std::array::from_fn(|channel| data[index * 4 + channel] as f64)
+    };
+
+    Ok(compare(
+        |index| pixel(original, index),
+        |index| pixel(decoded, index),
+        width as usize,
+        height as usize,
+        channels,
+        hdr_peak(original.iter().map(|&value| value as f64), channels),
+    ))
+}
+
+/// Compares a decoded normal map against its original normal map.
+///
+/// Both images must be tightly packed RGBA8 data with `width * height * 4` bytes, which store
+/// tangent-space normals in the red, green and blue channel as `n * 0.5 + 0.5`, for example the
+/// output of [`decode::decompress_normal_map_as_rgba8()`]. The normals of both images are
+/// renormalized before the angle between them is measured.
+///
+/// [`decode::decompress_normal_map_as_rgba8()`]: crate::decode::decompress_normal_map_as_rgba8
+///
+/// # Panics
+/// * If [`try_compare_normal_maps_rgba8()`] returns an error
+pub fn compare_normal_maps_rgba8(
+    original: &[u8],
+    decoded: &[u8],
+    width: u32,
+    height: u32,
+) -> AngularErrorMetrics {
+    match try_compare_normal_maps_rgba8(original, decoded, width, height) {
+        Ok(metrics) => metrics,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Compares a decoded normal map against its original normal map.
+///
+/// This is the fallible version of [`compare_normal_maps_rgba8()`].
+///
+/// # Errors
+/// * [`Error::BufferSizeMismatch`] if `original` or `decoded` has not the expected size
+///   (`width * height * 4`)
+pub fn try_compare_normal_maps_rgba8(
+    original: &[u8],
+    decoded: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<AngularErrorMetrics, Error> {
+    validate_images(original.len(), decoded.len(), width, height)?;
+
+    let mut metrics = AngularErrorMetrics::default();
+    for (original, decoded) in original.chunks_exact(4).zip(decoded.chunks_exact(4)) {
+        metrics.add_normal(unpack_normal(original), unpack_normal(decoded));
+    }
+
+    Ok(metrics)
+}
+
+/// Validates that both images hold `width * height` RGBA pixels.
+fn validate_images(
+    original_len: usize,
+    decoded_len: usize,
+    width: u32,
+    height: u32,
+) -> Result<(), Error> {
+    let expected = width as usize * height as usize * 4;
+
+    for actual in [original_len, decoded_len] {
+        if actual != expected {
+            return Err(Error::BufferSizeMismatch { expected, actual });
+        }
+    }
+
+    Ok(())
+}
+
+fn unorm8_pixel(pixel: &[u8]) -> [f64; 4] {
+    std::array::from_fn(|channel| pixel[channel] as f64 / 255.0)
+}
+
+/// Unpacks the normal stored as `n * 0.5 + 0.5` in the red, green and blue channel of an RGBA8
+/// pixel and renormalizes it.
+///
+/// The Z component of normals pointing below the surface is clamped to zero, zero length normals
+/// point straight up.
+pub(crate) fn unpack_normal(pixel: &[u8]) -> [f32; 3] {
+    let [x, y, z] = std::array::from_fn(|axis| pixel[axis] as f32 / 255.0 * 2.0 - 1.0);
+    let z = z.max(0.0);
+
+    let length = (x * x + y * y + z * z).sqrt();
+    if length > 0.0 {
+        [x / length, y / length, z / length]
+    } else {
+        [0.0, 0.0, 1.0]
+    }
+}
+
+/// Returns the largest absolute value of the measured channels, or 1.0 for black images.
+pub(crate) fn hdr_peak(values: impl Iterator<Item = f64>, channels: [bool; 4]) -> f64 {
+    let peak = values
+        .enumerate()
+        .filter(|(index, value)| channels[index % 4] && value.is_finite())
+        .fold(0.0, |peak: f64, (_, value)| peak.max(value.abs()));
+
+    if peak > 0.0 {
+        peak
+    } else {
+        1.0
+    }
+}
+
+/// Compares the pixels returned by `original` and `decoded` for every pixel index.
+fn compare<O, D>(
+    original: O,
+    decoded: D,
+    width: usize,
+    height: usize,
+    channels: [bool; 4],
+    peak: f64,
+) -> ImageMetrics
+where
+    O: Fn(usize) -> [f64; 4],
+    D: Fn(usize) -> [f64; 4],
+{
+    let mut error = ErrorMetrics::new(channels).with_peak(peak);
+
+    for index in 0..width * height {
+        error.add_pixel(original(index), decoded(index));
+    }
+
+    let channel_ssim = ssim(&original, &decoded, width, height, channels, peak);
+
+    ImageMetrics {
+        error,
+        channel_ssim,
+    }
+}
+
+/// Returns the start of every SSIM window along an axis of `size` pixels.
+///
+/// The last window is aligned to the end of the image, so that all pixels are covered.
+fn window_starts(size: usize) -> Vec<usize> {
+    let last = size.saturating_sub(SSIM_WINDOW);
+
+    let mut starts: Vec<usize> = (0..=last).step_by(SSIM_STEP).collect();
+    if last % SSIM_STEP != 0 {
+        starts.push(last);
+    }
+
+    starts
+}
+
+/// Calculates the mean SSIM of every channel over all windows of the image.
+fn ssim<O, D>(
+    original: &O,
+    decoded: &D,
+    width: usize,
+    height: usize,
+    channels: [bool; 4],
+    peak: f64,
+) -> [f64; 4]
+where
+    O: Fn(usize) -> [f64; 4],
+    D: Fn(usize) -> [f64; 4],
+{
+    if width == 0 || height == 0 {
+        return [1.0; 4];
+    }
+
+    let c1 = (0.01 * peak) * (0.01 * peak);
+    let c2 = (0.03 * peak) * (0.03 * peak);
+
+    let window_width = usize::min(width, SSIM_WINDOW);
+    let window_height = usize::min(height, SSIM_WINDOW);
+    let window_pixels = (window_width * window_height) as f64;
+
+    let mut ssim_sum = [0.0; 4];
+    let mut window_count = 0;
+
+    for window_y in window_starts(height) {
+        for window_x in window_starts(width) {
+            let mut sum_x = [0.0; 4];
+            let mut sum_y = [0.0; 4];
+            let mut sum_xx = [0.0; 4];
+            let mut sum_yy = [0.0; 4];
+            let mut sum_xy = [0.0; 4];
+
+            for y in window_y..window_y + window_height {
+                for x in window_x..window_x + window_width {
+                    let index = y * width + x;
+                    let original = original(index);
+                    let decoded = decoded(index);
+
+                    for channel in 0..4 {
+                        let (a, b) = (original[channel], decoded[channel]);
+                        sum_x[channel] += a;
+                        sum_y[channel] += b;
+                        sum_xx[channel] += a * a;
+                        sum_yy[channel] += b * b;
+                        sum_xy[channel] += a * b;
+                    }
+                }
+            }
+
+            for channel in 0..4 {
+                let mean_x = sum_x[channel] / window_pixels;
+                let mean_y = sum_y[channel] / window_pixels;
+                let variance_x = sum_xx[channel] / window_pixels - mean_x * mean_x;
+                let variance_y = sum_yy[channel] / window_pixels - mean_y * mean_y;
+                let covariance = sum_xy[channel] / window_pixels - mean_x * mean_y;
+
+                ssim_sum[channel] += ((2.0 * mean_x * mean_y + c1) * (2.0 * covariance + c2))
+                    / ((mean_x * mean_x + mean_y * mean_y + c1) * (variance_x + variance_y + c2));
+            }
+
+            window_count += 1;
+        }
+    }
+
+    std::array::from_fn(|channel| {
+        if channels[channel] {
+            ssim_sum[channel] / window_count as f64
+        } else {
+            1.0
+        }
+    })
+}
+
+fn psnr(mse: f64, peak: f64) -> f64 {
+    if mse == 0.0 {
+        f64::INFINITY
+    } else {
+        10.0 * (peak * peak / mse).log10()
+    }
+}
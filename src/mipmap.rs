@@ -0,0 +1,646 @@
+//! Mipmap chain generation and compression.
+//!
+//! The functions in this module build all mip levels of a base image with the given
+//! [`MipmapSettings`] and compress every level with a [`CompressionVariant`]. The compressed levels
+//! are stored back to back in a single buffer, starting with the base level. The GPU based path
+//! is provided by [`GpuBlockCompressor::add_mipmap_compression_tasks()`].
+//!
+//! [`GpuBlockCompressor::add_mipmap_compression_tasks()`]: crate::GpuBlockCompressor::add_mipmap_compression_tasks
+
+use std::f32::consts::PI;
+
+use crate::{encode, for_each_row, CompressionVariant, Error};
+
+/// Filter used to downsample an image to the next mip level.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MipmapFilter {
+    /// Averages all pixels covered by the destination pixel. Fast, but produces blurry mip levels.
+    #[default]
+    Box,
+    /// Kaiser windowed sinc filter. Produces sharp mip levels with little ringing.
+    Kaiser,
+    /// Three lobed Lanczos filter. Produces the sharpest mip levels, but can cause ringing around
+    /// hard edges.
+    Lanczos,
+}
+
+impl MipmapFilter {
+    /// The support radius of the filter kernel in destination pixels.
+    fn radius(self) -> f32 {
+        match self {
+            MipmapFilter::Box => 0.5,
+            MipmapFilter::Kaiser => 3.0,
+            MipmapFilter::Lanczos => 3.0,
+        }
+    }
+
+    fn evaluate(self, x: f32) -> f32 {
+        const KAISER_ALPHA: f32 = 4.0;
+        const KAISER_WIDTH: f32 = 3.0;
+        const LANCZOS_LOBES: f32 = 3.0;
+
+        let x = x.abs();
+
+        match self {
+            MipmapFilter::Box => {
+                if x <= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            MipmapFilter::Kaiser => {
+                if x < KAISER_WIDTH {
+                    let window = f32::sqrt(1.0 - (x / KAISER_WIDTH) * (x / KAISER_WIDTH));
+                    sinc(x) * bessel_i0(KAISER_ALPHA * window) / bessel_i0(KAISER_ALPHA)
+                } else {
+                    0.0
+                }
+            }
+            MipmapFilter::Lanczos => {
+                if x < LANCZOS_LOBES {
+                    sinc(x) * sinc(x / LANCZOS_LOBES)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1.0e-4 {
+        1.0
+    } else {
+        f32::sin(PI * x) / (PI * x)
+    }
+}
+
+/// Zeroth order modified Bessel function of the first kind.
+fn bessel_i0(x: f32) -> f32 {
+    let half_x = x * 0.5;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+
+    for k in 1..32 {
+        term *= half_x / k as f32;
+        let squared = term * term;
+        sum += squared;
+
+        if squared < sum * 1.0e-10 {
+            break;
+        }
+    }
+
+    sum
+}
+
+/// Settings used to build a mip chain.
+///
+/// By default the mip levels are filtered with a box filter directly on the stored values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MipmapSettings {
+    /// The filter used to downsample each mip level.
+    pub filter: MipmapFilter,
+    /// Treats the color channels of RGBA8 data as sRGB encoded. The colors are converted to
+    /// linear space before filtering and back to sRGB afterwards. Alpha is always linear.
+    /// RGBA16F data is always linear and ignores this setting.
+    pub srgb: bool,
+    /// Weights the color channels by alpha while filtering (premultiplied alpha), so that
+    /// colors of transparent pixels don't bleed into visible pixels. The stored mip levels
+    /// use straight alpha.
+    pub alpha_weighted: bool,
+}
+
+impl MipmapSettings {
+    /// Creates settings using the given `filter` on the stored values.
+    pub const fn new(filter: MipmapFilter) -> Self {
+        Self {
+            filter,
+            srgb: false,
+            alpha_weighted: false,
+        }
+    }
+
+    /// Sets whether the color channels of RGBA8 data are sRGB encoded.
+    pub const fn with_srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Sets whether the color channels are weighted by alpha while filtering.
+    pub const fn with_alpha_weighting(mut self, alpha_weighted: bool) -> Self {
+        self.alpha_weighted = alpha_weighted;
+        self
+    }
+}
+
+impl From<MipmapFilter> for MipmapSettings {
+    fn from(filter: MipmapFilter) -> Self {
+        Self::new(filter)
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// A single uncompressed mip level with tightly packed RGBA pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MipLevel<T> {
+    /// The width of the mip level in pixels.
+    pub width: u32,
+    /// The height of the mip level in pixels.
+    pub height: u32,
+    /// The RGBA pixel data of the mip level.
+    pub data: Vec<T>,
+}
+
+/// The location of a single compressed mip level inside the blocks buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompressedMipLevel {
+    /// The width of the mip level in pixels.
+    pub width: u32,
+    /// The height of the mip level in pixels.
+    pub height: u32,
+    /// The offset of the compressed blocks in bytes.
+    pub offset: usize,
+    /// The size of the compressed blocks in bytes.
+    pub size: usize,
+}
+
+/// The compressed blocks of a full mip chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressedMipmaps {
+    /// The compression variant used to compress the mip levels.
+    pub variant: CompressionVariant,
+    /// The location of every mip level inside `blocks`, starting with the base level.
+    pub levels: Vec<CompressedMipLevel>,
+    /// The compressed blocks of all mip levels.
+    pub blocks: Vec<u8>,
+}
+
+impl CompressedMipmaps {
+    /// Returns the compressed blocks of the given mip `level`.
+    ///
+    /// # Panics
+    /// - If `level` is out of range
+    pub fn level_data(&self, level: usize) -> &[u8] {
+        let level = self.levels[level];
+        &self.blocks[level.offset..level.offset + level.size]
+    }
+}
+
+/// Returns the number of mip levels of a full mip chain for the given dimensions.
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    u32::max(width, height).max(1).ilog2() + 1
+}
+
+/// Returns the dimensions of the mip `level` of an image with the given base dimensions.
+pub fn mip_level_size(width: u32, height: u32, level: u32) -> (u32, u32) {
+    (
+        width.checked_shr(level).unwrap_or(0).max(1),
+        height.checked_shr(level).unwrap_or(0).max(1),
+    )
+}
+
+/// Returns the location of every compressed mip level when `level_count` mip levels are stored
+/// back to back, starting with the base level.
+///
+/// The returned offsets are relative to the start of the base level.
+pub fn compressed_mip_levels(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    level_count: u32,
+) -> Vec<CompressedMipLevel> {
+    let mut offset = 0;
+
+    (0..level_count)
+        .map(|level| {
+            let (width, height) = mip_level_size(width, height, level);
+            let size = variant.blocks_byte_size(width, height);
+            let level = CompressedMipLevel {
+                width,
+                height,
+                offset,
+                size,
+            };
+            offset += size;
+            level
+        })
+        .collect()
+}
+
+/// Builds the full mip chain of RGBA8 data.
+///
+/// The returned levels start with a tightly packed copy of the base level. Every following level
+/// has half the size of the previous level and is filtered with the given `settings`.
+///
+/// # Panics
+/// - If [`try_generate_mipmaps_rgba8()`] returns an error
+pub fn generate_mipmaps_rgba8(
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Vec<MipLevel<u8>> {
+    match try_generate_mipmaps_rgba8(rgba_data, width, height, stride, settings) {
+        Ok(levels) => levels,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Builds the full mip chain of RGBA8 data.
+///
+/// This is the fallible version of [`generate_mipmaps_rgba8()`].
+///
+/// # Errors
+/// - [`Error::InvalidDimensions`] if `width` or `height` is zero
+/// - [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// - [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+pub fn try_generate_mipmaps_rgba8(
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Result<Vec<MipLevel<u8>>, Error> {
+    generate_mipmaps(
+        rgba_data,
+        width,
+        height,
+        stride,
+        settings,
+        |value| value as f32 / 255.0,
+        |value| (value * 255.0).round().clamp(0.0, 255.0) as u8,
+    )
+}
+
+/// Builds the full mip chain of RGBA16F data.
+///
+/// The returned levels start with a tightly packed copy of the base level. Every following level
+/// has half the size of the previous level and is filtered with the given `settings`.
+///
+/// # Panics
+/// - If [`try_generate_mipmaps_rgba16f()`] returns an error
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn generate_mipmaps_rgba16f(
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Vec<MipLevel<half::f16>> {
+    match try_generate_mipmaps_rgba16f(rgba_data, width, height, stride, settings) {
+        Ok(levels) => levels,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Builds the full mip chain of RGBA16F data.
+///
+/// This is the fallible version of [`generate_mipmaps_rgba16f()`].
+///
+/// # Errors
+/// - [`Error::InvalidDimensions`] if `width` or `height` is zero
+/// - [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// - [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_generate_mipmaps_rgba16f(
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Result<Vec<MipLevel<half::f16>>, Error> {
+    generate_mipmaps(
+        rgba_data,
+        width,
+        height,
+        stride,
+        MipmapSettings {
+            srgb: false,
+            ..settings
+        },
+        half::f16::to_f32,
+        half::f16::from_f32,
+    )
+}
+
+/// Builds the full mip chain of RGBA8 data and compresses every mip level.
+///
+/// # Panics
+/// - If [`try_compress_mipmaps_rgba8()`] returns an error
+///
+/// # Example
+/// ```
+/// use block_compression::{
+///     mipmap::{compress_mipmaps_rgba8, MipmapFilter, MipmapSettings},
+///     BC123Settings, CompressionVariant,
+/// };
+///
+/// let rgba_data = vec![0u8; 256 * 256 * 4]; // Your RGBA data
+/// let width = 256;
+/// let height = 256;
+/// let stride = width * 4; // Tightly packed rows
+///
+/// let mipmaps = compress_mipmaps_rgba8(
+///     CompressionVariant::BC1(BC123Settings::new()),
+///     &rgba_data,
+///     width,
+///     height,
+///     stride,
+///     MipmapSettings::new(MipmapFilter::Kaiser)
+///         .with_srgb(true)
+///         .with_alpha_weighting(true),
+/// );
+///
+/// assert_eq!(mipmaps.levels.len(), 9);
+/// assert_eq!(mipmaps.level_data(8).len(), 8);
+/// ```
+pub fn compress_mipmaps_rgba8(
+    variant: CompressionVariant,
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> CompressedMipmaps {
+    match try_compress_mipmaps_rgba8(variant, rgba_data, width, height, stride, settings) {
+        Ok(mipmaps) => mipmaps,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Builds the full mip chain of RGBA8 data and compresses every mip level.
+///
+/// This is the fallible version of [`compress_mipmaps_rgba8()`].
+///
+/// # Errors
+/// - [`Error::InvalidDimensions`] if `width` or `height` is zero
+/// - [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// - [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+pub fn try_compress_mipmaps_rgba8(
+    variant: CompressionVariant,
+    rgba_data: &[u8],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Result<CompressedMipmaps, Error> {
+    let mip_levels = try_generate_mipmaps_rgba8(rgba_data, width, height, stride, settings)?;
+
+    compress_mip_levels(variant, &mip_levels, encode::try_compress_rgba8)
+}
+
+/// Builds the full mip chain of RGBA16F data and compresses every mip level with BC6H.
+///
+/// # Panics
+/// - If [`try_compress_mipmaps_rgba16()`] returns an error
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn compress_mipmaps_rgba16(
+    variant: CompressionVariant,
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> CompressedMipmaps {
+    match try_compress_mipmaps_rgba16(variant, rgba_data, width, height, stride, settings) {
+        Ok(mipmaps) => mipmaps,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// Builds the full mip chain of RGBA16F data and compresses every mip level with BC6H.
+///
+/// This is the fallible version of [`compress_mipmaps_rgba16()`].
+///
+/// # Errors
+/// - [`Error::UnsupportedVariant`] if `variant` is not `CompressionVariant::BC6H`
+/// - [`Error::InvalidDimensions`] if `width` or `height` is zero
+/// - [`Error::InvalidStride`] if `stride` is smaller than `width * 4`
+/// - [`Error::BufferTooSmall`] if `rgba_data` is too small for the given dimensions and stride
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+pub fn try_compress_mipmaps_rgba16(
+    variant: CompressionVariant,
+    rgba_data: &[half::f16],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+) -> Result<CompressedMipmaps, Error> {
+    if !matches!(variant, CompressionVariant::BC6H(..)) {
+        return Err(Error::UnsupportedVariant);
+    }
+
+    let mip_levels = try_generate_mipmaps_rgba16f(rgba_data, width, height, stride, settings)?;
+
+    compress_mip_levels(variant, &mip_levels, encode::try_compress_rgba16)
+}
+
+fn compress_mip_levels<T>(
+    variant: CompressionVariant,
+    mip_levels: &[MipLevel<T>],
+    compress: impl Fn(CompressionVariant, &[T], &mut [u8], u32, u32, u32) -> Result<(), Error>,
+) -> Result<CompressedMipmaps, Error> {
+    let base = &mip_levels[0];
+    let levels = compressed_mip_levels(variant, base.width, base.height, mip_levels.len() as u32);
+
+    let total_size = levels.last().map_or(0, |level| level.offset + level.size);
+    let mut blocks = vec![0; total_size];
+
+    for (mip_level, level) in mip_levels.iter().zip(levels.iter()) {
+        compress(
+            variant,
+            &mip_level.data,
+            &mut blocks[level.offset..level.offset + level.size],
+            level.width,
+            level.height,
+            level.width * 4,
+        )?;
+    }
+
+    Ok(CompressedMipmaps {
+        variant,
+        levels,
+        blocks,
+    })
+}
+
+fn generate_mipmaps<T: Copy>(
+    data: &[T],
+    width: u32,
+    height: u32,
+    stride: u32,
+    settings: MipmapSettings,
+    to_f32: impl Fn(T) -> f32,
+    from_f32: impl Fn(f32) -> T,
+) -> Result<Vec<MipLevel<T>>, Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::InvalidDimensions { width, height });
+    }
+
+    encode::validate_source(data.len(), width, height, stride)?;
+
+    let row_size = width as usize * 4;
+    let base_data: Vec<T> = data
+        .chunks(stride as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[..row_size])
+        .copied()
+        .collect();
+
+    // The filtered levels are kept in linear and optionally premultiplied space, so that every
+    // level is filtered from the unquantized previous level.
+    let mut current: Vec<f32> = base_data.iter().map(|&value| to_f32(value)).collect();
+    for pixel in current.chunks_exact_mut(4) {
+        let alpha = pixel[3];
+        for value in pixel[..3].iter_mut() {
+            if settings.srgb {
+                *value = srgb_to_linear(*value);
+            }
+            if settings.alpha_weighted {
+                *value *= alpha;
+            }
+        }
+    }
+
+    let mut levels = vec![MipLevel {
+        width,
+        height,
+        data: base_data,
+    }];
+
+    for level in 1..mip_level_count(width, height) {
+        let (previous_width, previous_height) = mip_level_size(width, height, level - 1);
+        let (level_width, level_height) = mip_level_size(width, height, level);
+
+        current = downsample(
+            &current,
+            previous_width as usize,
+            previous_height as usize,
+            level_width as usize,
+            level_height as usize,
+            settings.filter,
+        );
+
+        let mut data = Vec::with_capacity(current.len());
+        for pixel in current.chunks_exact(4) {
+            let alpha = pixel[3];
+            for &value in pixel[..3].iter() {
+                let mut value = value;
+                if settings.alpha_weighted {
+                    value = if alpha > 0.0 { value / alpha } else { 0.0 };
+                }
+                if settings.srgb {
+                    value = linear_to_srgb(value.clamp(0.0, 1.0));
+                }
+                data.push(from_f32(value));
+            }
+            data.push(from_f32(alpha));
+        }
+
+        levels.push(MipLevel {
+            width: level_width,
+            height: level_height,
+            data,
+        });
+    }
+
+    Ok(levels)
+}
+
+/// Source pixel indices and normalized weights of every destination pixel along one axis.
+fn filter_weights(
+    filter: MipmapFilter,
+    src_size: usize,
+    dst_size: usize,
+) -> Vec<Vec<(usize, f32)>> {
+    let scale = src_size as f32 / dst_size as f32;
+    let support = filter.radius() * scale;
+
+    (0..dst_size)
+        .map(|index| {
+            let center = (index as f32 + 0.5) * scale;
+            let first = f32::floor(center - support) as isize;
+            let last = f32::ceil(center + support) as isize;
+
+            let mut weights: Vec<(usize, f32)> = (first..=last)
+                .filter_map(|source| {
+                    let x = (source as f32 + 0.5 - center) / scale;
+                    let weight = filter.evaluate(x);
+                    let source = source.clamp(0, src_size as isize - 1) as usize;
+                    (weight != 0.0).then_some((source, weight))
+                })
+                .collect();
+
+            let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+            for (_, weight) in weights.iter_mut() {
+                *weight /= sum;
+            }
+
+            weights
+        })
+        .collect()
+}
+
+/// Resamples RGBA data with a separable filter, first horizontally, then vertically.
+fn downsample(
+    src: &[f32],
+    src_width: usize,
+    src_height: usize,
+    dst_width: usize,
+    dst_height: usize,
+    filter: MipmapFilter,
+) -> Vec<f32> {
+    let horizontal = filter_weights(filter, src_width, dst_width);
+    let vertical = filter_weights(filter, src_height, dst_height);
+
+    let src_row_size = src_width * 4;
+    let dst_row_size = dst_width * 4;
+
+    let mut horizontal_pass = vec![0.0; dst_row_size * src_height];
+    for_each_row(&mut horizontal_pass, dst_row_size, |y, row| {
+        let src_row = &src[y * src_row_size..(y + 1) * src_row_size];
+
+        for (pixel, weights) in row.chunks_exact_mut(4).zip(horizontal.iter()) {
+            for &(source, weight) in weights {
+                let source_pixel = &src_row[source * 4..source * 4 + 4];
+                for (value, source_value) in pixel.iter_mut().zip(source_pixel) {
+                    *value += source_value * weight;
+                }
+            }
+        }
+    });
+
+    let mut vertical_pass = vec![0.0; dst_row_size * dst_height];
+    for_each_row(&mut vertical_pass, dst_row_size, |y, row| {
+        for &(source, weight) in vertical[y].iter() {
+            let src_row = &horizontal_pass[source * dst_row_size..(source + 1) * dst_row_size];
+            for (value, source_value) in row.iter_mut().zip(src_row) {
+                *value += source_value * weight;
+            }
+        }
+    });
+
+    vertical_pass
+}
@@ -0,0 +1,128 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use wgpu::{Buffer, CommandEncoder, MapMode};
+
+use crate::Error;
+
+enum ReadbackState {
+    Pending(Option<Waker>),
+    Mapped,
+    Failed,
+    Taken,
+}
+
+/// Compressed block data that is read back from the GPU.
+///
+/// Created by [`crate::GpuBlockCompressor::read_back_blocks()`]. The staging buffer is mapped
+/// once the command encoder that recorded the copy is submitted and the GPU finished the work.
+/// Mapping callbacks only run while the device is polled, for example with
+/// `device.poll(PollType::Poll)` once per frame, or when another submission is made.
+///
+/// The block data can be awaited, since `BlocksReadback` implements [`Future`], or be fetched
+/// without blocking with [`BlocksReadback::try_receive()`].
+pub struct BlocksReadback {
+    staging_buffer: Option<Buffer>,
+    state: Arc<Mutex<ReadbackState>>,
+}
+
+impl BlocksReadback {
+    /// Creates a readback of `staging_buffer`, which is mapped when `encoder` is submitted.
+    pub(crate) fn new(encoder: &CommandEncoder, staging_buffer: Buffer) -> Self {
+        let state = Arc::new(Mutex::new(ReadbackState::Pending(None)));
+
+        let callback_state = Arc::clone(&state);
+        encoder.map_buffer_on_submit(&staging_buffer, MapMode::Read, .., move |result| {
+            let mut state = callback_state.lock().unwrap();
+            let previous = std::mem::replace(
+                &mut *state,
+                match result {
+                    Ok(()) => ReadbackState::Mapped,
+                    Err(_) => ReadbackState::Failed,
+                },
+            );
+            if let ReadbackState::Pending(Some(waker)) = previous {
+                waker.wake();
+            }
+        });
+
+        Self {
+            staging_buffer: Some(staging_buffer),
+            state,
+        }
+    }
+
+    /// Creates a readback without any block data, which is ready immediately.
+    pub(crate) fn empty() -> Self {
+        Self {
+            staging_buffer: None,
+            state: Arc::new(Mutex::new(ReadbackState::Mapped)),
+        }
+    }
+
+    /// Returns `true` if the block data is available.
+    pub fn is_ready(&self) -> bool {
+        matches!(
+            *self.state.lock().unwrap(),
+            ReadbackState::Mapped | ReadbackState::Failed
+        )
+    }
+
+    /// Returns the block data if it is available, without blocking.
+    ///
+    /// Returns `None` while the copy is still in flight and after the block data was taken.
+    ///
+    /// # Errors
+    /// - [`Error::ReadbackFailed`] if the staging buffer couldn't be mapped
+    pub fn try_receive(&mut self) -> Option<Result<Vec<u8>, Error>> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            ReadbackState::Pending(..) | ReadbackState::Taken => None,
+            ReadbackState::Failed => {
+                *state = ReadbackState::Taken;
+                Some(Err(Error::ReadbackFailed))
+            }
+            ReadbackState::Mapped => {
+                *state = ReadbackState::Taken;
+
+                let data = match self.staging_buffer.take() {
+                    Some(staging_buffer) => {
+                        let data = staging_buffer.get_mapped_range(..).to_vec();
+                        staging_buffer.unmap();
+                        data
+                    }
+                    None => Vec::new(),
+                };
+
+                Some(Ok(data))
+            }
+        }
+    }
+}
+
+impl Future for BlocksReadback {
+    type Output = Result<Vec<u8>, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        {
+            let mut state = self.state.lock().unwrap();
+            match &mut *state {
+                ReadbackState::Pending(waker) => {
+                    *waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                ReadbackState::Taken => panic!("block data was already taken"),
+                ReadbackState::Mapped | ReadbackState::Failed => {}
+            }
+        }
+
+        match self.try_receive() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
@@ -1,6 +1,122 @@
-#[cfg(any(feature = "bc6h", feature = "bc7"))]
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
 use bytemuck::{Pod, Zeroable};
 
+#[cfg(any(feature = "bc15", feature = "bc6h", feature = "bc7"))]
+use crate::Error;
+
+/// The bits of a channel weight of `1.0`.
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+const UNIFORM_WEIGHT: u32 = 0x3F80_0000;
+
+/// The perceptual weights of the red, green and blue channel.
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+const PERCEPTUAL_WEIGHTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+/// The highest number of refinement iterations accepted by the settings builders.
+#[cfg(any(feature = "bc6h", feature = "bc7"))]
+const MAX_REFINE_ITERATIONS: u32 = 16;
+
+/// Validates the channel weights and normalizes them to sum up to 3, which keeps the balance to
+/// the alpha channel with a weight of `1.0`. Returns the bits of the normalized weights.
+#[cfg(any(feature = "bc15", feature = "bc7"))]
+fn normalize_channel_weights(channel_weights: [f32; 3]) -> Result<[u32; 3], Error> {
+    let sum: f32 = channel_weights.iter().sum();
+
+    if !sum.is_finite()
+        || channel_weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight <= 0.0)
+    {
+        return Err(Error::InvalidSettings {
+            reason: "channel weights must be finite, positive numbers",
+        });
+    }
+
+    Ok(channel_weights.map(|weight| (weight * 3.0 / sum).to_bits()))
+}
+
+/// Encoding settings for BC1, BC2 and BC3.
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct BC123Settings {
+    pub(crate) alpha_threshold: u32,
+    /// The bits of the normalized `f32` channel weights.
+    pub(crate) channel_weights: [u32; 3],
+}
+
+#[cfg(feature = "bc15")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc15")))]
+impl BC123Settings {
+    /// Default settings. The color channels are weighted equally and BC1 blocks are opaque.
+    pub const fn new() -> Self {
+        Self {
+            alpha_threshold: 0,
+            channel_weights: [UNIFORM_WEIGHT; 3],
+        }
+    }
+
+    /// Returns the settings with the given alpha threshold.
+    ///
+    /// Only used by BC1. Pixels with an alpha value below the threshold are encoded as transparent
+    /// black. Blocks with transparent pixels use the three color mode of BC1 (punch-through alpha),
+    /// all other blocks use the four color mode. A threshold of `0` disables transparency. `128`
+    /// is a common choice for cutout textures like foliage and decals.
+    pub const fn with_alpha_threshold(mut self, alpha_threshold: u8) -> Self {
+        self.alpha_threshold = alpha_threshold as _;
+        self
+    }
+
+    /// Returns the alpha threshold below which pixels are encoded as transparent.
+    pub const fn alpha_threshold(&self) -> u8 {
+        self.alpha_threshold as _
+    }
+
+    /// Returns the settings with the given weights of the red, green and blue channel.
+    ///
+    /// The weights scale the squared error of each channel when fitting the endpoints of the
+    /// color block. See [`BC123Settings::try_with_channel_weights()`] for the requirements.
+    ///
+    /// # Panics
+    /// - If [`BC123Settings::try_with_channel_weights()`] returns an error
+    pub fn with_channel_weights(self, channel_weights: [f32; 3]) -> Self {
+        match self.try_with_channel_weights(channel_weights) {
+            Ok(settings) => settings,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Returns the settings with the given weights of the red, green and blue channel.
+    ///
+    /// The weights are normalized, so that they sum up to 3.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidSettings`] if a weight is not a finite, positive number
+    pub fn try_with_channel_weights(mut self, channel_weights: [f32; 3]) -> Result<Self, Error> {
+        self.channel_weights = normalize_channel_weights(channel_weights)?;
+        Ok(self)
+    }
+
+    /// Returns the settings with the perceptual channel weights `0.299`, `0.587` and `0.114`,
+    /// which usually improve the quality of albedo textures.
+    pub fn with_perceptual_weights(self) -> Self {
+        self.with_channel_weights(PERCEPTUAL_WEIGHTS)
+    }
+
+    /// Returns the normalized weights of the red, green and blue channel.
+    pub fn channel_weights(&self) -> [f32; 3] {
+        self.channel_weights.map(f32::from_bits)
+    }
+}
+
+#[cfg(feature = "bc15")]
+impl Default for BC123Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Encoding settings for BC6H.
 #[cfg(feature = "bc6h")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
@@ -12,6 +128,7 @@ pub struct BC6HSettings {
     pub(crate) refine_iterations_1p: u32,
     pub(crate) refine_iterations_2p: u32,
     pub(crate) fast_skip_threshold: u32,
+    pub(crate) signed: u32,
 }
 
 #[cfg(feature = "bc6h")]
@@ -24,6 +141,7 @@ impl BC6HSettings {
             fast_skip_threshold: 0,
             refine_iterations_1p: 0,
             refine_iterations_2p: 0,
+            signed: false as _,
         }
     }
 
@@ -35,6 +153,7 @@ impl BC6HSettings {
             fast_skip_threshold: 2,
             refine_iterations_1p: 0,
             refine_iterations_2p: 1,
+            signed: false as _,
         }
     }
 
@@ -46,6 +165,7 @@ impl BC6HSettings {
             fast_skip_threshold: 4,
             refine_iterations_1p: 2,
             refine_iterations_2p: 2,
+            signed: false as _,
         }
     }
 
@@ -57,6 +177,7 @@ impl BC6HSettings {
             fast_skip_threshold: 10,
             refine_iterations_1p: 2,
             refine_iterations_2p: 2,
+            signed: false as _,
         }
     }
 
@@ -68,8 +189,142 @@ impl BC6HSettings {
             fast_skip_threshold: 32,
             refine_iterations_1p: 2,
             refine_iterations_2p: 2,
+            signed: false as _,
         }
     }
+
+    /// Returns the settings with signed encoding enabled or disabled.
+    ///
+    /// Signed BC6H (`BC6H_SF16`) can store negative HDR values. The decoder needs to know whether
+    /// the blocks are signed, so the same setting has to be used for decoding.
+    pub const fn with_signed(mut self, signed: bool) -> Self {
+        self.signed = signed as _;
+        self
+    }
+
+    /// Returns `true` if the settings encode signed BC6H (`BC6H_SF16`) blocks.
+    pub const fn is_signed(&self) -> bool {
+        self.signed != 0
+    }
+}
+
+/// Builder for custom BC6H settings.
+///
+/// Starts with the basic settings from [`BC6HSettingsBuilder::new()`] or from one of the presets
+/// of [`BC6HSettings`] and validates the settings in [`BC6HSettingsBuilder::build()`].
+///
+/// ```
+/// use block_compression::{BC6HSettings, BC6HSettingsBuilder};
+///
+/// // Between the basic and the slow preset.
+/// let settings = BC6HSettingsBuilder::from(BC6HSettings::basic())
+///     .with_fast_skip_threshold(8)
+///     .with_refine_iterations_2p(3)
+///     .build()
+///     .unwrap();
+/// ```
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BC6HSettingsBuilder {
+    settings: BC6HSettings,
+}
+
+#[cfg(feature = "bc6h")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc6h")))]
+impl BC6HSettingsBuilder {
+    /// Creates a builder with the basic settings.
+    pub const fn new() -> Self {
+        Self {
+            settings: BC6HSettings::basic(),
+        }
+    }
+
+    /// Sets whether all modes are fully encoded. Otherwise modes are skipped when the value
+    /// range of the block doesn't fit them.
+    pub const fn with_slow_mode(mut self, slow_mode: bool) -> Self {
+        self.settings.slow_mode = slow_mode as _;
+        self
+    }
+
+    /// Sets whether the full encoding of mode 1, the two partition mode with the widest value
+    /// range, is skipped. Can't be combined with the slow mode.
+    pub const fn with_fast_mode(mut self, fast_mode: bool) -> Self {
+        self.settings.fast_mode = fast_mode as _;
+        self
+    }
+
+    /// Sets the fast skip threshold, which is the number of the most promising partitions of the
+    /// two partition modes that are fully encoded. It must be between `0` and `32`. Outside of
+    /// the slow mode `0` skips the two partition modes.
+    pub const fn with_fast_skip_threshold(mut self, threshold: u32) -> Self {
+        self.settings.fast_skip_threshold = threshold;
+        self
+    }
+
+    /// Sets how often the endpoints of the one partition modes are refined.
+    pub const fn with_refine_iterations_1p(mut self, iterations: u32) -> Self {
+        self.settings.refine_iterations_1p = iterations;
+        self
+    }
+
+    /// Sets how often the endpoints of the two partition modes are refined.
+    pub const fn with_refine_iterations_2p(mut self, iterations: u32) -> Self {
+        self.settings.refine_iterations_2p = iterations;
+        self
+    }
+
+    /// Sets whether signed BC6H (`BC6H_SF16`) blocks are encoded. See
+    /// [`BC6HSettings::with_signed()`].
+    pub const fn with_signed(mut self, signed: bool) -> Self {
+        self.settings = self.settings.with_signed(signed);
+        self
+    }
+
+    /// Validates and returns the settings.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidSettings`] if the slow and the fast mode are both enabled, if the fast
+    ///   skip threshold is larger than `32` or if there are more than 16 refinement iterations
+    pub fn build(self) -> Result<BC6HSettings, Error> {
+        let settings = self.settings;
+
+        if settings.slow_mode != 0 && settings.fast_mode != 0 {
+            return Err(Error::InvalidSettings {
+                reason: "the slow and the fast mode of BC6H can't be combined",
+            });
+        }
+
+        if settings.fast_skip_threshold > 32 {
+            return Err(Error::InvalidSettings {
+                reason: "the fast skip threshold of BC6H must be between 0 and 32",
+            });
+        }
+
+        if settings.refine_iterations_1p > MAX_REFINE_ITERATIONS
+            || settings.refine_iterations_2p > MAX_REFINE_ITERATIONS
+        {
+            return Err(Error::InvalidSettings {
+                reason: "BC6H supports at most 16 refinement iterations",
+            });
+        }
+
+        Ok(settings)
+    }
+}
+
+#[cfg(feature = "bc6h")]
+impl Default for BC6HSettingsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "bc6h")]
+impl From<BC6HSettings> for BC6HSettingsBuilder {
+    fn from(settings: BC6HSettings) -> Self {
+        Self { settings }
+    }
 }
 
 #[cfg(feature = "bc7")]
@@ -79,14 +334,15 @@ impl BC6HSettings {
 #[repr(C)]
 pub struct BC7Settings {
     pub(crate) refine_iterations: [u32; 8],
-    pub(crate) mode_selection: [u32; 4],
-    pub(crate) skip_mode2: u32,
+    pub(crate) mode_selection: [u32; 8],
     pub(crate) fast_skip_threshold_mode1: u32,
     pub(crate) fast_skip_threshold_mode3: u32,
     pub(crate) fast_skip_threshold_mode7: u32,
     pub(crate) mode45_channel0: u32,
     pub(crate) refine_iterations_channel: u32,
     pub(crate) channels: u32,
+    /// The bits of the normalized `f32` channel weights.
+    pub(crate) channel_weights: [u32; 3],
 }
 
 #[cfg(feature = "bc7")]
@@ -96,14 +352,14 @@ impl BC7Settings {
     pub const fn opaque_ultra_fast() -> Self {
         Self {
             channels: 3,
-            mode_selection: [false as _, false as _, false as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 0, 0, 0, 0, 0, 1, 0],
             fast_skip_threshold_mode1: 3,
             fast_skip_threshold_mode3: 1,
             fast_skip_threshold_mode7: 0,
             mode45_channel0: 0,
             refine_iterations_channel: 0,
             refine_iterations: [2, 2, 2, 1, 2, 2, 1, 0],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -111,14 +367,14 @@ impl BC7Settings {
     pub const fn opaque_very_fast() -> Self {
         Self {
             channels: 3,
-            mode_selection: [false as _, true as _, false as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 1, 0, 1, 0, 0, 1, 0],
             fast_skip_threshold_mode1: 3,
             fast_skip_threshold_mode3: 1,
             fast_skip_threshold_mode7: 0,
             mode45_channel0: 0,
             refine_iterations_channel: 0,
             refine_iterations: [2, 2, 2, 1, 2, 2, 1, 0],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -126,14 +382,14 @@ impl BC7Settings {
     pub const fn opaque_fast() -> Self {
         Self {
             channels: 3,
-            mode_selection: [false as _, true as _, false as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 1, 0, 1, 0, 0, 1, 0],
             fast_skip_threshold_mode1: 12,
             fast_skip_threshold_mode3: 4,
             fast_skip_threshold_mode7: 0,
             mode45_channel0: 0,
             refine_iterations_channel: 0,
             refine_iterations: [2, 2, 2, 1, 2, 2, 2, 0],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -141,14 +397,14 @@ impl BC7Settings {
     pub const fn opaque_basic() -> Self {
         Self {
             channels: 3,
-            mode_selection: [true as _, true as _, true as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [1, 1, 0, 1, 1, 1, 1, 0],
             fast_skip_threshold_mode1: 12,
             fast_skip_threshold_mode3: 8,
             fast_skip_threshold_mode7: 0,
             mode45_channel0: 0,
             refine_iterations_channel: 2,
             refine_iterations: [2, 2, 2, 2, 2, 2, 2, 0],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -156,14 +412,14 @@ impl BC7Settings {
     pub const fn opaque_slow() -> Self {
         Self {
             channels: 3,
-            mode_selection: [true as _, true as _, true as _, true as _],
-            skip_mode2: false as _,
+            mode_selection: [1, 1, 1, 1, 1, 1, 1, 0],
             fast_skip_threshold_mode1: 64,
             fast_skip_threshold_mode3: 64,
             fast_skip_threshold_mode7: 0,
             mode45_channel0: 0,
             refine_iterations_channel: 4,
             refine_iterations: [4, 4, 4, 4, 4, 4, 4, 0],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -171,14 +427,14 @@ impl BC7Settings {
     pub const fn alpha_ultrafast() -> Self {
         Self {
             channels: 4,
-            mode_selection: [false as _, false as _, true as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 0, 0, 0, 1, 1, 1, 0],
             fast_skip_threshold_mode1: 0,
             fast_skip_threshold_mode3: 0,
             fast_skip_threshold_mode7: 4,
             mode45_channel0: 3,
             refine_iterations_channel: 1,
             refine_iterations: [2, 1, 2, 1, 1, 1, 2, 2],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -186,14 +442,14 @@ impl BC7Settings {
     pub const fn alpha_very_fast() -> Self {
         Self {
             channels: 4,
-            mode_selection: [false as _, true as _, true as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 0, 0, 0, 1, 1, 1, 1],
             fast_skip_threshold_mode1: 0,
             fast_skip_threshold_mode3: 0,
             fast_skip_threshold_mode7: 4,
             mode45_channel0: 3,
             refine_iterations_channel: 2,
             refine_iterations: [2, 1, 2, 1, 2, 2, 2, 2],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -201,14 +457,14 @@ impl BC7Settings {
     pub const fn alpha_fast() -> Self {
         Self {
             channels: 4,
-            mode_selection: [false as _, true as _, true as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [0, 1, 0, 1, 1, 1, 1, 1],
             fast_skip_threshold_mode1: 4,
             fast_skip_threshold_mode3: 4,
             fast_skip_threshold_mode7: 8,
             mode45_channel0: 3,
             refine_iterations_channel: 2,
             refine_iterations: [2, 1, 2, 1, 2, 2, 2, 2],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -216,14 +472,14 @@ impl BC7Settings {
     pub const fn alpha_basic() -> Self {
         Self {
             channels: 4,
-            mode_selection: [true as _, true as _, true as _, true as _],
-            skip_mode2: true as _,
+            mode_selection: [1, 1, 0, 1, 1, 1, 1, 1],
             fast_skip_threshold_mode1: 12,
             fast_skip_threshold_mode3: 8,
             fast_skip_threshold_mode7: 8,
             mode45_channel0: 0,
             refine_iterations_channel: 2,
             refine_iterations: [2, 2, 2, 2, 2, 2, 2, 2],
+            channel_weights: [UNIFORM_WEIGHT; 3],
         }
     }
 
@@ -231,14 +487,253 @@ impl BC7Settings {
     pub const fn alpha_slow() -> Self {
         Self {
             channels: 4,
-            mode_selection: [true as _, true as _, true as _, true as _],
-            skip_mode2: false as _,
+            mode_selection: [1, 1, 1, 1, 1, 1, 1, 1],
             fast_skip_threshold_mode1: 64,
             fast_skip_threshold_mode3: 64,
             fast_skip_threshold_mode7: 64,
             mode45_channel0: 0,
             refine_iterations_channel: 4,
             refine_iterations: [4, 4, 4, 4, 4, 4, 4, 4],
+            channel_weights: [UNIFORM_WEIGHT; 3],
+        }
+    }
+
+    /// Returns the settings with the given weights of the red, green and blue channel.
+    ///
+    /// The weights scale the squared error of each channel when the encoder compares the
+    /// candidate modes and endpoints. See [`BC7Settings::try_with_channel_weights()`] for the
+    /// requirements.
+    ///
+    /// # Panics
+    /// - If [`BC7Settings::try_with_channel_weights()`] returns an error
+    pub fn with_channel_weights(self, channel_weights: [f32; 3]) -> Self {
+        match self.try_with_channel_weights(channel_weights) {
+            Ok(settings) => settings,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Returns the settings with the given weights of the red, green and blue channel.
+    ///
+    /// The weights are normalized, so that they sum up to 3. The alpha channel always has a
+    /// weight of `1.0`.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidSettings`] if a weight is not a finite, positive number
+    pub fn try_with_channel_weights(mut self, channel_weights: [f32; 3]) -> Result<Self, Error> {
+        self.channel_weights = normalize_channel_weights(channel_weights)?;
+        Ok(self)
+    }
+
+    /// Returns the settings with the perceptual channel weights `0.299`, `0.587` and `0.114`,
+    /// which usually improve the quality of albedo textures.
+    pub fn with_perceptual_weights(self) -> Self {
+        self.with_channel_weights(PERCEPTUAL_WEIGHTS)
+    }
+
+    /// Returns the normalized weights of the red, green and blue channel.
+    pub fn channel_weights(&self) -> [f32; 3] {
+        self.channel_weights.map(f32::from_bits)
+    }
+}
+
+/// Builder for custom BC7 settings.
+///
+/// Starts either with no enabled modes from [`BC7SettingsBuilder::new()`] or from one of the
+/// presets of [`BC7Settings`] and validates the settings in [`BC7SettingsBuilder::build()`].
+///
+/// ```
+/// use block_compression::{BC7Settings, BC7SettingsBuilder};
+///
+/// // Only mode 6, which suits flat UI textures.
+/// let settings = BC7SettingsBuilder::new()
+///     .with_modes(&[6])
+///     .with_refine_iterations(6, 4)
+///     .build()
+///     .unwrap();
+///
+/// // The alpha basic preset with more partitions for mode 7.
+/// let settings = BC7SettingsBuilder::from(BC7Settings::alpha_basic())
+///     .with_fast_skip_threshold(7, 32)
+///     .build()
+///     .unwrap();
+/// ```
+#[cfg(feature = "bc7")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc7")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BC7SettingsBuilder {
+    settings: BC7Settings,
+    channel_rotation: bool,
+    error: Option<&'static str>,
+}
+
+#[cfg(feature = "bc7")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bc7")))]
+impl BC7SettingsBuilder {
+    /// Creates a builder for opaque textures without any enabled mode. The refinement iterations
+    /// and fast skip thresholds start with the values of the basic presets.
+    pub const fn new() -> Self {
+        Self {
+            settings: BC7Settings {
+                channels: 3,
+                mode_selection: [0; 8],
+                fast_skip_threshold_mode1: 12,
+                fast_skip_threshold_mode3: 8,
+                fast_skip_threshold_mode7: 8,
+                mode45_channel0: 0,
+                refine_iterations_channel: 2,
+                refine_iterations: [2; 8],
+                channel_weights: [UNIFORM_WEIGHT; 3],
+            },
+            channel_rotation: true,
+            error: None,
+        }
+    }
+
+    /// Sets whether the alpha channel of the texture is encoded. Opaque settings ignore the alpha
+    /// channel and always decode it as `255`, so they can't enable mode 7, which always encodes
+    /// the alpha channel.
+    pub const fn with_alpha(mut self, alpha: bool) -> Self {
+        self.settings.channels = if alpha { 4 } else { 3 };
+        self
+    }
+
+    /// Enables or disables the BC7 `mode` from `0` to `7`.
+    pub const fn with_mode(mut self, mode: usize, enabled: bool) -> Self {
+        if mode < 8 {
+            self.settings.mode_selection[mode] = enabled as _;
+        } else {
+            self.error = Some("BC7 modes are numbered from 0 to 7");
+        }
+        self
+    }
+
+    /// Enables exactly the given BC7 `modes` and disables all other modes.
+    pub const fn with_modes(mut self, modes: &[usize]) -> Self {
+        self.settings.mode_selection = [0; 8];
+
+        let mut index = 0;
+        while index < modes.len() {
+            self = self.with_mode(modes[index], true);
+            index += 1;
+        }
+
+        self
+    }
+
+    /// Sets how often the endpoints of the BC7 `mode` are refined. More iterations improve the
+    /// quality and take more time.
+    pub const fn with_refine_iterations(mut self, mode: usize, iterations: u32) -> Self {
+        if mode < 8 {
+            self.settings.refine_iterations[mode] = iterations;
+        } else {
+            self.error = Some("BC7 modes are numbered from 0 to 7");
+        }
+        self
+    }
+
+    /// Sets how often the endpoints of the separately encoded channel of mode 4 and 5 are
+    /// refined.
+    pub const fn with_channel_refine_iterations(mut self, iterations: u32) -> Self {
+        self.settings.refine_iterations_channel = iterations;
+        self
+    }
+
+    /// Sets the fast skip threshold of the partitioned BC7 `mode` 1, 3 or 7, which is the number
+    /// of the most promising partitions that are fully encoded. It must be between `1` and `64`
+    /// for enabled modes.
+    pub const fn with_fast_skip_threshold(mut self, mode: usize, threshold: u32) -> Self {
+        match mode {
+            1 => self.settings.fast_skip_threshold_mode1 = threshold,
+            3 => self.settings.fast_skip_threshold_mode3 = threshold,
+            7 => self.settings.fast_skip_threshold_mode7 = threshold,
+            _ => self.error = Some("only BC7 mode 1, 3 and 7 have a fast skip threshold"),
+        }
+        self
+    }
+
+    /// Sets whether mode 4 and 5 also try to encode a color channel separately, instead of only
+    /// the alpha channel. Only used when the alpha channel is encoded. Opaque settings always
+    /// rotate the color channels.
+    pub const fn with_channel_rotation(mut self, channel_rotation: bool) -> Self {
+        self.channel_rotation = channel_rotation;
+        self
+    }
+
+    /// Validates and returns the settings.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidSettings`] if a mode is not between `0` and `7`, if no mode is enabled,
+    ///   if mode 7 is enabled for opaque settings, if a mode has more than 16 refinement
+    ///   iterations or if an enabled mode 1, 3 or 7 has a fast skip threshold outside of `1` to
+    ///   `64`
+    pub fn build(self) -> Result<BC7Settings, Error> {
+        if let Some(reason) = self.error {
+            return Err(Error::InvalidSettings { reason });
+        }
+
+        let mut settings = self.settings;
+
+        if settings.mode_selection.iter().all(|&enabled| enabled == 0) {
+            return Err(Error::InvalidSettings {
+                reason: "at least one BC7 mode must be enabled",
+            });
+        }
+
+        if settings.channels == 3 && settings.mode_selection[7] != 0 {
+            return Err(Error::InvalidSettings {
+                reason: "BC7 mode 7 always encodes the alpha channel and requires alpha",
+            });
+        }
+
+        if settings
+            .refine_iterations
+            .iter()
+            .chain([&settings.refine_iterations_channel])
+            .any(|&iterations| iterations > MAX_REFINE_ITERATIONS)
+        {
+            return Err(Error::InvalidSettings {
+                reason: "BC7 modes support at most 16 refinement iterations",
+            });
+        }
+
+        let fast_skip_thresholds = [
+            (1, settings.fast_skip_threshold_mode1),
+            (3, settings.fast_skip_threshold_mode3),
+            (7, settings.fast_skip_threshold_mode7),
+        ];
+        if fast_skip_thresholds.iter().any(|&(mode, threshold)| {
+            settings.mode_selection[mode] != 0 && !(1..=64).contains(&threshold)
+        }) {
+            return Err(Error::InvalidSettings {
+                reason: "the fast skip threshold of BC7 mode 1, 3 and 7 must be between 1 and 64",
+            });
+        }
+
+        settings.mode45_channel0 = if settings.channels == 4 && !self.channel_rotation {
+            3
+        } else {
+            0
+        };
+
+        Ok(settings)
+    }
+}
+
+#[cfg(feature = "bc7")]
+impl Default for BC7SettingsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "bc7")]
+impl From<BC7Settings> for BC7SettingsBuilder {
+    fn from(settings: BC7Settings) -> Self {
+        Self {
+            settings,
+            channel_rotation: settings.channels == 3 || settings.mode45_channel0 == 0,
+            error: None,
         }
     }
 }
@@ -20,11 +20,20 @@ struct Uniforms {
     height: u32,
     texture_y_offset: u32,
     blocks_offset: u32,
+    base_array_layer: u32,
+    errors_offset: u32,
+    write_errors: u32,
+}
+
+struct BC123Settings {
+    alpha_threshold: u32,
+    channel_weights: array<f32, 3>,
 }
 
-@group(0) @binding(0) var source_texture: texture_2d<f32>;
 @group(0) @binding(1) var<storage, read_write> block_buffer: array<u32>;
 @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+@group(0) @binding(3) var<storage, read> bc123_settings: BC123Settings;
+@group(0) @binding(4) var<storage, read_write> error_buffer: array<f32>;
 
 fn sq(x: f32) -> f32 {
     return x * x;
@@ -38,12 +47,46 @@ fn rcp(x: f32) -> f32 {
     return 1.0 / x;
 }
 
+// Bit mask of the pixels of the current block that lie inside the image.
+var<private> valid_mask: u32;
+
+// Index of the compressed array layer relative to the first layer of the task.
+var<private> layer_index: u32;
+
+// Error weights of the red, green and blue channel.
+var<private> channel_weights: vec3<f32>;
+
+fn load_channel_weights() {
+    for (var p = 0u; p < 3u; p++) {
+        channel_weights[p] = bc123_settings.channel_weights[p];
+    }
+}
+
+fn compute_valid_mask(xx: u32, yy: u32) -> u32 {
+    let valid_x = min(uniforms.width - xx * 4u, 4u);
+    let valid_y = min(uniforms.height - yy * 4u, 4u);
+    let row_mask = (1u << valid_x) - 1u;
+
+    var mask = 0u;
+    for (var y = 0u; y < valid_y; y++) {
+        mask |= row_mask << (y * 4u);
+    }
+    return mask;
+}
+
+// Clamps the texel coordinates, so that partial blocks at the edge replicate the last pixel row and column.
+fn texel_coordinates(xx: u32, yy: u32, x: u32, y: u32) -> vec2<u32> {
+    let pixel_x = min(xx * 4u + x, uniforms.width - 1u);
+    let pixel_y = min(yy * 4u + y, uniforms.height - 1u) + uniforms.texture_y_offset;
+    return vec2<u32>(pixel_x, pixel_y);
+}
+
 fn load_block_interleaved_rgba(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y;
-            let rgba = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0);
+            let rgba = load_texel(texel_coordinates(xx, yy, x, y));
 
             (*block)[16u * 0u + y * 4u + x] = rgba.r * 255.0;
             (*block)[16u * 1u + y * 4u + x] = rgba.g * 255.0;
@@ -54,11 +97,11 @@ fn load_block_interleaved_rgba(block: ptr<function, array<f32, 64>>, xx: u32, yy
 }
 
 fn load_block_r_8bit(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y;
-            let red = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0).r;
+            let red = load_texel(texel_coordinates(xx, yy, x, y)).r;
 
             (*block)[48u + y * 4u + x] = red * 255.0;
         }
@@ -66,25 +109,49 @@ fn load_block_r_8bit(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
 }
 
 fn load_block_g_8bit(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y;
-            let green = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0).g;
+            let green = load_texel(texel_coordinates(xx, yy, x, y)).g;
 
             (*block)[48u + y * 4u + x] = green  * 255.0;
         }
     }
 }
 
+fn load_block_r_snorm(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
+    for (var y = 0u; y < 4u; y++) {
+        for (var x = 0u; x < 4u; x++) {
+            let red = load_texel(texel_coordinates(xx, yy, x, y)).r;
+
+            (*block)[48u + y * 4u + x] = round(red * 127.0);
+        }
+    }
+}
+
+fn load_block_g_snorm(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
+    for (var y = 0u; y < 4u; y++) {
+        for (var x = 0u; x < 4u; x++) {
+            let green = load_texel(texel_coordinates(xx, yy, x, y)).g;
+
+            (*block)[48u + y * 4u + x] = round(green * 127.0);
+        }
+    }
+}
+
 fn load_block_alpha_4bit(xx: u32, yy: u32) -> vec2<u32> {
+    valid_mask = compute_valid_mask(xx, yy);
+
     var alpha_bits: vec2<u32>;
 
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y + uniforms.texture_y_offset;;
-            let alpha = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0).a;
+            let alpha = load_texel(texel_coordinates(xx, yy, x, y)).a;
 
             // Convert alpha to 4 bits (0-15)
             let alpha4 = u32(alpha * 15.0);
@@ -102,14 +169,16 @@ fn load_block_alpha_4bit(xx: u32, yy: u32) -> vec2<u32> {
 }
 
 fn store_data_2(block_width: u32, xx: u32, yy: u32, data: vec2<u32>) {
-    let offset = uniforms.blocks_offset + (yy * block_width * 2u + xx * 2u);
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    let offset = uniforms.blocks_offset + (block_row * block_width * 2u + xx * 2u);
 
     block_buffer[offset + 0] = data[0];
     block_buffer[offset + 1] = data[1];
 }
 
 fn store_data_4(block_width: u32, xx: u32, yy: u32, data: vec4<u32>) {
-    let offset = uniforms.blocks_offset + (yy * block_width * 4u + xx * 4u);
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    let offset = uniforms.blocks_offset + (block_row * block_width * 4u + xx * 4u);
 
     block_buffer[offset + 0] = data[0];
     block_buffer[offset + 1] = data[1];
@@ -117,17 +186,31 @@ fn store_data_4(block_width: u32, xx: u32, yy: u32, data: vec4<u32>) {
     block_buffer[offset + 3] = data[3];
 }
 
+fn store_error(block_width: u32, xx: u32, yy: u32, error: f32) {
+    if (uniforms.write_errors == 0u) {
+        return;
+    }
+
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    error_buffer[uniforms.errors_offset + block_row * block_width + xx] = error;
+}
+
 fn compute_covar_dc(
     covar: ptr<function, array<f32, 6>>,
     dc: ptr<function, vec3<f32>>,
     block: ptr<function, array<f32, 64>>,
 ) {
+    let pixel_count = f32(countOneBits(valid_mask));
+
     for (var p = 0u; p < 3u; p++) {
         var acc = 0.0;
         for (var k = 0u; k < 16u; k++) {
+            if ((valid_mask & (1u << k)) == 0u) {
+                continue;
+            }
             acc += (*block)[k + p * 16u];
         }
-        (*dc)[p] = acc / 16.0;
+        (*dc)[p] = acc / pixel_count;
     }
 
     var covar0 = 0.0;
@@ -138,6 +221,10 @@ fn compute_covar_dc(
     var covar5 = 0.0;
 
     for (var k = 0u; k < 16u; k++) {
+        if ((valid_mask & (1u << k)) == 0u) {
+            continue;
+        }
+
         let rgb0 = (*block)[k + 0u * 16u] - (*dc)[0];
         let rgb1 = (*block)[k + 1u * 16u] - (*dc)[1];
         let rgb2 = (*block)[k + 2u * 16u] - (*dc)[2];
@@ -150,12 +237,15 @@ fn compute_covar_dc(
         covar5 += rgb2 * rgb2;
     }
 
-    (*covar)[0] = covar0;
-    (*covar)[1] = covar1;
-    (*covar)[2] = covar2;
-    (*covar)[3] = covar3;
-    (*covar)[4] = covar4;
-    (*covar)[5] = covar5;
+    // The covariance of the colors scaled by the square root of the channel weights.
+    let scale = sqrt(channel_weights);
+
+    (*covar)[0] = covar0 * scale[0] * scale[0];
+    (*covar)[1] = covar1 * scale[0] * scale[1];
+    (*covar)[2] = covar2 * scale[0] * scale[2];
+    (*covar)[3] = covar3 * scale[1] * scale[1];
+    (*covar)[4] = covar4 * scale[1] * scale[2];
+    (*covar)[5] = covar5 * scale[2] * scale[2];
 }
 
 fn ssymv(result: ptr<function, vec3<f32>>, covar: ptr<function, array<f32, 6>>, a_vector: ptr<function, vec3<f32>>) {
@@ -199,19 +289,22 @@ fn pick_endpoints(
     axis: ptr<function, vec3<f32>>,
     dc: ptr<function, vec3<f32>>
 ) {
+    let scale = sqrt(channel_weights);
     var min_dot = 256.0 * 256.0;
     var max_dot = 0.0;
 
-    for (var y = 0u; y < 4u; y++) {
-        for (var x = 0u; x < 4u; x++) {
-            var dot = 0.0;
-            for (var p = 0u; p < 3u; p++) {
-                dot += ((*block)[p * 16u + y * 4u + x] - (*dc)[p]) * (*axis)[p];
-            }
+    for (var k = 0u; k < 16u; k++) {
+        if ((valid_mask & (1u << k)) == 0u) {
+            continue;
+        }
 
-            min_dot = min(min_dot, dot);
-            max_dot = max(max_dot, dot);
+        var dot = 0.0;
+        for (var p = 0u; p < 3u; p++) {
+            dot += ((*block)[p * 16u + k] - (*dc)[p]) * scale[p] * (*axis)[p];
         }
+
+        min_dot = min(min_dot, dot);
+        max_dot = max(max_dot, dot);
     }
 
     if (max_dot - min_dot < 1.0) {
@@ -226,8 +319,9 @@ fn pick_endpoints(
 
     let rnorm_sq = rcp(norm_sq);
     for (var p = 0u; p < 3u; p++) {
-        (*c0)[p] = clamp((*dc)[p] + min_dot * rnorm_sq * (*axis)[p], 0.0, 255.0);
-        (*c1)[p] = clamp((*dc)[p] + max_dot * rnorm_sq * (*axis)[p], 0.0, 255.0);
+        let direction = (*axis)[p] / scale[p];
+        (*c0)[p] = clamp((*dc)[p] + min_dot * rnorm_sq * direction, 0.0, 255.0);
+        (*c1)[p] = clamp((*dc)[p] + max_dot * rnorm_sq * direction, 0.0, 255.0);
     }
 }
 
@@ -253,7 +347,9 @@ fn enc_rgb565(c: ptr<function, vec3<f32>>) -> i32 {
     return (r5 << 11) + (g6 << 5) + b5;
 }
 
-fn fast_quant(block: ptr<function, array<f32, 64>>, p0: i32, p1: i32) -> u32 {
+// Projects the pixels onto the line between both endpoints in the weighted color space and quantizes them to
+// steps + 1 positions.
+fn fast_quant(block: ptr<function, array<f32, 64>>, p0: i32, p1: i32, steps: i32) -> u32 {
     var c0: vec3<f32>;
     var c1: vec3<f32>;
     dec_rgb565(&c0, p0);
@@ -266,13 +362,17 @@ fn fast_quant(block: ptr<function, array<f32, 64>>, p0: i32, p1: i32) -> u32 {
 
     var sq_norm = 0.0;
     for (var p = 0u; p < 3u; p++) {
-        sq_norm += sq(dir[p]);
+        sq_norm += channel_weights[p] * sq(dir[p]);
+    }
+
+    if (sq_norm == 0.0) {
+        return 0u;
     }
 
     let rsq_norm = rcp(sq_norm);
 
     for (var p = 0u; p < 3u; p++) {
-        dir[p] *= rsq_norm * 3.0;
+        dir[p] *= channel_weights[p] * rsq_norm * f32(steps);
     }
 
     var bias = 0.5;
@@ -288,7 +388,7 @@ fn fast_quant(block: ptr<function, array<f32, 64>>, p0: i32, p1: i32) -> u32 {
             dot += (*block)[k + p * 16u] * dir[p];
         }
 
-        let q = clamp(i32(dot + bias), 0, 3);
+        let q = clamp(i32(dot + bias), 0, steps);
         bits += u32(q) * scaler;
         scaler *= 4u;
     }
@@ -296,47 +396,62 @@ fn fast_quant(block: ptr<function, array<f32, 64>>, p0: i32, p1: i32) -> u32 {
     return bits;
 }
 
-fn bc1_refine(pe: ptr<function, vec2<i32>>, block: ptr<function, array<f32, 64>>, bits: u32, dc: ptr<function, vec3<f32>>) {
+fn bc1_refine(
+    pe: ptr<function, vec2<i32>>,
+    block: ptr<function, array<f32, 64>>,
+    bits: u32,
+    dc: ptr<function, vec3<f32>>,
+    steps: i32
+) {
+    let s = f32(steps);
     var c0: vec3<f32>;
     var c1: vec3<f32>;
 
-    if ((bits ^ (bits * 4u)) < 4u) {
-        for (var p = 0u; p < 3u; p++) {
-            c0[p] = (*dc)[p];
-            c1[p] = (*dc)[p];
-        }
-    } else {
-        var atb1: vec3<f32>;
-        var sum_q = 0.0;
-        var sum_qq = 0.0;
-        var shifted_bits = bits;
+    var atb1: vec3<f32>;
+    var sum_q = 0.0;
+    var sum_qq = 0.0;
+    var shifted_bits = bits;
 
-        for (var k = 0u; k < 16u; k++) {
-            let q = f32(shifted_bits & 3u);
-            shifted_bits = shifted_bits >> 2u;
+    for (var k = 0u; k < 16u; k++) {
+        let q = f32(shifted_bits & 3u);
+        shifted_bits = shifted_bits >> 2u;
 
-            let x = 3.0 - q;
+        // Padding pixels outside the image don't contribute to the fit.
+        if ((valid_mask & (1u << k)) == 0u) {
+            continue;
+        }
 
-            sum_q += q;
-            sum_qq += q * q;
+        let x = s - q;
 
-            for (var p = 0u; p < 3u; p++) {
-                atb1[p] += x * (*block)[k + p * 16u];
-            }
+        sum_q += q;
+        sum_qq += q * q;
+
+        for (var p = 0u; p < 3u; p++) {
+            atb1[p] += x * (*block)[k + p * 16u];
         }
+    }
 
+    let pixel_count = f32(countOneBits(valid_mask));
+
+    // All pixels share the same position, so there is no line to fit.
+    if (sum_q * sum_q == sum_qq * pixel_count) {
+        for (var p = 0u; p < 3u; p++) {
+            c0[p] = (*dc)[p];
+            c1[p] = (*dc)[p];
+        }
+    } else {
         var sum: vec3<f32>;
         var atb2: vec3<f32>;
 
         for (var p = 0u; p < 3u; p++) {
-            sum[p] = (*dc)[p] * 16.0;
-            atb2[p] = 3.0 * sum[p] - atb1[p];
+            sum[p] = (*dc)[p] * pixel_count;
+            atb2[p] = s * sum[p] - atb1[p];
         }
 
-        let cxx = 16.0 * sq(3.0) - 2.0 * 3.0 * sum_q + sum_qq;
+        let cxx = pixel_count * sq(s) - 2.0 * s * sum_q + sum_qq;
         let cyy = sum_qq;
-        let cxy = 3.0 * sum_q - sum_qq;
-        let scale = 3.0 * rcp(cxx * cyy - cxy * cxy);
+        let cxy = s * sum_q - sum_qq;
+        let scale = s * rcp(cxx * cyy - cxy * cxy);
 
         for (var p = 0u; p < 3u; p++) {
             c0[p] = (atb1[p] * cyy - atb2[p] * cxy) * scale;
@@ -360,6 +475,45 @@ fn fix_qbits(qbits: u32) -> u32 {
     return (qbits1 >> 1u) + (qbits1 ^ (qbits0 << 1u));
 }
 
+// Maps the line positions of the three color mode to the palette indices and sets the index of the transparent
+// pixels to 3.
+fn fix_qbits_3color(qbits: u32, transparent_mask: u32) -> u32 {
+    const MASK_01B: u32 = 0x55555555u;
+
+    // Position 1 is the midpoint with index 2 and position 2 is the second endpoint.
+    var indices = ((qbits & MASK_01B) << 1u) | ((qbits >> 1u) & MASK_01B);
+
+    for (var k = 0u; k < 16u; k++) {
+        if ((transparent_mask & (1u << k)) != 0u) {
+            indices |= 3u << (k * 2u);
+        }
+    }
+
+    return indices;
+}
+
+// Returns the bit mask of the pixels with an alpha value below the threshold.
+fn transparent_mask(block: ptr<function, array<f32, 64>>, alpha_threshold: u32) -> u32 {
+    let threshold = f32(alpha_threshold);
+
+    var mask = 0u;
+    for (var k = 0u; k < 16u; k++) {
+        if ((*block)[48u + k] < threshold) {
+            mask |= 1u << k;
+        }
+    }
+    return mask;
+}
+
+fn compress_block_bc1(block: ptr<function, array<f32, 64>>, alpha_threshold: u32) -> vec2<u32> {
+    let transparent = transparent_mask(block, alpha_threshold);
+
+    if (transparent == 0u) {
+        return compress_block_bc1_core(block);
+    }
+    return compress_block_bc1_punch_through(block, transparent);
+}
+
 fn compress_block_bc1_core(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
     let power_iterations = 4;
     let refine_iterations = 1;
@@ -391,25 +545,89 @@ fn compress_block_bc1_core(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
 
     var data: vec2<u32>;
     data[0] = (u32(p[1]) << 16u) | u32(p[0]);
-    data[1] = fast_quant(block, p[0], p[1]);
+    data[1] = fast_quant(block, p[0], p[1], 3);
 
     for (var i = 0; i < refine_iterations; i++) {
-        bc1_refine(&p, block, data[1], &dc);
+        bc1_refine(&p, block, data[1], &dc, 3);
         if (p[0] < p[1]) {
             let temp = p[0];
             p[0] = p[1];
             p[1] = temp;
         }
         data[0] = (u32(p[1]) << 16u) | u32(p[0]);
-        data[1] = fast_quant(block, p[0], p[1]);
+        data[1] = fast_quant(block, p[0], p[1], 3);
     }
 
     data[1] = fix_qbits(data[1]);
     return data;
 }
 
+// Compresses the block with the three color mode of BC1, which stores transparent black as the fourth palette
+// entry. The transparent pixels are not considered when fitting the endpoints.
+fn compress_block_bc1_punch_through(block: ptr<function, array<f32, 64>>, transparent: u32) -> vec2<u32> {
+    let power_iterations = 4;
+    let refine_iterations = 1;
+
+    valid_mask &= ~transparent;
+
+    if (valid_mask == 0u) {
+        return vec2<u32>(0u, 0xFFFFFFFFu);
+    }
+
+    var covar: array<f32, 6>;
+    var dc: vec3<f32>;
+    compute_covar_dc(&covar, &dc, block);
+
+    const eps = 0.001;
+    covar[0] += eps;
+    covar[3] += eps;
+    covar[5] += eps;
+
+    var axis: vec3<f32>;
+    compute_axis3(&axis, &covar, power_iterations);
+
+    var c0: vec3<f32>;
+    var c1: vec3<f32>;
+    pick_endpoints(&c0, &c1, block, &axis, &dc);
+
+    // The three color mode is selected by storing the smaller endpoint first.
+    var p: vec2<i32>;
+    p[0] = enc_rgb565(&c0);
+    p[1] = enc_rgb565(&c1);
+    if (p[0] > p[1]) {
+        let temp = p[0];
+        p[0] = p[1];
+        p[1] = temp;
+    }
+
+    var bits = fast_quant(block, p[0], p[1], 2);
+
+    for (var i = 0; i < refine_iterations; i++) {
+        bc1_refine(&p, block, bits, &dc, 2);
+        if (p[0] > p[1]) {
+            let temp = p[0];
+            p[0] = p[1];
+            p[1] = temp;
+        }
+        bits = fast_quant(block, p[0], p[1], 2);
+    }
+
+    var data: vec2<u32>;
+    data[0] = (u32(p[1]) << 16u) | u32(p[0]);
+    data[1] = fix_qbits_3color(bits, transparent);
+    return data;
+}
+
 fn compress_block_bc3_alpha(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
-    var ep = vec2<f32>(255.0, 0.0);
+    return compress_block_bc4_channel(block, false);
+}
+
+fn compress_block_bc4_snorm(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
+    return compress_block_bc4_channel(block, true);
+}
+
+fn compress_block_bc4_channel(block: ptr<function, array<f32, 64>>, is_signed: bool) -> vec2<u32> {
+    var ep = vec2<f32>(3.40282347e+38, -3.40282347e+38);
 
     // Find min/max endpoints using block[48] to block[63] for alpha
     for (var k: u32 = 0u; k < 16u; k++) {
@@ -443,7 +661,13 @@ fn compress_block_bc3_alpha(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
     }
 
     var data: vec2<u32>;
-    data[0] = (clamp(u32(ep[0]), 0u, 255u) << 8u) | clamp(u32(ep[1]), 0u, 255u);
+    if (is_signed) {
+        let ep0 = u32(clamp(i32(round(ep[0])), -127, 127)) & 0xFFu;
+        let ep1 = u32(clamp(i32(round(ep[1])), -127, 127)) & 0xFFu;
+        data[0] = (ep0 << 8u) | ep1;
+    } else {
+        data[0] = (clamp(u32(ep[0]), 0u, 255u) << 8u) | clamp(u32(ep[1]), 0u, 255u);
+    }
     data[0] |= qblock[0] << 16u;
     data[1] = qblock[0] >> 16u;
     data[1] |= qblock[1] << 8u;
@@ -451,11 +675,119 @@ fn compress_block_bc3_alpha(block: ptr<function, array<f32, 64>>) -> vec2<u32> {
     return data;
 }
 
+// Returns the squared error of the pixels in `mask` against the BC1 color block in `data`, weighted with the channel
+// weights. The alpha error is weighted with `alpha_weight`. Color blocks of BC2 and BC3 always use four colors.
+fn bc1_block_error(
+    block: ptr<function, array<f32, 64>>,
+    data: vec2<u32>,
+    mask: u32,
+    four_colors: bool,
+    alpha_weight: f32
+) -> f32 {
+    let p0 = i32(data[0] & 0xFFFFu);
+    let p1 = i32(data[0] >> 16u);
+
+    var c0: vec3<f32>;
+    var c1: vec3<f32>;
+    dec_rgb565(&c0, p0);
+    dec_rgb565(&c1, p1);
+
+    var palette: array<vec4<f32>, 4>;
+    palette[0] = vec4<f32>(c0, 255.0);
+    palette[1] = vec4<f32>(c1, 255.0);
+    if (four_colors || p0 > p1) {
+        palette[2] = vec4<f32>((2.0 * c0 + c1) / 3.0, 255.0);
+        palette[3] = vec4<f32>((c0 + 2.0 * c1) / 3.0, 255.0);
+    } else {
+        palette[2] = vec4<f32>((c0 + c1) * 0.5, 255.0);
+        palette[3] = vec4<f32>(0.0);
+    }
+
+    var err = 0.0;
+    for (var k = 0u; k < 16u; k++) {
+        if ((mask & (1u << k)) == 0u) {
+            continue;
+        }
+
+        let color = palette[(data[1] >> (k * 2u)) & 3u];
+        for (var p = 0u; p < 3u; p++) {
+            err += channel_weights[p] * sq((*block)[k + p * 16u] - color[p]);
+        }
+        err += alpha_weight * sq((*block)[48u + k] - color[3]);
+    }
+    return err;
+}
+
+// Returns the squared error of the alpha of the pixels in `mask` against the 4 bit alpha values of BC2.
+fn bc2_alpha_error(block: ptr<function, array<f32, 64>>, alpha_bits: vec2<u32>, mask: u32) -> f32 {
+    var err = 0.0;
+    for (var k = 0u; k < 16u; k++) {
+        if ((mask & (1u << k)) == 0u) {
+            continue;
+        }
+
+        let alpha4 = (alpha_bits[k / 8u] >> ((k % 8u) * 4u)) & 0xFu;
+        err += sq((*block)[48u + k] - f32(alpha4 * 17u));
+    }
+    return err;
+}
+
+// Returns the squared error of the channel in block[48] to block[63] of the pixels in `mask` against the BC4 block
+// in `data`.
+fn bc4_block_error(block: ptr<function, array<f32, 64>>, data: vec2<u32>, mask: u32, is_signed: bool) -> f32 {
+    var ep: vec2<f32>;
+    if (is_signed) {
+        ep[0] = max(f32(extractBits(i32(data[0]), 0u, 8u)), -127.0);
+        ep[1] = max(f32(extractBits(i32(data[0]), 8u, 8u)), -127.0);
+    } else {
+        ep[0] = f32(data[0] & 0xFFu);
+        ep[1] = f32((data[0] >> 8u) & 0xFFu);
+    }
+
+    var palette: array<f32, 8>;
+    palette[0] = ep[0];
+    palette[1] = ep[1];
+    if (ep[0] > ep[1]) {
+        for (var i = 1u; i < 7u; i++) {
+            palette[i + 1u] = (f32(7u - i) * ep[0] + f32(i) * ep[1]) / 7.0;
+        }
+    } else {
+        for (var i = 1u; i < 5u; i++) {
+            palette[i + 1u] = (f32(5u - i) * ep[0] + f32(i) * ep[1]) / 5.0;
+        }
+        palette[6] = select(0.0, -127.0, is_signed);
+        palette[7] = select(255.0, 127.0, is_signed);
+    }
+
+    var err = 0.0;
+    for (var k = 0u; k < 16u; k++) {
+        if ((mask & (1u << k)) == 0u) {
+            continue;
+        }
+
+        // The 3 bit indices follow the two endpoint bytes and can straddle both words.
+        let bit = 16u + k * 3u;
+        var index: u32;
+        if (bit < 32u) {
+            index = data[0] >> bit;
+            if (bit > 29u) {
+                index |= data[1] << (32u - bit);
+            }
+        } else {
+            index = data[1] >> (bit - 32u);
+        }
+
+        err += sq((*block)[48u + k] - palette[index & 7u]);
+    }
+    return err;
+}
+
 @compute
 @workgroup_size(8, 8)
 fn compress_bc1(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -468,12 +800,17 @@ fn compress_bc1(@builtin(global_invocation_id) global_id: vec3<u32>) {
     var compressed_data: vec2<u32>;
 
     load_block_interleaved_rgba(&block, xx, yy);
+    load_channel_weights();
 
-    let color_result = compress_block_bc1_core(&block);
+    let color_result = compress_block_bc1(&block, bc123_settings.alpha_threshold);
     compressed_data[0] = color_result[0];
     compressed_data[1] = color_result[1];
 
+    let alpha_weight = select(0.0, 1.0, bc123_settings.alpha_threshold > 0u);
+    let error = bc1_block_error(&block, color_result, compute_valid_mask(xx, yy), false, alpha_weight);
+
     store_data_2(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
 }
 
 @compute
@@ -481,6 +818,7 @@ fn compress_bc1(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn compress_bc2(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -497,12 +835,17 @@ fn compress_bc2(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compressed_data[1] = alpha_result[1];
 
     load_block_interleaved_rgba(&block, xx, yy);
+    load_channel_weights();
 
     let color_result = compress_block_bc1_core(&block);
     compressed_data[2] = color_result[0];
     compressed_data[3] = color_result[1];
 
+    let error = bc2_alpha_error(&block, alpha_result, valid_mask)
+        + bc1_block_error(&block, color_result, valid_mask, true, 0.0);
+
     store_data_4(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
 }
 
 @compute
@@ -510,6 +853,7 @@ fn compress_bc2(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn compress_bc3(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -522,6 +866,7 @@ fn compress_bc3(@builtin(global_invocation_id) global_id: vec3<u32>) {
     var compressed_data: vec4<u32>;
 
     load_block_interleaved_rgba(&block, xx, yy);
+    load_channel_weights();
 
     let alpha_result = compress_block_bc3_alpha(&block);
     compressed_data[0] = alpha_result[0];
@@ -531,7 +876,11 @@ fn compress_bc3(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compressed_data[2] = color_result[0];
     compressed_data[3] = color_result[1];
 
+    let error = bc4_block_error(&block, alpha_result, valid_mask, false)
+        + bc1_block_error(&block, color_result, valid_mask, true, 0.0);
+
     store_data_4(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
 }
 
 @compute
@@ -539,6 +888,7 @@ fn compress_bc3(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn compress_bc4(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -556,7 +906,10 @@ fn compress_bc4(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compressed_data[0] = color_result[0];
     compressed_data[1] = color_result[1];
 
+    let error = bc4_block_error(&block, color_result, valid_mask, false);
+
     store_data_2(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
 }
 
 @compute
@@ -564,6 +917,7 @@ fn compress_bc4(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn compress_bc5(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -581,11 +935,82 @@ fn compress_bc5(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compressed_data[0] = red_result[0];
     compressed_data[1] = red_result[1];
 
+    var error = bc4_block_error(&block, red_result, valid_mask, false);
+
     load_block_g_8bit(&block, xx, yy);
 
     let green_result = compress_block_bc3_alpha(&block);
     compressed_data[2] = green_result[0];
     compressed_data[3] = green_result[1];
 
+    error += bc4_block_error(&block, green_result, valid_mask, false);
+
+    store_data_4(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn compress_bc4_snorm(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let xx = global_id.x;
+    let yy = global_id.y;
+    layer_index = global_id.z;
+
+    let block_width = (uniforms.width + 3u) / 4u;
+    let block_height = (uniforms.height + 3u) / 4u;
+
+    if (xx >= block_width || yy >= block_height) {
+        return;
+    }
+
+    var block: array<f32, 64>;
+    var compressed_data: vec2<u32>;
+
+    load_block_r_snorm(&block, xx, yy);
+
+    let color_result = compress_block_bc4_snorm(&block);
+    compressed_data[0] = color_result[0];
+    compressed_data[1] = color_result[1];
+
+    let error = bc4_block_error(&block, color_result, valid_mask, true);
+
+    store_data_2(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn compress_bc5_snorm(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let xx = global_id.x;
+    let yy = global_id.y;
+    layer_index = global_id.z;
+
+    let block_width = (uniforms.width + 3u) / 4u;
+    let block_height = (uniforms.height + 3u) / 4u;
+
+    if (xx >= block_width || yy >= block_height) {
+        return;
+    }
+
+    var block: array<f32, 64>;
+    var compressed_data: vec4<u32>;
+
+    load_block_r_snorm(&block, xx, yy);
+
+    let red_result = compress_block_bc4_snorm(&block);
+    compressed_data[0] = red_result[0];
+    compressed_data[1] = red_result[1];
+
+    var error = bc4_block_error(&block, red_result, valid_mask, true);
+
+    load_block_g_snorm(&block, xx, yy);
+
+    let green_result = compress_block_bc4_snorm(&block);
+    compressed_data[2] = green_result[0];
+    compressed_data[3] = green_result[1];
+
+    error += bc4_block_error(&block, green_result, valid_mask, true);
+
     store_data_4(block_width, xx, yy, compressed_data);
+    store_error(block_width, xx, yy, error);
 }
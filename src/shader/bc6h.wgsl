@@ -20,6 +20,9 @@ struct Uniforms {
     height: u32,
     texture_y_offset: u32,
     blocks_offset: u32,
+    base_array_layer: u32,
+    errors_offset: u32,
+    write_errors: u32,
 }
 
 struct Settings {
@@ -28,6 +31,7 @@ struct Settings {
     refine_iterations_1p: u32,
     refine_iterations_2p: u32,
     fast_skip_threshold: u32,
+    signed: u32,
 }
 
 struct State {
@@ -43,10 +47,10 @@ struct State {
     qbounds: array<i32, 8>,
 }
 
-@group(0) @binding(0) var source_texture: texture_2d<f32>;
 @group(0) @binding(1) var<storage, read_write> block_buffer: array<u32>;
 @group(0) @binding(2) var<uniform> uniforms: Uniforms;
 @group(0) @binding(3) var<storage, read> settings: Settings;
+@group(0) @binding(4) var<storage, read_write> error_buffer: array<f32>;
 
 fn sq(x: f32) -> f32 {
     return x * x;
@@ -69,12 +73,37 @@ fn f32_to_f16_bits(f: f32) -> u32 {
     return (sign << 15) | (exp << 10) | frac;
 }
 
+// Bit mask of the pixels of the current block that lie inside the image.
+var<private> valid_mask: u32;
+
+// Index of the compressed array layer relative to the first layer of the task.
+var<private> layer_index: u32;
+
+fn compute_valid_mask(xx: u32, yy: u32) -> u32 {
+    let valid_x = min(uniforms.width - xx * 4u, 4u);
+    let valid_y = min(uniforms.height - yy * 4u, 4u);
+    let row_mask = (1u << valid_x) - 1u;
+
+    var mask = 0u;
+    for (var y = 0u; y < valid_y; y++) {
+        mask |= row_mask << (y * 4u);
+    }
+    return mask;
+}
+
+// Clamps the texel coordinates, so that partial blocks at the edge replicate the last pixel row and column.
+fn texel_coordinates(xx: u32, yy: u32, x: u32, y: u32) -> vec2<u32> {
+    let pixel_x = min(xx * 4u + x, uniforms.width - 1u);
+    let pixel_y = min(yy * 4u + y, uniforms.height - 1u) + uniforms.texture_y_offset;
+    return vec2<u32>(pixel_x, pixel_y);
+}
+
 fn load_block_interleaved_16bit(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y + uniforms.texture_y_offset;;
-            let rgba = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0);
+            let rgba = load_texel(texel_coordinates(xx, yy, x, y));
 
             (*block)[16u * 0u + y * 4u + x] = f32(f32_to_f16_bits(rgba.r) & 0xFFFF);
             (*block)[16u * 1u + y * 4u + x] = f32(f32_to_f16_bits(rgba.g) & 0xFFFF);
@@ -85,7 +114,8 @@ fn load_block_interleaved_16bit(block: ptr<function, array<f32, 64>>, xx: u32, y
 }
 
 fn store_data(state: ptr<function, State>, block_width: u32, xx: u32, yy: u32) {
-    let offset = uniforms.blocks_offset + (yy * block_width * 4u + xx * 4u);
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    let offset = uniforms.blocks_offset + (block_row * block_width * 4u + xx * 4u);
 
     block_buffer[offset + 0] = (*state).data[0];
     block_buffer[offset + 1] = (*state).data[1];
@@ -93,6 +123,15 @@ fn store_data(state: ptr<function, State>, block_width: u32, xx: u32, yy: u32) {
     block_buffer[offset + 3] = (*state).data[3];
 }
 
+fn store_error(block_width: u32, xx: u32, yy: u32, error: f32) {
+    if (uniforms.write_errors == 0u) {
+        return;
+    }
+
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    error_buffer[uniforms.errors_offset + block_row * block_width + xx] = error;
+}
+
 fn get_unquant_value(bits: u32, index: i32) -> i32 {
     switch (bits) {
         case 2u: {
@@ -133,6 +172,15 @@ fn get_pattern(part_id: i32) -> u32 {
     return pattern_table[part_id];
 }
 
+// Restricts a pixel mask to the pixels inside the image. Falls back to the unrestricted mask for empty subsets.
+fn mask_valid_pixels(mask: u32) -> u32 {
+    let masked = mask & valid_mask;
+    if (masked != 0u) {
+        return masked;
+    }
+    return mask;
+}
+
 fn get_pattern_mask(part_id: i32, j: u32) -> u32 {
     const pattern_mask_table = array<u32, 128>(
 		0xCCCC3333u, 0x88887777u, 0xEEEE1111u, 0xECC81337u, 0xC880377Fu, 0xFEEC0113u, 0xFEC80137u, 0xEC80137Fu,
@@ -211,12 +259,26 @@ fn get_mode_bits(mode: u32) -> u32 {
     return mode_bits_table[mode];
 }
 
-fn ep_quant_bc6h_8(state: ptr<function, State>, ep: ptr<function, array<f32, 8>>, bits: u32, pairs: u32) {
+fn quantize(value: f32, bits: u32) -> i32 {
+    if (settings.signed != 0u) {
+        let max_value = (1 << (bits - 1u)) - 1;
+        let v = value - 32767.0;
+        let q = min(i32(abs(v) / 32767.0 * f32(max_value) + 0.5), max_value);
+
+        if (v < 0.0) {
+            return -q;
+        }
+        return q;
+    }
+
     let levels = 1u << bits;
+    let v = i32((value / (256.0 * 256.0 - 1.0) * f32(levels - 1u) + 0.5));
+    return clamp(v, 0, i32(levels - 1u));
+}
 
+fn ep_quant_bc6h_8(state: ptr<function, State>, ep: ptr<function, array<f32, 8>>, bits: u32, pairs: u32) {
     for (var i = 0u; i < 8u * pairs; i++) {
-        let v = i32(((*ep)[i] / (256.0 * 256.0 - 1.0) * f32(levels - 1u) + 0.5));
-        (*state).qbounds[i] = clamp(v, 0, i32(levels - 1u));
+        (*state).qbounds[i] = quantize((*ep)[i], bits);
     }
 }
 
@@ -551,18 +613,46 @@ fn unpack_to_uf16(v: u32, bits: u32) -> u32 {
     return (v * 2u + 1u) << (15u - bits);
 }
 
-fn ep_quant_bc6h(qep: ptr<function, array<i32, 24>>, ep:  ptr<function, array<f32, 24>>, bits: u32, pairs: u32) {
-    let levels = 1u << bits;
+// Unquantizes a signed endpoint and offsets it by 0x7FFF, so that the signed range
+// -0x7FFF to 0x7FFF maps to the same positive range the unsigned encoding works with.
+fn unpack_to_sf16(v: i32, bits: u32) -> i32 {
+    let magnitude = abs(v);
 
+    var unquantized: i32;
+    if (bits >= 16u || magnitude == 0) {
+        unquantized = magnitude;
+    } else if (magnitude >= (1 << (bits - 1u)) - 1) {
+        unquantized = 0x7FFF;
+    } else {
+        unquantized = ((magnitude << 15u) + 0x4000) >> (bits - 1u);
+    }
+
+    return 0x7FFF + unquantized * sign(v);
+}
+
+fn ep_quant_bc6h(qep: ptr<function, array<i32, 24>>, ep:  ptr<function, array<f32, 24>>, bits: u32, pairs: u32) {
     for (var i = 0u; i < 8u * pairs; i++) {
-        let v = i32(((*ep)[i] / (256.0 * 256.0 - 1.0) * f32(levels - 1u) + 0.5));
-        (*qep)[i] = clamp(v, 0, i32(levels - 1u));
+        (*qep)[i] = quantize((*ep)[i], bits);
     }
 }
 
 fn ep_dequant_bc6h(ep: ptr<function, array<f32, 24>>, qep: ptr<function, array<i32, 24>>, bits: u32, pairs: u32) {
     for (var i = 0u; i < 8u * pairs; i++) {
-        (*ep)[i] = f32(unpack_to_uf16(u32((*qep)[i]), bits));
+        if (settings.signed != 0u) {
+            (*ep)[i] = f32(unpack_to_sf16((*qep)[i], bits));
+        } else {
+            (*ep)[i] = f32(unpack_to_uf16(u32((*qep)[i]), bits));
+        }
+    }
+}
+
+// Signed endpoints are stored as two's complement values with `epb` bits.
+fn mask_signed_endpoints(state: ptr<function, State>, qep: ptr<function, array<i32, 24>>) {
+    if (settings.signed != 0u) {
+        let mask = (1 << (*state).epb) - 1;
+        for (var i = 0u; i < 24u; i++) {
+            (*qep)[i] &= mask;
+        }
     }
 }
 
@@ -770,6 +860,7 @@ fn bc6h_code_2p(state: ptr<function, State>, qep: ptr<function, array<i32, 24>>,
     let bits = 3u;
 
     let flips = bc7_code_apply_swap_mode01237(qep, qblock, 1u, part_id);
+    mask_signed_endpoints(state, qep);
 
     for (var k = 0u; k < 5u; k++) {
         (*state).data[k] = 0u;
@@ -797,6 +888,7 @@ fn bc6h_code_2p(state: ptr<function, State>, qep: ptr<function, array<i32, 24>>,
 
 fn bc6h_code_1p(state: ptr<function, State>, qep: ptr<function, array<i32, 24>>, qblock: ptr<function, vec2<u32>>, mode: u32) {
     bc7_code_apply_swap_mode456(qep, 4u, qblock, 4u);
+    mask_signed_endpoints(state, qep);
 
     for (var k = 0u; k < 5u; k++) {
         (*state).data[k] = 0u;
@@ -819,11 +911,11 @@ fn bc6h_code_1p(state: ptr<function, State>, qep: ptr<function, array<i32, 24>>,
 
 fn bc6h_enc_2p(state: ptr<function, State>, block: ptr<function, array<f32, 64>>) {
     var full_stats: array<f32, 15>;
-    compute_stats_masked(&full_stats, block, 0xFFFFFFFFu, 3u);
+    compute_stats_masked(&full_stats, block, valid_mask, 3u);
 
     var part_list: array<i32, 32>;
     for (var part = 0u; part < 32u; part++) {
-        let mask = get_pattern_mask(i32(part), 0u);
+        let mask = get_pattern_mask(i32(part), 0u) & valid_mask;
         let bound12 = block_pca_bound_split(block, mask, full_stats, 3u);
         let bound = i32(bound12);
         part_list[part] = i32(part) + bound * 64;
@@ -841,7 +933,7 @@ fn bc6h_enc_2p_part_fast(state: ptr<function, State>, block: ptr<function, array
 
     var ep: array<f32, 24>;
     for (var j = 0u; j < pairs; j++) {
-        let mask = get_pattern_mask(part_id, j);
+        let mask = mask_valid_pixels(get_pattern_mask(part_id, j));
         block_segment_core(&ep, j * 8u, block, mask, channels);
     }
 
@@ -887,7 +979,7 @@ fn bc6h_enc_2p_list(state: ptr<function, State>, block: ptr<function, array<f32,
     for (var i = 0u; i < settings.refine_iterations_2p; i++) {
         var ep: array<f32, 24>;
         for (var j = 0u; j < pairs; j++) {
-            let mask = get_pattern_mask(best_part_id, j);
+            let mask = mask_valid_pixels(get_pattern_mask(best_part_id, j));
             opt_endpoints(&ep, j * 8u, block, bits, best_qblock, mask, channels);
         }
 
@@ -917,7 +1009,7 @@ fn bc6h_enc_2p_list(state: ptr<function, State>, block: ptr<function, array<f32,
 
 fn bc6h_enc_1p(state: ptr<function, State>, block: ptr<function, array<f32, 64>>) {
     var ep: array<f32, 24>;
-    block_segment_core(&ep, 0, block, 0xFFFFFFFFu, 3u);
+    block_segment_core(&ep, 0, block, valid_mask, 3u);
 
     var qep: array<i32, 24>;
     ep_quant_dequant_bc6h(state, &qep, &ep, 1u);
@@ -928,7 +1020,7 @@ fn bc6h_enc_1p(state: ptr<function, State>, block: ptr<function, array<f32, 64>>
     // Refine
     let refine_iterations = settings.refine_iterations_1p;
     for (var i = 0u; i < refine_iterations; i++) {
-        opt_endpoints(&ep, 0, block, 4, qblock, 0xFFFFFFFFu, 3u);
+        opt_endpoints(&ep, 0, block, 4, qblock, valid_mask, 3u);
         ep_quant_dequant_bc6h(state, &qep, &ep, 1u);
         err = block_quant(&qblock, block, 4u, &ep, 0u, 3u);
     }
@@ -1338,7 +1430,19 @@ fn bc6h_setup(state: ptr<function, State>, block: ptr<function, array<f32, 64>>)
     // Find min/max bounds
     for (var p = 0u; p < 3u; p++) {
         for (var k = 0u; k < 16u; k++) {
-            let value = ((*block)[p * 16u + k] / 31.0) * 64.0;
+            var value: f32;
+            if (settings.signed != 0u) {
+                // Signed values are offset by 0x7FFF to stay positive
+                let bits = u32((*block)[p * 16u + k]);
+                let magnitude = (f32(bits & 0x7FFFu) / 31.0) * 32.0;
+                if ((bits & 0x8000u) != 0u) {
+                    value = 32767.0 - magnitude;
+                } else {
+                    value = 32767.0 + magnitude;
+                }
+            } else {
+                value = ((*block)[p * 16u + k] / 31.0) * 64.0;
+            }
             (*block)[p * 16u + k] = value;
             (*state).rgb_bounds[p] = min((*state).rgb_bounds[p], value);
             (*state).rgb_bounds[3u + p] = max((*state).rgb_bounds[3u + p], value);
@@ -1403,6 +1507,7 @@ fn compress_bc6h_core(state: ptr<function, State>, block: ptr<function, array<f3
 fn compress_bc6h(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -1421,4 +1526,5 @@ fn compress_bc6h(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compress_bc6h_core(&state, &block);
 
     store_data(&state, block_width, xx, yy);
+    store_error(block_width, xx, yy, state.best_err);
 }
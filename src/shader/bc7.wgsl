@@ -20,18 +20,21 @@ struct Uniforms {
     height: u32,
     texture_y_offset: u32,
     blocks_offset: u32,
+    base_array_layer: u32,
+    errors_offset: u32,
+    write_errors: u32,
 }
 
 struct Settings {
     refine_iterations: array<u32, 8>,
-    mode_selection: array<u32, 4>,
-    skip_mode2: u32,
+    mode_selection: array<u32, 8>,
     fast_skip_threshold_mode1: u32,
     fast_skip_threshold_mode3: u32,
     fast_skip_threshold_mode7: u32,
     mode45_channel0: u32,
     refine_iterations_channel: u32,
     channels: u32,
+    channel_weights: array<f32, 3>,
 }
 
 struct State {
@@ -49,10 +52,10 @@ struct Mode45Parameters {
     swap: u32,
 }
 
-@group(0) @binding(0) var source_texture: texture_2d<f32>;
 @group(0) @binding(1) var<storage, read_write> block_buffer: array<u32>;
 @group(0) @binding(2) var<uniform> uniforms: Uniforms;
 @group(0) @binding(3) var<storage, read> settings: Settings;
+@group(0) @binding(4) var<storage, read_write> error_buffer: array<f32>;
 
 fn sq(x: f32) -> f32 {
     return x * x;
@@ -62,12 +65,40 @@ fn rsqrt(x: f32) -> f32 {
     return 1.0 / sqrt(x);
 }
 
+// Bit mask of the pixels of the current block that lie inside the image.
+var<private> valid_mask: u32;
+
+// Index of the compressed array layer relative to the first layer of the task.
+var<private> layer_index: u32;
+
+// Error weights of the red, green, blue and alpha channel.
+var<private> channel_weights: vec4<f32>;
+
+fn compute_valid_mask(xx: u32, yy: u32) -> u32 {
+    let valid_x = min(uniforms.width - xx * 4u, 4u);
+    let valid_y = min(uniforms.height - yy * 4u, 4u);
+    let row_mask = (1u << valid_x) - 1u;
+
+    var mask = 0u;
+    for (var y = 0u; y < valid_y; y++) {
+        mask |= row_mask << (y * 4u);
+    }
+    return mask;
+}
+
+// Clamps the texel coordinates, so that partial blocks at the edge replicate the last pixel row and column.
+fn texel_coordinates(xx: u32, yy: u32, x: u32, y: u32) -> vec2<u32> {
+    let pixel_x = min(xx * 4u + x, uniforms.width - 1u);
+    let pixel_y = min(yy * 4u + y, uniforms.height - 1u) + uniforms.texture_y_offset;
+    return vec2<u32>(pixel_x, pixel_y);
+}
+
 fn load_block_interleaved_rgba(block: ptr<function, array<f32, 64>>, xx: u32, yy: u32) {
+    valid_mask = compute_valid_mask(xx, yy);
+
     for (var y = 0u; y < 4u; y++) {
         for (var x = 0u; x < 4u; x++) {
-            let pixel_x = xx * 4u + x;
-            let pixel_y = yy * 4u + y + uniforms.texture_y_offset;
-            let rgba = textureLoad(source_texture, vec2<u32>(pixel_x, pixel_y), 0);
+            let rgba = load_texel(texel_coordinates(xx, yy, x, y));
 
             (*block)[16u * 0u + y * 4u + x] = rgba.r * 255.0;
             (*block)[16u * 1u + y * 4u + x] = rgba.g * 255.0;
@@ -78,7 +109,8 @@ fn load_block_interleaved_rgba(block: ptr<function, array<f32, 64>>, xx: u32, yy
 }
 
 fn store_data(state: ptr<function, State>, block_width: u32, xx: u32, yy: u32) {
-    let offset = uniforms.blocks_offset + (yy * block_width * 4u + xx * 4u);
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    let offset = uniforms.blocks_offset + (block_row * block_width * 4u + xx * 4u);
 
     block_buffer[offset + 0] = (*state).data[0];
     block_buffer[offset + 1] = (*state).data[1];
@@ -86,6 +118,15 @@ fn store_data(state: ptr<function, State>, block_width: u32, xx: u32, yy: u32) {
     block_buffer[offset + 3] = (*state).data[3];
 }
 
+fn store_error(block_width: u32, xx: u32, yy: u32, error: f32) {
+    if (uniforms.write_errors == 0u) {
+        return;
+    }
+
+    let block_row = layer_index * ((uniforms.height + 3u) / 4u) + yy;
+    error_buffer[uniforms.errors_offset + block_row * block_width + xx] = error;
+}
+
 fn get_unquant_value(bits: u32, index: i32) -> i32 {
     switch (bits) {
         case 2u: {
@@ -126,6 +167,15 @@ fn get_pattern(part_id: i32) -> u32 {
     return pattern_table[part_id];
 }
 
+// Restricts a pixel mask to the pixels inside the image. Falls back to the unrestricted mask for empty subsets.
+fn mask_valid_pixels(mask: u32) -> u32 {
+    let masked = mask & valid_mask;
+    if (masked != 0u) {
+        return masked;
+    }
+    return mask;
+}
+
 fn get_pattern_mask(part_id: i32, j: u32) -> u32 {
     const pattern_mask_table = array<u32, 128>(
 		0xCCCC3333u, 0x88887777u, 0xEEEE1111u, 0xECC81337u, 0xC880377Fu, 0xFEEC0113u, 0xFEC80137u, 0xEC80137Fu,
@@ -495,7 +545,7 @@ fn ep_quant_dequant(qep: ptr<function, array<i32, 24>>, ep: ptr<function, array<
     ep_dequant(ep, qep, mode);
 }
 
-fn block_quant(qblock: ptr<function, vec2<u32>>, block: ptr<function, array<f32, 64>>, bits: u32, ep: ptr<function, array<f32, 24>>, pattern: u32, channels: u32) -> f32 {
+fn block_quant(qblock: ptr<function, vec2<u32>>, block: ptr<function, array<f32, 64>>, bits: u32, ep: ptr<function, array<f32, 24>>, pattern: u32, channels: u32, weights: vec4<f32>) -> f32 {
     var total_err = 0.0;
     let levels = 1u << bits;
 
@@ -512,8 +562,8 @@ fn block_quant(qblock: ptr<function, vec2<u32>>, block: ptr<function, array<f32,
         for (var p = 0u; p < channels; p++) {
             let ep_a = (*ep)[8u * j + 0u + p];
             let ep_b = (*ep)[8u * j + 4u + p];
-            proj += ((*block)[k + p * 16u] - ep_a) * (ep_b - ep_a);
-            div += sq(ep_b - ep_a);
+            proj += weights[p] * ((*block)[k + p * 16u] - ep_a) * (ep_b - ep_a);
+            div += weights[p] * sq(ep_b - ep_a);
         }
 
         proj = proj / div;
@@ -531,8 +581,8 @@ fn block_quant(qblock: ptr<function, vec2<u32>>, block: ptr<function, array<f32,
             let ep_b = (*ep)[8u * j + 4u + p];
             let dec_v0 = f32(((64 - w0) * i32(ep_a) + w0 * i32(ep_b) + 32) / 64);
             let dec_v1 = f32(((64 - w1) * i32(ep_a) + w1 * i32(ep_b) + 32) / 64);
-            err0 += sq(dec_v0 - (*block)[k + p * 16u]);
-            err1 += sq(dec_v1 - (*block)[k + p * 16u]);
+            err0 += weights[p] * sq(dec_v0 - (*block)[k + p * 16u]);
+            err1 += weights[p] * sq(dec_v1 - (*block)[k + p * 16u]);
         }
 
         var best_err = err1;
@@ -610,7 +660,7 @@ fn opt_channel(qblock: ptr<function, vec2<u32>>, qep: ptr<function, vec2<i32>>,
     // Refine
     let refine_iterations = settings.refine_iterations_channel;
     for (var i = 0u; i < refine_iterations; i++) {
-        channel_opt_endpoints(&ep, channel_block, bits, *qblock);
+        channel_opt_endpoints(&ep, channel_block, bits, *qblock, valid_mask);
         channel_quant_dequant(qep, &ep, epbits);
         err = channel_opt_quant(qblock, channel_block, bits, &ep);
     }
@@ -662,13 +712,14 @@ fn channel_opt_quant(qblock: ptr<function, vec2<u32>>, channel_block: ptr<functi
     return total_err;
 }
 
-fn channel_opt_endpoints(ep: ptr<function, vec2<f32>>, channel_block: ptr<function, array<f32, 16>>, bits: u32, qblock: vec2<u32>) {
+fn channel_opt_endpoints(ep: ptr<function, vec2<f32>>, channel_block: ptr<function, array<f32, 16>>, bits: u32, qblock: vec2<u32>, mask: u32) {
     let levels = i32(1u << bits);
 
     var Atb1 = 0.0;
     var sum_q = 0.0;
     var sum_qq = 0.0;
     var sum = 0.0;
+    var count = 0.0;
 
     for (var k1 = 0u; k1 < 2u; k1++) {
         var qbits_shifted = qblock[k1];
@@ -677,6 +728,12 @@ fn channel_opt_endpoints(ep: ptr<function, vec2<f32>>, channel_block: ptr<functi
             let q = f32(qbits_shifted & 15u);
             qbits_shifted >>= 4u;
 
+            if ((mask & (1u << k)) == 0u) {
+                continue;
+            }
+
+            count += 1.0;
+
             let x = f32(levels - 1) - q;
 
             sum_q += q;
@@ -689,7 +746,7 @@ fn channel_opt_endpoints(ep: ptr<function, vec2<f32>>, channel_block: ptr<functi
 
     let Atb2 = f32(levels - 1) * sum - Atb1;
 
-    let Cxx = 16.0 * sq(f32(levels - 1)) - 2.0 * f32(levels - 1) * sum_q + sum_qq;
+    let Cxx = count * sq(f32(levels - 1)) - 2.0 * f32(levels - 1) * sum_q + sum_qq;
     let Cyy = sum_qq;
     let Cxy = f32(levels - 1) * sum_q - sum_qq;
     let scale = f32(levels - 1) / (Cxx * Cyy - Cxy * Cxy);
@@ -701,7 +758,7 @@ fn channel_opt_endpoints(ep: ptr<function, vec2<f32>>, channel_block: ptr<functi
     (*ep)[1] = clamp((*ep)[1], 0.0, 255.0);
 
     if (abs(Cxx * Cyy - Cxy * Cxy) < 0.001) {
-        (*ep)[0] = sum / 16.0;
+        (*ep)[0] = sum / count;
         (*ep)[1] = (*ep)[0];
     }
 }
@@ -1048,13 +1105,13 @@ fn bc7_enc_mode01237_part_fast(qep: ptr<function, array<i32, 24>>, qblock: ptr<f
 
     var ep: array<f32, 24>;
     for (var j = 0u; j < pairs; j++) {
-        let mask = get_pattern_mask(part_id, j);
+        let mask = mask_valid_pixels(get_pattern_mask(part_id, j));
         block_segment(&ep, j * 8, block, mask, channels);
     }
 
     ep_quant_dequant(qep, &ep, mode, channels);
 
-    return block_quant(qblock, block, bits, &ep, pattern, channels);
+    return block_quant(qblock, block, bits, &ep, pattern, channels, channel_weights);
 }
 
 fn bc7_enc_mode01237(state: ptr<function, State>, block: ptr<function, array<f32, 64>>, mode: u32, part_list: array<i32, 64>, part_count: u32) {
@@ -1096,7 +1153,7 @@ fn bc7_enc_mode01237(state: ptr<function, State>, block: ptr<function, array<f32
     for (var i = 0u; i < refine_iterations; i++) {
         var ep: array<f32, 24>;
         for (var j = 0u; j < pairs; j++) {
-            let mask = get_pattern_mask(best_part_id, j);
+            let mask = mask_valid_pixels(get_pattern_mask(best_part_id, j));
             opt_endpoints(&ep, j * 8, block, bits, best_qblock, mask, channels);
         }
 
@@ -1106,7 +1163,7 @@ fn bc7_enc_mode01237(state: ptr<function, State>, block: ptr<function, array<f32
         ep_quant_dequant(&qep, &ep, mode, channels);
 
         let pattern = get_pattern(best_part_id);
-        let err = block_quant(&qblock, block, bits, &ep, pattern, channels);
+        let err = block_quant(&qblock, block, bits, &ep, pattern, channels, channel_weights);
 
         if (err < best_err) {
             for (var i = 0u; i < 8u * pairs; i++) {
@@ -1136,33 +1193,43 @@ fn bc7_enc_mode02(state: ptr<function, State>, block: ptr<function, array<f32, 6
         part_list[part] = part;
     }
 
-    bc7_enc_mode01237(state, block, 0u, part_list, 16u);
-
-    if (settings.skip_mode2 == 0) {
+    if (settings.mode_selection[0] != 0u) {
+        bc7_enc_mode01237(state, block, 0u, part_list, 16u);
+    }
+    if (settings.mode_selection[2] != 0u) {
         bc7_enc_mode01237(state, block, 2u, part_list, 64u);
     }
 }
 
 fn bc7_enc_mode13(state: ptr<function, State>, block: ptr<function, array<f32, 64>>) {
-    if (settings.fast_skip_threshold_mode1 == 0u && settings.fast_skip_threshold_mode3 == 0u) {
+    var fast_skip_threshold_mode1 = 0u;
+    if (settings.mode_selection[1] != 0u) {
+        fast_skip_threshold_mode1 = settings.fast_skip_threshold_mode1;
+    }
+    var fast_skip_threshold_mode3 = 0u;
+    if (settings.mode_selection[3] != 0u) {
+        fast_skip_threshold_mode3 = settings.fast_skip_threshold_mode3;
+    }
+
+    if (fast_skip_threshold_mode1 == 0u && fast_skip_threshold_mode3 == 0u) {
         return;
     }
 
     var full_stats: array<f32, 15>;
-    compute_stats_masked(&full_stats, block, 0xFFFFFFFFu, 3u);
+    compute_stats_masked(&full_stats, block, valid_mask, 3u);
 
     var part_list: array<i32, 64>;
     for (var part = 0; part < 64; part++) {
-        let mask = get_pattern_mask(part, 0u);
+        let mask = get_pattern_mask(part, 0u) & valid_mask;
         let bound12 = block_pca_bound_split(block, mask, full_stats, 3u);
         let bound = i32(bound12);
         part_list[part] = part + bound * 64;
     }
 
-    let partial_count = max(settings.fast_skip_threshold_mode1, settings.fast_skip_threshold_mode3);
+    let partial_count = max(fast_skip_threshold_mode1, fast_skip_threshold_mode3);
     partial_sort_list(&part_list, 64, i32(partial_count));
-    bc7_enc_mode01237(state, block, 1u, part_list, settings.fast_skip_threshold_mode1);
-    bc7_enc_mode01237(state, block, 3u, part_list, settings.fast_skip_threshold_mode3);
+    bc7_enc_mode01237(state, block, 1u, part_list, fast_skip_threshold_mode1);
+    bc7_enc_mode01237(state, block, 3u, part_list, fast_skip_threshold_mode3);
 }
 
 fn bc7_enc_mode45_candidate(best_candidate: ptr<function, Mode45Parameters>, best_err: ptr<function, f32>, block: ptr<function, array<f32, 64>>, mode: u32, rotation: u32, swap: u32) {
@@ -1199,21 +1266,26 @@ fn bc7_enc_mode45_candidate(best_candidate: ptr<function, Mode45Parameters>, bes
         }
     }
 
+    // The rotated channel takes the weight of the alpha channel and vice versa.
+    var candidate_weights = channel_weights;
+    candidate_weights[rotation] = channel_weights[3];
+    candidate_weights[3] = channel_weights[rotation];
+
     var ep: array<f32, 24>;
-    block_segment(&ep, 0u, &candidate_block, 0xFFFFFFFFu, 3u);
+    block_segment(&ep, 0u, &candidate_block, valid_mask, 3u);
 
     var qep: array<i32, 24>;
     ep_quant_dequant(&qep, &ep, mode, 3u);
 
     var qblock: vec2<u32>;
-    var err = block_quant(&qblock, &candidate_block, bits, &ep, 0u, 3u);
+    var err = block_quant(&qblock, &candidate_block, bits, &ep, 0u, 3u, candidate_weights);
 
     // Refine
     let refine_iterations = settings.refine_iterations[mode];
     for (var i = 0u; i < refine_iterations; i++) {
-        opt_endpoints(&ep, 0u, &candidate_block, bits, qblock, 0xFFFFFFFFu, 3u);
+        opt_endpoints(&ep, 0u, &candidate_block, bits, qblock, valid_mask, 3u);
         ep_quant_dequant(&qep, &ep, mode, 3u);
-        err = block_quant(&qblock, &candidate_block, bits, &ep, 0u, 3u);
+        err = block_quant(&qblock, &candidate_block, bits, &ep, 0u, 3u, candidate_weights);
     }
 
     var channel_data: array<f32, 16>;
@@ -1225,7 +1297,7 @@ fn bc7_enc_mode45_candidate(best_candidate: ptr<function, Mode45Parameters>, bes
     var aqep: vec2<i32>;
     var aqblock: vec2<u32>;
 
-    err += opt_channel(&aqblock, &aqep, &channel_data, abits, aepbits);
+    err += candidate_weights[3] * opt_channel(&aqblock, &aqep, &channel_data, abits, aepbits);
 
     if (err < *best_err) {
         for (var i = 0u; i < 8u; i++) {
@@ -1247,9 +1319,11 @@ fn bc7_enc_mode45(state: ptr<function, State>, block: ptr<function, array<f32, 6
     var best_err = (*state).best_err;
 
     let channel0 = settings.mode45_channel0;
-    for (var p = channel0; p < settings.channels; p++) {
-        bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 4u, p, 0u);
-        bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 4u, p, 1u);
+    if (settings.mode_selection[4] != 0u) {
+        for (var p = channel0; p < settings.channels; p++) {
+            bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 4u, p, 0u);
+            bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 4u, p, 1u);
+        }
     }
 
     // Mode 4
@@ -1258,8 +1332,10 @@ fn bc7_enc_mode45(state: ptr<function, State>, block: ptr<function, array<f32, 6
         bc7_code_mode45(state, &best_candidate, 4u);
     }
 
-    for (var p = channel0; p < settings.channels; p++) {
-        bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 5u, p, 0u);
+    if (settings.mode_selection[5] != 0u) {
+        for (var p = channel0; p < settings.channels; p++) {
+            bc7_enc_mode45_candidate(&best_candidate, &best_err, block, 5u, p, 0u);
+        }
     }
 
 
@@ -1275,7 +1351,7 @@ fn bc7_enc_mode6(state: ptr<function, State>, block: ptr<function, array<f32, 64
     const bits = 4u;
 
     var ep: array<f32, 24>;
-    block_segment(&ep, 0u, block, 0xFFFFFFFFu, settings.channels);
+    block_segment(&ep, 0u, block, valid_mask, settings.channels);
 
     if (settings.channels == 3u) {
         ep[3] = 255.0;
@@ -1286,13 +1362,13 @@ fn bc7_enc_mode6(state: ptr<function, State>, block: ptr<function, array<f32, 64
     ep_quant_dequant(&qep, &ep, mode, settings.channels);
 
     var qblock: vec2<u32>;
-    var err = block_quant(&qblock, block, bits, &ep, 0u, settings.channels);
+    var err = block_quant(&qblock, block, bits, &ep, 0u, settings.channels, channel_weights);
 
     let refine_iterations = settings.refine_iterations[mode];
     for (var i = 0u; i < refine_iterations; i++) {
-        opt_endpoints(&ep, 0u, block, bits, qblock, 0xFFFFFFFFu, settings.channels);
+        opt_endpoints(&ep, 0u, block, bits, qblock, valid_mask, settings.channels);
         ep_quant_dequant(&qep, &ep, mode, settings.channels);
-        err = block_quant(&qblock, block, bits, &ep, 0u, settings.channels);
+        err = block_quant(&qblock, block, bits, &ep, 0u, settings.channels, channel_weights);
     }
 
     if (err < (*state).best_err) {
@@ -1302,16 +1378,16 @@ fn bc7_enc_mode6(state: ptr<function, State>, block: ptr<function, array<f32, 64
 }
 
 fn bc7_enc_mode7(state: ptr<function, State>, block: ptr<function, array<f32, 64>>) {
-    if (settings.fast_skip_threshold_mode7 == 0u) {
+    if (settings.mode_selection[7] == 0u || settings.fast_skip_threshold_mode7 == 0u) {
         return;
     }
 
     var full_stats: array<f32, 15>;
-    compute_stats_masked(&full_stats, block, 0xFFFFFFFFu, settings.channels);
+    compute_stats_masked(&full_stats, block, valid_mask, settings.channels);
 
     var part_list: array<i32, 64>;
     for (var part = 0; part < 64; part++) {
-        let mask = get_pattern_mask(part, 0u);
+        let mask = get_pattern_mask(part, 0u) & valid_mask;
         let bound12 = block_pca_bound_split(block, mask, full_stats, settings.channels);
         let bound = i32(bound12);
         part_list[part] = part + bound * 64;
@@ -1322,17 +1398,11 @@ fn bc7_enc_mode7(state: ptr<function, State>, block: ptr<function, array<f32, 64
 }
 
 fn compress_block_bc7_core(state: ptr<function, State>, block: ptr<function, array<f32, 64>>) {
-    if (settings.mode_selection[0] != 0u) {
-        bc7_enc_mode02(state, block);
-    }
-    if (settings.mode_selection[1] != 0u) {
-        bc7_enc_mode13(state,block);
-        bc7_enc_mode7(state, block);
-    }
-    if (settings.mode_selection[2] != 0u) {
-        bc7_enc_mode45(state, block);
-    }
-    if (settings.mode_selection[3] != 0u) {
+    bc7_enc_mode02(state, block);
+    bc7_enc_mode13(state, block);
+    bc7_enc_mode7(state, block);
+    bc7_enc_mode45(state, block);
+    if (settings.mode_selection[6] != 0u) {
         bc7_enc_mode6(state, block);
     }
 }
@@ -1354,6 +1424,7 @@ fn compute_opaque_err(block: ptr<function, array<f32, 64>>) -> f32 {
 fn compress_bc7(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let xx = global_id.x;
     let yy = global_id.y;
+    layer_index = global_id.z;
 
     let block_width = (uniforms.width + 3u) / 4u;
     let block_height = (uniforms.height + 3u) / 4u;
@@ -1362,6 +1433,8 @@ fn compress_bc7(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
 
+    channel_weights = vec4<f32>(settings.channel_weights[0], settings.channel_weights[1], settings.channel_weights[2], 1.0);
+
     var block: array<f32, 64>;
 
     load_block_interleaved_rgba(&block, xx, yy);
@@ -1373,4 +1446,5 @@ fn compress_bc7(@builtin(global_invocation_id) global_id: vec3<u32>) {
     compress_block_bc7_core(&state, &block);
 
     store_data(&state, block_width, xx, yy);
+    store_error(block_width, xx, yy, state.best_err);
 }
@@ -0,0 +1,283 @@
+// Port of the BC1 to BC5 block decoders of "bcdec.h - v0.98" (https://github.com/iOrange/bcdec).
+//
+// bcdec is free and unencumbered software released into the public domain.
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    blocks_offset: u32,
+    is_signed: u32,
+}
+
+@group(0) @binding(0) var<storage, read> block_buffer: array<u32>;
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+
+// Decoded texels of the current block in row-major order.
+var<private> texels: array<vec4<f32>, 16>;
+
+fn is_outside(block_id: vec2<u32>) -> bool {
+    return block_id.x * 4u >= uniforms.width || block_id.y * 4u >= uniforms.height;
+}
+
+// Returns the offset of the block in u32 words inside the block buffer.
+fn block_offset(block_id: vec2<u32>, block_words: u32) -> u32 {
+    let blocks_x = (uniforms.width + 3u) / 4u;
+    return uniforms.blocks_offset + (block_id.y * blocks_x + block_id.x) * block_words;
+}
+
+// Writes all texels of the current block, which lie inside the image.
+fn store_block(block_id: vec2<u32>) {
+    for (var y = 0u; y < 4u; y++) {
+        for (var x = 0u; x < 4u; x++) {
+            let coordinates = block_id * 4u + vec2<u32>(x, y);
+            if (coordinates.x < uniforms.width && coordinates.y < uniforms.height) {
+                store_texel(coordinates, texels[y * 4u + x]);
+            }
+        }
+    }
+}
+
+fn unorm8(value: vec4<u32>) -> vec4<f32> {
+    return vec4<f32>(value) / 255.0;
+}
+
+fn snorm8(value: i32) -> f32 {
+    return f32(value) / 127.0;
+}
+
+// Decodes a BC1 color block into the RGBA channels of the texels.
+fn decode_color_block(color_endpoints: u32, color_indices: u32, opaque_mode: bool) {
+    let c0 = color_endpoints & 0xFFFFu;
+    let c1 = color_endpoints >> 16u;
+
+    // Unpack 565 ref colors
+    let r0 = (c0 >> 11u) & 0x1Fu;
+    let g0 = (c0 >> 5u) & 0x3Fu;
+    let b0 = c0 & 0x1Fu;
+
+    let r1 = (c1 >> 11u) & 0x1Fu;
+    let g1 = (c1 >> 5u) & 0x3Fu;
+    let b1 = c1 & 0x1Fu;
+
+    // Expand 565 ref colors to 888
+    var ref_colors: array<vec4<u32>, 4>;
+    ref_colors[0] = vec4<u32>((r0 * 527u + 23u) >> 6u, (g0 * 259u + 33u) >> 6u, (b0 * 527u + 23u) >> 6u, 255u);
+    ref_colors[1] = vec4<u32>((r1 * 527u + 23u) >> 6u, (g1 * 259u + 33u) >> 6u, (b1 * 527u + 23u) >> 6u, 255u);
+
+    if (c0 > c1 || opaque_mode) {
+        // color_2 = 2/3*color_0 + 1/3*color_1
+        // color_3 = 1/3*color_0 + 2/3*color_1
+        ref_colors[2] = vec4<u32>(
+            ((2u * r0 + r1) * 351u + 61u) >> 7u,
+            ((2u * g0 + g1) * 2763u + 1039u) >> 11u,
+            ((2u * b0 + b1) * 351u + 61u) >> 7u,
+            255u,
+        );
+        ref_colors[3] = vec4<u32>(
+            ((r0 + r1 * 2u) * 351u + 61u) >> 7u,
+            ((g0 + g1 * 2u) * 2763u + 1039u) >> 11u,
+            ((b0 + b1 * 2u) * 351u + 61u) >> 7u,
+            255u,
+        );
+    } else {
+        // color_2 = 1/2*color_0 + 1/2*color_1
+        // color_3 = 0
+        ref_colors[2] = vec4<u32>(
+            ((r0 + r1) * 1053u + 125u) >> 8u,
+            ((g0 + g1) * 4145u + 1019u) >> 11u,
+            ((b0 + b1) * 1053u + 125u) >> 8u,
+            255u,
+        );
+        ref_colors[3] = vec4<u32>(0u);
+    }
+
+    for (var i = 0u; i < 16u; i++) {
+        texels[i] = unorm8(ref_colors[(color_indices >> (2u * i)) & 0x03u]);
+    }
+}
+
+// Decodes a BC2 alpha block (sharp transitions) into the alpha channel of the texels.
+fn decode_sharp_alpha_block(alpha_low: u32, alpha_high: u32) {
+    for (var i = 0u; i < 8u; i++) {
+        texels[i].a = f32(((alpha_low >> (4u * i)) & 0x0Fu) * 17u) / 255.0;
+        texels[i + 8u].a = f32(((alpha_high >> (4u * i)) & 0x0Fu) * 17u) / 255.0;
+    }
+}
+
+// Reads the 3 bit index at bit `position` of a 64 bit block.
+fn read_index(low: u32, high: u32, position: u32) -> u32 {
+    if (position >= 32u) {
+        return (high >> (position - 32u)) & 0x07u;
+    }
+    if (position + 3u <= 32u) {
+        return (low >> position) & 0x07u;
+    }
+    return ((low >> position) | (high << (32u - position))) & 0x07u;
+}
+
+// Decodes a BC3/BC4/BC5 channel block (smooth transitions) into UNORM8 values.
+fn decode_smooth_block(low: u32, high: u32) -> array<u32, 16> {
+    var values: array<u32, 8>;
+    values[0] = low & 0xFFu;
+    values[1] = (low >> 8u) & 0xFFu;
+
+    if (values[0] > values[1]) {
+        // 6 interpolated values
+        for (var i = 2u; i < 8u; i++) {
+            values[i] = ((8u - i) * values[0] + (i - 1u) * values[1]) / 7u;
+        }
+    } else {
+        // 4 interpolated values
+        for (var i = 2u; i < 6u; i++) {
+            values[i] = ((6u - i) * values[0] + (i - 1u) * values[1]) / 5u;
+        }
+        values[6] = 0x00u;
+        values[7] = 0xFFu;
+    }
+
+    var decoded: array<u32, 16>;
+    for (var i = 0u; i < 16u; i++) {
+        decoded[i] = values[read_index(low, high, 16u + 3u * i)];
+    }
+    return decoded;
+}
+
+// Decodes a signed BC4/BC5 channel block (smooth transitions) into SNORM8 values.
+fn decode_signed_smooth_block(low: u32, high: u32) -> array<i32, 16> {
+    // -128 and -127 both map to -1.0
+    var values: array<i32, 8>;
+    values[0] = max(i32(low << 24u) >> 24u, -127);
+    values[1] = max(i32(low << 16u) >> 24u, -127);
+
+    // The interpolated values never lie halfway between two SNORM8 values, so rounding the
+    // quotient matches rounding the interpolated float value.
+    if (values[0] > values[1]) {
+        // 6 interpolated values
+        for (var i = 2; i < 8; i++) {
+            values[i] = i32(round(f32((8 - i) * values[0] + (i - 1) * values[1]) / 7.0));
+        }
+    } else {
+        // 4 interpolated values
+        for (var i = 2; i < 6; i++) {
+            values[i] = i32(round(f32((6 - i) * values[0] + (i - 1) * values[1]) / 5.0));
+        }
+        values[6] = -127;
+        values[7] = 127;
+    }
+
+    var decoded: array<i32, 16>;
+    for (var i = 0u; i < 16u; i++) {
+        decoded[i] = values[read_index(low, high, 16u + 3u * i)];
+    }
+    return decoded;
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc1(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 2u);
+    decode_color_block(block_buffer[offset], block_buffer[offset + 1u], false);
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc2(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    decode_color_block(block_buffer[offset + 2u], block_buffer[offset + 3u], true);
+    decode_sharp_alpha_block(block_buffer[offset], block_buffer[offset + 1u]);
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc3(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    decode_color_block(block_buffer[offset + 2u], block_buffer[offset + 3u], true);
+    let alpha = decode_smooth_block(block_buffer[offset], block_buffer[offset + 1u]);
+    for (var i = 0u; i < 16u; i++) {
+        texels[i].a = f32(alpha[i]) / 255.0;
+    }
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc4(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 2u);
+    let red = decode_smooth_block(block_buffer[offset], block_buffer[offset + 1u]);
+    for (var i = 0u; i < 16u; i++) {
+        texels[i] = unorm8(vec4<u32>(red[i], 0u, 0u, 0u));
+    }
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc5(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    let red = decode_smooth_block(block_buffer[offset], block_buffer[offset + 1u]);
+    let green = decode_smooth_block(block_buffer[offset + 2u], block_buffer[offset + 3u]);
+    for (var i = 0u; i < 16u; i++) {
+        texels[i] = unorm8(vec4<u32>(red[i], green[i], 0u, 0u));
+    }
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc4_snorm(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 2u);
+    let red = decode_signed_smooth_block(block_buffer[offset], block_buffer[offset + 1u]);
+    for (var i = 0u; i < 16u; i++) {
+        texels[i] = vec4<f32>(snorm8(red[i]), 0.0, 0.0, 0.0);
+    }
+    store_block(block_id);
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc5_snorm(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    let red = decode_signed_smooth_block(block_buffer[offset], block_buffer[offset + 1u]);
+    let green = decode_signed_smooth_block(block_buffer[offset + 2u], block_buffer[offset + 3u]);
+    for (var i = 0u; i < 16u; i++) {
+        texels[i] = vec4<f32>(snorm8(red[i]), snorm8(green[i]), 0.0, 0.0);
+    }
+    store_block(block_id);
+}
@@ -0,0 +1,630 @@
+// Port of the BC6H block decoder of "bcdec.h - v0.98" (https://github.com/iOrange/bcdec).
+//
+// bcdec is free and unencumbered software released into the public domain.
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    blocks_offset: u32,
+    is_signed: u32,
+}
+
+@group(0) @binding(0) var<storage, read> block_buffer: array<u32>;
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+
+// Decoded texels of the current block in row-major order.
+var<private> texels: array<vec4<f32>, 16>;
+
+// Bits of the current block and the position of the next bit to read.
+var<private> block: array<u32, 4>;
+var<private> bit_position: u32;
+
+fn is_outside(block_id: vec2<u32>) -> bool {
+    return block_id.x * 4u >= uniforms.width || block_id.y * 4u >= uniforms.height;
+}
+
+// Returns the offset of the block in u32 words inside the block buffer.
+fn block_offset(block_id: vec2<u32>, block_words: u32) -> u32 {
+    let blocks_x = (uniforms.width + 3u) / 4u;
+    return uniforms.blocks_offset + (block_id.y * blocks_x + block_id.x) * block_words;
+}
+
+// Writes all texels of the current block, which lie inside the image.
+fn store_block(block_id: vec2<u32>) {
+    for (var y = 0u; y < 4u; y++) {
+        for (var x = 0u; x < 4u; x++) {
+            let coordinates = block_id * 4u + vec2<u32>(x, y);
+            if (coordinates.x < uniforms.width && coordinates.y < uniforms.height) {
+                store_texel(coordinates, texels[y * 4u + x]);
+            }
+        }
+    }
+}
+
+fn read_bits(num_bits: u32) -> u32 {
+    let word = bit_position >> 5u;
+    let shift = bit_position & 31u;
+    bit_position += num_bits;
+
+    if (word >= 4u) {
+        return 0u;
+    }
+
+    var bits = block[word] >> shift;
+    if (shift + num_bits > 32u && word < 3u) {
+        bits |= block[word + 1u] << (32u - shift);
+    }
+    return bits & ((1u << num_bits) - 1u);
+}
+
+fn read_bits_i32(num_bits: u32) -> i32 {
+    return i32(read_bits(num_bits));
+}
+
+fn read_bit_i32() -> i32 {
+    return i32(read_bits(1u));
+}
+
+fn read_bits_reversed(num_bits: u32) -> i32 {
+    return i32(reverseBits(read_bits(num_bits)) >> (32u - num_bits));
+}
+
+// Returns the subset of every texel of a two-region partition with 2 bits per texel.
+fn get_partition_subsets(partition_id: u32) -> u32 {
+    const partition_table = array<u32, 32>(
+        0x50505050u, 0x40404040u, 0x54545454u, 0x54505040u, 0x50404000u, 0x55545450u, 0x55545040u, 0x54504000u,
+        0x50400000u, 0x55555450u, 0x55544000u, 0x54400000u, 0x55555440u, 0x55550000u, 0x55555500u, 0x55000000u,
+        0x55150100u, 0x00004054u, 0x15010000u, 0x00405054u, 0x00004050u, 0x15050100u, 0x05010000u, 0x40505054u,
+        0x00404050u, 0x05010100u, 0x14141414u, 0x05141450u, 0x01155440u, 0x00555500u, 0x15014054u, 0x05414150u,
+    );
+    return partition_table[partition_id];
+}
+
+// Returns the mask of the texels of a two-region partition, whose index is stored with one
+// less bit.
+fn get_partition_fixups(partition_id: u32) -> u32 {
+    const fixup_table = array<u32, 32>(
+        0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u,
+        0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u,
+        0x00008001u, 0x00000005u, 0x00000101u, 0x00000005u, 0x00000005u, 0x00000101u, 0x00000101u, 0x00008001u,
+        0x00000005u, 0x00000101u, 0x00000005u, 0x00000005u, 0x00000101u, 0x00000101u, 0x00000005u, 0x00000005u,
+    );
+    return fixup_table[partition_id];
+}
+
+fn get_weight(index: i32, weight_bits: u32) -> i32 {
+    if (weight_bits == 3u) {
+        const table = array<i32, 8>(0, 9, 18, 27, 37, 46, 55, 64);
+        return table[index];
+    }
+    const table = array<i32, 16>(0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64);
+    return table[index];
+}
+
+fn interpolate(a: i32, b: i32, weight: i32) -> i32 {
+    return (a * (64 - weight) + b * weight + 32) >> 6u;
+}
+
+fn extend_sign(value: i32, bits: u32) -> i32 {
+    return (value << (32u - bits)) >> (32u - bits);
+}
+
+fn transform_inverse(value: i32, a0: i32, bits: u32, is_signed: bool) -> i32 {
+    // If the precision of A0 is "p" bits, then the transform algorithm is:
+    // B0 = (B0 + A0) & ((1 << p) - 1)
+    let transformed = (value + a0) & ((1 << bits) - 1);
+    if (is_signed) {
+        return extend_sign(transformed, bits);
+    }
+    return transformed;
+}
+
+fn unquantize(value: i32, bits: u32, is_signed: bool) -> i32 {
+    if (!is_signed) {
+        if (bits >= 15u) {
+            return value;
+        } else if (value == 0) {
+            return 0;
+        } else if (value == ((1 << bits) - 1)) {
+            return 0xFFFF;
+        }
+        return ((value << 16u) + 0x8000) >> bits;
+    }
+
+    if (bits >= 16u) {
+        return value;
+    }
+
+    let magnitude = abs(value);
+    var unquantized = 0;
+    if (magnitude == 0) {
+        unquantized = 0;
+    } else if (magnitude >= ((1 << (bits - 1u)) - 1)) {
+        unquantized = 0x7FFF;
+    } else {
+        unquantized = ((magnitude << 15u) + 0x4000) >> (bits - 1u);
+    }
+
+    if (value < 0) {
+        return -unquantized;
+    }
+    return unquantized;
+}
+
+// Returns the value as half float.
+fn finish_unquantize(value: i32, is_signed: bool) -> f32 {
+    if (!is_signed) {
+        // Scale the magnitude by 31 / 64
+        return unpack2x16float(u32((value * 31) >> 6u)).x;
+    }
+
+    // Scale the magnitude by 31 / 32
+    let magnitude = u32((abs(value) * 31) >> 5u);
+    if (value < 0 && magnitude != 0u) {
+        return unpack2x16float(0x8000u | magnitude).x;
+    }
+    return unpack2x16float(magnitude).x;
+}
+
+fn decode_block(is_signed: bool) {
+    const actual_bits_count = array<array<u32, 14>, 4>(
+        array<u32, 14>(10u, 7u, 11u, 11u, 11u, 9u, 8u, 8u, 8u, 6u, 10u, 11u, 12u, 16u), // W
+        array<u32, 14>(5u, 6u, 5u, 4u, 4u, 5u, 6u, 5u, 5u, 6u, 10u, 9u, 8u, 4u),        // dR
+        array<u32, 14>(5u, 6u, 4u, 5u, 4u, 5u, 5u, 6u, 5u, 6u, 10u, 9u, 8u, 4u),        // dG
+        array<u32, 14>(5u, 6u, 4u, 4u, 5u, 5u, 5u, 5u, 6u, 6u, 10u, 9u, 8u, 4u),        // dB
+    );
+
+    var r: array<i32, 4>;
+    var g: array<i32, 4>;
+    var b: array<i32, 4>;
+
+    var mode = read_bits(2u);
+    if (mode > 1u) {
+        mode |= read_bits(3u) << 2u;
+    }
+
+    // Modes 11 to 14 (10 to 13 in this code) have a single region and no partition
+    var partition_id = 0;
+
+    switch (mode) {
+        // Mode 1
+        case 0u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 75 bits (10.555, 10.555, 10.555)
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(5u); // rx[4:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(5u); // gx[4:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(5u); // bx[4:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(5u); // ry[4:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(5u); // rz[4:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 0u;
+        }
+
+        // Mode 2
+        case 1u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 75 bits (7666, 7666, 7666)
+            g[2] |= read_bit_i32() << 5u; // gy[5]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[3] |= read_bit_i32() << 5u; // gz[5]
+            r[0] |= read_bits_i32(7u); // rw[6:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(7u); // gw[6:0]
+            b[2] |= read_bit_i32() << 5u; // by[5]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(7u); // bw[6:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            b[3] |= read_bit_i32() << 5u; // bz[5]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(6u); // rx[5:0]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(6u); // gx[5:0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(6u); // bx[5:0]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(6u); // ry[5:0]
+            r[3] |= read_bits_i32(6u); // rz[5:0]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 1u;
+        }
+
+        // Mode 3
+        case 2u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (11.555, 11.444, 11.444)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(5u); // rx[4:0]
+            r[0] |= read_bit_i32() << 10u; // rw[10]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(4u); // gx[3:0]
+            g[0] |= read_bit_i32() << 10u; // gw[10]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(4u); // bx[3:0]
+            b[0] |= read_bit_i32() << 10u; // bw[10]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(5u); // ry[4:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(5u); // rz[4:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 2u;
+        }
+        // Mode 4
+        case 6u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (11.444, 11.555, 11.444)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(4u); // rx[3:0]
+            r[0] |= read_bit_i32() << 10u; // rw[10]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(5u); // gx[4:0]
+            g[0] |= read_bit_i32() << 10u; // gw[10]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(4u); // bx[3:0]
+            b[0] |= read_bit_i32() << 10u; // bw[10]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(4u); // ry[3:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(4u); // rz[3:0]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 3u;
+        }
+        // Mode 5
+        case 10u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (11.444, 11.444, 11.555)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(4u); // rx[3:0]
+            r[0] |= read_bit_i32() << 10u; // rw[10]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(4u); // gx[3:0]
+            g[0] |= read_bit_i32() << 10u; // gw[10]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(5u); // bx[4:0]
+            b[0] |= read_bit_i32() << 10u; // bw[10]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(4u); // ry[3:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(4u); // rz[3:0]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 4u;
+        }
+        // Mode 6
+        case 14u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (9555, 9555, 9555)
+            r[0] |= read_bits_i32(9u); // rw[8:0]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(9u); // gw[8:0]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(9u); // bw[8:0]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(5u); // rx[4:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(5u); // gx[4:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gx[3:0]
+            b[1] |= read_bits_i32(5u); // bx[4:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(5u); // ry[4:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(5u); // rz[4:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 5u;
+        }
+        // Mode 7
+        case 18u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (8666, 8555, 8555)
+            r[0] |= read_bits_i32(8u); // rw[7:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(8u); // gw[7:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(8u); // bw[7:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(6u); // rx[5:0]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(5u); // gx[4:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(5u); // bx[4:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(6u); // ry[5:0]
+            r[3] |= read_bits_i32(6u); // rz[5:0]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 6u;
+        }
+        // Mode 8
+        case 22u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (8555, 8666, 8555)
+            r[0] |= read_bits_i32(8u); // rw[7:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(8u); // gw[7:0]
+            g[2] |= read_bit_i32() << 5u; // gy[5]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(8u); // bw[7:0]
+            g[3] |= read_bit_i32() << 5u; // gz[5]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(5u); // rx[4:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(6u); // gx[5:0]
+            g[3] |= read_bits_i32(4u); // zx[3:0]
+            b[1] |= read_bits_i32(5u); // bx[4:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(5u); // ry[4:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(5u); // rz[4:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 7u;
+        }
+        // Mode 9
+        case 26u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (8555, 8555, 8666)
+            r[0] |= read_bits_i32(8u); // rw[7:0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(8u); // gw[7:0]
+            b[2] |= read_bit_i32() << 5u; // by[5]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(8u); // bw[7:0]
+            b[3] |= read_bit_i32() << 5u; // bz[5]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(5u); // bw[4:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(5u); // gx[4:0]
+            b[3] |= read_bit_i32(); // bz[0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(6u); // bx[5:0]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(5u); // ry[4:0]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            r[3] |= read_bits_i32(5u); // rz[4:0]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 8u;
+        }
+        // Mode 10
+        case 30u: {
+            // Partition indices: 46 bits
+            // Partition: 5 bits
+            // Color Endpoints: 72 bits (6666, 6666, 6666)
+            r[0] |= read_bits_i32(6u); // rw[5:0]
+            g[3] |= read_bit_i32() << 4u; // gz[4]
+            b[3] |= read_bit_i32(); // bz[0]
+            b[3] |= read_bit_i32() << 1u; // bz[1]
+            b[2] |= read_bit_i32() << 4u; // by[4]
+            g[0] |= read_bits_i32(6u); // gw[5:0]
+            g[2] |= read_bit_i32() << 5u; // gy[5]
+            b[2] |= read_bit_i32() << 5u; // by[5]
+            b[3] |= read_bit_i32() << 2u; // bz[2]
+            g[2] |= read_bit_i32() << 4u; // gy[4]
+            b[0] |= read_bits_i32(6u); // bw[5:0]
+            g[3] |= read_bit_i32() << 5u; // gz[5]
+            b[3] |= read_bit_i32() << 3u; // bz[3]
+            b[3] |= read_bit_i32() << 5u; // bz[5]
+            b[3] |= read_bit_i32() << 4u; // bz[4]
+            r[1] |= read_bits_i32(6u); // rx[5:0]
+            g[2] |= read_bits_i32(4u); // gy[3:0]
+            g[1] |= read_bits_i32(6u); // gx[5:0]
+            g[3] |= read_bits_i32(4u); // gz[3:0]
+            b[1] |= read_bits_i32(6u); // bx[5:0]
+            b[2] |= read_bits_i32(4u); // by[3:0]
+            r[2] |= read_bits_i32(6u); // ry[5:0]
+            r[3] |= read_bits_i32(6u); // rz[5:0]
+            partition_id = read_bits_i32(5u); // d[4:0]
+            mode = 9u;
+        }
+        // Mode 11
+        case 3u: {
+            // Partition indices: 63 bits
+            // Partition: 0 bits
+            // Color Endpoints: 60 bits (10.10, 10.10, 10.10)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(10u); // rx[9:0]
+            g[1] |= read_bits_i32(10u); // gx[9:0]
+            b[1] |= read_bits_i32(10u); // bx[9:0]
+            mode = 10u;
+        }
+        // Mode 12
+        case 7u: {
+            // Partition indices: 63 bits
+            // Partition: 0 bits
+            // Color Endpoints: 60 bits (11.9, 11.9, 11.9)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(9u); // rx[8:0]
+            r[0] |= read_bit_i32() << 10u; // rw[10]
+            g[1] |= read_bits_i32(9u); // gx[8:0]
+            g[0] |= read_bit_i32() << 10u; // gw[10]
+            b[1] |= read_bits_i32(9u); // bx[8:0]
+            b[0] |= read_bit_i32() << 10u; // bw[10]
+            mode = 11u;
+        }
+        // Mode 13
+        case 11u: {
+            // Partition indices: 63 bits
+            // Partition: 0 bits
+            // Color Endpoints: 60 bits (12.8, 12.8, 12.8)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(8u); // rx[7:0]
+            r[0] |= read_bits_reversed(2u) << 10u; // rx[10:11]
+            g[1] |= read_bits_i32(8u); // gx[7:0]
+            g[0] |= read_bits_reversed(2u) << 10u; // gx[10:11]
+            b[1] |= read_bits_i32(8u); // bx[7:0]
+            b[0] |= read_bits_reversed(2u) << 10u; // bx[10:11]
+            mode = 12u;
+        }
+        // Mode 14
+        case 15u: {
+            // Partition indices: 63 bits
+            // Partition: 0 bits
+            // Color Endpoints: 60 bits (16.4, 16.4, 16.4)
+            r[0] |= read_bits_i32(10u); // rw[9:0]
+            g[0] |= read_bits_i32(10u); // gw[9:0]
+            b[0] |= read_bits_i32(10u); // bw[9:0]
+            r[1] |= read_bits_i32(4u); // rx[3:0]
+            r[0] |= read_bits_reversed(6u) << 10u; // rw[10:15]
+            g[1] |= read_bits_i32(4u); // gx[3:0]
+            g[0] |= read_bits_reversed(6u) << 10u; // gw[10:15]
+            b[1] |= read_bits_i32(4u); // bx[3:0]
+            b[0] |= read_bits_reversed(6u) << 10u; // bw[10:15]
+            mode = 13u;
+        }
+
+        default: {
+            // Modes 10011, 10111, 11011, and 11111 are reserved. Blocks with one of these modes
+            // decode to zeroes in all channels.
+            for (var i = 0u; i < 16u; i++) {
+                texels[i] = vec4<f32>(0.0);
+            }
+            return;
+        }
+    }
+
+    var num_partitions = 1u;
+    if (mode >= 10u) {
+        num_partitions = 0u;
+    }
+
+    let actual_bits0_mode = actual_bits_count[0][mode];
+    if (is_signed) {
+        r[0] = extend_sign(r[0], actual_bits0_mode);
+        g[0] = extend_sign(g[0], actual_bits0_mode);
+        b[0] = extend_sign(b[0], actual_bits0_mode);
+    }
+
+    // Mode 11 (like Mode 10) does not use delta compression,
+    // and instead stores both color endpoints explicitly.
+    if ((mode != 9u && mode != 10u) || is_signed) {
+        for (var i = 1u; i < (num_partitions + 1u) * 2u; i++) {
+            r[i] = extend_sign(r[i], actual_bits_count[1][mode]);
+            g[i] = extend_sign(g[i], actual_bits_count[2][mode]);
+            b[i] = extend_sign(b[i], actual_bits_count[3][mode]);
+        }
+    }
+
+    if (mode != 9u && mode != 10u) {
+        for (var i = 1u; i < (num_partitions + 1u) * 2u; i++) {
+            r[i] = transform_inverse(r[i], r[0], actual_bits0_mode, is_signed);
+            g[i] = transform_inverse(g[i], g[0], actual_bits0_mode, is_signed);
+            b[i] = transform_inverse(b[i], b[0], actual_bits0_mode, is_signed);
+        }
+    }
+
+    for (var i = 0u; i < (num_partitions + 1u) * 2u; i++) {
+        r[i] = unquantize(r[i], actual_bits0_mode, is_signed);
+        g[i] = unquantize(g[i], actual_bits0_mode, is_signed);
+        b[i] = unquantize(b[i], actual_bits0_mode, is_signed);
+    }
+
+    var weight_bits = 3u;
+    var subsets = 0u;
+    var fixups = 0u;
+    if (mode >= 10u) {
+        weight_bits = 4u;
+        fixups = 1u;
+    } else {
+        subsets = get_partition_subsets(u32(partition_id));
+        fixups = get_partition_fixups(u32(partition_id));
+    }
+
+    for (var i = 0u; i < 16u; i++) {
+        // fix-up index is specified with one less bit
+        // The fix-up index for subset 0 is always index 0
+        var index_bits = weight_bits;
+        if (((fixups >> i) & 1u) != 0u) {
+            index_bits -= 1u;
+        }
+
+        let weight = get_weight(read_bits_i32(index_bits), weight_bits);
+        let ep_i = ((subsets >> (2u * i)) & 1u) * 2u;
+
+        texels[i] = vec4<f32>(
+            finish_unquantize(interpolate(r[ep_i], r[ep_i + 1u], weight), is_signed),
+            finish_unquantize(interpolate(g[ep_i], g[ep_i + 1u], weight), is_signed),
+            finish_unquantize(interpolate(b[ep_i], b[ep_i + 1u], weight), is_signed),
+            0.0,
+        );
+    }
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc6h(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    for (var i = 0u; i < 4u; i++) {
+        block[i] = block_buffer[offset + i];
+    }
+    bit_position = 0u;
+
+    decode_block(uniforms.is_signed != 0u);
+    store_block(block_id);
+}
@@ -0,0 +1,357 @@
+// Port of the BC7 block decoder of "bcdec.h - v0.98" (https://github.com/iOrange/bcdec).
+//
+// bcdec is free and unencumbered software released into the public domain.
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    blocks_offset: u32,
+    is_signed: u32,
+}
+
+@group(0) @binding(0) var<storage, read> block_buffer: array<u32>;
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+
+// Decoded texels of the current block in row-major order.
+var<private> texels: array<vec4<f32>, 16>;
+
+// Bits of the current block and the position of the next bit to read.
+var<private> block: array<u32, 4>;
+var<private> bit_position: u32;
+
+fn is_outside(block_id: vec2<u32>) -> bool {
+    return block_id.x * 4u >= uniforms.width || block_id.y * 4u >= uniforms.height;
+}
+
+// Returns the offset of the block in u32 words inside the block buffer.
+fn block_offset(block_id: vec2<u32>, block_words: u32) -> u32 {
+    let blocks_x = (uniforms.width + 3u) / 4u;
+    return uniforms.blocks_offset + (block_id.y * blocks_x + block_id.x) * block_words;
+}
+
+// Writes all texels of the current block, which lie inside the image.
+fn store_block(block_id: vec2<u32>) {
+    for (var y = 0u; y < 4u; y++) {
+        for (var x = 0u; x < 4u; x++) {
+            let coordinates = block_id * 4u + vec2<u32>(x, y);
+            if (coordinates.x < uniforms.width && coordinates.y < uniforms.height) {
+                store_texel(coordinates, texels[y * 4u + x]);
+            }
+        }
+    }
+}
+
+fn read_bits(num_bits: u32) -> u32 {
+    let word = bit_position >> 5u;
+    let shift = bit_position & 31u;
+    bit_position += num_bits;
+
+    if (word >= 4u) {
+        return 0u;
+    }
+
+    var bits = block[word] >> shift;
+    if (shift + num_bits > 32u && word < 3u) {
+        bits |= block[word + 1u] << (32u - shift);
+    }
+    return bits & ((1u << num_bits) - 1u);
+}
+
+// Returns the subset of every texel with 2 bits per texel. The first 64 partitions are the
+// two-subset partitions, the last 64 partitions the three-subset partitions.
+fn get_partition_subsets(partition_id: u32) -> u32 {
+    const partition_table = array<u32, 128>(
+        0x50505050u, 0x40404040u, 0x54545454u, 0x54505040u, 0x50404000u, 0x55545450u, 0x55545040u, 0x54504000u,
+        0x50400000u, 0x55555450u, 0x55544000u, 0x54400000u, 0x55555440u, 0x55550000u, 0x55555500u, 0x55000000u,
+        0x55150100u, 0x00004054u, 0x15010000u, 0x00405054u, 0x00004050u, 0x15050100u, 0x05010000u, 0x40505054u,
+        0x00404050u, 0x05010100u, 0x14141414u, 0x05141450u, 0x01155440u, 0x00555500u, 0x15014054u, 0x05414150u,
+        0x44444444u, 0x55005500u, 0x11441144u, 0x05055050u, 0x05500550u, 0x11114444u, 0x41144114u, 0x44111144u,
+        0x15055054u, 0x01055040u, 0x05041050u, 0x05455150u, 0x14414114u, 0x50050550u, 0x41411414u, 0x00141400u,
+        0x00041504u, 0x00105410u, 0x10541000u, 0x04150400u, 0x50410514u, 0x41051450u, 0x05415014u, 0x14054150u,
+        0x41050514u, 0x41505014u, 0x40011554u, 0x54150140u, 0x50505500u, 0x00555050u, 0x15151010u, 0x54540404u,
+        0xaa685050u, 0x6a5a5040u, 0x5a5a4200u, 0x5450a0a8u, 0xa5a50000u, 0xa0a05050u, 0x5555a0a0u, 0x5a5a5050u,
+        0xaa550000u, 0xaa555500u, 0xaaaa5500u, 0x90909090u, 0x94949494u, 0xa4a4a4a4u, 0xa9a59450u, 0x2a0a4250u,
+        0xa5945040u, 0x0a425054u, 0xa5a5a500u, 0x55a0a0a0u, 0xa8a85454u, 0x6a6a4040u, 0xa4a45000u, 0x1a1a0500u,
+        0x0050a4a4u, 0xaaa59090u, 0x14696914u, 0x69691400u, 0xa08585a0u, 0xaa821414u, 0x50a4a450u, 0x6a5a0200u,
+        0xa9a58000u, 0x5090a0a8u, 0xa8a09050u, 0x24242424u, 0x00aa5500u, 0x24924924u, 0x24499224u, 0x50a50a50u,
+        0x500aa550u, 0xaaaa4444u, 0x66660000u, 0xa5a0a5a0u, 0x50a050a0u, 0x69286928u, 0x44aaaa44u, 0x66666600u,
+        0xaa444444u, 0x54a854a8u, 0x95809580u, 0x96969600u, 0xa85454a8u, 0x80959580u, 0xaa141414u, 0x96960000u,
+        0xaaaa1414u, 0xa05050a0u, 0xa0a5a5a0u, 0x96000000u, 0x40804080u, 0xa9a8a9a8u, 0xaaaaaa44u, 0x2a4a5254u,
+    );
+    return partition_table[partition_id];
+}
+
+// Returns the mask of the texels, whose index is stored with one less bit.
+fn get_partition_fixups(partition_id: u32) -> u32 {
+    const fixup_table = array<u32, 128>(
+        0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u,
+        0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u,
+        0x00008001u, 0x00000005u, 0x00000101u, 0x00000005u, 0x00000005u, 0x00000101u, 0x00000101u, 0x00008001u,
+        0x00000005u, 0x00000101u, 0x00000005u, 0x00000005u, 0x00000101u, 0x00000101u, 0x00000005u, 0x00000005u,
+        0x00008001u, 0x00008001u, 0x00000041u, 0x00000101u, 0x00000005u, 0x00000101u, 0x00008001u, 0x00008001u,
+        0x00000005u, 0x00000101u, 0x00000005u, 0x00000005u, 0x00000005u, 0x00008001u, 0x00008001u, 0x00000041u,
+        0x00000041u, 0x00000005u, 0x00000041u, 0x00000101u, 0x00008001u, 0x00008001u, 0x00000005u, 0x00000005u,
+        0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00008001u, 0x00000005u, 0x00000005u, 0x00008001u,
+        0x00008009u, 0x00000109u, 0x00008101u, 0x00008009u, 0x00008101u, 0x00008009u, 0x00008009u, 0x00008101u,
+        0x00008101u, 0x00008101u, 0x00008041u, 0x00008041u, 0x00008041u, 0x00008021u, 0x00008009u, 0x00000109u,
+        0x00008009u, 0x00000109u, 0x00008101u, 0x00008009u, 0x00008009u, 0x00000109u, 0x00008041u, 0x00000501u,
+        0x00000029u, 0x00008101u, 0x00000141u, 0x00000441u, 0x00008101u, 0x00008021u, 0x00008401u, 0x00008101u,
+        0x00008101u, 0x00008009u, 0x00008009u, 0x00000421u, 0x00000441u, 0x00000501u, 0x00000301u, 0x00008401u,
+        0x00008041u, 0x00008009u, 0x00008101u, 0x00008021u, 0x00008009u, 0x00008041u, 0x00008041u, 0x00008101u,
+        0x00008009u, 0x00008009u, 0x00008021u, 0x00008021u, 0x00008021u, 0x00008101u, 0x00008021u, 0x00008401u,
+        0x00008021u, 0x00008401u, 0x00008101u, 0x0000a001u, 0x00008009u, 0x00009001u, 0x00008009u, 0x00000109u,
+    );
+    return fixup_table[partition_id];
+}
+
+fn get_weight(index: i32, weight_bits: u32) -> i32 {
+    switch (weight_bits) {
+        case 2u: {
+            const table = array<i32, 4>(0, 21, 43, 64);
+            return table[index];
+        }
+        case 3u: {
+            const table = array<i32, 8>(0, 9, 18, 27, 37, 46, 55, 64);
+            return table[index];
+        }
+        default: {
+            const table = array<i32, 16>(0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64);
+            return table[index];
+        }
+    }
+}
+
+fn interpolate(a: i32, b: i32, index: i32, weight_bits: u32) -> i32 {
+    let weight = get_weight(index, weight_bits);
+    return (a * (64 - weight) + b * weight + 32) >> 6u;
+}
+
+fn decode_block() {
+    const actual_bits_count = array<array<u32, 8>, 2>(
+        array<u32, 8>(4u, 6u, 5u, 7u, 5u, 7u, 7u, 5u), // RGBA
+        array<u32, 8>(0u, 0u, 0u, 0u, 6u, 8u, 7u, 5u), // Alpha
+    );
+
+    const mode_has_p_bits = 0xCBu;
+
+    // Find mode
+    var mode = 0u;
+    while (mode < 8u && read_bits(1u) == 0u) {
+        mode++;
+    }
+
+    // Unexpected mode, clear the block (transparent black)
+    if (mode >= 8u) {
+        for (var i = 0u; i < 16u; i++) {
+            texels[i] = vec4<f32>(0.0);
+        }
+        return;
+    }
+
+    var partition_id = 0u;
+    var num_partitions = 1u;
+    var rotation = 0u;
+    var index_selection_bit = 0u;
+
+    if (mode == 0u || mode == 1u || mode == 2u || mode == 3u || mode == 7u) {
+        if (mode == 0u || mode == 2u) {
+            num_partitions = 3u;
+        } else {
+            num_partitions = 2u;
+        }
+
+        if (mode == 0u) {
+            partition_id = read_bits(4u);
+        } else {
+            partition_id = read_bits(6u);
+        }
+    }
+
+    let num_endpoints = num_partitions * 2u;
+
+    if (mode == 4u || mode == 5u) {
+        rotation = read_bits(2u);
+        if (mode == 4u) {
+            index_selection_bit = read_bits(1u);
+        }
+    }
+
+    // Extract endpoints
+    var endpoints: array<array<i32, 4>, 6>;
+
+    // RGB
+    for (var i = 0u; i < 3u; i++) {
+        for (var j = 0u; j < num_endpoints; j++) {
+            endpoints[j][i] = i32(read_bits(actual_bits_count[0][mode]));
+        }
+    }
+
+    // Alpha (if any)
+    if (actual_bits_count[1][mode] > 0u) {
+        for (var j = 0u; j < num_endpoints; j++) {
+            endpoints[j][3] = i32(read_bits(actual_bits_count[1][mode]));
+        }
+    }
+
+    // Handle modes that have P-bits
+    if (mode == 0u || mode == 1u || mode == 3u || mode == 6u || mode == 7u) {
+        // Component-wise left-shift
+        for (var j = 0u; j < num_endpoints; j++) {
+            for (var k = 0u; k < 4u; k++) {
+                endpoints[j][k] <<= 1u;
+            }
+        }
+
+        if (mode == 1u) {
+            // P-bit is shared
+            let i = i32(read_bits(1u));
+            let j = i32(read_bits(1u));
+
+            // RGB component-wise insert pbits
+            for (var k = 0u; k < 3u; k++) {
+                endpoints[0][k] |= i;
+                endpoints[1][k] |= i;
+                endpoints[2][k] |= j;
+                endpoints[3][k] |= j;
+            }
+        } else if ((mode_has_p_bits & (1u << mode)) != 0u) {
+            // Unique P-bit per endpoint
+            for (var j = 0u; j < num_endpoints; j++) {
+                let p_bit = i32(read_bits(1u));
+                for (var k = 0u; k < 4u; k++) {
+                    endpoints[j][k] |= p_bit;
+                }
+            }
+        }
+    }
+
+    // Component-wise precision adjustment
+    let p_bit_count = (mode_has_p_bits >> mode) & 1u;
+    for (var i = 0u; i < num_endpoints; i++) {
+        // Left shift endpoint components so that their MSB lies in bit 7 and replicate each
+        // component's MSB into the LSBs revealed by the left-shift operation
+        let color_bits = actual_bits_count[0][mode] + p_bit_count;
+        for (var k = 0u; k < 3u; k++) {
+            endpoints[i][k] <<= 8u - color_bits;
+            endpoints[i][k] |= endpoints[i][k] >> color_bits;
+        }
+
+        let alpha_bits = actual_bits_count[1][mode] + p_bit_count;
+        endpoints[i][3] <<= 8u - alpha_bits;
+        endpoints[i][3] |= endpoints[i][3] >> alpha_bits;
+    }
+
+    // If this mode does not explicitly define the alpha component, set alpha to 255 (1.0)
+    if (actual_bits_count[1][mode] == 0u) {
+        for (var j = 0u; j < num_endpoints; j++) {
+            endpoints[j][3] = 0xFF;
+        }
+    }
+
+    // Determine weights tables
+    var index_bits = 2u;
+    if (mode == 0u || mode == 1u) {
+        index_bits = 3u;
+    } else if (mode == 6u) {
+        index_bits = 4u;
+    }
+
+    var index_bits2 = 0u;
+    if (mode == 4u) {
+        index_bits2 = 3u;
+    } else if (mode == 5u) {
+        index_bits2 = 2u;
+    }
+
+    var subsets = 0u;
+    var fixups = 1u;
+    if (num_partitions > 1u) {
+        subsets = get_partition_subsets((num_partitions - 2u) * 64u + partition_id);
+        fixups = get_partition_fixups((num_partitions - 2u) * 64u + partition_id);
+    }
+
+    // Collect indices in two passes
+    // Pass #1: collecting color indices
+    var indices: array<i32, 16>;
+    for (var i = 0u; i < 16u; i++) {
+        // Fix-up index is specified with one less bit
+        // The fix-up index for subset 0 is always index 0
+        var bits = index_bits;
+        if (((fixups >> i) & 1u) != 0u) {
+            bits -= 1u;
+        }
+        indices[i] = i32(read_bits(bits));
+    }
+
+    // Pass #2: reading alpha indices (if any) and interpolating & rotating
+    for (var i = 0u; i < 16u; i++) {
+        let subset = (subsets >> (2u * i)) & 0x03u;
+        let e0 = endpoints[subset * 2u];
+        let e1 = endpoints[subset * 2u + 1u];
+
+        var color_index = indices[i];
+        var color_bits = index_bits;
+        var alpha_index = indices[i];
+        var alpha_bits = index_bits;
+
+        if (index_bits2 != 0u) {
+            var bits = index_bits2;
+            if (i == 0u) {
+                bits -= 1u;
+            }
+            let index2 = i32(read_bits(bits));
+
+            if (index_selection_bit == 0u) {
+                alpha_index = index2;
+                alpha_bits = index_bits2;
+            } else {
+                color_index = index2;
+                color_bits = index_bits2;
+            }
+        }
+
+        var color = vec4<i32>(
+            interpolate(e0[0], e1[0], color_index, color_bits),
+            interpolate(e0[1], e1[1], color_index, color_bits),
+            interpolate(e0[2], e1[2], color_index, color_bits),
+            interpolate(e0[3], e1[3], alpha_index, alpha_bits),
+        );
+
+        // Handle rotation
+        switch (rotation) {
+            // 01 – Block format is Scalar(R) Vector(AGB) - swap A and R
+            case 1u: {
+                color = color.agbr;
+            }
+            // 10 – Block format is Scalar(G) Vector(RAB) - swap A and G
+            case 2u: {
+                color = color.rabg;
+            }
+            // 11 - Block format is Scalar(B) Vector(RGA) - swap A and B
+            case 3u: {
+                color = color.rgab;
+            }
+            default: {}
+        }
+
+        texels[i] = vec4<f32>(color) / 255.0;
+    }
+}
+
+@compute
+@workgroup_size(8, 8)
+fn decode_bc7(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let block_id = global_id.xy;
+    if (is_outside(block_id)) {
+        return;
+    }
+
+    let offset = block_offset(block_id, 4u);
+    for (var i = 0u; i < 4u; i++) {
+        block[i] = block_buffer[offset + i];
+    }
+    bit_position = 0u;
+
+    decode_block();
+    store_block(block_id);
+}
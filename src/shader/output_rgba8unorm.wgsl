@@ -0,0 +1,7 @@
+
+// Destination texture of the decoded texels.
+@group(0) @binding(1) var output_texture: texture_storage_2d<rgba8unorm, write>;
+
+fn store_texel(coordinates: vec2<u32>, value: vec4<f32>) {
+    textureStore(output_texture, coordinates, value);
+}
@@ -0,0 +1,7 @@
+
+// Source texture of tasks that compress a single texture layer.
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+
+fn load_texel(coordinates: vec2<u32>) -> vec4<f32> {
+    return textureLoad(source_texture, coordinates, 0);
+}
@@ -0,0 +1,7 @@
+
+// Source texture of tasks that compress a range of array layers. The layers are dispatched along the z axis.
+@group(0) @binding(0) var source_texture: texture_2d_array<f32>;
+
+fn load_texel(coordinates: vec2<u32>) -> vec4<f32> {
+    return textureLoad(source_texture, coordinates, uniforms.base_array_layer + layer_index, 0);
+}
@@ -0,0 +1,224 @@
+use block_compression::{
+    analysis::{
+        analyze_rgba16, analyze_rgba8, try_analyze_rgba8, AlphaContent, ChannelContent,
+        FormatPriority, Reason,
+    },
+    decode::decompress_blocks_as_rgba8,
+    encode::compress_rgba8,
+    half::f16,
+    metrics::{compare_rgba8, stored_channels},
+    CompressionVariant, Error,
+};
+
+use self::common::{hemisphere_normals, read_image};
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn opaque_color_image() {
+    let (width, height, rgba_data) = read_image("tests/images/marble.png");
+
+    let quality = analyze_rgba8(
+        &rgba_data,
+        width,
+        height,
+        width * 4,
+        FormatPriority::Quality,
+    );
+    assert_eq!(quality.alpha, AlphaContent::Opaque);
+    assert_eq!(quality.channels, ChannelContent::Color);
+    assert!(matches!(quality.variant, CompressionVariant::BC7(..)));
+    assert!(!stored_channels(quality.variant)[3]);
+    assert_eq!(
+        quality.reasons,
+        [
+            Reason::OpaqueAlpha,
+            Reason::Priority(FormatPriority::Quality)
+        ]
+    );
+
+    let size = analyze_rgba8(&rgba_data, width, height, width * 4, FormatPriority::Size);
+    assert!(matches!(size.variant, CompressionVariant::BC1(..)));
+    assert!(!stored_channels(size.variant)[3]);
+}
+
+#[test]
+fn grayscale_mask() {
+    let (width, height, mut rgba_data) = read_image("tests/images/marble.png");
+    for pixel in rgba_data.chunks_exact_mut(4) {
+        pixel[1] = pixel[0];
+        pixel[2] = pixel[0];
+    }
+
+    let analysis = analyze_rgba8(
+        &rgba_data,
+        width,
+        height,
+        width * 4,
+        FormatPriority::Quality,
+    );
+    assert_eq!(analysis.channels, ChannelContent::Grayscale);
+    assert!(matches!(analysis.variant, CompressionVariant::BC4));
+    assert!(analysis.reasons.contains(&Reason::Grayscale));
+
+    // The recommendation feeds straight into the encoder and keeps the mask.
+    let mut blocks = vec![0; analysis.variant.blocks_byte_size(width, height)];
+    compress_rgba8(
+        analysis.variant,
+        &rgba_data,
+        &mut blocks,
+        width,
+        height,
+        width * 4,
+    );
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(analysis.variant, width, height, &blocks, &mut decoded);
+    let channels = stored_channels(analysis.variant);
+    let metrics = compare_rgba8(&rgba_data, &decoded, width, height, channels);
+    assert!(metrics.psnr() > 35.0);
+}
+
+#[test]
+fn normal_map_and_two_channels() {
+    let size = 32;
+    let mut rgba_data = hemisphere_normals(size, 1.0);
+
+    let analysis = analyze_rgba8(&rgba_data, size, size, size * 4, FormatPriority::Quality);
+    assert_eq!(analysis.channels, ChannelContent::NormalMap);
+    assert!(matches!(analysis.variant, CompressionVariant::BC5));
+    assert_eq!(analysis.reasons, [Reason::OpaqueAlpha, Reason::NormalMap]);
+
+    for pixel in rgba_data.chunks_exact_mut(4) {
+        pixel[2] = 0;
+        pixel[3] = 64;
+    }
+
+    let analysis = analyze_rgba8(&rgba_data, size, size, size * 4, FormatPriority::Size);
+    assert_eq!(analysis.alpha, AlphaContent::Constant);
+    assert_eq!(analysis.channels, ChannelContent::TwoChannel);
+    assert!(matches!(analysis.variant, CompressionVariant::BC5));
+    assert_eq!(
+        analysis.reasons,
+        [
+            Reason::ConstantAlpha(64.0 / 255.0),
+            Reason::TwoChannel,
+            Reason::AlphaDiscarded
+        ]
+    );
+}
+
+#[test]
+fn constant_alpha_is_discarded() {
+    let (width, height, mut rgba_data) = read_image("tests/images/marble.png");
+    for pixel in rgba_data.chunks_exact_mut(4) {
+        pixel[3] = 0;
+    }
+
+    for priority in [FormatPriority::Quality, FormatPriority::Size] {
+        let analysis = analyze_rgba8(&rgba_data, width, height, width * 4, priority);
+        assert_eq!(analysis.alpha, AlphaContent::Constant);
+        assert!(!stored_channels(analysis.variant)[3]);
+        assert_eq!(
+            analysis.reasons,
+            [
+                Reason::ConstantAlpha(0.0),
+                Reason::Priority(priority),
+                Reason::AlphaDiscarded
+            ]
+        );
+    }
+}
+
+#[test]
+fn cutout_and_smooth_alpha() {
+    let (width, height, mut rgba_data) = read_image("tests/images/marble.png");
+    for (index, pixel) in rgba_data.chunks_exact_mut(4).enumerate() {
+        pixel[3] = if index % 3 == 0 { 0 } else { 255 };
+    }
+
+    let cutout = analyze_rgba8(&rgba_data, width, height, width * 4, FormatPriority::Size);
+    assert_eq!(cutout.alpha, AlphaContent::Cutout);
+    match cutout.variant {
+        CompressionVariant::BC1(settings) => assert_eq!(settings.alpha_threshold(), 128),
+        variant => panic!("unexpected variant {variant:?}"),
+    }
+
+    let quality = analyze_rgba8(
+        &rgba_data,
+        width,
+        height,
+        width * 4,
+        FormatPriority::Quality,
+    );
+    assert!(matches!(quality.variant, CompressionVariant::BC7(..)));
+    assert!(stored_channels(quality.variant)[3]);
+
+    rgba_data[3] = 100;
+    let smooth = analyze_rgba8(&rgba_data, width, height, width * 4, FormatPriority::Size);
+    assert_eq!(smooth.alpha, AlphaContent::Smooth);
+    assert!(matches!(smooth.variant, CompressionVariant::BC3(..)));
+}
+
+#[test]
+fn hdr_images() {
+    let size = 16;
+    let mut rgba_data: Vec<f16> = (0..size * size)
+        .flat_map(|index| {
+            let (x, y) = ((index % size) as f32, (index / size) as f32);
+            [x * 0.5, y * 0.25, 0.5, 0.5].map(f16::from_f32)
+        })
+        .collect();
+
+    let analysis = analyze_rgba16(&rgba_data, size, size, size * 4, FormatPriority::Quality);
+    assert!(analysis.high_dynamic_range);
+    match analysis.variant {
+        CompressionVariant::BC6H(settings) => assert!(!settings.is_signed()),
+        variant => panic!("unexpected variant {variant:?}"),
+    }
+    assert_eq!(
+        analysis.reasons,
+        [
+            Reason::ConstantAlpha(0.5),
+            Reason::HighDynamicRange,
+            Reason::AlphaDiscarded
+        ]
+    );
+
+    rgba_data[0] = f16::from_f32(-1.0);
+    let analysis = analyze_rgba16(&rgba_data, size, size, size * 4, FormatPriority::Quality);
+    match analysis.variant {
+        CompressionVariant::BC6H(settings) => assert!(settings.is_signed()),
+        variant => panic!("unexpected variant {variant:?}"),
+    }
+
+    // Images in the range of 0.0 to 1.0 are analyzed like RGBA8 images.
+    let ldr_data: Vec<f16> = [0.25, 0.25, 0.25, 1.0]
+        .map(f16::from_f32)
+        .repeat((size * size) as usize);
+    let analysis = analyze_rgba16(&ldr_data, size, size, size * 4, FormatPriority::Quality);
+    assert!(!analysis.high_dynamic_range);
+    assert!(matches!(analysis.variant, CompressionVariant::BC4));
+    assert_eq!(analysis.reasons.last(), Some(&Reason::LowDynamicRange));
+}
+
+#[test]
+fn rejects_invalid_input() {
+    let rgba_data = vec![0; 16 * 16 * 4];
+
+    assert_eq!(
+        try_analyze_rgba8(&rgba_data, 16, 16, 60, FormatPriority::Quality).err(),
+        Some(Error::InvalidStride {
+            stride: 60,
+            min_stride: 64
+        })
+    );
+    assert_eq!(
+        try_analyze_rgba8(&rgba_data, 16, 17, 64, FormatPriority::Quality).err(),
+        Some(Error::BufferTooSmall {
+            required: 17 * 64,
+            actual: 16 * 64
+        })
+    );
+}
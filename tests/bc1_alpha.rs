@@ -0,0 +1,85 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8, encode::compress_rgba8, BC123Settings, CompressionVariant,
+};
+
+/// Color gradients with a cutout circle, like the ones of foliage or decal textures.
+fn create_cutout_image(width: u32, height: u32) -> Vec<u8> {
+    (0..width * height)
+        .flat_map(|index| {
+            let x = index % width;
+            let y = index / width;
+            let dx = x as i32 - width as i32 / 2;
+            let dy = y as i32 - height as i32 / 2;
+            let alpha = if dx * dx + dy * dy < 100 { 255 } else { 20 };
+            [(x * 8) as u8, (y * 8) as u8, 96, alpha]
+        })
+        .collect()
+}
+
+fn roundtrip(settings: BC123Settings, width: u32, height: u32, rgba_data: &[u8]) -> Vec<u8> {
+    let variant = CompressionVariant::BC1(settings);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8(variant, rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+
+    decoded
+}
+
+#[test]
+fn bc1_punch_through_alpha() {
+    let (width, height) = (30, 26);
+    let rgba_data = create_cutout_image(width, height);
+    let settings = BC123Settings::new().with_alpha_threshold(128);
+
+    let decoded = roundtrip(settings, width, height, &rgba_data);
+
+    for (decoded, original) in decoded.chunks(4).zip(rgba_data.chunks(4)) {
+        if original[3] < 128 {
+            assert_eq!(decoded, [0, 0, 0, 0]);
+        } else {
+            assert_eq!(decoded[3], 255);
+            for channel in 0..3 {
+                assert!(decoded[channel].abs_diff(original[channel]) <= 24);
+            }
+        }
+    }
+}
+
+#[test]
+fn bc1_opaque_blocks_unchanged() {
+    let (width, height) = (30, 26);
+    let mut rgba_data = create_cutout_image(width, height);
+    for pixel in rgba_data.chunks_mut(4) {
+        pixel[3] = 255;
+    }
+
+    let opaque = roundtrip(BC123Settings::new(), width, height, &rgba_data);
+    let alpha = roundtrip(
+        BC123Settings::new().with_alpha_threshold(128),
+        width,
+        height,
+        &rgba_data,
+    );
+
+    assert_eq!(opaque, alpha);
+}
+
+#[test]
+fn bc1_opaque_ignores_alpha() {
+    let (width, height) = (16, 16);
+    let rgba_data = create_cutout_image(width, height);
+
+    let decoded = roundtrip(BC123Settings::new(), width, height, &rgba_data);
+
+    assert!(decoded.chunks(4).all(|pixel| pixel[3] == 255));
+    assert_eq!(BC123Settings::new().alpha_threshold(), 0);
+    assert_eq!(
+        BC123Settings::new()
+            .with_alpha_threshold(128)
+            .alpha_threshold(),
+        128
+    );
+}
@@ -0,0 +1,67 @@
+use block_compression::{
+    decode::{
+        decode_block_bc6h, decode_block_bc6h_float, decompress_blocks_as_rgba16f,
+        decompress_blocks_as_rgba32f,
+    },
+    encode::compress_rgba16,
+    half::f16,
+    BC6HSettings, CompressionVariant,
+};
+
+const WIDTH: u32 = 8;
+const HEIGHT: u32 = 4;
+const VARIANT: CompressionVariant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+
+/// Compresses a gradient with a different color in every pixel.
+fn compress_gradient() -> Vec<u8> {
+    let rgba_data: Vec<f16> = (0..WIDTH * HEIGHT)
+        .flat_map(|index| {
+            let (x, y) = ((index % WIDTH) as f32, (index / WIDTH) as f32);
+            [
+                f16::from_f32(x * 0.5),
+                f16::from_f32(y * 0.75),
+                f16::from_f32(4.0 - x * 0.25),
+                f16::ONE,
+            ]
+        })
+        .collect();
+
+    let mut blocks = vec![0; VARIANT.blocks_byte_size(WIDTH, HEIGHT)];
+    compress_rgba16(VARIANT, &rgba_data, &mut blocks, WIDTH, HEIGHT, WIDTH * 4);
+    blocks
+}
+
+/// The block decoders write packed RGB pixels, the image decoders must place them into RGBA
+/// pixels with an alpha of 0.
+#[test]
+fn decompress_writes_rgba_pixels() {
+    const PITCH: usize = 12;
+
+    let blocks = compress_gradient();
+
+    let mut rgba16f = vec![f16::ONE; (WIDTH * HEIGHT * 4) as usize];
+    decompress_blocks_as_rgba16f(VARIANT, WIDTH, HEIGHT, &blocks, &mut rgba16f);
+
+    let mut rgba32f = vec![1.0; (WIDTH * HEIGHT * 4) as usize];
+    decompress_blocks_as_rgba32f(VARIANT, WIDTH, HEIGHT, &blocks, &mut rgba32f);
+
+    for (bx, block) in blocks.chunks_exact(16).enumerate() {
+        let mut rgb16f = [f16::ZERO; PITCH * 4];
+        decode_block_bc6h(block, &mut rgb16f, PITCH, false);
+
+        let mut rgb32f = [0.0; PITCH * 4];
+        decode_block_bc6h_float(block, &mut rgb32f, PITCH, false);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let rgb = y * PITCH + x * 3;
+                let rgba = (y * WIDTH as usize + bx * 4 + x) * 4;
+
+                assert_eq!(rgba16f[rgba..rgba + 3], rgb16f[rgb..rgb + 3]);
+                assert_eq!(rgba16f[rgba + 3], f16::ZERO);
+                assert_eq!(rgba32f[rgba..rgba + 3], rgb32f[rgb..rgb + 3]);
+                assert_eq!(rgba32f[rgba + 3], 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use block_compression::{bytemuck::bytes_of, BC6HSettings, BC6HSettingsBuilder, Error};
+
+const PRESETS: [BC6HSettings; 5] = [
+    BC6HSettings::very_fast(),
+    BC6HSettings::fast(),
+    BC6HSettings::basic(),
+    BC6HSettings::slow(),
+    BC6HSettings::very_slow(),
+];
+
+#[test]
+fn builder_keeps_presets() {
+    for preset in PRESETS {
+        let settings = BC6HSettingsBuilder::from(preset).build().unwrap();
+        assert_eq!(bytes_of(&settings), bytes_of(&preset));
+
+        let settings = BC6HSettingsBuilder::from(preset)
+            .with_signed(true)
+            .build()
+            .unwrap();
+        assert_eq!(settings, preset.with_signed(true));
+    }
+
+    assert_eq!(
+        BC6HSettingsBuilder::new().build(),
+        Ok(BC6HSettings::basic())
+    );
+}
+
+#[test]
+fn builder_creates_custom_settings() {
+    let settings = BC6HSettingsBuilder::new()
+        .with_slow_mode(true)
+        .with_fast_skip_threshold(16)
+        .with_refine_iterations_1p(3)
+        .with_refine_iterations_2p(3)
+        .build()
+        .unwrap();
+
+    assert!(PRESETS.iter().all(|preset| *preset != settings));
+    assert!(!settings.is_signed());
+}
+
+#[test]
+fn builder_rejects_invalid_settings() {
+    let invalid_builders = [
+        BC6HSettingsBuilder::new()
+            .with_slow_mode(true)
+            .with_fast_mode(true),
+        BC6HSettingsBuilder::new().with_fast_skip_threshold(33),
+        BC6HSettingsBuilder::new().with_refine_iterations_1p(17),
+        BC6HSettingsBuilder::new().with_refine_iterations_2p(17),
+    ];
+
+    for builder in invalid_builders {
+        assert!(matches!(
+            builder.build(),
+            Err(Error::InvalidSettings { .. })
+        ));
+    }
+}
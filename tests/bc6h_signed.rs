@@ -0,0 +1,120 @@
+use block_compression::{
+    decode::{decode_block_bc6h_float, decompress_blocks_as_rgba32f},
+    encode::compress_rgba16,
+    half::f16,
+    BC6HSettings, CompressionVariant,
+};
+
+const SETTINGS: [BC6HSettings; 3] = [
+    BC6HSettings::very_fast(),
+    BC6HSettings::basic(),
+    BC6HSettings::very_slow(),
+];
+
+/// Smooth HDR gradients, like the ones of a vector field. `map` is applied to every color value.
+fn create_hdr_image(width: u32, height: u32, map: fn(f32) -> f32) -> Vec<f16> {
+    (0..width * height)
+        .flat_map(|index| {
+            let x = (index % width) as f32 / (width - 1) as f32;
+            let y = (index / width) as f32 / (height - 1) as f32;
+            [
+                f16::from_f32(map(x * 8.0 - 4.0)),
+                f16::from_f32(map(2.0 - y * 3.0)),
+                f16::from_f32(map((x - y) * 0.5)),
+                f16::ONE,
+            ]
+        })
+        .collect()
+}
+
+/// Compresses and decompresses the image and returns the decoded pixels.
+fn roundtrip(variant: CompressionVariant, width: u32, height: u32, rgba_data: &[f16]) -> Vec<f32> {
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba16(variant, rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0.0; rgba_data.len()];
+    decompress_blocks_as_rgba32f(variant, width, height, &blocks, &mut decoded);
+
+    // The first block must decode the same as when decoding it directly with the block decoder.
+    let mut block_pixels = [0.0; 48];
+    decode_block_bc6h_float(
+        &blocks[..16],
+        &mut block_pixels,
+        12,
+        variant_is_signed(variant),
+    );
+    assert_eq!(block_pixels[0], decoded[0]);
+    assert_eq!(block_pixels[13], decoded[width as usize * 4 + 1]);
+
+    decoded
+}
+
+fn variant_is_signed(variant: CompressionVariant) -> bool {
+    matches!(variant, CompressionVariant::BC6H(settings) if settings.is_signed())
+}
+
+/// Returns the maximum and mean absolute error of the RGB channels.
+fn errors(decoded: &[f32], original: &[f16]) -> (f32, f32) {
+    let mut max_error = 0.0f32;
+    let mut error_sum = 0.0;
+
+    for (decoded, original) in decoded.chunks(4).zip(original.chunks(4)) {
+        for channel in 0..3 {
+            let error = (decoded[channel] - original[channel].to_f32()).abs();
+            max_error = max_error.max(error);
+            error_sum += error;
+        }
+    }
+
+    (max_error, error_sum / (original.len() / 4 * 3) as f32)
+}
+
+#[test]
+fn bc6h_signed_roundtrip() {
+    let (width, height) = (32, 16);
+    let rgba_data = create_hdr_image(width, height, |value| value);
+
+    for settings in SETTINGS {
+        let variant = CompressionVariant::BC6H(settings.with_signed(true));
+        let decoded = roundtrip(variant, width, height, &rgba_data);
+
+        let (_, mean_error) = errors(&decoded, &rgba_data);
+        assert!(mean_error < 0.15, "{settings:?}: mean error {mean_error}");
+
+        for (decoded, original) in decoded.iter().zip(rgba_data.iter()) {
+            let original = original.to_f32();
+            if original.abs() > 0.25 {
+                assert_eq!(decoded.signum(), original.signum());
+            }
+        }
+    }
+}
+
+#[test]
+fn bc6h_signed_matches_unsigned_quality() {
+    let (width, height) = (32, 16);
+    let positive_data = create_hdr_image(width, height, f32::abs);
+    let negative_data = create_hdr_image(width, height, |value| -value.abs());
+
+    for settings in SETTINGS {
+        let unsigned_variant = CompressionVariant::BC6H(settings);
+        let signed_variant = CompressionVariant::BC6H(settings.with_signed(true));
+
+        let unsigned_decoded = roundtrip(unsigned_variant, width, height, &positive_data);
+        let signed_decoded = roundtrip(signed_variant, width, height, &negative_data);
+
+        let (unsigned_max, unsigned_mean) = errors(&unsigned_decoded, &positive_data);
+        let (signed_max, signed_mean) = errors(&signed_decoded, &negative_data);
+
+        assert!(signed_max <= unsigned_max * 1.1);
+        assert!(signed_mean <= unsigned_mean * 1.1);
+    }
+}
+
+#[test]
+fn bc6h_signed_setting() {
+    let settings = BC6HSettings::basic();
+    assert!(!settings.is_signed());
+    assert!(settings.with_signed(true).is_signed());
+    assert!(!settings.with_signed(true).with_signed(false).is_signed());
+}
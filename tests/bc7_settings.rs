@@ -0,0 +1,111 @@
+use block_compression::{
+    encode::compress_rgba8, BC7Settings, BC7SettingsBuilder, CompressionVariant, Error,
+};
+
+/// Returns the mode of every BC7 block, which is the number of leading zero bits.
+fn compressed_modes(settings: BC7Settings) -> Vec<u32> {
+    let image = image::open("tests/images/marble-alpha.png")
+        .unwrap()
+        .to_rgba8();
+    let (width, height) = (64, 64);
+
+    let variant = CompressionVariant::BC7(settings);
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8(
+        variant,
+        image.as_raw(),
+        &mut blocks,
+        width,
+        height,
+        image.width() * 4,
+    );
+
+    blocks
+        .chunks(16)
+        .map(|block| block[0].trailing_zeros())
+        .collect()
+}
+
+#[test]
+fn builder_enables_single_modes() {
+    for mode in [1, 6] {
+        let settings = BC7SettingsBuilder::new()
+            .with_modes(&[mode])
+            .build()
+            .unwrap();
+
+        assert!(compressed_modes(settings)
+            .iter()
+            .all(|&block_mode| block_mode == mode as u32));
+    }
+
+    let settings = BC7SettingsBuilder::new()
+        .with_alpha(true)
+        .with_modes(&[5])
+        .with_channel_rotation(false)
+        .build()
+        .unwrap();
+
+    assert!(compressed_modes(settings)
+        .iter()
+        .all(|&block_mode| block_mode == 5));
+
+    let settings = BC7SettingsBuilder::new()
+        .with_alpha(true)
+        .with_modes(&[7])
+        .build()
+        .unwrap();
+
+    assert!(compressed_modes(settings)
+        .iter()
+        .all(|&block_mode| block_mode == 7));
+}
+
+#[test]
+fn builder_keeps_presets() {
+    for preset in [
+        BC7Settings::opaque_ultra_fast(),
+        BC7Settings::opaque_very_fast(),
+        BC7Settings::opaque_fast(),
+        BC7Settings::opaque_basic(),
+        BC7Settings::opaque_slow(),
+        BC7Settings::alpha_ultrafast(),
+        BC7Settings::alpha_very_fast(),
+        BC7Settings::alpha_fast(),
+        BC7Settings::alpha_basic(),
+        BC7Settings::alpha_slow(),
+    ] {
+        assert_eq!(BC7SettingsBuilder::from(preset).build(), Ok(preset));
+    }
+}
+
+#[test]
+fn builder_rejects_invalid_settings() {
+    let invalid_builders = [
+        BC7SettingsBuilder::new(),
+        BC7SettingsBuilder::new().with_modes(&[8]),
+        BC7SettingsBuilder::new()
+            .with_modes(&[6])
+            .with_refine_iterations(6, 17),
+        BC7SettingsBuilder::new()
+            .with_modes(&[1])
+            .with_fast_skip_threshold(1, 0),
+        BC7SettingsBuilder::new()
+            .with_modes(&[3])
+            .with_fast_skip_threshold(3, 65),
+        BC7SettingsBuilder::new()
+            .with_modes(&[6])
+            .with_fast_skip_threshold(6, 8),
+        BC7SettingsBuilder::new().with_modes(&[7]),
+        BC7SettingsBuilder::new()
+            .with_modes(&[6, 7])
+            .with_alpha(false),
+    ];
+
+    for builder in invalid_builders {
+        assert!(matches!(
+            builder.build(),
+            Err(Error::InvalidSettings { .. })
+        ));
+    }
+}
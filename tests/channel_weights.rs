@@ -0,0 +1,100 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8, encode::compress_rgba8, BC123Settings, BC7Settings,
+    CompressionVariant, Error,
+};
+
+use self::common::read_image;
+
+#[allow(dead_code)]
+mod common;
+
+const PERCEPTUAL_WEIGHTS: [f64; 3] = [0.299, 0.587, 0.114];
+
+/// The mean of the squared color errors, weighted by the perceptual channel weights.
+fn perceptual_error(variant: CompressionVariant, file_path: &str) -> f64 {
+    let (width, height, rgba_data) = read_image(file_path);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8(variant, &rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+
+    let error: f64 = decoded
+        .chunks(4)
+        .zip(rgba_data.chunks(4))
+        .map(|(decoded, original)| {
+            (0..3)
+                .map(|channel| {
+                    let difference = decoded[channel] as f64 - original[channel] as f64;
+                    PERCEPTUAL_WEIGHTS[channel] * difference * difference
+                })
+                .sum::<f64>()
+        })
+        .sum();
+
+    error / (width * height) as f64
+}
+
+#[test]
+fn perceptual_weights_reduce_perceptual_error() {
+    for file_path in ["tests/images/brick.png", "tests/images/marble.png"] {
+        let uniform = perceptual_error(CompressionVariant::BC1(BC123Settings::new()), file_path);
+        let perceptual = perceptual_error(
+            CompressionVariant::BC1(BC123Settings::new().with_perceptual_weights()),
+            file_path,
+        );
+        assert!(
+            perceptual < uniform,
+            "BC1 {file_path}: {perceptual} >= {uniform}"
+        );
+
+        let uniform = perceptual_error(
+            CompressionVariant::BC7(BC7Settings::opaque_ultra_fast()),
+            file_path,
+        );
+        let perceptual = perceptual_error(
+            CompressionVariant::BC7(BC7Settings::opaque_ultra_fast().with_perceptual_weights()),
+            file_path,
+        );
+        assert!(
+            perceptual < uniform,
+            "BC7 {file_path}: {perceptual} >= {uniform}"
+        );
+    }
+}
+
+#[test]
+fn channel_weights_are_normalized() {
+    assert_eq!(BC123Settings::new().channel_weights(), [1.0; 3]);
+    assert_eq!(
+        BC123Settings::new().with_channel_weights([2.0, 2.0, 2.0]),
+        BC123Settings::new()
+    );
+    assert_eq!(
+        BC7Settings::alpha_basic()
+            .with_channel_weights([0.5, 1.0, 1.5])
+            .channel_weights(),
+        [0.5, 1.0, 1.5]
+    );
+}
+
+#[test]
+fn invalid_channel_weights() {
+    for channel_weights in [
+        [0.0, 1.0, 1.0],
+        [1.0, -1.0, 1.0],
+        [1.0, 1.0, f32::NAN],
+        [f32::INFINITY, 1.0, 1.0],
+        [f32::MAX, f32::MAX, 1.0],
+    ] {
+        assert!(matches!(
+            BC123Settings::new().try_with_channel_weights(channel_weights),
+            Err(Error::InvalidSettings { .. })
+        ));
+        assert!(matches!(
+            BC7Settings::opaque_basic().try_with_channel_weights(channel_weights),
+            Err(Error::InvalidSettings { .. })
+        ));
+    }
+}
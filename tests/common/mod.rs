@@ -3,7 +3,7 @@ use std::{
     time::Duration,
 };
 
-use block_compression::CompressionVariant;
+use block_compression::{CompressionVariant, GpuBlockCompressor};
 use half::f16;
 use image::ImageReader;
 use pollster::block_on;
@@ -11,10 +11,11 @@ use wgpu::{
     util::{DeviceExt, TextureDataOrder},
     wgt::{Dx12SwapchainKind, Dx12UseFrameLatencyWaitableObject},
     BackendOptions, Backends, Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor,
-    Device, DeviceDescriptor, Dx12BackendOptions, Dx12Compiler, Error, ExperimentalFeatures,
-    Extent3d, Features, ForceShaderModelToken, Instance, InstanceDescriptor, InstanceFlags, Limits,
-    MapMode, MemoryHints, PollType, PowerPreference, Queue, Texture, TextureDescriptor,
-    TextureDimension, TextureFormat, TextureUsages, Trace,
+    ComputePassDescriptor, Device, DeviceDescriptor, Dx12BackendOptions, Dx12Compiler, Error,
+    ExperimentalFeatures, Extent3d, Features, ForceShaderModelToken, Instance, InstanceDescriptor,
+    InstanceFlags, Limits, MapMode, MemoryHints, PollType, PowerPreference, Queue, Texture,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
+    Trace,
 };
 
 #[inline]
@@ -30,6 +31,32 @@ pub fn srgb_to_linear(srgb: u8) -> f64 {
 pub const BRICK_FILE_PATH: &str = "tests/images/brick.png";
 pub const MARBLE_FILE_PATH: &str = "tests/images/marble.png";
 
+/// Reads an image file as tightly packed RGBA8 data and returns its width, height and data.
+pub fn read_image(file_path: &str) -> (u32, u32, Vec<u8>) {
+    let image = ImageReader::open(file_path)
+        .expect("can't open input image")
+        .decode()
+        .expect("can't decode image")
+        .to_rgba8();
+
+    (image.width(), image.height(), image.into_raw())
+}
+
+/// Encodes the normals of a hemisphere as unsigned RGBA8 colors, scaled by `length`. Alpha is
+/// 255.
+pub fn hemisphere_normals(size: u32, length: f32) -> Vec<u8> {
+    (0..size * size)
+        .flat_map(|index| {
+            let x = (index % size) as f32 / (size - 1) as f32 * 1.4 - 0.7;
+            let y = (index / size) as f32 / (size - 1) as f32 * 1.4 - 0.7;
+            let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+            let [r, g, b] =
+                [x, y, z].map(|value| ((value * length * 0.5 + 0.5) * 255.0).round() as u8);
+            [r, g, b, 255]
+        })
+        .collect()
+}
+
 pub fn create_wgpu_resources() -> (Device, Queue) {
     static CACHE: LazyLock<(Device, Queue)> = LazyLock::new(|| {
         let instance = Instance::new(InstanceDescriptor {
@@ -59,7 +86,7 @@ pub fn create_wgpu_resources() -> (Device, Queue) {
 
         let (device, queue) = block_on(adapter.request_device(&DeviceDescriptor {
             label: Some("main device"),
-            required_features: Features::default(),
+            required_features: adapter.features() & Features::TEXTURE_COMPRESSION_BC,
             required_limits: Limits::default(),
             experimental_features: ExperimentalFeatures::disabled(),
             memory_hints: MemoryHints::Performance,
@@ -155,6 +182,76 @@ pub fn read_image_and_create_texture(
     (texture, rgba_image.to_vec())
 }
 
+/// Creates a texture of the given format from tightly packed texel data.
+pub fn create_texture(
+    device: &Device,
+    queue: &Queue,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    data: &[u8],
+) -> Texture {
+    device.create_texture_with_data(
+        queue,
+        &TextureDescriptor {
+            label: Some("source texture"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        data,
+    )
+}
+
+/// Compresses the whole texture with the GPU compressor and returns the compressed blocks.
+pub fn compress_texture(
+    device: &Device,
+    queue: &Queue,
+    variant: CompressionVariant,
+    texture: &Texture,
+) -> Vec<u8> {
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let blocks_size = variant.blocks_byte_size(texture.width(), texture.height());
+    let blocks = create_blocks_buffer(device, blocks_size as u64);
+
+    block_compressor.add_compression_task(
+        variant,
+        &texture.create_view(&TextureViewDescriptor::default()),
+        texture.width(),
+        texture.height(),
+        &blocks,
+        None,
+        None,
+    );
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    queue.submit([encoder.finish()]);
+
+    download_blocks_data(device, queue, blocks)
+}
+
 pub fn create_blocks_buffer(device: &Device, size: u64) -> Buffer {
     device.create_buffer(&BufferDescriptor {
         label: Some("blocks buffer"),
@@ -0,0 +1,215 @@
+use block_compression::{
+    mipmap::mip_level_size, BC123Settings, CompressedSubresource, CompressionVariant, Error,
+    GpuBlockCompressor, TextureCompressionTask,
+};
+use wgpu::{
+    util::{DeviceExt, TextureDataOrder},
+    BufferDescriptor, BufferUsages, CommandEncoder, CommandEncoderDescriptor,
+    ComputePassDescriptor, Extent3d, Origin3d, TexelCopyBufferInfo, TexelCopyBufferLayout,
+    TexelCopyTextureInfo, Texture, TextureAspect, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+use self::common::{create_blocks_buffer, create_wgpu_resources, download_blocks_data};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 256;
+const HEIGHT: u32 = 32;
+const MIP_LEVEL_COUNT: u32 = 4;
+const LAYER_COUNT: u32 = 2;
+
+/// Creates a texture with a noise pattern that differs between all subresources.
+fn create_texture(device: &wgpu::Device, queue: &wgpu::Queue) -> Texture {
+    let mut state = 0x8765_4321u32;
+    let mut data = Vec::new();
+    for _ in 0..LAYER_COUNT {
+        for level in 0..MIP_LEVEL_COUNT {
+            let (width, height) = mip_level_size(WIDTH, HEIGHT, level);
+            data.extend((0..width * height * 4).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            }));
+        }
+    }
+
+    device.create_texture_with_data(
+        queue,
+        &TextureDescriptor {
+            label: Some("source texture"),
+            size: Extent3d {
+                width: WIDTH,
+                height: HEIGHT,
+                depth_or_array_layers: LAYER_COUNT,
+            },
+            mip_level_count: MIP_LEVEL_COUNT,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        &data,
+    )
+}
+
+/// Records the copies of all `subresources` of the BC `texture` back into a tightly packed
+/// buffer with the same layout.
+fn copy_back(
+    device: &wgpu::Device,
+    encoder: &mut CommandEncoder,
+    variant: CompressionVariant,
+    texture: &Texture,
+    subresources: &[CompressedSubresource],
+) -> Vec<(CompressedSubresource, wgpu::Buffer)> {
+    subresources
+        .iter()
+        .map(|subresource| {
+            let block_rows = subresource.height.div_ceil(4);
+            let padded_bytes_per_row = variant
+                .bytes_per_row(subresource.width)
+                .next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+
+            let buffer = device.create_buffer(&BufferDescriptor {
+                label: Some("readback buffer"),
+                size: u64::from(padded_bytes_per_row * block_rows),
+                usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST | BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            });
+
+            encoder.copy_texture_to_buffer(
+                TexelCopyTextureInfo {
+                    texture,
+                    mip_level: subresource.mip_level,
+                    origin: Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: subresource.array_layer,
+                    },
+                    aspect: TextureAspect::All,
+                },
+                TexelCopyBufferInfo {
+                    buffer: &buffer,
+                    layout: TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_bytes_per_row),
+                        rows_per_image: Some(block_rows),
+                    },
+                },
+                Extent3d {
+                    width: subresource.width.div_ceil(4) * 4,
+                    height: block_rows * 4,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            (*subresource, buffer)
+        })
+        .collect()
+}
+
+fn test_compressed_texture(variant: CompressionVariant, blocks_offset: u32) {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let source = create_texture(&device, &queue);
+    let blocks = create_blocks_buffer(&device, 32768);
+    let task = TextureCompressionTask::new(variant, &source, &blocks)
+        .with_array_layers(1..2)
+        .with_blocks_offset(blocks_offset);
+    let subresources = block_compressor.add_texture_compression_task(&task);
+
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some("compressed texture"),
+        size: Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: LAYER_COUNT,
+        },
+        mip_level_count: MIP_LEVEL_COUNT,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: GpuBlockCompressor::texture_format(variant, true),
+        usage: TextureUsages::COPY_DST | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    block_compressor.copy_to_compressed_texture(&mut encoder, &task, &texture);
+    let created_texture = block_compressor.create_compressed_texture(&mut encoder, &task, false);
+    let readback = copy_back(&device, &mut encoder, variant, &texture, &subresources);
+
+    queue.submit([encoder.finish()]);
+
+    assert_eq!(
+        created_texture.format(),
+        texture.format().remove_srgb_suffix()
+    );
+    assert_eq!(created_texture.size(), texture.size());
+    assert_eq!(created_texture.mip_level_count(), MIP_LEVEL_COUNT);
+
+    let blocks_data = download_blocks_data(&device, &queue, blocks);
+    for (subresource, buffer) in readback {
+        let bytes_per_row = variant.bytes_per_row(subresource.width) as usize;
+        let padded_bytes_per_row = bytes_per_row.next_multiple_of(256);
+        let texture_data = download_blocks_data(&device, &queue, buffer);
+
+        let expected = &blocks_data[subresource.offset..subresource.offset + subresource.size];
+        let actual: Vec<u8> = texture_data
+            .chunks(padded_bytes_per_row)
+            .flat_map(|row| &row[..bytes_per_row])
+            .copied()
+            .collect();
+
+        assert!(
+            actual == expected,
+            "mip level {} differs",
+            subresource.mip_level
+        );
+    }
+}
+
+#[test]
+fn compressed_texture_bc1() {
+    test_compressed_texture(CompressionVariant::BC1(BC123Settings::new()), 0);
+}
+
+#[test]
+fn compressed_texture_bc3_unaligned_offset() {
+    test_compressed_texture(CompressionVariant::BC3(BC123Settings::new()), 8);
+}
+
+#[test]
+fn compressed_texture_rejects_other_formats() {
+    let (device, queue) = create_wgpu_resources();
+    let block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let source = create_texture(&device, &queue);
+    let blocks = create_blocks_buffer(&device, 32768);
+    let task = TextureCompressionTask::new(CompressionVariant::BC4, &source, &blocks);
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    assert_eq!(
+        block_compressor.try_copy_to_compressed_texture(&mut encoder, &task, &source),
+        Err(Error::IncompatibleTextureFormat)
+    );
+}
@@ -0,0 +1,134 @@
+use block_compression::{
+    decode::{decompress_blocks_as_rgba16f, decompress_blocks_as_rgba8},
+    encode::{compress_rgba16_with_metrics, compress_rgba8_with_metrics},
+    half::f16,
+    metrics::compare_rgba16f,
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant,
+};
+
+use self::common::read_image;
+
+#[allow(dead_code)]
+mod common;
+
+/// Compresses the top left `width` x `height` pixels of the image and compares the reported
+/// metrics against the error of the decoded image.
+fn compare_with_decoded(variant: CompressionVariant, channels: usize, width: u32, height: u32) {
+    let (image_width, _, rgba_data) = read_image("tests/images/brick-alpha.png");
+    let stride = image_width * 4;
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    let metrics =
+        compress_rgba8_with_metrics(variant, &rgba_data, &mut blocks, width, height, stride);
+
+    let mut decoded = vec![0; (width * height * 4) as usize];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+
+    let mut channel_mse = [0.0; 4];
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let original = &rgba_data[y * stride as usize + x * 4..];
+            let decoded = &decoded[(y * width as usize + x) * 4..];
+
+            for channel in 0..channels {
+                let difference = (original[channel] as f64 - decoded[channel] as f64) / 255.0;
+                channel_mse[channel] += difference * difference;
+            }
+        }
+    }
+    channel_mse
+        .iter_mut()
+        .for_each(|mse| *mse /= (width * height) as f64);
+
+    let image = metrics.image();
+    assert_eq!(image.pixel_count(), width * height);
+    for channel in 0..4 {
+        assert!((image.channel_mse()[channel] - channel_mse[channel]).abs() < 1e-12);
+    }
+
+    let expected_mse = channel_mse.iter().sum::<f64>() / channels as f64;
+    assert!((image.mse() - expected_mse).abs() < 1e-12);
+    assert!((image.psnr() - -10.0 * expected_mse.log10()).abs() < 1e-9);
+
+    assert_eq!(metrics.blocks_per_row(), width.div_ceil(4));
+    assert_eq!(
+        metrics.blocks().len() as u32,
+        width.div_ceil(4) * height.div_ceil(4)
+    );
+
+    let (x, y, worst) = metrics.worst_block().unwrap();
+    assert_eq!(metrics.block(x, y), Some(worst));
+    assert!(worst.mse() >= image.mse());
+}
+
+#[test]
+fn metrics_bc1() {
+    compare_with_decoded(CompressionVariant::BC1(BC123Settings::new()), 3, 128, 128);
+}
+
+#[test]
+fn metrics_bc3_edge_blocks() {
+    compare_with_decoded(CompressionVariant::BC3(BC123Settings::new()), 4, 61, 37);
+}
+
+#[test]
+fn metrics_bc4() {
+    compare_with_decoded(CompressionVariant::BC4, 1, 64, 64);
+}
+
+#[test]
+fn metrics_bc7() {
+    compare_with_decoded(
+        CompressionVariant::BC7(BC7Settings::alpha_ultrafast()),
+        4,
+        64,
+        64,
+    );
+}
+
+#[test]
+fn metrics_lossless_block() {
+    let rgba_data = [255, 0, 0, 255].repeat(16);
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(4, 4)];
+    let metrics = compress_rgba8_with_metrics(variant, &rgba_data, &mut blocks, 4, 4, 16);
+
+    assert_eq!(metrics.image().mse(), 0.0);
+    assert_eq!(metrics.image().psnr(), f64::INFINITY);
+    assert_eq!(metrics.image().channels(), [true, true, true, false]);
+}
+
+#[test]
+fn metrics_bc6h_edge_blocks() {
+    let (width, height) = (22, 14);
+    let rgba_data: Vec<f16> = (0..width * height)
+        .flat_map(|index| {
+            let (x, y) = ((index % width) as f32, (index / width) as f32);
+            [x * 0.75, y * 0.5, (x + y) * 0.1, 1.0].map(f16::from_f32)
+        })
+        .collect();
+    let variant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    let metrics =
+        compress_rgba16_with_metrics(variant, &rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![f16::ZERO; rgba_data.len()];
+    decompress_blocks_as_rgba16f(variant, width, height, &blocks, &mut decoded);
+    let expected = compare_rgba16f(
+        &rgba_data,
+        &decoded,
+        width,
+        height,
+        [true, true, true, false],
+    );
+
+    let image = metrics.image();
+    assert_eq!(image.pixel_count(), width * height);
+    assert_eq!(image.channels(), [true, true, true, false]);
+    assert_eq!(image.peak(), expected.error().peak());
+    assert!((image.mse() - expected.error().mse()).abs() < 1e-9);
+    assert!((image.psnr() - expected.psnr()).abs() < 1e-6);
+    assert_eq!(metrics.blocks().len(), 6 * 4);
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "dds")]
+
+use block_compression::{
+    dds::{read_dds, try_write_dds, write_dds, DdsDescriptor},
+    decode::decompress_blocks_as_rgba8,
+    encode::compress_rgba8,
+    BC123Settings, CompressionVariant, Error,
+};
+
+#[test]
+fn dds_cubemap_array_roundtrip() {
+    let variant = CompressionVariant::BC3(BC123Settings::new());
+    let descriptor = DdsDescriptor::new(variant, 20, 12)
+        .with_mip_level_count(3)
+        .with_array_layer_count(2)
+        .with_cubemap(true)
+        .with_srgb(true);
+
+    assert_eq!(descriptor.layer_count(), 12);
+
+    let blocks: Vec<u8> = (0..descriptor.blocks_byte_size())
+        .map(|index| index as u8)
+        .collect();
+
+    let file = write_dds(&descriptor, &blocks);
+    let image = read_dds(&file).expect("can't read DDS file");
+
+    assert_eq!(image.descriptor, descriptor);
+    assert_eq!(image.blocks, blocks);
+
+    let layer_size = descriptor.layer_byte_size();
+    let levels = descriptor.mip_levels();
+    assert_eq!(
+        image.level_data(7, 2),
+        &blocks[layer_size * 7 + levels[2].offset..][..levels[2].size]
+    );
+}
+
+#[test]
+fn dds_blocks_decompress() {
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let (width, height) = (8, 8);
+    let rgba_data = [40, 120, 200, 255].repeat(width * height);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width as u32, height as u32)];
+    compress_rgba8(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        width as u32,
+        height as u32,
+        width as u32 * 4,
+    );
+
+    let descriptor = DdsDescriptor::new(variant, width as u32, height as u32);
+    let image = read_dds(&write_dds(&descriptor, &blocks)).expect("can't read DDS file");
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(
+        image.descriptor.variant,
+        image.descriptor.width,
+        image.descriptor.height,
+        image.level_data(0, 0),
+        &mut decoded,
+    );
+
+    for (decoded, original) in decoded.iter().zip(rgba_data.iter()) {
+        assert!(decoded.abs_diff(*original) <= 4);
+    }
+
+    assert_eq!(
+        try_write_dds(&descriptor, &blocks[1..]),
+        Err(Error::BufferSizeMismatch {
+            expected: blocks.len(),
+            actual: blocks.len() - 1,
+        })
+    );
+    assert_eq!(read_dds(&blocks), Err(Error::InvalidContainer));
+}
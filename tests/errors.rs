@@ -0,0 +1,214 @@
+use block_compression::{
+    decode::{
+        try_decompress_blocks_as_rgba16f, try_decompress_blocks_as_rgba32f,
+        try_decompress_blocks_as_rgba8, try_decompress_blocks_as_rgba8_snorm,
+    },
+    encode::{try_compress_rgba16, try_compress_rgba8},
+    half::f16,
+    BC123Settings, BC6HSettings, CompressionVariant, Error,
+};
+
+const BC1: CompressionVariant = CompressionVariant::BC1(BC123Settings::new());
+const BC6H: CompressionVariant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+
+#[test]
+fn compress_rgba8_errors() {
+    let rgba_data = vec![0; 8 * 8 * 4];
+    let mut blocks = vec![0; BC1.blocks_byte_size(8, 8)];
+
+    assert_eq!(
+        try_compress_rgba8(BC1, &rgba_data, &mut blocks, 8, 8, 31),
+        Err(Error::InvalidStride {
+            stride: 31,
+            min_stride: 32
+        })
+    );
+    assert_eq!(
+        try_compress_rgba8(BC1, &rgba_data, &mut blocks, 8, 9, 32),
+        Err(Error::BufferTooSmall {
+            required: 9 * 32,
+            actual: 8 * 32
+        })
+    );
+    assert_eq!(
+        try_compress_rgba8(BC1, &rgba_data, &mut blocks[..31], 8, 8, 32),
+        Err(Error::BufferTooSmall {
+            required: 32,
+            actual: 31
+        })
+    );
+    assert_eq!(
+        try_compress_rgba8(BC1, &rgba_data, &mut blocks, 1 << 30, 1, u32::MAX),
+        Err(Error::InvalidDimensions {
+            width: 1 << 30,
+            height: 1
+        })
+    );
+    assert_eq!(
+        try_compress_rgba8(
+            CompressionVariant::BC4Snorm,
+            &rgba_data,
+            &mut blocks,
+            8,
+            8,
+            32
+        ),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_compress_rgba8(BC1, &rgba_data, &mut blocks, 8, 8, 32),
+        Ok(())
+    );
+}
+
+#[test]
+fn compress_rgba16_errors() {
+    let rgba_data = vec![f16::ZERO; 8 * 8 * 4];
+    let mut blocks = vec![0; BC6H.blocks_byte_size(8, 8)];
+
+    assert_eq!(
+        try_compress_rgba16(BC6H, &rgba_data, &mut blocks, 8, 8, 31),
+        Err(Error::InvalidStride {
+            stride: 31,
+            min_stride: 32
+        })
+    );
+    assert_eq!(
+        try_compress_rgba16(BC6H, &rgba_data, &mut blocks, 8, 9, 32),
+        Err(Error::BufferTooSmall {
+            required: 9 * 32,
+            actual: 8 * 32
+        })
+    );
+    assert_eq!(
+        try_compress_rgba16(BC6H, &rgba_data, &mut blocks[..63], 8, 8, 32),
+        Err(Error::BufferTooSmall {
+            required: 64,
+            actual: 63
+        })
+    );
+    assert_eq!(
+        try_compress_rgba16(BC6H, &rgba_data, &mut blocks, 1 << 30, 1, u32::MAX),
+        Err(Error::InvalidDimensions {
+            width: 1 << 30,
+            height: 1
+        })
+    );
+    assert_eq!(
+        try_compress_rgba16(BC1, &rgba_data, &mut blocks, 8, 8, 32),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_compress_rgba16(BC6H, &rgba_data, &mut blocks, 8, 8, 32),
+        Ok(())
+    );
+}
+
+#[test]
+fn decompress_rgba8_errors() {
+    let blocks = vec![0; BC1.blocks_byte_size(6, 6)];
+    let mut rgba_data = vec![0; 6 * 6 * 4];
+
+    assert_eq!(
+        try_decompress_blocks_as_rgba8(BC1, 6, 6, &blocks[..24], &mut rgba_data),
+        Err(Error::BufferSizeMismatch {
+            expected: 32,
+            actual: 24
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba8(BC1, 6, 6, &blocks, &mut rgba_data[..100]),
+        Err(Error::BufferSizeMismatch {
+            expected: 144,
+            actual: 100
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba8(BC1, 6, 6, &blocks, &mut rgba_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn decompress_rgba8_snorm_errors() {
+    let variant = CompressionVariant::BC4Snorm;
+    let blocks = vec![0; variant.blocks_byte_size(6, 6)];
+    let mut rgba_data = vec![0; 6 * 6 * 4];
+
+    assert_eq!(
+        try_decompress_blocks_as_rgba8_snorm(BC1, 6, 6, &blocks, &mut rgba_data),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba8_snorm(variant, 6, 6, &blocks[..24], &mut rgba_data),
+        Err(Error::BufferSizeMismatch {
+            expected: 32,
+            actual: 24
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba8_snorm(variant, 6, 6, &blocks, &mut rgba_data[..100]),
+        Err(Error::BufferSizeMismatch {
+            expected: 144,
+            actual: 100
+        })
+    );
+}
+
+#[test]
+fn decompress_rgba16f_errors() {
+    let blocks = vec![0; BC6H.blocks_byte_size(6, 6)];
+    let mut rgba_data = vec![f16::ZERO; 6 * 6 * 4];
+
+    assert_eq!(
+        try_decompress_blocks_as_rgba16f(BC1, 6, 6, &blocks, &mut rgba_data),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba16f(BC6H, 6, 6, &blocks[..48], &mut rgba_data),
+        Err(Error::BufferSizeMismatch {
+            expected: 64,
+            actual: 48
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba16f(BC6H, 6, 6, &blocks, &mut rgba_data[..100]),
+        Err(Error::BufferSizeMismatch {
+            expected: 144,
+            actual: 100
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba16f(BC6H, 6, 6, &blocks, &mut rgba_data),
+        Ok(())
+    );
+}
+
+#[test]
+fn decompress_rgba32f_errors() {
+    let blocks = vec![0; BC6H.blocks_byte_size(6, 6)];
+    let mut rgba_data = vec![0.0; 6 * 6 * 4];
+
+    assert_eq!(
+        try_decompress_blocks_as_rgba32f(BC1, 6, 6, &blocks[..32], &mut rgba_data),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba32f(BC6H, 6, 6, &blocks[..48], &mut rgba_data),
+        Err(Error::BufferSizeMismatch {
+            expected: 64,
+            actual: 48
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba32f(BC6H, 6, 6, &blocks, &mut rgba_data[..100]),
+        Err(Error::BufferSizeMismatch {
+            expected: 144,
+            actual: 100
+        })
+    );
+    assert_eq!(
+        try_decompress_blocks_as_rgba32f(BC6H, 6, 6, &blocks, &mut rgba_data),
+        Ok(())
+    );
+}
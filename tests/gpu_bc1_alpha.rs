@@ -0,0 +1,58 @@
+use block_compression::{decode::decompress_blocks_as_rgba8, BC123Settings, CompressionVariant};
+use wgpu::TextureFormat;
+
+use self::common::{compress_texture, create_texture, create_wgpu_resources};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 32;
+const HEIGHT: u32 = 28;
+
+/// Color gradients with an opaque circle on a mostly transparent background.
+fn create_cutout_image() -> Vec<u8> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|index| {
+            let x = index % WIDTH;
+            let y = index / WIDTH;
+            let dx = x as i32 - WIDTH as i32 / 2;
+            let dy = y as i32 - HEIGHT as i32 / 2;
+            let alpha = if dx * dx + dy * dy < 100 { 255 } else { 20 };
+            [(x * 8) as u8, (y * 8) as u8, 96, alpha]
+        })
+        .collect()
+}
+
+/// Compresses a cutout image with punch-through alpha on the GPU. Pixels below the threshold
+/// must decode as transparent black and all other pixels as opaque.
+#[test]
+fn gpu_bc1_punch_through_alpha() {
+    let (device, queue) = create_wgpu_resources();
+
+    let rgba_data = create_cutout_image();
+    let texture = create_texture(
+        &device,
+        &queue,
+        WIDTH,
+        HEIGHT,
+        TextureFormat::Rgba8Unorm,
+        &rgba_data,
+    );
+
+    let variant = CompressionVariant::BC1(BC123Settings::new().with_alpha_threshold(128));
+    let blocks = compress_texture(&device, &queue, variant, &texture);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, WIDTH, HEIGHT, &blocks, &mut decoded);
+
+    for (decoded, original) in decoded.chunks(4).zip(rgba_data.chunks(4)) {
+        if original[3] < 128 {
+            assert_eq!(decoded, [0, 0, 0, 0]);
+        } else {
+            assert_eq!(decoded[3], 255);
+            for channel in 0..3 {
+                assert!(decoded[channel].abs_diff(original[channel]) <= 24);
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use block_compression::{
+    decode::decode_block_bc6h_float, half::f16, BC6HSettings, CompressionVariant,
+};
+use wgpu::TextureFormat;
+
+use self::common::{compress_texture, create_texture, create_wgpu_resources};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 32;
+const HEIGHT: u32 = 16;
+
+/// Smooth HDR gradients from -4.0 to 4.0, which cover negative values in every channel.
+fn create_signed_hdr_image() -> Vec<f16> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|index| {
+            let x = (index % WIDTH) as f32 / (WIDTH - 1) as f32;
+            let y = (index / WIDTH) as f32 / (HEIGHT - 1) as f32;
+            [
+                f16::from_f32(x * 8.0 - 4.0),
+                f16::from_f32(2.0 - y * 3.0),
+                f16::from_f32((x - y) * 0.5),
+                f16::ONE,
+            ]
+        })
+        .collect()
+}
+
+/// Compresses negative HDR values with signed BC6H on the GPU and decodes them with the signed
+/// CPU block decoder.
+#[test]
+fn gpu_bc6h_signed_roundtrip() {
+    const PITCH: usize = 12;
+
+    let (device, queue) = create_wgpu_resources();
+
+    let rgba_data = create_signed_hdr_image();
+    let texel_data: Vec<u8> = rgba_data
+        .iter()
+        .flat_map(|value| value.to_ne_bytes())
+        .collect();
+    let texture = create_texture(
+        &device,
+        &queue,
+        WIDTH,
+        HEIGHT,
+        TextureFormat::Rgba16Float,
+        &texel_data,
+    );
+
+    let variant = CompressionVariant::BC6H(BC6HSettings::basic().with_signed(true));
+    let blocks = compress_texture(&device, &queue, variant, &texture);
+
+    let blocks_x = WIDTH as usize / 4;
+    let mut error_sum = 0.0;
+
+    for (index, block) in blocks.chunks_exact(16).enumerate() {
+        let (bx, by) = (index % blocks_x, index / blocks_x);
+
+        let mut decoded = [0.0; PITCH * 4];
+        decode_block_bc6h_float(block, &mut decoded, PITCH, true);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let pixel = ((by * 4 + y) * WIDTH as usize + bx * 4 + x) * 4;
+
+                for channel in 0..3 {
+                    let original = rgba_data[pixel + channel].to_f32();
+                    let decoded = decoded[y * PITCH + x * 3 + channel];
+
+                    if original.abs() > 0.25 {
+                        assert_eq!(
+                            decoded.signum(),
+                            original.signum(),
+                            "block {bx}x{by}: {decoded} doesn't have the sign of {original}"
+                        );
+                    }
+                    error_sum += (decoded - original).abs();
+                }
+            }
+        }
+    }
+
+    let mean_error = error_sum / (WIDTH * HEIGHT * 3) as f32;
+    assert!(mean_error < 0.15, "mean error {mean_error}");
+}
@@ -0,0 +1,165 @@
+use block_compression::{
+    bytemuck::cast_slice, decode::decompress_blocks_as_rgba8, BC123Settings, BC7Settings,
+    CompressionVariant, Error, GpuBlockCompressor, TextureCompressionTask,
+};
+use wgpu::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor, Texture,
+};
+
+use self::common::{
+    create_blocks_buffer, create_wgpu_resources, download_blocks_data,
+    read_image_and_create_texture, BRICK_FILE_PATH,
+};
+
+#[allow(dead_code)]
+mod common;
+
+/// Compresses the texture on the GPU and returns the compressed blocks and the error of every
+/// block.
+fn compress_with_errors(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    variant: CompressionVariant,
+    texture: &Texture,
+) -> (Vec<u8>, Vec<f32>) {
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let blocks_size = variant.blocks_byte_size(texture.width(), texture.height());
+    let block_count = texture.width().div_ceil(4) * texture.height().div_ceil(4);
+    let blocks = create_blocks_buffer(device, blocks_size as u64);
+    let errors = create_blocks_buffer(device, u64::from(block_count * 4));
+
+    block_compressor.add_texture_compression_task(
+        &TextureCompressionTask::new(variant, texture, &blocks).with_error_buffer(&errors),
+    );
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    queue.submit([encoder.finish()]);
+
+    let blocks = download_blocks_data(device, queue, blocks);
+    let errors = download_blocks_data(device, queue, errors);
+
+    (blocks, cast_slice(&errors).to_vec())
+}
+
+/// Decodes the blocks and returns the squared error of the given channels of every block.
+fn decoded_block_errors(
+    variant: CompressionVariant,
+    width: u32,
+    height: u32,
+    blocks: &[u8],
+    rgba_data: &[u8],
+    channels: &[usize],
+) -> Vec<f64> {
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, blocks, &mut decoded);
+
+    let blocks_per_row = width.div_ceil(4) as usize;
+    let mut errors = vec![0.0; blocks_per_row * height.div_ceil(4) as usize];
+
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let offset = (y * width as usize + x) * 4;
+            errors[y / 4 * blocks_per_row + x / 4] += channels
+                .iter()
+                .map(|&channel| {
+                    let difference =
+                        decoded[offset + channel] as f64 - rgba_data[offset + channel] as f64;
+                    difference * difference
+                })
+                .sum::<f64>();
+        }
+    }
+
+    errors
+}
+
+#[test]
+fn gpu_block_errors_match_decoded_blocks() {
+    let (device, queue) = create_wgpu_resources();
+
+    for (variant, channels) in [
+        (
+            CompressionVariant::BC1(BC123Settings::new()),
+            [0, 1, 2].as_slice(),
+        ),
+        (
+            CompressionVariant::BC3(BC123Settings::new()),
+            [0, 1, 2, 3].as_slice(),
+        ),
+        (CompressionVariant::BC4, [0].as_slice()),
+        (CompressionVariant::BC5, [0, 1].as_slice()),
+        (
+            CompressionVariant::BC7(BC7Settings::opaque_basic()),
+            [0, 1, 2].as_slice(),
+        ),
+    ] {
+        let (texture, rgba_data) =
+            read_image_and_create_texture(&device, &queue, BRICK_FILE_PATH, variant);
+        let (width, height) = (texture.width(), texture.height());
+
+        let (blocks, errors) = compress_with_errors(&device, &queue, variant, &texture);
+        let expected = decoded_block_errors(variant, width, height, &blocks, &rgba_data, channels);
+
+        assert_eq!(errors.len(), expected.len());
+
+        // The decoder rounds the interpolated palette entries to integers, the encoders don't.
+        // With a rounding difference of at most 1 per value, the errors differ by at most
+        // `2 * sqrt(expected * values) + values`.
+        let values = (16 * channels.len()) as f64;
+        for (block, (&error, &expected)) in errors.iter().zip(expected.iter()).enumerate() {
+            let error = error as f64;
+            let tolerance = 2.0 * (expected * values).sqrt() + values + expected * 1e-3;
+            assert!(
+                (error - expected).abs() <= tolerance,
+                "{variant:?} block {block}: {error} != {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn gpu_block_errors_validate_error_buffer() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC4;
+    let (texture, _) = read_image_and_create_texture(&device, &queue, BRICK_FILE_PATH, variant);
+    let blocks_size = variant.blocks_byte_size(texture.width(), texture.height());
+    let blocks = create_blocks_buffer(&device, blocks_size as u64);
+
+    let too_small = create_blocks_buffer(&device, (blocks_size / 2 - 4) as u64);
+    let result = block_compressor.try_add_texture_compression_task(
+        &TextureCompressionTask::new(variant, &texture, &blocks).with_error_buffer(&too_small),
+    );
+    assert_eq!(
+        result,
+        Err(Error::BufferTooSmall {
+            required: blocks_size / 2,
+            actual: blocks_size / 2 - 4,
+        })
+    );
+
+    let not_storage = device.create_buffer(&BufferDescriptor {
+        label: Some("error buffer"),
+        size: blocks_size as u64,
+        usage: BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let result = block_compressor.try_add_texture_compression_task(
+        &TextureCompressionTask::new(variant, &texture, &blocks).with_error_buffer(&not_storage),
+    );
+    assert_eq!(result, Err(Error::NotStorageBuffer));
+}
@@ -0,0 +1,70 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8, BC123Settings, BC7Settings, CompressionVariant,
+};
+
+use self::common::{
+    compress_texture, create_wgpu_resources, read_image_and_create_texture, BRICK_FILE_PATH,
+    MARBLE_FILE_PATH,
+};
+
+#[allow(dead_code)]
+mod common;
+
+const PERCEPTUAL_WEIGHTS: [f64; 3] = [0.299, 0.587, 0.114];
+
+/// The mean of the squared color errors of the GPU compressor, weighted by the perceptual
+/// channel weights.
+fn perceptual_error(variant: CompressionVariant, file_path: &str) -> f64 {
+    let (device, queue) = create_wgpu_resources();
+
+    let (texture, rgba_data) = read_image_and_create_texture(&device, &queue, file_path, variant);
+    let (width, height) = (texture.width(), texture.height());
+
+    let blocks = compress_texture(&device, &queue, variant, &texture);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+
+    let error: f64 = decoded
+        .chunks(4)
+        .zip(rgba_data.chunks(4))
+        .map(|(decoded, original)| {
+            (0..3)
+                .map(|channel| {
+                    let difference = decoded[channel] as f64 - original[channel] as f64;
+                    PERCEPTUAL_WEIGHTS[channel] * difference * difference
+                })
+                .sum::<f64>()
+        })
+        .sum();
+
+    error / (width * height) as f64
+}
+
+#[test]
+fn gpu_perceptual_weights_reduce_perceptual_error() {
+    for file_path in [BRICK_FILE_PATH, MARBLE_FILE_PATH] {
+        let uniform = perceptual_error(CompressionVariant::BC1(BC123Settings::new()), file_path);
+        let perceptual = perceptual_error(
+            CompressionVariant::BC1(BC123Settings::new().with_perceptual_weights()),
+            file_path,
+        );
+        assert!(
+            perceptual < uniform,
+            "BC1 {file_path}: {perceptual} >= {uniform}"
+        );
+
+        let uniform = perceptual_error(
+            CompressionVariant::BC7(BC7Settings::opaque_ultra_fast()),
+            file_path,
+        );
+        let perceptual = perceptual_error(
+            CompressionVariant::BC7(BC7Settings::opaque_ultra_fast().with_perceptual_weights()),
+            file_path,
+        );
+        assert!(
+            perceptual < uniform,
+            "BC7 {file_path}: {perceptual} >= {uniform}"
+        );
+    }
+}
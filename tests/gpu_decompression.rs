@@ -0,0 +1,254 @@
+use block_compression::{
+    decode::{
+        decompress_blocks_as_rgba16f, decompress_blocks_as_rgba8, decompress_blocks_as_rgba8_snorm,
+    },
+    half::f16,
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant, Error, GpuBlockDecompressor,
+};
+use wgpu::{
+    util::{BufferInitDescriptor, DeviceExt},
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor, Extent3d,
+    Origin3d, TexelCopyBufferInfo, TexelCopyBufferLayout, TexelCopyTextureInfo, Texture,
+    TextureAspect, TextureDescriptor, TextureDimension, TextureUsages, TextureViewDescriptor,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+use self::common::{create_blocks_buffer, create_wgpu_resources, download_blocks_data};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 70;
+const HEIGHT: u32 = 45;
+const BLOCKS_OFFSET: u32 = 48;
+
+/// Creates random block data, so that all modes of BC6H and BC7 are decoded.
+fn create_blocks_data(variant: CompressionVariant) -> Vec<u8> {
+    let mut state = 0x2468_ace1u32;
+    (0..variant.blocks_byte_size(WIDTH, HEIGHT))
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+fn create_output_texture(device: &wgpu::Device, variant: CompressionVariant) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("decompressed texture"),
+        size: Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: GpuBlockDecompressor::output_format(variant),
+        usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+/// Decompresses `blocks_data` on the GPU and returns the texels as tightly packed bytes.
+fn decompress_on_gpu(variant: CompressionVariant, blocks_data: &[u8]) -> Vec<u8> {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_decompressor = GpuBlockDecompressor::new(device.clone(), queue.clone());
+
+    let mut contents = vec![0; BLOCKS_OFFSET as usize];
+    contents.extend_from_slice(blocks_data);
+    let blocks = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("blocks buffer"),
+        contents: &contents,
+        usage: BufferUsages::STORAGE,
+    });
+
+    let texture = create_output_texture(&device, variant);
+    block_decompressor.add_decompression_task(
+        variant,
+        &texture.create_view(&TextureViewDescriptor::default()),
+        WIDTH,
+        HEIGHT,
+        &blocks,
+        Some(BLOCKS_OFFSET),
+    );
+
+    let bytes_per_row = WIDTH * texture.format().block_copy_size(None).unwrap();
+    let padded_bytes_per_row = bytes_per_row.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+    let output = device.create_buffer(&BufferDescriptor {
+        label: Some("output buffer"),
+        size: u64::from(padded_bytes_per_row * HEIGHT),
+        usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_decompressor.decompress(&mut pass);
+    }
+
+    encoder.copy_texture_to_buffer(
+        TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        TexelCopyBufferInfo {
+            buffer: &output,
+            layout: TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(HEIGHT),
+            },
+        },
+        texture.size(),
+    );
+
+    queue.submit([encoder.finish()]);
+
+    download_blocks_data(&device, &queue, output)
+        .chunks(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..bytes_per_row as usize])
+        .copied()
+        .collect()
+}
+
+fn test_rgba8(variant: CompressionVariant) {
+    let blocks_data = create_blocks_data(variant);
+
+    let mut expected = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    decompress_blocks_as_rgba8(variant, WIDTH, HEIGHT, &blocks_data, &mut expected);
+
+    assert!(decompress_on_gpu(variant, &blocks_data) == expected);
+}
+
+fn test_rgba8_snorm(variant: CompressionVariant) {
+    let blocks_data = create_blocks_data(variant);
+
+    let mut expected = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    decompress_blocks_as_rgba8_snorm(variant, WIDTH, HEIGHT, &blocks_data, &mut expected);
+    let expected: Vec<u8> = expected.iter().map(|&value| value as u8).collect();
+
+    assert!(decompress_on_gpu(variant, &blocks_data) == expected);
+}
+
+fn test_rgba16f(variant: CompressionVariant) {
+    let blocks_data = create_blocks_data(variant);
+
+    let mut expected = vec![f16::ZERO; (WIDTH * HEIGHT * 4) as usize];
+    decompress_blocks_as_rgba16f(variant, WIDTH, HEIGHT, &blocks_data, &mut expected);
+    let expected: Vec<u8> = expected
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    assert!(decompress_on_gpu(variant, &blocks_data) == expected);
+}
+
+#[test]
+fn gpu_decompression_bc1() {
+    test_rgba8(CompressionVariant::BC1(BC123Settings::new()));
+}
+
+#[test]
+fn gpu_decompression_bc2() {
+    test_rgba8(CompressionVariant::BC2(BC123Settings::new()));
+}
+
+#[test]
+fn gpu_decompression_bc3() {
+    test_rgba8(CompressionVariant::BC3(BC123Settings::new()));
+}
+
+#[test]
+fn gpu_decompression_bc4() {
+    test_rgba8(CompressionVariant::BC4);
+}
+
+#[test]
+fn gpu_decompression_bc5() {
+    test_rgba8(CompressionVariant::BC5);
+}
+
+#[test]
+fn gpu_decompression_bc4_snorm() {
+    test_rgba8_snorm(CompressionVariant::BC4Snorm);
+}
+
+#[test]
+fn gpu_decompression_bc5_snorm() {
+    test_rgba8_snorm(CompressionVariant::BC5Snorm);
+}
+
+#[test]
+fn gpu_decompression_bc6h() {
+    test_rgba16f(CompressionVariant::BC6H(BC6HSettings::basic()));
+}
+
+#[test]
+fn gpu_decompression_bc6h_signed() {
+    test_rgba16f(CompressionVariant::BC6H(
+        BC6HSettings::basic().with_signed(true),
+    ));
+}
+
+#[test]
+fn gpu_decompression_bc7() {
+    test_rgba8(CompressionVariant::BC7(BC7Settings::alpha_basic()));
+}
+
+#[test]
+fn gpu_decompression_rejects_invalid_textures() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_decompressor = GpuBlockDecompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let blocks = create_blocks_buffer(&device, 4096);
+    let texture = create_output_texture(&device, CompressionVariant::BC4Snorm);
+    let view = texture.create_view(&TextureViewDescriptor::default());
+
+    assert_eq!(
+        block_decompressor.try_add_decompression_task(variant, &view, WIDTH, HEIGHT, &blocks, None),
+        Err(Error::IncompatibleTextureFormat)
+    );
+
+    let texture = create_output_texture(&device, variant);
+    let view = texture.create_view(&TextureViewDescriptor::default());
+
+    assert_eq!(
+        block_decompressor.try_add_decompression_task(
+            variant,
+            &view,
+            WIDTH + 1,
+            HEIGHT,
+            &blocks,
+            None
+        ),
+        Err(Error::InvalidDimensions {
+            width: WIDTH + 1,
+            height: HEIGHT,
+        })
+    );
+    assert!(matches!(
+        block_decompressor.try_add_decompression_task(
+            variant,
+            &view,
+            WIDTH,
+            HEIGHT,
+            &blocks,
+            Some(3072)
+        ),
+        Err(Error::BufferTooSmall { .. })
+    ));
+}
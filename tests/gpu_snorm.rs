@@ -0,0 +1,73 @@
+use block_compression::{
+    bytemuck::cast_slice, decode::decompress_blocks_as_rgba8_snorm, CompressionVariant,
+};
+use wgpu::TextureFormat;
+
+use self::common::{compress_texture, create_texture, create_wgpu_resources};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 16;
+const HEIGHT: u32 = 8;
+
+/// Smooth signed gradients in the red and green channels, which start at -128 in the red
+/// channel.
+fn create_snorm_image() -> Vec<i8> {
+    (0..WIDTH * HEIGHT)
+        .flat_map(|index| {
+            let x = (index % WIDTH) as i32;
+            let y = (index / WIDTH) as i32;
+            [
+                (-128 + x * 255 / (WIDTH as i32 - 1)) as i8,
+                (100 - y * 28) as i8,
+                0,
+                0,
+            ]
+        })
+        .collect()
+}
+
+/// Compresses signed data on the GPU and returns the original and the decoded pixels.
+fn roundtrip(variant: CompressionVariant) -> (Vec<i8>, Vec<i8>) {
+    let (device, queue) = create_wgpu_resources();
+
+    let rgba_data = create_snorm_image();
+    let texture = create_texture(
+        &device,
+        &queue,
+        WIDTH,
+        HEIGHT,
+        TextureFormat::Rgba8Snorm,
+        cast_slice(&rgba_data),
+    );
+
+    let blocks = compress_texture(&device, &queue, variant, &texture);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8_snorm(variant, WIDTH, HEIGHT, &blocks, &mut decoded);
+
+    (rgba_data, decoded)
+}
+
+#[test]
+fn gpu_bc4_snorm() {
+    let (rgba_data, decoded) = roundtrip(CompressionVariant::BC4Snorm);
+
+    for (decoded, original) in decoded.chunks(4).zip(rgba_data.chunks(4)) {
+        // -128 and -127 both map to -1.0, so -128 is clamped to -127.
+        let original_red = i8::max(original[0], -127);
+        assert!(decoded[0].abs_diff(original_red) <= 8);
+    }
+}
+
+#[test]
+fn gpu_bc5_snorm() {
+    let (rgba_data, decoded) = roundtrip(CompressionVariant::BC5Snorm);
+
+    for (decoded, original) in decoded.chunks(4).zip(rgba_data.chunks(4)) {
+        let original_red = i8::max(original[0], -127);
+        assert!(decoded[0].abs_diff(original_red) <= 8);
+        assert!(decoded[1].abs_diff(original[1]) <= 8);
+    }
+}
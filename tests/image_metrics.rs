@@ -0,0 +1,144 @@
+use block_compression::{
+    decode::{decompress_blocks_as_rgba32f, decompress_blocks_as_rgba8},
+    encode::{compress_rgba16, compress_rgba8, compress_rgba8_with_metrics},
+    half::f16,
+    metrics::{
+        compare_rgba16f, compare_rgba32f, compare_rgba8, stored_channels, try_compare_rgba8,
+    },
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant, Error,
+};
+
+use self::common::read_image;
+
+#[allow(dead_code)]
+mod common;
+
+fn compress_and_decode(
+    variant: CompressionVariant,
+    file_path: &str,
+) -> (u32, u32, Vec<u8>, Vec<u8>) {
+    let (width, height, rgba_data) = read_image(file_path);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8(variant, &rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+
+    (width, height, rgba_data, decoded)
+}
+
+#[test]
+fn identical_images() {
+    let (width, height, rgba_data) = read_image("tests/images/brick.png");
+    let metrics = compare_rgba8(&rgba_data, &rgba_data, width, height, [true; 4]);
+
+    assert_eq!(metrics.rmse(), 0.0);
+    assert_eq!(metrics.psnr(), f64::INFINITY);
+    assert_eq!(metrics.perceptual_psnr(), f64::INFINITY);
+    assert!((metrics.ssim() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn constant_offset() {
+    let original = vec![100u8; 13 * 9 * 4];
+    let decoded = vec![110u8; 13 * 9 * 4];
+    let metrics = compare_rgba8(&original, &decoded, 13, 9, [true, true, true, false]);
+
+    let difference = 10.0 / 255.0;
+    assert!((metrics.rmse() - difference).abs() < 1e-12);
+    assert!((metrics.psnr() - -20.0 * f64::log10(difference)).abs() < 1e-9);
+    assert!((metrics.perceptual_mse() - difference * difference).abs() < 1e-12);
+    assert_eq!(metrics.error().channel_mse()[3], 0.0);
+    assert_eq!(metrics.channel_ssim()[3], 1.0);
+}
+
+#[test]
+fn better_settings_score_higher() {
+    let file_path = "tests/images/marble.png";
+
+    let bc1 = CompressionVariant::BC1(BC123Settings::new());
+    let (width, height, original, decoded) = compress_and_decode(bc1, file_path);
+    let bc1_metrics = compare_rgba8(&original, &decoded, width, height, stored_channels(bc1));
+
+    let bc7 = CompressionVariant::BC7(BC7Settings::opaque_ultra_fast());
+    let (width, height, original, decoded) = compress_and_decode(bc7, file_path);
+    let bc7_metrics = compare_rgba8(&original, &decoded, width, height, stored_channels(bc7));
+
+    assert!(bc7_metrics.psnr() > bc1_metrics.psnr());
+    assert!(bc7_metrics.ssim() > bc1_metrics.ssim());
+    assert!(bc7_metrics.perceptual_psnr() > bc1_metrics.perceptual_psnr());
+    assert!(bc1_metrics.ssim() > 0.8 && bc7_metrics.ssim() < 1.0);
+}
+
+#[test]
+fn matches_encoder_metrics() {
+    let (width, height, rgba_data) = read_image("tests/images/brick-alpha.png");
+    let variant = CompressionVariant::BC3(BC123Settings::new());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    let encoder_metrics =
+        compress_rgba8_with_metrics(variant, &rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+    let metrics = compare_rgba8(
+        &rgba_data,
+        &decoded,
+        width,
+        height,
+        stored_channels(variant),
+    );
+
+    assert!((metrics.error().mse() - encoder_metrics.image().mse()).abs() < 1e-12);
+}
+
+#[test]
+fn hdr_images() {
+    let (width, height) = (16, 16);
+    let original: Vec<f32> = (0..width * height)
+        .flat_map(|index| {
+            let (x, y) = ((index % width) as f32, (index / width) as f32);
+            [x * 1.5, y * 0.5, (x + y) * 0.25, 1.0]
+        })
+        .collect();
+    let original_f16: Vec<f16> = original.iter().copied().map(f16::from_f32).collect();
+
+    let variant = CompressionVariant::BC6H(BC6HSettings::basic());
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba16(
+        variant,
+        &original_f16,
+        &mut blocks,
+        width,
+        height,
+        width * 4,
+    );
+
+    let mut decoded = vec![0.0; original.len()];
+    decompress_blocks_as_rgba32f(variant, width, height, &blocks, &mut decoded);
+    let decoded_f16: Vec<f16> = decoded.iter().copied().map(f16::from_f32).collect();
+
+    let channels = stored_channels(variant);
+    let metrics = compare_rgba32f(&original, &decoded, width, height, channels);
+    let metrics_f16 = compare_rgba16f(&original_f16, &decoded_f16, width, height, channels);
+
+    assert_eq!(metrics.error().peak(), 22.5);
+    assert!(metrics.psnr() > 30.0 && metrics.psnr().is_finite());
+    assert!(metrics.ssim() > 0.9);
+    assert!((metrics.psnr() - metrics_f16.psnr()).abs() < 0.5);
+}
+
+#[test]
+fn rejects_mismatched_images() {
+    let original = vec![0u8; 16 * 16 * 4];
+    let decoded = vec![0u8; 16 * 15 * 4];
+
+    assert_eq!(
+        try_compare_rgba8(&original, &decoded, 16, 16, [true; 4]),
+        Err(Error::BufferSizeMismatch {
+            expected: 16 * 16 * 4,
+            actual: 16 * 15 * 4,
+        })
+    );
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "ktx2")]
+
+use block_compression::{
+    decode::decompress_blocks_as_rgba8,
+    encode::compress_rgba8,
+    ktx2::{read_ktx2, try_write_ktx2, write_ktx2, Ktx2Descriptor},
+    BC123Settings, CompressionVariant, Error,
+};
+
+#[test]
+fn ktx2_cubemap_array_roundtrip() {
+    let variant = CompressionVariant::BC5;
+    let descriptor = Ktx2Descriptor::new(variant, 20, 12)
+        .with_mip_level_count(3)
+        .with_array_layer_count(2)
+        .with_cubemap(true);
+
+    assert_eq!(descriptor.layer_count(), 12);
+
+    let blocks: Vec<u8> = (0..descriptor.blocks_byte_size())
+        .map(|index| index as u8)
+        .collect();
+
+    let file = write_ktx2(&descriptor, &blocks);
+    assert!(file.starts_with(b"\xABKTX 20\xBB\r\n\x1A\n"));
+
+    let image = read_ktx2(&file).expect("can't read KTX2 file");
+
+    assert_eq!(image.descriptor, descriptor);
+    assert_eq!(image.blocks, blocks);
+
+    let layer_size = descriptor.layer_byte_size();
+    let levels = descriptor.mip_levels();
+    assert_eq!(
+        image.level_data(7, 2),
+        &blocks[layer_size * 7 + levels[2].offset..][..levels[2].size]
+    );
+}
+
+#[test]
+fn ktx2_blocks_decompress() {
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let (width, height) = (8, 8);
+    let rgba_data = [40, 120, 200, 255].repeat(width * height);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width as u32, height as u32)];
+    compress_rgba8(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        width as u32,
+        height as u32,
+        width as u32 * 4,
+    );
+
+    let descriptor = Ktx2Descriptor::new(variant, width as u32, height as u32);
+    let image = read_ktx2(&write_ktx2(&descriptor, &blocks)).expect("can't read KTX2 file");
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(
+        image.descriptor.variant,
+        image.descriptor.width,
+        image.descriptor.height,
+        image.level_data(0, 0),
+        &mut decoded,
+    );
+
+    for (decoded, original) in decoded.iter().zip(rgba_data.iter()) {
+        assert!(decoded.abs_diff(*original) <= 4);
+    }
+
+    assert_eq!(
+        try_write_ktx2(&descriptor, &blocks[1..]),
+        Err(Error::BufferSizeMismatch {
+            expected: blocks.len(),
+            actual: blocks.len() - 1,
+        })
+    );
+    assert_eq!(read_ktx2(&blocks), Err(Error::InvalidContainer));
+}
+
+/// Returns the channel type byte (channel id and qualifiers) of every DFD sample of a KTX2 file.
+fn dfd_sample_channel_types(file: &[u8]) -> Vec<u8> {
+    let read_u32 = |offset: usize| u32::from_le_bytes(file[offset..offset + 4].try_into().unwrap());
+
+    // The DFD starts with its total size, followed by the 24 bytes of the basic block header.
+    let dfd_offset = read_u32(48) as usize;
+    let dfd_size = read_u32(dfd_offset) as usize;
+
+    file[dfd_offset + 28..dfd_offset + dfd_size]
+        .chunks_exact(16)
+        .map(|sample| sample[3])
+        .collect()
+}
+
+#[test]
+fn ktx2_alpha_sample_channels() {
+    const LINEAR: u8 = 0x10;
+
+    let bc1 = CompressionVariant::BC1(BC123Settings::new().with_alpha_threshold(128));
+    let bc3 = CompressionVariant::BC3(BC123Settings::new());
+
+    for (variant, channel_types) in [(bc1, vec![1]), (bc3, vec![15, 0])] {
+        let blocks = vec![0; variant.blocks_byte_size(4, 4)];
+
+        let linear = write_ktx2(&Ktx2Descriptor::new(variant, 4, 4), &blocks);
+        assert_eq!(dfd_sample_channel_types(&linear), channel_types);
+
+        // Only the alpha sample is marked as linear in sRGB files.
+        let srgb = write_ktx2(&Ktx2Descriptor::new(variant, 4, 4).with_srgb(true), &blocks);
+        let mut srgb_channel_types = channel_types;
+        srgb_channel_types[0] |= LINEAR;
+        assert_eq!(dfd_sample_channel_types(&srgb), srgb_channel_types);
+    }
+}
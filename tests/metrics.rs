@@ -1,139 +1,34 @@
 use block_compression::{
-    decode::decompress_blocks_as_rgba8, encode::compress_rgba8, BC6HSettings, BC7Settings,
-    CompressionVariant, GpuBlockCompressor,
+    decode::decompress_blocks_as_rgba8,
+    encode::compress_rgba8,
+    metrics::{compare_rgba8, ImageMetrics},
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant, GpuBlockCompressor,
 };
 use wgpu::{CommandEncoderDescriptor, ComputePassDescriptor, TextureViewDescriptor};
 
 use self::common::{
     create_blocks_buffer, create_wgpu_resources, download_blocks_data,
-    read_image_and_create_texture, srgb_to_linear, BRICK_FILE_PATH, MARBLE_FILE_PATH,
+    read_image_and_create_texture, BRICK_FILE_PATH, MARBLE_FILE_PATH,
 };
 
+#[allow(dead_code)]
 mod common;
 
 pub const BRICK_ALPHA_FILE_PATH: &str = "tests/images/brick-alpha.png";
 pub const MARBLE_ALPHA_FILE_PATH: &str = "tests/images/marble-alpha.png";
 pub const BLENDER_FILE_PATH: &str = "tests/images/blender.png";
 
-#[derive(Debug, Clone)]
-pub struct PsnrResult {
-    pub overall_psnr: f64,
-    pub overall_mse: f64,
-    pub channel_results: ChannelResults,
-}
-
-#[derive(Debug, Clone)]
-pub struct ChannelResults {
-    pub red: ChannelMetrics,
-    pub green: ChannelMetrics,
-    pub blue: ChannelMetrics,
-    pub alpha: ChannelMetrics,
-}
-
-#[derive(Debug, Clone)]
-pub struct ChannelMetrics {
-    pub psnr: f64,
-    pub mse: f64,
-}
-
-/// Calculates quality metrics for a given image. The input data and output data must be RGBA data.
-pub fn calculate_image_metrics_rgba8(
-    original: &[u8],
-    compressed: &[u8],
-    width: u32,
-    height: u32,
-    channels: u32,
-) -> PsnrResult {
-    if original.len() != compressed.len() {
-        panic!("Image buffers must have same length");
-    }
-    if original.len() != (width * height * 4) as usize {
-        panic!("Buffer size doesn't match dimensions");
-    }
-
-    let mut channel_mse = [0.0; 4];
-    let pixel_count = (width * height) as f64;
-
-    for index in (0..original.len()).step_by(4) {
-        for channel in 0..channels as usize {
-            let orig = if channel < 3 {
-                srgb_to_linear(original[index + channel])
-            } else {
-                (original[index + channel] as f64) / 255.0
-            };
-
-            let comp = if channel < 3 {
-                srgb_to_linear(compressed[index + channel])
-            } else {
-                (compressed[index + channel] as f64) / 255.0
-            };
-
-            let diff = orig - comp;
-            channel_mse[channel] += diff * diff;
-        }
-    }
-
-    // Normalize MSE values
-    channel_mse.iter_mut().for_each(|mse| *mse /= pixel_count);
-
-    let calculate_psnr = |mse: f64| -> f64 {
-        if mse == 0.0 {
-            0.0
-        } else {
-            20.0 * (1.0 / mse.sqrt()).log10()
-        }
-    };
-
-    let overall_mse = channel_mse.iter().sum::<f64>() / channels as f64;
-    let overall_psnr = calculate_psnr(overall_mse);
-
-    let channel_results = ChannelResults {
-        red: ChannelMetrics {
-            mse: channel_mse[0],
-            psnr: calculate_psnr(channel_mse[0]),
-        },
-        green: ChannelMetrics {
-            mse: channel_mse[1],
-            psnr: calculate_psnr(channel_mse[1]),
-        },
-        blue: ChannelMetrics {
-            mse: channel_mse[2],
-            psnr: calculate_psnr(channel_mse[2]),
-        },
-        alpha: ChannelMetrics {
-            mse: channel_mse[3],
-            psnr: calculate_psnr(channel_mse[3]),
-        },
-    };
-
-    PsnrResult {
-        overall_psnr,
-        overall_mse,
-        channel_results,
-    }
-}
+fn print_metrics(name: &str, metrics: &ImageMetrics) {
+    let [red, green, blue, alpha] = metrics.error().channel_psnr();
 
-fn print_metrics(name: &str, metrics: &PsnrResult) {
     println!("-----------------------");
     println!("Image name: {name}");
-    println!("Overall PSNR: {:.4} dB", metrics.overall_psnr);
-    println!("Overall MSE: {:.9}", metrics.overall_mse);
-    println!(
-        "Red channel PSNR: {:.4} dB",
-        metrics.channel_results.red.psnr
-    );
-    println!(
-        "Green channel PSNR: {:.4} dB",
-        metrics.channel_results.green.psnr
-    );
-    println!(
-        "Blue channel PSNR: {:.4} dB",
-        metrics.channel_results.blue.psnr
-    );
-    println!(
-        "Alpha channel PSNR: {:.4} dB",
-        metrics.channel_results.alpha.psnr
-    );
+    println!("Overall PSNR: {:.4} dB", metrics.psnr());
+    println!("Overall MSE: {:.9}", metrics.error().mse());
+    println!("Red channel PSNR: {red:.4} dB");
+    println!("Green channel PSNR: {green:.4} dB");
+    println!("Blue channel PSNR: {blue:.4} dB");
+    println!("Alpha channel PSNR: {alpha:.4} dB");
     println!("-----------------------");
 }
 
@@ -201,13 +96,14 @@ fn calculate_psnr(
     height: u32,
     original_data: &[u8],
     blocks_data: &[u8],
-) -> PsnrResult {
+) -> ImageMetrics {
     let size = width * height * 4;
 
     let mut decompressed_data = vec![0; size as usize];
     decompress_blocks_as_rgba8(variant, width, height, blocks_data, &mut decompressed_data);
 
-    calculate_image_metrics_rgba8(original_data, &decompressed_data, width, height, channels)
+    let channels = std::array::from_fn(|channel| channel < channels as usize);
+    compare_rgba8(original_data, &decompressed_data, width, height, channels)
 }
 
 fn compare_psnr(image_path: &str, variant: CompressionVariant, channels: u32) {
@@ -244,25 +140,46 @@ fn compare_psnr(image_path: &str, variant: CompressionVariant, channels: u32) {
 
     const DIFFERENCE: f64 = 0.0035;
 
-    if f64::abs(reference_psnr.overall_psnr - psnr.overall_psnr) > DIFFERENCE {
+    if f64::abs(reference_psnr.psnr() - psnr.psnr()) > DIFFERENCE {
         panic!(
             "Significant overall PSNR difference for image `{image_name}`: {:.3} != {:.3}",
-            reference_psnr.overall_psnr, psnr.overall_psnr
+            reference_psnr.psnr(),
+            psnr.psnr()
         );
     }
 }
 
 #[test]
 fn psnr_bc1() {
-    compare_psnr(BRICK_FILE_PATH, CompressionVariant::BC1, 3);
-    compare_psnr(MARBLE_FILE_PATH, CompressionVariant::BC1, 3);
-    compare_psnr(BLENDER_FILE_PATH, CompressionVariant::BC1, 3);
+    compare_psnr(
+        BRICK_FILE_PATH,
+        CompressionVariant::BC1(BC123Settings::new()),
+        3,
+    );
+    compare_psnr(
+        MARBLE_FILE_PATH,
+        CompressionVariant::BC1(BC123Settings::new()),
+        3,
+    );
+    compare_psnr(
+        BLENDER_FILE_PATH,
+        CompressionVariant::BC1(BC123Settings::new()),
+        3,
+    );
 }
 
 #[test]
 fn psnr_bc3() {
-    compare_psnr(BRICK_ALPHA_FILE_PATH, CompressionVariant::BC3, 4);
-    compare_psnr(MARBLE_ALPHA_FILE_PATH, CompressionVariant::BC3, 4);
+    compare_psnr(
+        BRICK_ALPHA_FILE_PATH,
+        CompressionVariant::BC3(BC123Settings::new()),
+        4,
+    );
+    compare_psnr(
+        MARBLE_ALPHA_FILE_PATH,
+        CompressionVariant::BC3(BC123Settings::new()),
+        4,
+    );
 }
 
 #[test]
@@ -0,0 +1,134 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8,
+    mipmap::{
+        compress_mipmaps_rgba8, generate_mipmaps_rgba8, mip_level_count, MipmapFilter,
+        MipmapSettings,
+    },
+    BC7Settings, CompressionVariant, GpuBlockCompressor,
+};
+use wgpu::{CommandEncoderDescriptor, ComputePassDescriptor};
+
+use self::common::{create_blocks_buffer, create_wgpu_resources, download_blocks_data, read_image};
+
+#[allow(dead_code)]
+mod common;
+
+const FILTERS: [MipmapFilter; 3] = [
+    MipmapFilter::Box,
+    MipmapFilter::Kaiser,
+    MipmapFilter::Lanczos,
+];
+
+#[test]
+fn mipmap_filters_preserve_constant_color() {
+    let width = 37;
+    let height = 11;
+    let color = [12, 99, 200, 255];
+    let rgba_data = color.repeat((width * height) as usize);
+
+    for filter in FILTERS {
+        let levels = generate_mipmaps_rgba8(&rgba_data, width, height, width * 4, filter.into());
+
+        assert_eq!(levels.len() as u32, mip_level_count(width, height));
+        assert_eq!((levels[5].width, levels[5].height), (1, 1));
+
+        for level in levels.iter() {
+            assert_eq!(level.data.len() as u32, level.width * level.height * 4);
+            assert!(level.data.chunks(4).all(|pixel| pixel == color));
+        }
+    }
+}
+
+#[test]
+fn mipmap_srgb_and_alpha_weighting() {
+    // Checkerboard of opaque white and transparent black pixels.
+    let rgba_data: Vec<u8> = (0..4 * 4)
+        .flat_map(|index| match (index % 4 + index / 4) % 2 {
+            0 => [255, 255, 255, 255],
+            _ => [0, 0, 0, 0],
+        })
+        .collect();
+
+    let settings = MipmapSettings::new(MipmapFilter::Box);
+
+    let levels = generate_mipmaps_rgba8(&rgba_data, 4, 4, 16, settings);
+    assert_eq!(levels[1].data[..4], [128, 128, 128, 128]);
+
+    let levels = generate_mipmaps_rgba8(&rgba_data, 4, 4, 16, settings.with_srgb(true));
+    assert_eq!(levels[1].data[..4], [188, 188, 188, 128]);
+
+    let levels = generate_mipmaps_rgba8(&rgba_data, 4, 4, 16, settings.with_alpha_weighting(true));
+    assert_eq!(levels[1].data[..4], [255, 255, 255, 128]);
+}
+
+#[test]
+fn mipmap_cpu_gpu_similar() {
+    let variant = CompressionVariant::BC7(BC7Settings::alpha_basic());
+    let (width, height, rgba_data) = read_image("tests/images/brick-alpha.png");
+
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    for filter in FILTERS {
+        let reference =
+            compress_mipmaps_rgba8(variant, &rgba_data, width, height, width * 4, filter.into());
+
+        let mip_levels =
+            generate_mipmaps_rgba8(&rgba_data, width, height, width * 4, filter.into());
+        let texture = block_compressor.create_mipmapped_texture_rgba8(&mip_levels);
+        let blocks = create_blocks_buffer(&device, reference.blocks.len() as u64);
+
+        let levels =
+            block_compressor.add_mipmap_compression_tasks(variant, &texture, &blocks, None);
+        assert_eq!(levels, reference.levels);
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("command encoder"),
+        });
+
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("compute pass"),
+                timestamp_writes: None,
+            });
+
+            block_compressor.compress(&mut pass);
+        }
+
+        queue.submit([encoder.finish()]);
+
+        let blocks_data = download_blocks_data(&device, &queue, blocks);
+
+        for (index, level) in levels.iter().enumerate() {
+            let mut gpu_rgba = vec![0; (level.width * level.height * 4) as usize];
+            let mut cpu_rgba = vec![0; (level.width * level.height * 4) as usize];
+
+            decompress_blocks_as_rgba8(
+                variant,
+                level.width,
+                level.height,
+                &blocks_data[level.offset..level.offset + level.size],
+                &mut gpu_rgba,
+            );
+            decompress_blocks_as_rgba8(
+                variant,
+                level.width,
+                level.height,
+                reference.level_data(index),
+                &mut cpu_rgba,
+            );
+
+            let max_difference = gpu_rgba
+                .iter()
+                .zip(cpu_rgba.iter())
+                .map(|(gpu, cpu)| gpu.abs_diff(*cpu))
+                .max()
+                .unwrap_or(0);
+
+            assert!(
+                max_difference <= 8,
+                "mip level {index} with {filter:?} filter differs by {max_difference}"
+            );
+        }
+    }
+}
@@ -6,6 +6,7 @@ use crate::common::{
     read_image_and_create_texture, BRICK_FILE_PATH, MARBLE_FILE_PATH,
 };
 
+#[allow(dead_code)]
 mod common;
 
 fn test_multi_task_compression(variant: CompressionVariant) {
@@ -107,17 +108,17 @@ fn test_multi_task_compression(variant: CompressionVariant) {
 
 #[test]
 fn multi_task_compression_bc1() {
-    test_multi_task_compression(CompressionVariant::BC1);
+    test_multi_task_compression(CompressionVariant::BC1(BC123Settings::new()));
 }
 
 #[test]
 fn multi_task_compression_bc2() {
-    test_multi_task_compression(CompressionVariant::BC2);
+    test_multi_task_compression(CompressionVariant::BC2(BC123Settings::new()));
 }
 
 #[test]
 fn multi_task_compression_bc3() {
-    test_multi_task_compression(CompressionVariant::BC3);
+    test_multi_task_compression(CompressionVariant::BC3(BC123Settings::new()));
 }
 
 #[test]
@@ -0,0 +1,129 @@
+use block_compression::{
+    decode::{
+        decompress_blocks_as_rgba8, decompress_normal_map_as_rgba32f,
+        decompress_normal_map_as_rgba8, try_decompress_normal_map_as_rgba8,
+    },
+    encode::{compress_normal_map_rgba8, try_compress_normal_map_rgba8},
+    metrics::compare_normal_maps_rgba8,
+    BC123Settings, BC7Settings, CompressionVariant, Error,
+};
+
+use self::common::hemisphere_normals;
+
+#[allow(dead_code)]
+mod common;
+
+fn compress_and_compare(variant: CompressionVariant, max_mean_angle: f64) {
+    let size = 32;
+    let rgba_data = hemisphere_normals(size, 1.0);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let metrics = compress_normal_map_rgba8(variant, &rgba_data, &mut blocks, size, size, size * 4);
+
+    assert_eq!(metrics.pixel_count(), size * size);
+    assert!(metrics.mean_angle() < max_mean_angle);
+    assert!(metrics.rms_angle() >= metrics.mean_angle());
+    assert!(metrics.max_angle() >= metrics.rms_angle());
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_normal_map_as_rgba8(variant, size, size, &blocks, &mut decoded);
+    assert!(decoded.chunks_exact(4).all(|pixel| pixel[3] == 255));
+
+    // The decoded normal map is only quantized once more.
+    let decoded_metrics = compare_normal_maps_rgba8(&rgba_data, &decoded, size, size);
+    assert!((decoded_metrics.mean_angle() - metrics.mean_angle()).abs() < 0.5);
+}
+
+#[test]
+fn normal_map_bc5() {
+    compress_and_compare(CompressionVariant::BC5, 1.0);
+}
+
+#[test]
+fn normal_map_bc5_snorm() {
+    compress_and_compare(CompressionVariant::BC5Snorm, 1.0);
+}
+
+#[test]
+fn normal_map_bc3() {
+    compress_and_compare(CompressionVariant::BC3(BC123Settings::new()), 2.0);
+}
+
+#[test]
+fn normal_map_bc7() {
+    compress_and_compare(
+        CompressionVariant::BC7(BC7Settings::opaque_very_fast()),
+        2.0,
+    );
+}
+
+#[test]
+fn bc3_layout() {
+    let size = 16;
+    let rgba_data = hemisphere_normals(size, 1.0);
+    let variant = CompressionVariant::BC3(BC123Settings::new());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    compress_normal_map_rgba8(variant, &rgba_data, &mut blocks, size, size, size * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, size, size, &blocks, &mut decoded);
+
+    for (original, decoded) in rgba_data.chunks_exact(4).zip(decoded.chunks_exact(4)) {
+        assert_eq!((decoded[0], decoded[2]), (255, 0));
+        assert!(original[0].abs_diff(decoded[3]) <= 4);
+        assert!(original[1].abs_diff(decoded[1]) <= 8);
+    }
+}
+
+#[test]
+fn renormalizes_input() {
+    let size = 16;
+    let variant = CompressionVariant::BC5;
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let metrics = compress_normal_map_rgba8(
+        variant,
+        &hemisphere_normals(size, 0.5),
+        &mut blocks,
+        size,
+        size,
+        size * 4,
+    );
+    assert!(metrics.mean_angle() < 1.5);
+
+    let mut decoded = vec![0.0; (size * size * 4) as usize];
+    decompress_normal_map_as_rgba32f(variant, size, size, &blocks, &mut decoded);
+
+    for normal in decoded.chunks_exact(4) {
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        assert!((length - 1.0).abs() < 1e-5);
+        assert!(normal[2] >= 0.0);
+        assert_eq!(normal[3], 1.0);
+    }
+}
+
+#[test]
+fn rejects_unsupported_variants() {
+    let rgba_data = vec![0; 16 * 16 * 4];
+    let mut blocks = vec![0; 256];
+
+    assert_eq!(
+        try_compress_normal_map_rgba8(
+            CompressionVariant::BC1(BC123Settings::new()),
+            &rgba_data,
+            &mut blocks,
+            16,
+            16,
+            16 * 4,
+        )
+        .err(),
+        Some(Error::UnsupportedVariant)
+    );
+
+    let mut decoded = vec![0; rgba_data.len()];
+    assert_eq!(
+        try_decompress_normal_map_as_rgba8(CompressionVariant::BC4, 16, 16, &blocks, &mut decoded),
+        Err(Error::UnsupportedVariant)
+    );
+}
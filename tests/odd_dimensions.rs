@@ -0,0 +1,160 @@
+use block_compression::{
+    decode::{decompress_blocks_as_rgba16f, decompress_blocks_as_rgba8},
+    encode::{compress_rgba16, compress_rgba8},
+    half::f16,
+    metrics::{compare_rgba16f, compare_rgba8, stored_channels},
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant,
+};
+
+/// The dimensions of the source images, which cover the padded blocks of all tested sizes.
+const SOURCE_WIDTH: u32 = 8;
+const SOURCE_HEIGHT: u32 = 12;
+
+/// Creates a RGBA8 image with smooth gradients in the top left corner and strongly contrasting
+/// pixels everywhere else, so that fitting the block endpoints to pixels outside of the visible
+/// area would increase the error of the visible pixels.
+fn source_rgba8() -> Vec<u8> {
+    (0..SOURCE_WIDTH * SOURCE_HEIGHT)
+        .flat_map(|index| {
+            let (x, y) = (index % SOURCE_WIDTH, index / SOURCE_WIDTH);
+            if x < 6 && y < 10 {
+                [
+                    (60 + x * 20) as u8,
+                    (90 + y * 8) as u8,
+                    (140 - x * 5 - y * 3) as u8,
+                    (200 + x * 5) as u8,
+                ]
+            } else if (x + y) % 2 == 0 {
+                [255, 0, 255, 0]
+            } else {
+                [0, 255, 0, 255]
+            }
+        })
+        .collect()
+}
+
+/// Returns the top left `width` x `height` pixels of a source image as tightly packed data.
+fn crop<T: Copy>(data: &[T], width: u32, height: u32) -> Vec<T> {
+    (0..height as usize)
+        .flat_map(|y| &data[y * SOURCE_WIDTH as usize * 4..][..width as usize * 4])
+        .copied()
+        .collect()
+}
+
+/// Compresses the top left `width` x `height` pixels of the source image once as an image of
+/// that size and once as the 4-aligned image which also contains the contrasting pixels, and
+/// returns the PSNR of the visible pixels of both.
+fn round_trip_rgba8(variant: CompressionVariant, width: u32, height: u32) -> (f64, f64) {
+    let rgba_data = source_rgba8();
+    let original = crop(&rgba_data, width, height);
+    let channels = stored_channels(variant);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        width,
+        height,
+        SOURCE_WIDTH * 4,
+    );
+    let mut decoded = vec![0; original.len()];
+    decompress_blocks_as_rgba8(variant, width, height, &blocks, &mut decoded);
+    let padded = compare_rgba8(&original, &decoded, width, height, channels);
+
+    let (aligned_width, aligned_height) = (width.next_multiple_of(4), height.next_multiple_of(4));
+    let mut aligned_blocks = vec![0; variant.blocks_byte_size(aligned_width, aligned_height)];
+    compress_rgba8(
+        variant,
+        &rgba_data,
+        &mut aligned_blocks,
+        aligned_width,
+        aligned_height,
+        SOURCE_WIDTH * 4,
+    );
+    // The blocks are identical in size, decoding them as the smaller image drops the outside
+    // pixels.
+    decompress_blocks_as_rgba8(variant, width, height, &aligned_blocks, &mut decoded);
+    let aligned = compare_rgba8(&original, &decoded, width, height, channels);
+
+    (padded.psnr(), aligned.psnr())
+}
+
+fn check_rgba8(variant: CompressionVariant, min_psnr: f64) {
+    for (width, height) in [(1, 1), (2, 2), (6, 10)] {
+        let (padded, aligned) = round_trip_rgba8(variant, width, height);
+        assert!(
+            padded > min_psnr,
+            "{variant:?} {width}x{height}: PSNR {padded}"
+        );
+        assert!(
+            padded > aligned,
+            "{variant:?} {width}x{height}: PSNR {padded} not above {aligned} of the aligned image"
+        );
+    }
+}
+
+#[test]
+fn round_trip_bc1() {
+    check_rgba8(CompressionVariant::BC1(BC123Settings::new()), 30.0);
+}
+
+#[test]
+fn round_trip_bc3() {
+    check_rgba8(CompressionVariant::BC3(BC123Settings::new()), 30.0);
+}
+
+#[test]
+fn round_trip_bc7() {
+    check_rgba8(
+        CompressionVariant::BC7(BC7Settings::alpha_very_fast()),
+        35.0,
+    );
+}
+
+#[test]
+fn round_trip_bc6h() {
+    let variant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+    let rgba_data: Vec<f16> = source_rgba8()
+        .into_iter()
+        .map(|value| f16::from_f32(value as f32 / 64.0))
+        .collect();
+    let channels = stored_channels(variant);
+
+    for (width, height) in [(1, 1), (2, 2), (6, 10)] {
+        let original = crop(&rgba_data, width, height);
+
+        let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+        compress_rgba16(
+            variant,
+            &rgba_data,
+            &mut blocks,
+            width,
+            height,
+            SOURCE_WIDTH * 4,
+        );
+        let mut decoded = vec![f16::ZERO; original.len()];
+        decompress_blocks_as_rgba16f(variant, width, height, &blocks, &mut decoded);
+        let padded = compare_rgba16f(&original, &decoded, width, height, channels).psnr();
+
+        let (aligned_width, aligned_height) =
+            (width.next_multiple_of(4), height.next_multiple_of(4));
+        let mut aligned_blocks = vec![0; variant.blocks_byte_size(aligned_width, aligned_height)];
+        compress_rgba16(
+            variant,
+            &rgba_data,
+            &mut aligned_blocks,
+            aligned_width,
+            aligned_height,
+            SOURCE_WIDTH * 4,
+        );
+        decompress_blocks_as_rgba16f(variant, width, height, &aligned_blocks, &mut decoded);
+        let aligned = compare_rgba16f(&original, &decoded, width, height, channels).psnr();
+
+        assert!(padded > 25.0, "{width}x{height}: PSNR {padded}");
+        assert!(
+            padded > aligned,
+            "{width}x{height}: PSNR {padded} not above {aligned} of the aligned image"
+        );
+    }
+}
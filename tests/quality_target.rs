@@ -0,0 +1,178 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8,
+    encode::{
+        compress_rgba16_to_quality, compress_rgba8, compress_rgba8_to_quality,
+        try_compress_rgba8_to_quality, QualityTarget,
+    },
+    half::f16,
+    metrics::{compare_rgba8, stored_channels},
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant, Error,
+};
+
+/// Returns the top left `size` x `size` pixels of the image.
+fn read_image_crop(file_path: &str, size: u32) -> Vec<u8> {
+    let image = image::open(file_path).unwrap().to_rgba8();
+    let stride = image.width() as usize * 4;
+
+    image
+        .as_raw()
+        .chunks(stride)
+        .take(size as usize)
+        .flat_map(|row| &row[..size as usize * 4])
+        .copied()
+        .collect()
+}
+
+#[test]
+fn easy_target_selects_cheapest_preset() {
+    let size = 32;
+    let rgba_data = read_image_crop("tests/images/brick-alpha.png", size);
+    let variant = CompressionVariant::BC7(BC7Settings::alpha_basic());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let selection = compress_rgba8_to_quality(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        size,
+        size,
+        size * 4,
+        QualityTarget::min_psnr(20.0),
+    );
+
+    assert!(selection.target_met());
+    assert_eq!(selection.tiles().len(), 1);
+    assert_eq!(selection.tiles()[0].preset, 0);
+
+    let mut expected = vec![0; blocks.len()];
+    let cheapest = CompressionVariant::BC7(BC7Settings::alpha_ultrafast());
+    compress_rgba8(cheapest, &rgba_data, &mut expected, size, size, size * 4);
+    assert_eq!(blocks, expected);
+}
+
+#[test]
+fn unreachable_target_selects_slowest_preset() {
+    let size = 16;
+    let rgba_data = read_image_crop("tests/images/marble.png", size);
+    let variant = CompressionVariant::BC7(BC7Settings::opaque_basic());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let selection = compress_rgba8_to_quality(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        size,
+        size,
+        size * 4,
+        QualityTarget::max_error(0.0),
+    );
+
+    assert!(!selection.target_met());
+    assert_eq!(selection.tiles()[0].preset, 4);
+}
+
+#[test]
+fn tiles_escalate_separately() {
+    let size = 32;
+    let mut rgba_data = read_image_crop("tests/images/brick.png", size);
+
+    // The left half of the image is a single color, which every preset compresses well.
+    for row in rgba_data.chunks_mut(size as usize * 4) {
+        row[..size as usize * 2].copy_from_slice(&[128, 64, 32, 255].repeat(size as usize / 2));
+    }
+
+    let variant = CompressionVariant::BC7(BC7Settings::opaque_basic());
+    let target = QualityTarget::min_psnr(60.0).with_tile_size(16);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let selection = compress_rgba8_to_quality(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        size,
+        size,
+        size * 4,
+        target,
+    );
+
+    assert_eq!(selection.tiles_per_row(), 2);
+    assert_eq!(selection.tiles().len(), 4);
+
+    for tile in selection.tiles() {
+        assert_eq!((tile.width, tile.height), (16, 16));
+
+        // Only the textured tiles escalate to the slowest preset.
+        if tile.x == 0 {
+            assert_eq!(tile.preset, 0);
+            assert!(tile.target_met);
+        } else {
+            assert_eq!(tile.preset, 4);
+            assert!(!tile.target_met);
+        }
+    }
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8(variant, size, size, &blocks, &mut decoded);
+    let metrics = compare_rgba8(&rgba_data, &decoded, size, size, stored_channels(variant));
+
+    assert!((metrics.error().mse() - selection.image().mse()).abs() < 1e-12);
+}
+
+#[test]
+fn hdr_target() {
+    let size = 16;
+    let rgba_data: Vec<f16> = (0..size * size)
+        .flat_map(|index| {
+            let (x, y) = ((index % size) as f32, (index / size) as f32);
+            [x * 0.5, y * 0.25, (x * y) * 0.03, 1.0].map(f16::from_f32)
+        })
+        .collect();
+    let variant = CompressionVariant::BC6H(BC6HSettings::basic());
+
+    let mut blocks = vec![0; variant.blocks_byte_size(size, size)];
+    let selection = compress_rgba16_to_quality(
+        variant,
+        &rgba_data,
+        &mut blocks,
+        size,
+        size,
+        size * 4,
+        QualityTarget::max_error(2.0),
+    );
+
+    assert!(selection.target_met());
+    assert!(selection.image().max_error() <= 2.0);
+    assert_eq!(selection.image().peak(), 7.5);
+}
+
+#[test]
+fn rejects_invalid_input() {
+    let rgba_data = vec![0; 16 * 16 * 4];
+    let mut blocks = vec![0; 256];
+
+    assert_eq!(
+        try_compress_rgba8_to_quality(
+            CompressionVariant::BC1(BC123Settings::new()),
+            &rgba_data,
+            &mut blocks,
+            16,
+            16,
+            16 * 4,
+            QualityTarget::min_psnr(40.0),
+        )
+        .err(),
+        Some(Error::UnsupportedVariant)
+    );
+    assert!(matches!(
+        try_compress_rgba8_to_quality(
+            CompressionVariant::BC7(BC7Settings::alpha_basic()),
+            &rgba_data,
+            &mut blocks,
+            16,
+            16,
+            16 * 4,
+            QualityTarget::min_psnr(40.0).with_tile_size(6),
+        ),
+        Err(Error::InvalidSettings { .. })
+    ));
+}
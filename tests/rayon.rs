@@ -0,0 +1,121 @@
+#![cfg(feature = "rayon")]
+
+use block_compression::{
+    decode::{decompress_blocks_as_rgba16f, decompress_blocks_as_rgba8},
+    encode::{compress_rgba16, compress_rgba8, compress_rgba8_with_metrics},
+    half::f16,
+    BC123Settings, BC6HSettings, BC7Settings, CompressionVariant,
+};
+use rayon::ThreadPoolBuilder;
+
+use self::common::read_image;
+
+#[allow(dead_code)]
+mod common;
+
+/// Not a multiple of 4, so the last block row and column are padded.
+const WIDTH: u32 = 123;
+const HEIGHT: u32 = 77;
+
+/// Runs `f` once on a single thread, which processes the rows in order like the serial path,
+/// and once on multiple threads and returns both results.
+fn serial_and_parallel<T: Send>(f: impl Fn() -> T + Sync) -> (T, T) {
+    let serial = ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap()
+        .install(&f);
+    let parallel = ThreadPoolBuilder::new()
+        .num_threads(8)
+        .build()
+        .unwrap()
+        .install(&f);
+    (serial, parallel)
+}
+
+fn compare_rgba8(variant: CompressionVariant) {
+    let (image_width, _, rgba_data) = read_image("tests/images/brick-alpha.png");
+    let stride = image_width * 4;
+
+    let compress = || {
+        let mut blocks = vec![0; variant.blocks_byte_size(WIDTH, HEIGHT)];
+        compress_rgba8(variant, &rgba_data, &mut blocks, WIDTH, HEIGHT, stride);
+        blocks
+    };
+    let (serial_blocks, parallel_blocks) = serial_and_parallel(compress);
+    assert!(
+        serial_blocks == parallel_blocks,
+        "{variant:?}: compressed blocks differ"
+    );
+
+    let decompress = || {
+        let mut decoded = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        decompress_blocks_as_rgba8(variant, WIDTH, HEIGHT, &serial_blocks, &mut decoded);
+        decoded
+    };
+    let (serial_decoded, parallel_decoded) = serial_and_parallel(decompress);
+    assert!(
+        serial_decoded == parallel_decoded,
+        "{variant:?}: decoded pixels differ"
+    );
+
+    let measure = || {
+        let mut blocks = vec![0; variant.blocks_byte_size(WIDTH, HEIGHT)];
+        compress_rgba8_with_metrics(variant, &rgba_data, &mut blocks, WIDTH, HEIGHT, stride)
+    };
+    let (serial_metrics, parallel_metrics) = serial_and_parallel(measure);
+    assert_eq!(serial_metrics, parallel_metrics);
+}
+
+#[test]
+fn rayon_bc1() {
+    compare_rgba8(CompressionVariant::BC1(BC123Settings::new()));
+}
+
+#[test]
+fn rayon_bc3() {
+    compare_rgba8(CompressionVariant::BC3(BC123Settings::new()));
+}
+
+#[test]
+fn rayon_bc5() {
+    compare_rgba8(CompressionVariant::BC5);
+}
+
+#[test]
+fn rayon_bc7() {
+    compare_rgba8(CompressionVariant::BC7(BC7Settings::alpha_ultrafast()));
+}
+
+#[test]
+fn rayon_bc6h() {
+    let (image_width, _, rgba_data) = read_image("tests/images/brick-alpha.png");
+    let stride = image_width * 4;
+    let rgba_data: Vec<f16> = rgba_data
+        .into_iter()
+        .map(|value| f16::from_f32(value as f32 / 32.0))
+        .collect();
+    let variant = CompressionVariant::BC6H(BC6HSettings::very_fast());
+
+    let compress = || {
+        let mut blocks = vec![0; variant.blocks_byte_size(WIDTH, HEIGHT)];
+        compress_rgba16(variant, &rgba_data, &mut blocks, WIDTH, HEIGHT, stride);
+        blocks
+    };
+    let (serial_blocks, parallel_blocks) = serial_and_parallel(compress);
+    assert!(serial_blocks == parallel_blocks, "compressed blocks differ");
+
+    let decompress = || {
+        let mut decoded = vec![f16::ZERO; (WIDTH * HEIGHT * 4) as usize];
+        decompress_blocks_as_rgba16f(variant, WIDTH, HEIGHT, &serial_blocks, &mut decoded);
+        decoded
+    };
+    let (serial_decoded, parallel_decoded) = serial_and_parallel(decompress);
+    assert!(
+        serial_decoded
+            .iter()
+            .zip(&parallel_decoded)
+            .all(|(serial, parallel)| serial.to_bits() == parallel.to_bits()),
+        "decoded pixels differ"
+    );
+}
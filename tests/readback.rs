@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use block_compression::{BC123Settings, CompressionVariant, Error, GpuBlockCompressor};
+use pollster::block_on;
+use wgpu::{CommandEncoderDescriptor, ComputePassDescriptor, PollType, TextureViewDescriptor};
+
+use self::common::{
+    create_blocks_buffer, create_wgpu_resources, download_blocks_data,
+    read_image_and_create_texture, BRICK_FILE_PATH,
+};
+
+#[allow(dead_code)]
+mod common;
+
+#[test]
+fn readback_matches_download() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let (texture, _) = read_image_and_create_texture(&device, &queue, BRICK_FILE_PATH, variant);
+    let (width, height) = (texture.width(), texture.height());
+    let size = variant.blocks_byte_size(width, height);
+    let blocks_offset = 256;
+
+    let blocks = create_blocks_buffer(&device, (blocks_offset + size) as u64);
+    block_compressor.add_compression_task(
+        variant,
+        &texture.create_view(&TextureViewDescriptor::default()),
+        width,
+        height,
+        &blocks,
+        None,
+        Some(blocks_offset as u32),
+    );
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    let mut readback =
+        block_compressor.read_back_blocks(&mut encoder, &blocks, blocks_offset, size);
+    let empty_readback = block_compressor.read_back_blocks(&mut encoder, &blocks, 0, 0);
+    assert!(!readback.is_ready());
+    assert!(readback.try_receive().is_none());
+    assert!(empty_readback.is_ready());
+
+    queue.submit([encoder.finish()]);
+
+    let _ = device.poll(PollType::Wait {
+        submission_index: None,
+        timeout: Some(Duration::from_secs(60)),
+    });
+
+    assert!(readback.is_ready());
+    let data = block_on(readback).unwrap();
+    let downloaded = download_blocks_data(&device, &queue, blocks);
+
+    assert_eq!(data.len(), size);
+    assert!(data == downloaded[blocks_offset..]);
+    assert_eq!(block_on(empty_readback), Ok(Vec::new()));
+}
+
+#[test]
+fn readback_rejects_out_of_bounds_range() {
+    let (device, queue) = create_wgpu_resources();
+    let block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let blocks = create_blocks_buffer(&device, 1024);
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    assert!(matches!(
+        block_compressor.try_read_back_blocks(&mut encoder, &blocks, 512, 1024),
+        Err(Error::BufferTooSmall {
+            required: 1536,
+            actual: 1024,
+        })
+    ));
+}
+
+#[test]
+fn readback_rejects_unaligned_and_overflowing_range() {
+    let (device, queue) = create_wgpu_resources();
+    let block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let blocks = create_blocks_buffer(&device, 1024);
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    assert!(matches!(
+        block_compressor.try_read_back_blocks(&mut encoder, &blocks, 2, 16),
+        Err(Error::UnalignedCopy {
+            offset: 2,
+            size: 16
+        })
+    ));
+    assert!(matches!(
+        block_compressor.try_read_back_blocks(&mut encoder, &blocks, 0, 18),
+        Err(Error::UnalignedCopy {
+            offset: 0,
+            size: 18
+        })
+    ));
+    assert!(matches!(
+        block_compressor.try_read_back_blocks(&mut encoder, &blocks, 16, usize::MAX - 3),
+        Err(Error::BufferTooSmall {
+            required: usize::MAX,
+            actual: 1024,
+        })
+    ));
+}
@@ -0,0 +1,80 @@
+use block_compression::{
+    decode::{decompress_blocks_as_rgba32f, decompress_blocks_as_rgba8_snorm},
+    encode::{compress_rgba8_snorm, try_compress_rgba8, try_compress_rgba8_snorm},
+    CompressionVariant, Error,
+};
+
+#[test]
+fn bc5_snorm_roundtrip() {
+    let variant = CompressionVariant::BC5Snorm;
+    let (width, height) = (13, 6);
+
+    // Smooth signed gradients in the red and green channels, including the -128 edge case.
+    let rgba_data: Vec<i8> = (0..width * height)
+        .flat_map(|index| {
+            let x = (index % width) as i32;
+            let y = (index / width) as i32;
+            [
+                (-128 + x * 255 / (width as i32 - 1)) as i8,
+                (100 - y * 40) as i8,
+                0,
+                0,
+            ]
+        })
+        .collect();
+
+    let mut blocks = vec![0; variant.blocks_byte_size(width, height)];
+    compress_rgba8_snorm(variant, &rgba_data, &mut blocks, width, height, width * 4);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8_snorm(variant, width, height, &blocks, &mut decoded);
+
+    for (decoded, original) in decoded.chunks(4).zip(rgba_data.chunks(4)) {
+        let original_red = i8::max(original[0], -127);
+        assert!(decoded[0].abs_diff(original_red) <= 8);
+        assert!(decoded[1].abs_diff(original[1]) <= 8);
+    }
+
+    let mut decoded_float = vec![0.0; rgba_data.len()];
+    decompress_blocks_as_rgba32f(variant, width, height, &blocks, &mut decoded_float);
+
+    for (float, snorm) in decoded_float.iter().zip(decoded.iter()) {
+        assert!((-1.0..=1.0).contains(float));
+        assert_eq!((float * 127.0).round() as i8, *snorm);
+    }
+}
+
+#[test]
+fn bc4_snorm_constant_block() {
+    let variant = CompressionVariant::BC4Snorm;
+    let rgba_data = [-90, 0, 0, 0].repeat(16);
+
+    let mut blocks = vec![0; variant.blocks_byte_size(4, 4)];
+    compress_rgba8_snorm(variant, &rgba_data, &mut blocks, 4, 4, 16);
+
+    let mut decoded = vec![0; rgba_data.len()];
+    decompress_blocks_as_rgba8_snorm(variant, 4, 4, &blocks, &mut decoded);
+
+    assert_eq!(decoded, rgba_data);
+}
+
+#[test]
+fn snorm_variant_errors() {
+    let mut blocks = vec![0; 16];
+
+    assert_eq!(
+        try_compress_rgba8(
+            CompressionVariant::BC5Snorm,
+            &[0; 64],
+            &mut blocks,
+            4,
+            4,
+            16
+        ),
+        Err(Error::UnsupportedVariant)
+    );
+    assert_eq!(
+        try_compress_rgba8_snorm(CompressionVariant::BC5, &[0; 64], &mut blocks, 4, 4, 16),
+        Err(Error::UnsupportedVariant)
+    );
+}
@@ -0,0 +1,164 @@
+use block_compression::{
+    BC123Settings, BC7Settings, CompressionVariant, Error, GpuBlockCompressor,
+};
+use image::ImageReader;
+use wgpu::{
+    util::{DeviceExt, TextureDataOrder},
+    CommandEncoderDescriptor, ComputePassDescriptor, Extent3d, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor, TextureViewDimension,
+};
+
+use self::common::{
+    create_blocks_buffer, create_wgpu_resources, download_blocks_data, BRICK_FILE_PATH,
+    MARBLE_FILE_PATH,
+};
+
+#[allow(dead_code)]
+mod common;
+
+const SIZE: u32 = 252;
+
+/// Creates a texture with six layers, which alternate between a brick and a marble crop.
+fn create_array_texture(device: &wgpu::Device, queue: &wgpu::Queue) -> Texture {
+    let layers: Vec<Vec<u8>> = [BRICK_FILE_PATH, MARBLE_FILE_PATH]
+        .iter()
+        .map(|file_path| {
+            ImageReader::open(file_path)
+                .expect("can't open input image")
+                .decode()
+                .expect("can't decode image")
+                .crop_imm(0, 0, SIZE, SIZE)
+                .to_rgba8()
+                .into_raw()
+        })
+        .collect();
+
+    let data: Vec<u8> = (0..6).flat_map(|layer| layers[layer % 2].clone()).collect();
+
+    device.create_texture_with_data(
+        queue,
+        &TextureDescriptor {
+            label: Some("array texture"),
+            size: Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        &data,
+    )
+}
+
+fn test_array_compression(variant: CompressionVariant) {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let texture = create_array_texture(&device, &queue);
+    let layer_size = variant.blocks_byte_size(SIZE, SIZE);
+
+    // Layers 1 to 5 with a single array task.
+    let array_blocks = create_blocks_buffer(&device, (layer_size * 5) as u64);
+    block_compressor.add_array_compression_task(
+        variant,
+        &texture,
+        SIZE,
+        SIZE,
+        1..6,
+        &array_blocks,
+        None,
+    );
+
+    // Layers 1 to 5 with one task per layer.
+    let layer_blocks = create_blocks_buffer(&device, (layer_size * 5) as u64);
+    for layer in 1..6 {
+        block_compressor.add_compression_task(
+            variant,
+            &texture.create_view(&TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2),
+                base_array_layer: layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            }),
+            SIZE,
+            SIZE,
+            &layer_blocks,
+            None,
+            Some(layer_size as u32 * (layer - 1)),
+        );
+    }
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    queue.submit([encoder.finish()]);
+
+    let array_data = download_blocks_data(&device, &queue, array_blocks);
+    let layer_data = download_blocks_data(&device, &queue, layer_blocks);
+
+    assert!(array_data == layer_data, "array and layer tasks differ");
+    assert!(
+        array_data[..layer_size] != array_data[layer_size..layer_size * 2],
+        "brick and marble layers are equal"
+    );
+}
+
+#[test]
+fn array_compression_bc1() {
+    test_array_compression(CompressionVariant::BC1(BC123Settings::new()));
+}
+
+#[test]
+fn array_compression_bc5() {
+    test_array_compression(CompressionVariant::BC5);
+}
+
+#[test]
+fn array_compression_bc7() {
+    test_array_compression(CompressionVariant::BC7(BC7Settings::opaque_ultra_fast()));
+}
+
+#[test]
+fn array_compression_invalid_layers() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let texture = create_array_texture(&device, &queue);
+    let blocks = create_blocks_buffer(&device, (variant.blocks_byte_size(SIZE, SIZE) * 6) as u64);
+
+    for layers in [2..2, 4..7] {
+        assert_eq!(
+            block_compressor.try_add_array_compression_task(
+                variant,
+                &texture,
+                SIZE,
+                SIZE,
+                layers.clone(),
+                &blocks,
+                None,
+            ),
+            Err(Error::InvalidLayerRange {
+                start: layers.start,
+                end: layers.end,
+                layer_count: 6,
+            })
+        );
+    }
+}
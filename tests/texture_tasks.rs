@@ -0,0 +1,168 @@
+use block_compression::{
+    mipmap::mip_level_size, BC123Settings, CompressedSubresource, CompressionVariant, Error,
+    GpuBlockCompressor, TextureCompressionTask,
+};
+use wgpu::{
+    util::{DeviceExt, TextureDataOrder},
+    CommandEncoderDescriptor, ComputePassDescriptor, Extent3d, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor, TextureViewDimension,
+};
+
+use self::common::{create_blocks_buffer, create_wgpu_resources, download_blocks_data};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 40;
+const HEIGHT: u32 = 24;
+const MIP_LEVEL_COUNT: u32 = 4;
+const LAYER_COUNT: u32 = 3;
+
+/// Creates a texture with a noise pattern that differs between all subresources.
+fn create_texture(device: &wgpu::Device, queue: &wgpu::Queue) -> Texture {
+    let mut state = 0x1234_5678u32;
+    let mut data = Vec::new();
+    for _ in 0..LAYER_COUNT {
+        for level in 0..MIP_LEVEL_COUNT {
+            let (width, height) = mip_level_size(WIDTH, HEIGHT, level);
+            data.extend((0..width * height * 4).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            }));
+        }
+    }
+
+    device.create_texture_with_data(
+        queue,
+        &TextureDescriptor {
+            label: Some("mipmapped array texture"),
+            size: Extent3d {
+                width: WIDTH,
+                height: HEIGHT,
+                depth_or_array_layers: LAYER_COUNT,
+            },
+            mip_level_count: MIP_LEVEL_COUNT,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        TextureDataOrder::LayerMajor,
+        &data,
+    )
+}
+
+#[test]
+fn texture_task_matches_single_tasks() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC1(BC123Settings::new());
+    let texture = create_texture(&device, &queue);
+    let blocks_offset = 64;
+
+    let task_blocks = create_blocks_buffer(&device, 4096);
+    let subresources = block_compressor.add_texture_compression_task(
+        &TextureCompressionTask::new(variant, &texture, &task_blocks)
+            .with_mip_levels(1..3)
+            .with_array_layers(1..3)
+            .with_blocks_offset(blocks_offset),
+    );
+
+    let mut offset = blocks_offset as usize;
+    let mut expected = Vec::new();
+    for array_layer in 1..3 {
+        for mip_level in 1..3 {
+            let (width, height) = mip_level_size(WIDTH, HEIGHT, mip_level);
+            let size = variant.blocks_byte_size(width, height);
+            expected.push(CompressedSubresource {
+                mip_level,
+                array_layer,
+                width,
+                height,
+                offset,
+                size,
+            });
+            offset += size;
+        }
+    }
+    assert_eq!(subresources, expected);
+
+    let single_blocks = create_blocks_buffer(&device, 4096);
+    for subresource in subresources.iter() {
+        block_compressor.add_compression_task(
+            variant,
+            &texture.create_view(&TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2),
+                base_mip_level: subresource.mip_level,
+                mip_level_count: Some(1),
+                base_array_layer: subresource.array_layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            }),
+            subresource.width,
+            subresource.height,
+            &single_blocks,
+            None,
+            Some(subresource.offset as u32),
+        );
+    }
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    queue.submit([encoder.finish()]);
+
+    let task_data = download_blocks_data(&device, &queue, task_blocks);
+    let single_data = download_blocks_data(&device, &queue, single_blocks);
+
+    assert!(task_data[..offset] == single_data[..offset]);
+    assert!(task_data[blocks_offset as usize..offset]
+        .iter()
+        .any(|&byte| byte != 0));
+}
+
+#[test]
+fn texture_task_rejects_invalid_ranges() {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let variant = CompressionVariant::BC4;
+    let texture = create_texture(&device, &queue);
+    let blocks = create_blocks_buffer(&device, 4096);
+    let task = TextureCompressionTask::new(variant, &texture, &blocks);
+
+    assert_eq!(
+        block_compressor.try_add_texture_compression_task(&task.clone().with_mip_levels(2..5)),
+        Err(Error::InvalidMipRange {
+            start: 2,
+            end: 5,
+            mip_level_count: MIP_LEVEL_COUNT,
+        })
+    );
+    assert_eq!(
+        block_compressor.try_add_texture_compression_task(&task.clone().with_array_layers(1..1)),
+        Err(Error::InvalidLayerRange {
+            start: 1,
+            end: 1,
+            layer_count: LAYER_COUNT,
+        })
+    );
+    assert!(matches!(
+        block_compressor.try_add_texture_compression_task(&task.with_blocks_offset(4000)),
+        Err(Error::BufferTooSmall { .. })
+    ));
+}
@@ -0,0 +1,110 @@
+use block_compression::{
+    decode::decompress_blocks_as_rgba8,
+    encode::compress_rgba8,
+    metrics::{compare_rgba8, stored_channels},
+    BC123Settings, BC7Settings, CompressionVariant, GpuBlockCompressor,
+};
+use wgpu::{CommandEncoderDescriptor, ComputePassDescriptor, TextureViewDescriptor};
+
+use self::common::{
+    create_blocks_buffer, create_wgpu_resources, download_blocks_data,
+    read_image_and_create_texture, BRICK_FILE_PATH,
+};
+
+#[allow(dead_code)]
+mod common;
+
+const WIDTH: u32 = 61;
+const HEIGHT: u32 = 37;
+const TEXTURE_Y_OFFSET: u32 = 13;
+
+/// Returns the `WIDTH` x `HEIGHT` pixels starting at `TEXTURE_Y_OFFSET` as tightly packed data.
+fn crop(rgba_data: &[u8], stride: u32) -> Vec<u8> {
+    (TEXTURE_Y_OFFSET..TEXTURE_Y_OFFSET + HEIGHT)
+        .flat_map(|y| &rgba_data[(y * stride) as usize..][..(WIDTH * 4) as usize])
+        .copied()
+        .collect()
+}
+
+/// Compresses a region of the brick texture, which is neither aligned to nor a multiple of the
+/// block size, on the GPU and the CPU and compares the PSNR of both.
+fn compare_with_cpu(variant: CompressionVariant) {
+    let (device, queue) = create_wgpu_resources();
+    let mut block_compressor = GpuBlockCompressor::new(device.clone(), queue.clone());
+
+    let (texture, rgba_data) =
+        read_image_and_create_texture(&device, &queue, BRICK_FILE_PATH, variant);
+    let stride = texture.width() * 4;
+    let blocks_size = variant.blocks_byte_size(WIDTH, HEIGHT);
+
+    let blocks = create_blocks_buffer(&device, blocks_size as u64);
+    block_compressor.add_compression_task(
+        variant,
+        &texture.create_view(&TextureViewDescriptor::default()),
+        WIDTH,
+        HEIGHT,
+        &blocks,
+        Some(TEXTURE_Y_OFFSET),
+        None,
+    );
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("command encoder"),
+    });
+
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute pass"),
+            timestamp_writes: None,
+        });
+
+        block_compressor.compress(&mut pass);
+    }
+
+    queue.submit([encoder.finish()]);
+
+    let gpu_blocks = download_blocks_data(&device, &queue, blocks);
+
+    let mut cpu_blocks = vec![0; blocks_size];
+    compress_rgba8(
+        variant,
+        &rgba_data[(TEXTURE_Y_OFFSET * stride) as usize..],
+        &mut cpu_blocks,
+        WIDTH,
+        HEIGHT,
+        stride,
+    );
+
+    let original = crop(&rgba_data, stride);
+    let channels = stored_channels(variant);
+    let mut decoded = vec![0; original.len()];
+
+    decompress_blocks_as_rgba8(variant, WIDTH, HEIGHT, &gpu_blocks, &mut decoded);
+    let gpu_psnr = compare_rgba8(&original, &decoded, WIDTH, HEIGHT, channels).psnr();
+
+    decompress_blocks_as_rgba8(variant, WIDTH, HEIGHT, &cpu_blocks, &mut decoded);
+    let cpu_psnr = compare_rgba8(&original, &decoded, WIDTH, HEIGHT, channels).psnr();
+
+    const DIFFERENCE: f64 = 0.0035;
+
+    assert!(
+        f64::abs(cpu_psnr - gpu_psnr) <= DIFFERENCE,
+        "PSNR of the GPU ({gpu_psnr:.4}) and the CPU encoder ({cpu_psnr:.4}) differ"
+    );
+    assert!(cpu_psnr > 25.0, "PSNR {cpu_psnr:.4} is too low");
+}
+
+#[test]
+fn unaligned_texture_bc1() {
+    compare_with_cpu(CompressionVariant::BC1(BC123Settings::new()));
+}
+
+#[test]
+fn unaligned_texture_bc4() {
+    compare_with_cpu(CompressionVariant::BC4);
+}
+
+#[test]
+fn unaligned_texture_bc7() {
+    compare_with_cpu(CompressionVariant::BC7(BC7Settings::opaque_ultra_fast()));
+}